@@ -0,0 +1,59 @@
+use chrono::{DateTime, NaiveDateTime};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use perception_eval::dataset::{get_current_frame, index::FrameIndex, FrameGroundTruth};
+
+/// Frames of a long log recorded at 10 Hz.
+fn long_log(num_frames: i64) -> Vec<FrameGroundTruth> {
+    (0..num_frames)
+        .map(|i| FrameGroundTruth::new(timestamp(i * 100_000), Vec::new()))
+        .collect()
+}
+
+fn timestamp(micros: i64) -> NaiveDateTime {
+    DateTime::from_timestamp_micros(micros).unwrap().naive_utc()
+}
+
+fn bench_frame_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame_lookup");
+    for num_frames in [1_000, 10_000, 100_000] {
+        let frames = long_log(num_frames);
+        let index = FrameIndex::new(&frames);
+        // Estimations are delayed by 20 [ms] from GTs.
+        let queries = (0..num_frames)
+            .step_by((num_frames / 100) as usize)
+            .map(|i| timestamp(i * 100_000 + 20_000))
+            .collect::<Vec<_>>();
+
+        group.bench_with_input(
+            BenchmarkId::new("linear", num_frames),
+            &queries,
+            |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|t| get_current_frame(black_box(&frames), t))
+                        .count()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("index", num_frames),
+            &queries,
+            |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|t| black_box(&index).find(t).map(|i| frames[i].clone()))
+                        .count()
+                })
+            },
+        );
+        group.bench_function(BenchmarkId::new("build", num_frames), |b| {
+            b.iter(|| FrameIndex::new(black_box(&frames)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_frame_lookup);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use perception_eval::dataset::nuscenes::{
+    json::{parse_json_array, split_json_array},
+    schema::SampleAnnotation,
+};
+
+/// Table of sample annotations in the same layout as `sample_annotation.json`.
+fn sample_annotation_table(num_annotations: usize) -> Vec<u8> {
+    let token = |i: usize| format!("{:032x}", i);
+    let items = (0..num_annotations)
+        .map(|i| {
+            format!(
+                r#"{{
+"token": "{}",
+"sample_token": "{}",
+"instance_token": "{}",
+"visibility_token": "4",
+"attribute_tokens": ["{}"],
+"translation": [{}.123, -12.345, 1.234],
+"size": [1.9, 4.5, 1.6],
+"rotation": [0.7071, 0.0, 0.0, 0.7071],
+"prev": "{}",
+"next": "",
+"num_lidar_pts": 120,
+"num_radar_pts": 3
+}}"#,
+                token(i),
+                token(i / 50),
+                token(i % 1000),
+                token(7),
+                i % 200,
+                token(i + 1),
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]", items.join(",\n")).into_bytes()
+}
+
+fn bench_parse_json(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_sample_annotation");
+    group.sample_size(10);
+    let num_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    for num_annotations in [10_000, 100_000] {
+        let table = sample_annotation_table(num_annotations);
+        group.throughput(Throughput::Bytes(table.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("serial", num_annotations),
+            &table,
+            |b, table| {
+                b.iter(|| {
+                    serde_json::from_slice::<Vec<SampleAnnotation>>(black_box(table))
+                        .unwrap()
+                        .len()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parallel", num_annotations),
+            &table,
+            |b, table| {
+                b.iter(|| {
+                    parse_json_array::<SampleAnnotation>(black_box(table), num_threads)
+                        .unwrap()
+                        .len()
+                })
+            },
+        );
+        // Elements are split on the current thread before being deserialized in parallel.
+        group.bench_with_input(
+            BenchmarkId::new("split", num_annotations),
+            &table,
+            |b, table| b.iter(|| split_json_array(black_box(table)).unwrap().len()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_json);
+criterion_main!(benches);
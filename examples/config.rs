@@ -11,7 +11,7 @@ struct Args {
     scenario: String,
 }
 
-type Result<T> = std::result::Result<T, Box<dyn Error>>;
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
 
 fn main() -> Result<()> {
     let Args { scenario } = Args::parse();
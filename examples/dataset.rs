@@ -17,7 +17,7 @@ struct Args {
     data_root: PathBuf,
 }
 
-type Result<T> = std::result::Result<T, Box<dyn Error>>;
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
 
 fn main() -> Result<()> {
     let log_dir_name = format!(
@@ -40,7 +40,7 @@ fn main() -> Result<()> {
 
     for i in 0..num_frames {
         let gt = get_current_frame(
-            &frame_ground_truths.as_ref(),
+            frame_ground_truths.as_ref(),
             &frame_ground_truths[i].timestamp,
         )
         .unwrap();
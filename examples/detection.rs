@@ -1,6 +1,7 @@
 use clap::Parser;
-use perception_eval::{config::PerceptionEvaluationConfig, manager::PerceptionEvaluationManager};
-use std::error::Error;
+use perception_eval::{
+    config::PerceptionEvaluationConfig, manager::PerceptionEvaluationManager, Result,
+};
 
 #[derive(Parser)]
 struct Args {
@@ -12,8 +13,6 @@ struct Args {
     scenario: String,
 }
 
-type Result<T> = std::result::Result<T, Box<dyn Error>>;
-
 fn main() -> Result<()> {
     let Args { scenario } = Args::parse();
 
@@ -26,8 +25,8 @@ fn main() -> Result<()> {
 
     let mut manager = PerceptionEvaluationManager::from(&config)?;
 
-    let mut frames = manager.frame_ground_truths.clone();
-    for (_, frame) in frames.iter_mut().enumerate() {
+    let mut frames = manager.frame_ground_truths().to_vec();
+    for frame in frames.iter_mut() {
         let frame_ground_truth = manager.get_frame_ground_truth(&frame.timestamp);
         match frame_ground_truth {
             Some(frame_gt) => manager.add_frame_result(&frame.objects, &frame_gt)?,
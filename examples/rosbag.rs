@@ -0,0 +1,59 @@
+use clap::Parser;
+use perception_eval::{
+    config::PerceptionEvaluationConfig, manager::PerceptionEvaluationManager, rosbag::RosbagReader,
+    Result,
+};
+
+#[derive(Parser)]
+struct Args {
+    #[clap(
+        short = 's',
+        long = "scenario",
+        default_value = "tests/config/perception.yaml"
+    )]
+    scenario: String,
+    /// Directory of the rosbag2 recorded in the SQLite format, or its `.db3` file.
+    #[clap(short = 'b', long = "bag")]
+    bag: String,
+    #[clap(
+        short = 't',
+        long = "topic",
+        default_value = "/perception/object_recognition/detection/objects"
+    )]
+    topic: String,
+}
+
+fn main() -> Result<()> {
+    let Args {
+        scenario,
+        bag,
+        topic,
+    } = Args::parse();
+
+    let result_dir = &format!(
+        "./work_dir/{}",
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    );
+
+    let config = PerceptionEvaluationConfig::from(&scenario, result_dir, false)?;
+
+    let mut manager = PerceptionEvaluationManager::from(&config)?;
+
+    let reader = RosbagReader::open(&bag)?;
+    let frames = reader.read_detected_objects(&topic)?;
+    println!(">>>Read {} messages of {}", frames.len(), topic);
+
+    for frame in frames.iter() {
+        let frame_ground_truth = manager.get_frame_ground_truth(&frame.timestamp);
+        match frame_ground_truth {
+            Some(frame_gt) => manager.add_frame_result(&frame.objects, &frame_gt)?,
+            None => continue,
+        }
+    }
+
+    println!(">>>Start getting metrics score");
+    let score = manager.get_metrics_score()?;
+    println!("{}", score);
+
+    Ok(())
+}
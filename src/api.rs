@@ -0,0 +1,20 @@
+pub use crate::{
+    config::{
+        schema::{ConfigParams, Scenario, ScenarioBuilder},
+        ConfigError, ConfigResult, FilterParams, MetricsParams, PerceptionEvaluationConfig,
+    },
+    dataset::{load_dataset, DatasetResult, FrameGroundTruth},
+    error::{Error, Result},
+    evaluation_task::EvaluationTask,
+    frame_id::FrameID,
+    label::{Label, LabelConverter},
+    manager::PerceptionEvaluationManager,
+    matching::{MatchingError, MatchingMode, MatchingResult},
+    metrics::{
+        error::{MetricsError, MetricsResult},
+        score::MetricsScore,
+    },
+    object::object3d::DynamicObject,
+    quickstart::{quickstart, Quickstart},
+    result::{frame::PerceptionFrameResult, object::PerceptionResult},
+};
@@ -0,0 +1,329 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Error as IoError, Write},
+    path::Path,
+};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use thiserror::Error as ThisError;
+
+use crate::{
+    dataset::timeline::FrameLabel,
+    label::Label,
+    matching::{get_matching_method, MatchingMode, PlaneDistanceConfig},
+    object::object3d::DynamicObject,
+    result::object::PerceptionResult,
+    threshold::get_label_threshold,
+};
+
+pub type AuditResult<T> = Result<T, AuditError>;
+
+/// Represents errors that occur while writing or reading audit logs.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum AuditError {
+    #[error("I/O error: {0}")]
+    IoError(#[from] IoError),
+    #[error("corrupted record: {0}")]
+    CorruptedRecord(String),
+}
+
+/// Represents the decision made for a pair of objects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuditDecision {
+    /// The pair was considered, but not assigned.
+    Unassigned,
+    /// The pair was assigned and determined as TP.
+    TruePositive,
+    /// The pair was assigned and determined as FP, or there was no GT to be paired.
+    FalsePositive,
+}
+
+/// A record of one matching decision.
+///
+/// * `timestamp`           - Timestamp of the frame.
+/// * `estimated_uuid`      - Uuid of the estimated object.
+/// * `ground_truth_uuid`   - Uuid of the GT object. None, if there was no GT to be paired.
+/// * `label`               - Name of label of the estimated object.
+/// * `matching_mode`       - Matching mode to determine TP or FP.
+/// * `assignment_score`    - Score used to assign the pair, which is center distance.
+/// * `matching_score`      - Score of `matching_mode`, only for assigned pairs.
+/// * `threshold`           - Threshold of `matching_mode`.
+/// * `decision`            - Decision of the pair.
+/// * `frame`               - Human-readable label of the frame, if available.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: NaiveDateTime,
+    pub estimated_uuid: Option<String>,
+    pub ground_truth_uuid: Option<String>,
+    pub label: String,
+    pub matching_mode: MatchingMode,
+    pub assignment_score: Option<f64>,
+    pub matching_score: Option<f64>,
+    pub threshold: Option<f64>,
+    pub decision: AuditDecision,
+    #[serde(default)]
+    pub frame: Option<FrameLabel>,
+}
+
+/// Returns audit records of every pair considered at one frame.
+///
+/// * `timestamp`               - Timestamp of the frame.
+/// * `estimated_objects`       - List of estimated objects considered for matching.
+/// * `ground_truth_objects`    - List of GT objects considered for matching.
+/// * `results`                 - List of PerceptionResult at the frame.
+/// * `target_labels`           - List of Label instances.
+/// * `matching_mode`           - MatchingMode to determine TP or FP.
+/// * `matching_thresholds`     - List of matching thresholds.
+/// * `plane_distance_config`   - Configuration used for `MatchingMode::PlaneDistance`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn get_audit_records(
+    timestamp: &NaiveDateTime,
+    estimated_objects: &[&DynamicObject],
+    ground_truth_objects: &[&DynamicObject],
+    results: &[PerceptionResult],
+    target_labels: &[Label],
+    matching_mode: &MatchingMode,
+    matching_thresholds: &[f64],
+    plane_distance_config: &PlaneDistanceConfig,
+) -> Vec<AuditRecord> {
+    let assignment_method =
+        get_matching_method(&MatchingMode::CenterDistance, plane_distance_config);
+    let matching_method = get_matching_method(matching_mode, plane_distance_config);
+
+    let mut records = Vec::new();
+    for est in estimated_objects {
+        let threshold = get_label_threshold(&est.label, target_labels, matching_thresholds);
+        let assigned_gt = results
+            .iter()
+            .find(|result| result.estimated_object == **est)
+            .and_then(|result| result.ground_truth_object.as_ref());
+
+        let mut is_paired = false;
+        for gt in ground_truth_objects
+            .iter()
+            .filter(|gt| gt.label == est.label)
+        {
+            is_paired = true;
+            let assignment_score = assignment_method.calculate_matching_score(est, gt);
+            let (matching_score, decision) = match assigned_gt {
+                Some(assigned) if assigned == *gt => {
+                    let score = matching_method.calculate_matching_score(est, gt);
+                    let is_tp = threshold
+                        .map(|t| matching_method.is_better_than(est, gt, &t))
+                        .unwrap_or(false);
+                    let decision = if is_tp {
+                        AuditDecision::TruePositive
+                    } else {
+                        AuditDecision::FalsePositive
+                    };
+                    (Some(score), decision)
+                }
+                _ => (None, AuditDecision::Unassigned),
+            };
+            records.push(AuditRecord {
+                timestamp: timestamp.to_owned(),
+                estimated_uuid: est.uuid.to_owned(),
+                ground_truth_uuid: gt.uuid.to_owned(),
+                label: est.label_name(),
+                matching_mode: matching_mode.to_owned(),
+                assignment_score: Some(assignment_score),
+                matching_score,
+                threshold,
+                decision,
+                frame: None,
+            });
+        }
+
+        if !is_paired {
+            records.push(AuditRecord {
+                timestamp: timestamp.to_owned(),
+                estimated_uuid: est.uuid.to_owned(),
+                ground_truth_uuid: None,
+                label: est.label_name(),
+                matching_mode: matching_mode.to_owned(),
+                assignment_score: None,
+                matching_score: None,
+                threshold,
+                decision: AuditDecision::FalsePositive,
+                frame: None,
+            });
+        }
+    }
+    records
+}
+
+/// Append audit records to the zstd compressed log.
+///
+/// Records are written as one zstd frame per call, so that the log can be appended
+/// frame by frame during evaluation.
+///
+/// * `path`    - Path of the log file.
+/// * `records` - List of audit records.
+pub fn write_audit_records<P>(path: P, records: &[AuditRecord]) -> AuditResult<()>
+where
+    P: AsRef<Path>,
+{
+    if let Some(parent) = path.as_ref().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut encoder = zstd::Encoder::new(file, 0)?;
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|err| AuditError::CorruptedRecord(format!("{:?}", err)))?;
+        writeln!(encoder, "{}", line)?;
+    }
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Reader of the zstd compressed audit log.
+///
+/// # Examples
+/// ```no_run
+/// use perception_eval::audit::{AuditReader, AuditResult};
+///
+/// fn main() -> AuditResult<()> {
+///     let reader = AuditReader::open("work_dir/audit.log.zst")?;
+///     for record in reader {
+///         println!("{:?}", record?);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct AuditReader {
+    lines: std::io::Lines<BufReader<zstd::Decoder<'static, BufReader<File>>>>,
+}
+
+impl AuditReader {
+    /// Open the audit log.
+    ///
+    /// * `path`    - Path of the log file.
+    pub fn open<P>(path: P) -> AuditResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let decoder = zstd::Decoder::new(File::open(path)?)?;
+        Ok(Self {
+            lines: BufReader::new(decoder).lines(),
+        })
+    }
+}
+
+impl Iterator for AuditReader {
+    type Item = AuditResult<AuditRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        let record = line.map_err(AuditError::from).and_then(|line| {
+            serde_json::from_str(&line)
+                .map_err(|err| AuditError::CorruptedRecord(format!("{:?}", err)))
+        });
+        Some(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_audit_records, write_audit_records, AuditDecision, AuditReader, AuditRecord};
+    use crate::{
+        diagnostics::Diagnostic,
+        frame_id::FrameID,
+        label::Label,
+        manager::PerceptionEvaluationManager,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        quickstart,
+        result::object::get_perception_results,
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_audit_records_round_trip() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let estimation = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        let ground_truth = DynamicObject {
+            uuid: Some("100".to_string()),
+            ..estimation.clone()
+        };
+
+        let estimations = vec![&estimation];
+        let ground_truths = vec![&ground_truth];
+        let results = get_perception_results(&estimations, &ground_truths);
+        let records = get_audit_records(
+            &timestamp,
+            &estimations,
+            &ground_truths,
+            &results,
+            &[Label::Car],
+            &MatchingMode::PlaneDistance,
+            &[1.0],
+            &PlaneDistanceConfig::default(),
+        );
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].decision, AuditDecision::TruePositive);
+
+        let path = std::env::temp_dir().join(format!("audit_{}.log.zst", std::process::id()));
+        write_audit_records(&path, &records).unwrap();
+        write_audit_records(&path, &records).unwrap();
+        let loaded = AuditReader::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<AuditRecord>, _>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, [records.clone(), records].concat());
+    }
+
+    #[test]
+    fn test_audit_log_of_manager() {
+        let quickstart = quickstart();
+        let dir = std::env::temp_dir().join(format!("audit_manager_{}", std::process::id()));
+        let evaluate = |audit_log| {
+            let mut config = quickstart.config.clone();
+            config.audit_log = Some(audit_log);
+            let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+                &config,
+                quickstart.frame_ground_truths.clone(),
+            );
+            let frame = &quickstart.frame_ground_truths[0];
+            manager
+                .add_frame_result(&quickstart.estimations[0], frame)
+                .unwrap();
+            manager.diagnostics()
+        };
+
+        // Decisions are recorded for every evaluated matching mode.
+        let path = dir.join("audit.log.zst");
+        assert!(evaluate(path.clone()).is_empty());
+        let records = AuditReader::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<AuditRecord>, _>>()
+            .unwrap();
+        for mode in [MatchingMode::CenterDistance, MatchingMode::PlaneDistance] {
+            assert!(records.iter().any(|record| record.matching_mode == mode));
+        }
+
+        // The failure to write is reported, while the evaluation continues.
+        let diagnostics = evaluate(path.join("audit.log.zst"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(
+            diagnostics.as_slice(),
+            [Diagnostic::WriteFailure { .. }]
+        ));
+    }
+}
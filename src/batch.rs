@@ -0,0 +1,478 @@
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+    io::{BufWriter, Error as IoError, Write},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error as ThisError;
+
+use crate::{
+    config::PerceptionEvaluationConfig, dataset::FrameGroundTruth, fault::create_file,
+    manager::PerceptionEvaluationManager, object::object3d::DynamicObject,
+};
+
+pub type BatchResult<T> = Result<T, BatchError>;
+
+/// Represents errors that occur while running scenarios in batch.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum BatchError {
+    #[error("I/O error: {0}")]
+    IoError(#[from] IoError),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+}
+
+/// Outcome of one scenario in batch.
+///
+/// * `scenario`        - Path of the scenario.
+/// * `name`            - Name of the scenario, which is the file stem.
+/// * `result_dir`      - Directory path where productions of the scenario are saved.
+/// * `num_frames`      - Number of evaluated frames.
+/// * `mean_ap`         - mAP for each matching mode.
+/// * `elapsed_seconds` - Elapsed time to run the scenario in [s].
+/// * `error`           - Error message if the scenario failed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioOutcome {
+    pub scenario: PathBuf,
+    pub name: String,
+    pub result_dir: PathBuf,
+    pub num_frames: usize,
+    pub mean_ap: BTreeMap<String, f64>,
+    pub elapsed_seconds: f64,
+    pub error: Option<String>,
+}
+
+impl ScenarioOutcome {
+    /// Returns whether the scenario has been evaluated without error.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Machine-readable result of batch run, ordered by scenario paths.
+///
+/// * `num_succeeded`   - Number of scenarios evaluated without error.
+/// * `num_failed`      - Number of scenarios failed.
+/// * `outcomes`        - Outcome of each scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub num_succeeded: usize,
+    pub num_failed: usize,
+    pub outcomes: Vec<ScenarioOutcome>,
+}
+
+impl BatchSummary {
+    /// Save summary as JSON.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save<P>(&self, path: P) -> BatchResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut writer = BufWriter::new(create_file(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Save summary as HTML, which links to the result directory of each scenario.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save_html<P>(&self, path: P) -> BatchResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        let modes = self
+            .outcomes
+            .iter()
+            .flat_map(|outcome| outcome.mean_ap.keys())
+            .collect::<BTreeSet<_>>();
+
+        let mut writer = BufWriter::new(create_file(path)?);
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(
+            writer,
+            "<html><head><meta charset=\"utf-8\"><title>Batch Summary</title></head><body>"
+        )?;
+        writeln!(
+            writer,
+            "<h1>Batch Summary</h1><p>succeeded: {}, failed: {}</p>",
+            self.num_succeeded, self.num_failed
+        )?;
+        write!(
+            writer,
+            "<table border=\"1\"><tr><th>Scenario</th><th>Status</th><th>Frames</th>"
+        )?;
+        for mode in &modes {
+            write!(writer, "<th>mAP ({})</th>", escape_html(mode))?;
+        }
+        writeln!(writer, "<th>Time [s]</th><th>Error</th></tr>")?;
+        for outcome in &self.outcomes {
+            write!(
+                writer,
+                "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td>",
+                escape_html(&outcome.result_dir.to_string_lossy()),
+                escape_html(&outcome.name),
+                if outcome.is_success() { "OK" } else { "FAILED" },
+                outcome.num_frames
+            )?;
+            for mode in &modes {
+                match outcome.mean_ap.get(*mode) {
+                    Some(value) => write!(writer, "<td>{:.4}</td>", value)?,
+                    None => write!(writer, "<td>-</td>")?,
+                }
+            }
+            writeln!(
+                writer,
+                "<td>{:.2}</td><td>{}</td></tr>",
+                outcome.elapsed_seconds,
+                escape_html(outcome.error.as_deref().unwrap_or(""))
+            )?;
+        }
+        writeln!(writer, "</table></body></html>")?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Runner to evaluate every scenario `.yaml` in a directory.
+///
+/// Each scenario is evaluated with its own result directory `result_dir/<name>`,
+/// and a failure of one scenario, including a panic, does not stop the others.
+/// After all scenarios are evaluated, `batch_summary.json` and `index.html` are saved under `result_dir`.
+///
+/// * `scenario_dir`    - Directory path containing scenario `.yaml` files.
+/// * `result_dir`      - Root directory path to save productions.
+/// * `num_threads`     - Number of scenarios evaluated in parallel.
+/// * `load_raw_data`   - Indicates whether to load raw data, which is pointcloud or image.
+///
+/// # Examples
+/// ```no_run
+/// use perception_eval::batch::BatchRunner;
+///
+/// let runner = BatchRunner::new("scenarios", "work_dir/batch")
+///     .with_threads(4)
+///     .unwrap();
+/// // Evaluate GTs as estimations.
+/// let summary = runner.run(|frame| frame.objects.clone()).unwrap();
+/// println!("succeeded: {}, failed: {}", summary.num_succeeded, summary.num_failed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchRunner {
+    scenario_dir: PathBuf,
+    result_dir: PathBuf,
+    num_threads: usize,
+    load_raw_data: bool,
+}
+
+impl BatchRunner {
+    /// Construct `BatchRunner`, which evaluates scenarios sequentially.
+    ///
+    /// * `scenario_dir`    - Directory path containing scenario `.yaml` files.
+    /// * `result_dir`      - Root directory path to save productions.
+    pub fn new<P, Q>(scenario_dir: P, result_dir: Q) -> Self
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        Self {
+            scenario_dir: scenario_dir.as_ref().to_path_buf(),
+            result_dir: result_dir.as_ref().to_path_buf(),
+            num_threads: 1,
+            load_raw_data: false,
+        }
+    }
+
+    /// Set the number of scenarios evaluated in parallel.
+    ///
+    /// * `num_threads` - Number of threads, which must be positive.
+    pub fn with_threads(mut self, num_threads: usize) -> BatchResult<Self> {
+        if num_threads == 0 {
+            Err(BatchError::InvalidParameter(
+                "num_threads must be positive".to_string(),
+            ))?
+        }
+        self.num_threads = num_threads;
+        Ok(self)
+    }
+
+    /// Set whether to load raw data, which is pointcloud or image.
+    ///
+    /// * `load_raw_data`   - Indicates whether to load raw data.
+    pub fn with_raw_data(mut self, load_raw_data: bool) -> Self {
+        self.load_raw_data = load_raw_data;
+        self
+    }
+
+    /// Returns paths of scenario `.yaml` files in the directory, which are sorted.
+    ///
+    /// Productions of each scenario are saved in the directory named after its file stem,
+    /// so scenarios with the same file stem, e.g. `foo.yaml` and `foo.yml`, are rejected.
+    pub fn scenarios(&self) -> BatchResult<Vec<PathBuf>> {
+        let mut paths = std::fs::read_dir(&self.scenario_dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|ext| ext == "yaml" || ext == "yml")
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut names = BTreeMap::new();
+        for path in &paths {
+            if let Some(other) = names.insert(scenario_name(path), path) {
+                Err(BatchError::InvalidParameter(format!(
+                    "scenarios {:?} and {:?} have the same name",
+                    other, path
+                )))?
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Evaluate all scenarios and returns the summary, which is also saved under `result_dir`.
+    ///
+    /// * `estimate`    - Function returning estimated objects for each frame of GTs.
+    pub fn run<F>(&self, estimate: F) -> BatchResult<BatchSummary>
+    where
+        F: Fn(&FrameGroundTruth) -> Vec<DynamicObject> + Sync,
+    {
+        let scenarios = self.scenarios()?;
+        std::fs::create_dir_all(&self.result_dir)?;
+
+        let next_index = AtomicUsize::new(0);
+        let outcomes = Mutex::new(Vec::with_capacity(scenarios.len()));
+        std::thread::scope(|scope| {
+            for _ in 0..self.num_threads.min(scenarios.len()) {
+                scope.spawn(|| loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let scenario = match scenarios.get(index) {
+                        Some(scenario) => scenario,
+                        None => break,
+                    };
+                    let outcome = self.run_scenario(scenario, &estimate);
+                    outcomes.lock().unwrap().push((index, outcome));
+                });
+            }
+        });
+
+        let mut outcomes = outcomes.into_inner().unwrap();
+        outcomes.sort_by_key(|(index, _)| *index);
+        let outcomes = outcomes
+            .into_iter()
+            .map(|(_, outcome)| outcome)
+            .collect::<Vec<_>>();
+        let num_succeeded = outcomes
+            .iter()
+            .filter(|outcome| outcome.is_success())
+            .count();
+        let summary = BatchSummary {
+            num_succeeded,
+            num_failed: outcomes.len() - num_succeeded,
+            outcomes,
+        };
+        summary.save(self.result_dir.join("batch_summary.json"))?;
+        summary.save_html(self.result_dir.join("index.html"))?;
+        Ok(summary)
+    }
+
+    /// Evaluate one scenario and returns its outcome.
+    ///
+    /// * `scenario`    - Path of the scenario.
+    /// * `estimate`    - Function returning estimated objects for each frame of GTs.
+    fn run_scenario<F>(&self, scenario: &Path, estimate: &F) -> ScenarioOutcome
+    where
+        F: Fn(&FrameGroundTruth) -> Vec<DynamicObject>,
+    {
+        let name = scenario_name(scenario);
+        let result_dir = self.result_dir.join(&name);
+
+        let start = Instant::now();
+        let mut outcome = ScenarioOutcome {
+            scenario: scenario.to_path_buf(),
+            name,
+            result_dir: result_dir.to_owned(),
+            num_frames: 0,
+            mean_ap: BTreeMap::new(),
+            elapsed_seconds: 0.0,
+            error: None,
+        };
+        // A panic of the scenario, such as in `estimate`, is recorded as a failure not to stop the others.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.evaluate(scenario, &result_dir, estimate, &mut outcome)
+        }));
+        let error = match result {
+            Ok(Ok(())) => None,
+            Ok(Err(err)) => Some(err.to_string()),
+            Err(payload) => Some(format!("panicked: {}", panic_message(payload.as_ref()))),
+        };
+        if let Some(error) = error {
+            log::error!("failed to evaluate {:?}: {}", scenario, error);
+            outcome.error = Some(error);
+        }
+        outcome.elapsed_seconds = start.elapsed().as_secs_f64();
+        outcome
+    }
+
+    /// Run the evaluation pipeline of the scenario and fill metrics of the outcome.
+    fn evaluate<F>(
+        &self,
+        scenario: &Path,
+        result_dir: &Path,
+        estimate: &F,
+        outcome: &mut ScenarioOutcome,
+    ) -> crate::Result<()>
+    where
+        F: Fn(&FrameGroundTruth) -> Vec<DynamicObject>,
+    {
+        let config = PerceptionEvaluationConfig::from(
+            &scenario.to_string_lossy(),
+            &result_dir.to_string_lossy(),
+            self.load_raw_data,
+        )?;
+        let mut manager = PerceptionEvaluationManager::from(&config)?;
+        let frames = manager.frame_ground_truths().to_vec();
+        for frame in &frames {
+            manager.add_frame_result(&estimate(frame), frame)?;
+        }
+        let score = manager.get_metrics_score()?;
+
+        outcome.num_frames = manager.num_frame_results();
+        score.matching_modes().iter().for_each(|mode| {
+            if let Some(value) = score.mean_ap(mode) {
+                outcome.mean_ap.insert(format!("{:?}", mode), value);
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Returns the name of the scenario, which is the file stem.
+///
+/// * `scenario`    - Path of the scenario.
+fn scenario_name(scenario: &Path) -> String {
+    scenario
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the message of the panic payload, which is either `&str` or `String` in most cases.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Returns the text with HTML special characters escaped.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchRunner, BatchSummary};
+    use crate::object::object3d::DynamicObject;
+
+    #[test]
+    fn test_batch_runner() {
+        let scenario_dir = std::env::temp_dir().join("perception_eval_test_batch_runner");
+        std::fs::create_dir_all(&scenario_dir).unwrap();
+        std::fs::copy(
+            "tests/config/perception.yaml",
+            scenario_dir.join("a_perception.yaml"),
+        )
+        .unwrap();
+        std::fs::write(scenario_dir.join("b_corrupted.yaml"), "ScenarioName: [").unwrap();
+        std::fs::write(scenario_dir.join("readme.txt"), "not a scenario").unwrap();
+
+        let runner = BatchRunner::new(&scenario_dir, "work_dir/batch")
+            .with_threads(2)
+            .unwrap();
+        let summary = runner.run(|frame| frame.objects.clone()).unwrap();
+        std::fs::remove_dir_all(&scenario_dir).unwrap();
+
+        assert_eq!(summary.outcomes.len(), 2);
+        assert_eq!(summary.num_succeeded, 1);
+        assert_eq!(summary.num_failed, 1);
+
+        let succeeded = &summary.outcomes[0];
+        assert_eq!(succeeded.name, "a_perception");
+        assert!(succeeded.num_frames > 0);
+        assert!(succeeded.mean_ap.contains_key("CenterDistance"));
+        assert!(succeeded.mean_ap.contains_key("PlaneDistance"));
+        assert!(summary.outcomes[1].error.is_some());
+
+        assert!(runner.result_dir.join("batch_summary.json").exists());
+        assert!(runner.result_dir.join("index.html").exists());
+        assert!(BatchRunner::new(".", ".").with_threads(0).is_err());
+    }
+
+    #[test]
+    fn test_batch_runner_panic() {
+        let scenario_dir = std::env::temp_dir().join("perception_eval_test_batch_runner_panic");
+        std::fs::create_dir_all(&scenario_dir).unwrap();
+        for name in ["a_perception.yaml", "b_perception.yaml"] {
+            std::fs::copy("tests/config/perception.yaml", scenario_dir.join(name)).unwrap();
+        }
+
+        let runner = BatchRunner::new(&scenario_dir, "work_dir/batch_panic")
+            .with_threads(2)
+            .unwrap();
+        let summary = runner
+            .run(|_| -> Vec<DynamicObject> { panic!("estimation failed") })
+            .unwrap();
+        std::fs::remove_dir_all(&scenario_dir).unwrap();
+
+        // Every scenario fails without tearing down the others.
+        assert_eq!(summary.outcomes.len(), 2);
+        assert_eq!(summary.num_failed, 2);
+        assert!(summary
+            .outcomes
+            .iter()
+            .all(|outcome| outcome.error.as_deref() == Some("panicked: estimation failed")));
+
+        // The saved summary is flushed completely.
+        let saved = std::fs::read_to_string(runner.result_dir.join("batch_summary.json")).unwrap();
+        let saved = serde_json::from_str::<BatchSummary>(&saved).unwrap();
+        assert_eq!(saved.num_failed, 2);
+    }
+
+    #[test]
+    fn test_batch_runner_duplicated_names() {
+        let scenario_dir = std::env::temp_dir().join("perception_eval_test_batch_runner_names");
+        std::fs::create_dir_all(&scenario_dir).unwrap();
+        for name in ["perception.yaml", "perception.yml"] {
+            std::fs::copy("tests/config/perception.yaml", scenario_dir.join(name)).unwrap();
+        }
+
+        let runner = BatchRunner::new(&scenario_dir, "work_dir/batch_names");
+        let scenarios = runner.scenarios();
+        let summary = runner.run(|frame| frame.objects.clone());
+        std::fs::remove_dir_all(&scenario_dir).unwrap();
+
+        // Both scenarios would be saved under `perception`.
+        assert!(scenarios.is_err());
+        assert!(summary.is_err());
+    }
+}
@@ -1,42 +1,67 @@
 pub mod schema;
 
+use crate::dataset::{maneuver::ManeuverParams, FrameGroundTruth};
 use crate::evaluation_task::EvaluationTask;
-use crate::label::{convert_labels, LabelConverter, LabelResult};
-use crate::utils::logger::configure_logger;
-use crate::{frame_id::FrameID, label::Label};
+use crate::label::{EvaluationLabels, LabelConverter, LabelRemap, LabelResult};
+use crate::matching::{MatchingMode, PlaneDistanceConfig};
+use crate::metrics::breakdown::BreakdownAxis;
+use crate::utils::{logger::configure_logger, projection::CameraModel};
+use crate::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+use chrono::{Duration, NaiveDateTime};
 use itertools::Itertools;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
+    fmt::{Display, Formatter, Result as FormatResult},
     fs::File,
-    io::{BufReader, Error as IoError},
+    io::{BufRead, BufReader, Error as IoError},
     path::{Path, PathBuf},
+    sync::Arc,
     vec,
 };
 use thiserror::Error as ThisError;
 
 use self::schema::Scenario;
 
+/// Default maximum BEV center distance in [m] used for velocity matching.
+pub const DEFAULT_VELOCITY_POSITION_GATE: f64 = 4.0;
+
 pub type ConfigResult<T> = Result<T, ConfigError>;
 
 /// Represents errors that is associated with `PerceptionEvaluationConfig`.
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum ConfigError {
-    #[error("internal error")]
-    InternalError,
-    #[error("corrupted file: {0}")]
-    CorruptedFile(String),
+    #[error("corrupted file: {path}")]
+    CorruptedFile {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+    #[error("failed to serialize: {path}")]
+    SerializeFailed {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
     #[error("I/O error: {0}")]
     IoError(#[from] IoError),
-    #[error("value error: {0}")]
-    ValueError(f64),
-    #[error("key error: {0}")]
-    KeyError(String),
+    #[error("{name} is out of range: {value}")]
+    OutOfRange { name: &'static str, value: f64 },
+    #[error("{name} must be at least 1")]
+    ZeroCount { name: &'static str },
+    #[error("conflicted parameters: {0}")]
+    ConflictedParameters(String),
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
 }
 
 /// Configuration of entire evaluation settings.
+///
+/// Configurations are constructed from scenarios, or by `PerceptionEvaluationConfig::new()` in code.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct PerceptionEvaluationConfig {
-    pub version: String,
+    pub versions: Vec<String>,
     pub dataset_path: PathBuf,
     pub evaluation_task: EvaluationTask,
     pub frame_id: FrameID,
@@ -46,9 +71,158 @@ pub struct PerceptionEvaluationConfig {
     pub filter_params: FilterParams,
     pub metrics_params: MetricsParams,
     pub load_raw_data: bool,
+    pub audit_log: Option<PathBuf>,
+    pub timestamp_correction: Option<TimestampCorrection>,
+    pub target_sample_tokens: Option<Vec<String>>,
+    pub estimation_label_remap: Option<LabelRemap>,
+    pub export_pr_curves: bool,
+    pub frame_id_policy: FrameIdPolicy,
+    pub latency_compensation: Option<LatencyCompensation>,
 }
 
 impl PerceptionEvaluationConfig {
+    /// Construct `PerceptionEvaluationConfig` instance, where optional settings are disabled.
+    /// Unlike `PerceptionEvaluationConfig::from()`, the logger is not configured.
+    ///
+    /// * `versions`        - List of dataset versions to be loaded.
+    /// * `dataset_path`    - Root directory path of the dataset.
+    /// * `evaluation_task` - Task of evaluation.
+    /// * `frame_id`        - FrameID that objects are with respect to.
+    /// * `result_dir`      - Root directory path to save productions such as log.
+    /// * `filter_params`   - FilterParams instance.
+    /// * `metrics_params`  - MetricsParams instance.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{
+    ///     config::{FilterParams, MetricsParams, PerceptionEvaluationConfig},
+    ///     evaluation_task::EvaluationTask,
+    ///     frame_id::FrameID,
+    /// };
+    ///
+    /// let target_labels = vec!["car", "pedestrian"];
+    /// let config = PerceptionEvaluationConfig::new(
+    ///     vec!["annotation".to_string()],
+    ///     "./tests/sample_data",
+    ///     EvaluationTask::Detection,
+    ///     FrameID::BaseLink,
+    ///     "./work_dir/config_new",
+    ///     FilterParams::new(&target_labels, 100.0, 100.0, None, None).unwrap(),
+    ///     MetricsParams::new(&target_labels, 1.0, 1.0, 0.5, 0.5).unwrap(),
+    /// )
+    /// .with_export_pr_curves(true);
+    ///
+    /// assert_eq!(config.log_dir.to_str(), Some("./work_dir/config_new/log"));
+    /// assert!(config.export_pr_curves);
+    /// ```
+    pub fn new<P, R>(
+        versions: Vec<String>,
+        dataset_path: P,
+        evaluation_task: EvaluationTask,
+        frame_id: FrameID,
+        result_dir: R,
+        filter_params: FilterParams,
+        metrics_params: MetricsParams,
+    ) -> Self
+    where
+        P: AsRef<Path>,
+        R: AsRef<Path>,
+    {
+        let result_dir = result_dir.as_ref();
+        Self {
+            versions,
+            dataset_path: dataset_path.as_ref().to_owned(),
+            evaluation_task,
+            frame_id,
+            result_dir: result_dir.to_owned(),
+            log_dir: result_dir.join("log"),
+            viz_dir: result_dir.join("visualize"),
+            filter_params,
+            metrics_params,
+            load_raw_data: false,
+            audit_log: None,
+            timestamp_correction: None,
+            target_sample_tokens: None,
+            estimation_label_remap: None,
+            export_pr_curves: false,
+            frame_id_policy: FrameIdPolicy::default(),
+            latency_compensation: None,
+        }
+    }
+
+    /// Set whether to load raw data, which is pointcloud or image.
+    ///
+    /// * `load_raw_data`   - Indicates whether to load raw data.
+    pub fn with_load_raw_data(mut self, load_raw_data: bool) -> Self {
+        self.load_raw_data = load_raw_data;
+        self
+    }
+
+    /// Set the path of the audit log. If None, audit records are not written.
+    ///
+    /// * `audit_log`   - Path of the audit log.
+    pub fn with_audit_log(mut self, audit_log: Option<PathBuf>) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
+    /// Set the correction of estimation timestamps. If None, timestamps are not corrected.
+    ///
+    /// * `timestamp_correction`    - TimestampCorrection instance.
+    pub fn with_timestamp_correction(
+        mut self,
+        timestamp_correction: Option<TimestampCorrection>,
+    ) -> Self {
+        self.timestamp_correction = timestamp_correction;
+        self
+    }
+
+    /// Set the tokens of samples to be evaluated. If None, all samples are evaluated.
+    ///
+    /// * `target_sample_tokens`    - List of sample tokens.
+    pub fn with_target_sample_tokens(mut self, target_sample_tokens: Option<Vec<String>>) -> Self {
+        self.target_sample_tokens = target_sample_tokens;
+        self
+    }
+
+    /// Set the remap of estimation labels. If None, labels are not remapped.
+    ///
+    /// * `estimation_label_remap`  - LabelRemap instance.
+    pub fn with_estimation_label_remap(
+        mut self,
+        estimation_label_remap: Option<LabelRemap>,
+    ) -> Self {
+        self.estimation_label_remap = estimation_label_remap;
+        self
+    }
+
+    /// Set whether to export PR curves.
+    ///
+    /// * `export_pr_curves`    - Indicates whether to export PR curves.
+    pub fn with_export_pr_curves(mut self, export_pr_curves: bool) -> Self {
+        self.export_pr_curves = export_pr_curves;
+        self
+    }
+
+    /// Set the policy for estimations whose frame ID differs from the configured one.
+    ///
+    /// * `frame_id_policy` - FrameIdPolicy instance.
+    pub fn with_frame_id_policy(mut self, frame_id_policy: FrameIdPolicy) -> Self {
+        self.frame_id_policy = frame_id_policy;
+        self
+    }
+
+    /// Set the latency compensation of estimations. If None, estimations are not compensated.
+    ///
+    /// * `latency_compensation`    - LatencyCompensation instance.
+    pub fn with_latency_compensation(
+        mut self,
+        latency_compensation: Option<LatencyCompensation>,
+    ) -> Self {
+        self.latency_compensation = latency_compensation;
+        self
+    }
+
     /// Construct `PerceptionEvaluationConfig` instance.
     ///
     /// * `scenario`        - Scenario path of `.yaml`.
@@ -60,7 +234,7 @@ impl PerceptionEvaluationConfig {
     /// use perception_eval::config::PerceptionEvaluationConfig;
     /// use std::error::Error;
     ///
-    /// type Result<T> = std::result::Result<T, Box<dyn Error>>;
+    /// type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
     ///
     /// fn main() -> Result<()> {
     ///     let scenario = "tests/config/perception.yaml";
@@ -74,54 +248,303 @@ impl PerceptionEvaluationConfig {
     /// }
     /// ```
     pub fn from(scenario: &str, result_dir: &str, load_raw_data: bool) -> ConfigResult<Self> {
-        let scenario: Scenario = load_yaml(scenario)?;
+        let scenario = Scenario::load(scenario)?;
+        Self::from_scenario(scenario, result_dir, load_raw_data)
+    }
+
+    /// Construct `PerceptionEvaluationConfig` instance from the scenario constructed in code.
+    ///
+    /// * `scenario`        - Scenario instance.
+    /// * `result_dir`      - Root directory path to save productions such as log.
+    /// * `load_raw_data`   - Indicates whether to load raw data, which is pointcloud or image.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::{schema::Scenario, DetectionRange, PerceptionEvaluationConfig};
+    /// use std::{collections::HashMap, error::Error};
+    ///
+    /// type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut scenario = Scenario::load("tests/config/perception.yaml")?;
+    ///     scenario.evaluation.config.params.max_x_position = 50.0;
+    ///     scenario.evaluation.config.params.detection_ranges = Some(HashMap::from([(
+    ///         "car".to_string(),
+    ///         DetectionRange::new(80.0, 40.0, 50.0, 50.0),
+    ///     )]));
+    ///     let result_dir = &format!(
+    ///         "./work_dir/{}",
+    ///         chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ///     );
+    ///
+    ///     let mut invalid = scenario.clone();
+    ///     let config = PerceptionEvaluationConfig::from_scenario(scenario.clone(), result_dir, false)?;
+    ///
+    ///     // Detection ranges must be given to target labels.
+    ///     invalid.evaluation.config.params.detection_ranges = Some(HashMap::from([(
+    ///         "bus".to_string(),
+    ///         DetectionRange::new(80.0, 40.0, 50.0, 50.0),
+    ///     )]));
+    ///     assert!(PerceptionEvaluationConfig::from_scenario(invalid, result_dir, false).is_err());
+    ///
+    ///     // Versions of datasets under the same root are merged.
+    ///     let mut merged = scenario.clone();
+    ///     let mut dataset = merged.evaluation.datasets[0].clone();
+    ///     dataset.values_mut().for_each(|value| value.version = "test".to_string());
+    ///     merged.evaluation.datasets.push(dataset.clone());
+    ///     let config = PerceptionEvaluationConfig::from_scenario(merged.clone(), result_dir, false)?;
+    ///     assert_eq!(config.versions, vec!["annotation", "test"]);
+    ///
+    ///     // Datasets under different roots cannot be merged.
+    ///     let mut other_root = merged.clone();
+    ///     other_root.evaluation.datasets[1] = HashMap::from([(
+    ///         "tests/other_data".to_string(),
+    ///         dataset.into_values().next().unwrap(),
+    ///     )]);
+    ///     assert!(PerceptionEvaluationConfig::from_scenario(other_root, result_dir, false).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_scenario(
+        scenario: Scenario,
+        result_dir: &str,
+        load_raw_data: bool,
+    ) -> ConfigResult<Self> {
         let datasets = scenario.evaluation.datasets;
+        if datasets.is_empty() {
+            return Err(ConfigError::InvalidParameter(
+                "scenario must contain at least one dataset".to_string(),
+            ));
+        }
 
-        // TODO
-        let mut dataset_path = PathBuf::new();
-        let mut version = String::new();
-        for (key, value) in &datasets[0] {
-            dataset_path.set_file_name(key);
-            version = value.version.clone();
+        // Versions of all datasets are merged, so that they must be under the same root.
+        let mut dataset_path: Option<PathBuf> = None;
+        let mut versions = Vec::new();
+        for (key, value) in datasets.iter().flatten() {
+            match &dataset_path {
+                Some(path) if path != Path::new(key) => {
+                    Err(ConfigError::ConflictedParameters(format!(
+                        "datasets must be under the same root: {:?} and {}",
+                        path, key
+                    )))?
+                }
+                _ => dataset_path = Some(PathBuf::from(key)),
+            }
+            if versions.contains(&value.version) {
+                Err(ConfigError::InvalidParameter(format!(
+                    "datasets: duplicated version {}",
+                    value.version
+                )))?
+            }
+            versions.push(value.version.clone());
         }
+        let dataset_path = dataset_path.unwrap_or_default();
 
         let params = scenario.evaluation.config.params;
         let target_labels = params.target_labels.iter().map(|s| s as &str).collect_vec();
-        let filter_params = FilterParams::new(
-            &target_labels,
+        let converter = LabelConverter::new("autoware")
+            .map_err(|err| ConfigError::InvalidParameter(format!("target_labels: {}", err)))?;
+        let labels = EvaluationLabels::from_converter(&target_labels, &converter);
+        let occlusion_filter = match (params.max_occlusion_ratio, params.occlusion_num_rays) {
+            (None, Some(_)) => Err(ConfigError::InvalidParameter(
+                "occlusion_num_rays requires max_occlusion_ratio".to_string(),
+            ))?,
+            (Some(ratio), _) if ratio <= 0.0 || 1.0 < ratio => Err(ConfigError::OutOfRange {
+                name: "max_occlusion_ratio",
+                value: ratio,
+            })?,
+            (_, Some(0)) => Err(ConfigError::ZeroCount {
+                name: "occlusion_num_rays",
+            })?,
+            (Some(ratio), num_rays) => Some(
+                OcclusionFilter::new(ratio)
+                    .with_num_rays(num_rays.unwrap_or(OcclusionFilter::DEFAULT_NUM_RAYS)),
+            ),
+            (None, None) => None,
+        };
+        let mut filter_params = FilterParams::from_labels(
+            &labels,
             params.max_x_position,
             params.max_y_position,
             params.min_point_number,
             params.target_uuids,
         )
-        .unwrap(); // TODO
-        let metrics_params = MetricsParams::new(
-            &target_labels,
+        .with_occlusion_filter(occlusion_filter);
+        if let Some(detection_ranges) = params.detection_ranges {
+            for (name, range) in detection_ranges {
+                let label = converter
+                    .try_convert(&name)
+                    .filter(|label| labels.index_of(label).is_some())
+                    .ok_or_else(|| {
+                        ConfigError::InvalidParameter(format!(
+                            "detection_ranges: {} is not a target label",
+                            name
+                        ))
+                    })?;
+                if let Some((direction, value)) = range.find_negative() {
+                    Err(ConfigError::InvalidParameter(format!(
+                        "detection_ranges: {} of {} must not be negative: {}",
+                        direction, name, value
+                    )))?
+                }
+                filter_params = filter_params.with_detection_range(&label, range);
+            }
+        }
+        let cameras = params
+            .camera_models
+            .into_iter()
+            .map(|camera| CameraModel {
+                frame_id: camera.frame_id,
+                intrinsic: camera.intrinsic,
+                image_size: camera.image_size,
+                translation: camera.translation,
+                rotation: camera.rotation,
+            })
+            .collect::<Vec<_>>();
+        let filter_params = match params.fp_suppression_policy {
+            FpSuppressionPolicy::Disabled => filter_params,
+            policy
+                if cameras
+                    .iter()
+                    .any(|camera| camera.frame_id == params.frame_id) =>
+            {
+                filter_params
+                    .with_cross_camera_filter(Some(CrossCameraFilter::new(policy, cameras)))
+            }
+            policy => Err(ConfigError::InvalidParameter(format!(
+                "fp_suppression_policy {:?} requires camera_models including frame_id {}",
+                policy, params.frame_id
+            )))?,
+        };
+        let tracking_warmup = match (params.warmup_frames, params.warmup_seconds) {
+            (Some(_), Some(_)) => Err(ConfigError::ConflictedParameters(
+                "only one of warmup_frames and warmup_seconds can be specified".to_string(),
+            ))?,
+            (Some(frames), None) => Some(TrackingWarmup::Frames(frames)),
+            (None, Some(seconds)) if seconds < 0.0 => Err(ConfigError::OutOfRange {
+                name: "warmup_seconds",
+                value: seconds,
+            })?,
+            (None, Some(seconds)) => Some(TrackingWarmup::Seconds(seconds)),
+            (None, None) => None,
+        };
+        let metrics_params = MetricsParams::from_labels(
+            &labels,
             params.center_distance_threshold,
             params.plane_distance_threshold,
             params.iou_2d_threshold,
             params.iou_3d_threshold,
         )
-        .unwrap(); // TODO
+        .with_tracking_warmup(tracking_warmup)
+        .with_max_missed_frames(params.max_missed_frames);
+        let metrics_params = match params.plane_distance_config {
+            Some(plane_distance_config) => {
+                plane_distance_config.validate().map_err(|_| {
+                    ConfigError::InvalidParameter(format!(
+                        "plane_distance_config: {:?}",
+                        plane_distance_config
+                    ))
+                })?;
+                metrics_params.with_plane_distance_config(plane_distance_config)
+            }
+            None => metrics_params,
+        };
+        let metrics_params = metrics_params
+            .with_candidate_pruning(params.candidate_pruning)
+            .with_ap_interpolation(params.ap_interpolation)
+            .with_maneuver_breakdown(params.maneuver_breakdown.then(ManeuverParams::default))
+            .with_alignment_analysis(params.alignment_analysis);
+        let metrics_params = match (params.velocity_threshold, params.velocity_position_gate) {
+            (None, Some(_)) => Err(ConfigError::InvalidParameter(
+                "velocity_position_gate requires velocity_threshold".to_string(),
+            ))?,
+            (Some(threshold), _) if threshold <= 0.0 => Err(ConfigError::OutOfRange {
+                name: "velocity_threshold",
+                value: threshold,
+            })?,
+            (_, Some(gate)) if gate <= 0.0 => Err(ConfigError::OutOfRange {
+                name: "velocity_position_gate",
+                value: gate,
+            })?,
+            (Some(threshold), gate) => metrics_params
+                .with_velocity_matching(threshold, gate.unwrap_or(DEFAULT_VELOCITY_POSITION_GATE)),
+            (None, None) => metrics_params,
+        };
+        let metrics_params = match params.deterministic_threads {
+            Some(0) => Err(ConfigError::ZeroCount {
+                name: "deterministic_threads",
+            })?,
+            num_threads => metrics_params.with_deterministic_parallel(num_threads),
+        };
+        let breakdown_axes = params
+            .breakdown_axes
+            .iter()
+            .map(|axis| {
+                axis.build()
+                    .map_err(|err| ConfigError::InvalidParameter(err.to_string()))
+            })
+            .collect::<ConfigResult<Vec<_>>>()?;
+        let metrics_params = metrics_params.with_breakdown_axes(breakdown_axes);
 
         let result_dir = Path::new(result_dir);
-        let log_dir = result_dir.join("log");
-        let viz_dir = result_dir.join("visualize");
+        let audit_log = params.audit_trail.then(|| result_dir.join("audit.log.zst"));
+        let timestamp_correction = match (params.timestamp_offset, params.auto_timestamp_offset) {
+            (Some(_), true) => Err(ConfigError::ConflictedParameters(
+                "only one of timestamp_offset and auto_timestamp_offset can be specified"
+                    .to_string(),
+            ))?,
+            (Some(offset), false) => Some(TimestampCorrection::Offset(offset)),
+            (None, true) => Some(TimestampCorrection::Auto),
+            (None, false) => None,
+        };
+        let latency_compensation = params
+            .latency
+            .map(|latency| LatencyCompensation::new(latency, params.latency_compensation))
+            .transpose()?;
+        let target_sample_tokens = match (
+            params.target_sample_tokens,
+            params.target_sample_tokens_file,
+        ) {
+            (Some(_), Some(_)) => Err(ConfigError::ConflictedParameters(
+                "only one of target_sample_tokens and target_sample_tokens_file can be specified"
+                    .to_string(),
+            ))?,
+            (Some(tokens), None) => Some(tokens),
+            (None, Some(path)) => Some(load_sample_tokens(path)?),
+            (None, None) => None,
+        };
 
-        configure_logger(&log_dir, log::Level::Debug).unwrap();
+        let estimation_label_remap = params
+            .estimation_label_remap
+            .map(|pairs| LabelRemap::from_names(&pairs, "autoware"))
+            .transpose()
+            .map_err(|err| {
+                ConfigError::InvalidParameter(format!("estimation_label_remap: {}", err))
+            })?;
 
-        let config = Self {
-            version,
+        let config = Self::new(
+            versions,
             dataset_path,
-            evaluation_task: params.evaluation_task,
-            frame_id: params.frame_id,
-            result_dir: result_dir.to_owned(),
-            log_dir,
-            viz_dir,
+            params.evaluation_task,
+            params.frame_id,
+            result_dir,
             filter_params,
             metrics_params,
-            load_raw_data,
-        };
+        )
+        .with_load_raw_data(load_raw_data)
+        .with_audit_log(audit_log)
+        .with_timestamp_correction(timestamp_correction)
+        .with_target_sample_tokens(target_sample_tokens)
+        .with_estimation_label_remap(estimation_label_remap)
+        .with_export_pr_curves(params.export_pr_curves)
+        .with_frame_id_policy(params.frame_id_policy)
+        .with_latency_compensation(latency_compensation);
+
+        // The logger is global, so only the first configuration in the process takes effect.
+        if let Err(err) = configure_logger(&config.log_dir, log::Level::Debug) {
+            log::warn!("logger is not configured for {:?}: {}", config.log_dir, err);
+        }
+
         Ok(config)
     }
 }
@@ -130,10 +553,11 @@ impl PerceptionEvaluationConfig {
 #[derive(Debug, Clone)]
 pub struct FilterParams {
     pub(crate) target_labels: Vec<Label>,
-    pub(crate) max_x_positions: Vec<f64>,
-    pub(crate) max_y_positions: Vec<f64>,
+    pub(crate) detection_ranges: Vec<DetectionRange>,
     pub(crate) min_point_numbers: Option<Vec<usize>>,
     pub(crate) target_uuids: Option<Vec<String>>,
+    pub(crate) occlusion_filter: Option<OcclusionFilter>,
+    pub(crate) cross_camera_filter: Option<CrossCameraFilter>,
 }
 
 impl FilterParams {
@@ -158,21 +582,631 @@ impl FilterParams {
         min_point_number: Option<usize>,
         target_uuids: Option<Vec<String>>,
     ) -> LabelResult<Self> {
-        let label_converter = LabelConverter::new("autoware")?;
-        let target_labels = convert_labels(target_labels, &label_converter)?;
+        let labels = EvaluationLabels::new(target_labels, "autoware")?;
+        Ok(Self::from_labels(
+            &labels,
+            max_x_position,
+            max_y_position,
+            min_point_number,
+            target_uuids,
+        ))
+    }
+
+    /// Construct `FilterParams` from shared `EvaluationLabels`.
+    ///
+    /// * `labels`              - Target labels of evaluation.
+    /// * `max_x_position`      - Maximum absolute value in the x direction from ego that can be evaluated.
+    /// * `max_y_position`      - Maximum absolute value in the y direction from ego that can be evaluated.
+    /// * `min_point_number`    - Minimum number of points that GT that can be evaluated should contain.
+    /// * `target_uuids`        - List of uuids that GT that can be evaluated should have.
+    pub fn from_labels(
+        labels: &EvaluationLabels,
+        max_x_position: f64,
+        max_y_position: f64,
+        min_point_number: Option<usize>,
+        target_uuids: Option<Vec<String>>,
+    ) -> Self {
+        let target_labels = labels.labels().to_owned();
         let num_target_labels = target_labels.len();
-        let max_x_positions = vec![max_x_position; num_target_labels];
-        let max_y_positions = vec![max_y_position; num_target_labels];
+        let detection_ranges =
+            vec![DetectionRange::symmetric(max_x_position, max_y_position); num_target_labels];
         let min_point_numbers = min_point_number.map(|num_pt| vec![num_pt; num_target_labels]);
 
-        let ret = Self {
+        Self {
             target_labels,
-            max_x_positions,
-            max_y_positions,
+            detection_ranges,
             min_point_numbers,
             target_uuids,
+            occlusion_filter: None,
+            cross_camera_filter: None,
+        }
+    }
+
+    /// Set the asymmetric detection range of the label in `base_link`, which replaces the symmetric one
+    /// given by `max_x_position` and `max_y_position`.
+    /// If the label is not a target, the range is ignored.
+    ///
+    /// * `label`   - Label instance.
+    /// * `range`   - DetectionRange instance.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{config::{DetectionRange, FilterParams}, label::Label};
+    ///
+    /// let params = FilterParams::new(&vec!["Car", "Pedestrian", "Bus"], 100.0, 100.0, None, None)
+    ///     .unwrap()
+    ///     .with_detection_range(&Label::Car, DetectionRange::new(120.0, 60.0, 50.0, 50.0));
+    /// ```
+    pub fn with_detection_range(mut self, label: &Label, range: DetectionRange) -> Self {
+        match self.target_labels.iter().position(|l| l == label) {
+            Some(index) => self.detection_ranges[index] = range,
+            None => log::warn!(
+                "{} is not a target label, ignore its detection range",
+                label
+            ),
+        }
+        self
+    }
+
+    /// Set the filter to ignore heavily occluded GTs for FN counting.
+    ///
+    /// * `occlusion_filter`    - OcclusionFilter instance. If None, no GT is ignored by occlusion.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::{FilterParams, OcclusionFilter};
+    ///
+    /// let params = FilterParams::new(&vec!["Car", "Pedestrian", "Bus"], 100.0, 100.0, None, None)
+    ///     .unwrap()
+    ///     .with_occlusion_filter(Some(OcclusionFilter::new(0.9)));
+    /// ```
+    pub fn with_occlusion_filter(mut self, occlusion_filter: Option<OcclusionFilter>) -> Self {
+        self.occlusion_filter = occlusion_filter;
+        self
+    }
+
+    /// Set the filter to suppress FPs of camera frames based on visibility in cameras.
+    ///
+    /// * `cross_camera_filter` - CrossCameraFilter instance. If None, no FP is suppressed.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{
+    ///     config::{CrossCameraFilter, FilterParams, FpSuppressionPolicy},
+    ///     frame_id::FrameID,
+    ///     utils::projection::CameraModel,
+    /// };
+    ///
+    /// let camera = CameraModel {
+    ///     frame_id: FrameID::CamFront,
+    ///     intrinsic: [[1000.0, 0.0, 800.0], [0.0, 1000.0, 450.0], [0.0, 0.0, 1.0]],
+    ///     image_size: [1600, 900],
+    ///     translation: [0.0, 0.0, 1.5],
+    ///     rotation: [0.5, -0.5, 0.5, -0.5],
+    /// };
+    /// let params = FilterParams::new(&vec!["Car", "Pedestrian", "Bus"], 100.0, 100.0, None, None)
+    ///     .unwrap()
+    ///     .with_cross_camera_filter(Some(CrossCameraFilter::new(
+    ///         FpSuppressionPolicy::VisibleInOtherCameras,
+    ///         vec![camera],
+    ///     )));
+    /// ```
+    pub fn with_cross_camera_filter(
+        mut self,
+        cross_camera_filter: Option<CrossCameraFilter>,
+    ) -> Self {
+        self.cross_camera_filter = cross_camera_filter;
+        self
+    }
+}
+
+/// Maximum distances in each direction from ego in `base_link` in which objects are evaluated,
+/// since sensors usually see farther forward than backward.
+///
+/// Objects are kept if `-backward < x < forward` and `-right < y < left`.
+///
+/// * `forward`     - Maximum distance in the +x direction in [m].
+/// * `backward`    - Maximum distance in the -x direction in [m].
+/// * `left`        - Maximum distance in the +y direction in [m].
+/// * `right`       - Maximum distance in the -y direction in [m].
+///
+/// # Examples
+/// ```
+/// use perception_eval::config::DetectionRange;
+///
+/// let range = DetectionRange::new(100.0, 50.0, 30.0, 30.0);
+///
+/// assert!(range.contains(&[80.0, 0.0, 0.0]));
+/// assert!(!range.contains(&[-80.0, 0.0, 0.0]));
+/// assert!(!range.contains(&[0.0, -30.0, 0.0]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DetectionRange {
+    pub forward: f64,
+    pub backward: f64,
+    pub left: f64,
+    pub right: f64,
+}
+
+impl DetectionRange {
+    /// Construct `DetectionRange`.
+    ///
+    /// * `forward`     - Maximum distance in the +x direction in [m].
+    /// * `backward`    - Maximum distance in the -x direction in [m].
+    /// * `left`        - Maximum distance in the +y direction in [m].
+    /// * `right`       - Maximum distance in the -y direction in [m].
+    pub fn new(forward: f64, backward: f64, left: f64, right: f64) -> Self {
+        Self {
+            forward,
+            backward,
+            left,
+            right,
+        }
+    }
+
+    /// Construct `DetectionRange` symmetric with respect to ego,
+    /// which is equivalent to thresholds on absolute values of x and y.
+    ///
+    /// * `max_x_position`  - Maximum absolute value in the x direction in [m].
+    /// * `max_y_position`  - Maximum absolute value in the y direction in [m].
+    pub fn symmetric(max_x_position: f64, max_y_position: f64) -> Self {
+        Self::new(
+            max_x_position,
+            max_x_position,
+            max_y_position,
+            max_y_position,
+        )
+    }
+
+    /// Returns whether the position is inside the range.
+    ///
+    /// * `position`    - Position in the coord system of ego.
+    pub fn contains(&self, position: &[f64; 3]) -> bool {
+        -self.backward < position[0]
+            && position[0] < self.forward
+            && -self.right < position[1]
+            && position[1] < self.left
+    }
+
+    /// Returns the name and value of the first negative distance, if any.
+    fn find_negative(&self) -> Option<(&'static str, f64)> {
+        [
+            ("forward", self.forward),
+            ("backward", self.backward),
+            ("left", self.left),
+            ("right", self.right),
+        ]
+        .into_iter()
+        .find(|(_, value)| *value < 0.0)
+    }
+}
+
+/// Policy to suppress FPs of camera frames based on visibility of estimations.
+///
+/// Only estimations not paired with any GT are suppressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FpSuppressionPolicy {
+    /// No FP is suppressed.
+    #[default]
+    Disabled,
+    /// Suppress FPs which are not visible in the current camera.
+    OutOfView,
+    /// Suppress FPs which are not visible in the current camera but visible in any other camera.
+    VisibleInOtherCameras,
+}
+
+/// Policy for objects whose frame_id differs from the configured one, which are checked
+/// before matching in `PerceptionEvaluationManager::add_frame_result()`.
+///
+/// # Examples
+/// ```
+/// use perception_eval::{
+///     config::FrameIdPolicy, frame_id::FrameID, manager::PerceptionEvaluationManager,
+/// };
+///
+/// let quickstart = perception_eval::quickstart();
+/// let frame = &quickstart.frame_ground_truths[0];
+/// // Estimations published in `map` by mistake, while the ego vehicle is at the origin.
+/// let estimations = quickstart.estimations[0]
+///     .iter()
+///     .map(|object| {
+///         let mut object = object.clone();
+///         object.frame_id = FrameID::Map;
+///         object
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let mut config = quickstart.config.clone();
+/// config.frame_id_policy = FrameIdPolicy::Strict;
+/// let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+///     &config,
+///     quickstart.frame_ground_truths.clone(),
+/// );
+/// assert!(manager.add_frame_result(&estimations, frame).is_err());
+///
+/// config.frame_id_policy = FrameIdPolicy::Transform;
+/// let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+///     &config,
+///     quickstart.frame_ground_truths.clone(),
+/// );
+/// manager.add_frame_result(&estimations, frame).unwrap();
+/// assert!(manager.frame_results[0]
+///     .results()
+///     .iter()
+///     .all(|result| result.estimated_object.frame_id == FrameID::BaseLink));
+/// // The ego pose is kept in the frame result for later transforms.
+/// let stored = manager.frame_results[0].frame_ground_truth();
+/// assert_eq!(stored.ego_translation, frame.ego_translation);
+/// assert_eq!(stored.ego_rotation, frame.ego_rotation);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameIdPolicy {
+    /// No object is checked, so that objects in other frames are evaluated as they are.
+    #[default]
+    Disabled,
+    /// Adding the frame result fails if any object is in another frame.
+    Strict,
+    /// Objects are transformed between `base_link` and `map` with the ego pose of the frame,
+    /// and adding the frame result fails if they cannot be transformed.
+    Transform,
+}
+
+/// Filter to suppress FPs of camera frames with visibility checked by projecting estimations onto images.
+///
+/// Estimations are regarded as in the coord system of the camera of the frame,
+/// and the visibility is checked at their centers.
+///
+/// * `policy`  - Policy to suppress FPs.
+/// * `cameras` - Camera models including the camera of the frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossCameraFilter {
+    pub policy: FpSuppressionPolicy,
+    pub cameras: Vec<CameraModel>,
+}
+
+impl CrossCameraFilter {
+    /// Construct `CrossCameraFilter`.
+    ///
+    /// * `policy`  - Policy to suppress FPs.
+    /// * `cameras` - Camera models including the camera of the frame.
+    pub fn new(policy: FpSuppressionPolicy, cameras: Vec<CameraModel>) -> Self {
+        Self { policy, cameras }
+    }
+
+    /// Returns whether FP of the estimation at the camera frame should be suppressed.
+    /// If the camera of the frame is not found, returns false.
+    ///
+    /// * `frame_id`    - FrameID of the camera where the estimation is.
+    /// * `position`    - Position of the estimation in the camera coord system.
+    pub fn is_suppressed(&self, frame_id: &FrameID, position: &[f64; 3]) -> bool {
+        let current = match self
+            .cameras
+            .iter()
+            .find(|camera| camera.frame_id == *frame_id)
+        {
+            Some(camera) => camera,
+            None => return false,
+        };
+        match self.policy {
+            FpSuppressionPolicy::Disabled => false,
+            _ if current.is_visible(position) => false,
+            FpSuppressionPolicy::OutOfView => true,
+            FpSuppressionPolicy::VisibleInOtherCameras => {
+                let position = current.to_base_link(position);
+                self.cameras
+                    .iter()
+                    .filter(|camera| camera.frame_id != *frame_id)
+                    .any(|camera| camera.is_visible_from_base_link(&position))
+            }
+        }
+    }
+}
+
+/// Filter to ignore GTs shadowed by closer GTs seen from the sensor origin.
+///
+/// Rays are cast in BEV from the origin to the visible span of each GT, and the ratio of rays
+/// blocked by the footprint of closer GTs is regarded as the occlusion ratio.
+/// GTs whose ratio is not less than `max_occlusion_ratio` are ignored for FN counting,
+/// unless any estimation is paired with them.
+///
+/// * `max_occlusion_ratio` - Occlusion ratio in `(0, 1]` from which GTs are ignored.
+/// * `num_rays`            - Number of rays cast to each GT.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcclusionFilter {
+    pub max_occlusion_ratio: f64,
+    pub num_rays: usize,
+}
+
+impl OcclusionFilter {
+    /// Default number of rays cast to each GT.
+    pub const DEFAULT_NUM_RAYS: usize = 16;
+
+    /// Construct `OcclusionFilter` with the default number of rays.
+    ///
+    /// * `max_occlusion_ratio` - Occlusion ratio in `(0, 1]` from which GTs are ignored.
+    pub fn new(max_occlusion_ratio: f64) -> Self {
+        Self {
+            max_occlusion_ratio,
+            num_rays: Self::DEFAULT_NUM_RAYS,
+        }
+    }
+
+    /// Set the number of rays cast to each GT.
+    ///
+    /// * `num_rays`    - Number of rays, which must be positive.
+    pub fn with_num_rays(mut self, num_rays: usize) -> Self {
+        self.num_rays = num_rays;
+        self
+    }
+}
+
+/// Warm-up period at the beginning of each scene, which is excluded from tracking metrics.
+/// Scenes are split where the dataset version changes or the interval between frames exceeds 10 s.
+///
+/// Frames in the warm-up period are still evaluated frame by frame,
+/// so that the state continuity of tracks is kept.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackingWarmup {
+    /// Number of frames from the first frame of the scene.
+    Frames(usize),
+    /// Elapsed time from the first frame of the scene in [s].
+    Seconds(f64),
+}
+
+impl TrackingWarmup {
+    /// Returns whether the frame is in the warm-up period.
+    ///
+    /// * `frame_index` - Index of the frame from the first frame of the scene.
+    /// * `elapsed`     - Elapsed time from the first frame of the scene in [s].
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::TrackingWarmup;
+    ///
+    /// let warmup = TrackingWarmup::Frames(3);
+    /// assert!(warmup.is_warming_up(2, 0.2));
+    /// assert!(!warmup.is_warming_up(3, 0.3));
+    ///
+    /// let warmup = TrackingWarmup::Seconds(0.5);
+    /// assert!(warmup.is_warming_up(4, 0.4));
+    /// assert!(!warmup.is_warming_up(5, 0.5));
+    /// ```
+    pub fn is_warming_up(&self, frame_index: usize, elapsed: f64) -> bool {
+        match self {
+            TrackingWarmup::Frames(frames) => frame_index < *frames,
+            TrackingWarmup::Seconds(seconds) => elapsed < *seconds,
+        }
+    }
+}
+
+/// Correction of timestamps of estimations whose clock differs from the dataset,
+/// e.g. ROS sim time starting from zero while the dataset uses the unix epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimestampCorrection {
+    /// Offset in [s] added to timestamps of estimations.
+    Offset(f64),
+    /// Detect the offset from the first estimation timestamp.
+    Auto,
+}
+
+impl TimestampCorrection {
+    /// Margin in [s] around the dataset time span, within which timestamps are regarded as synchronized.
+    const AUTO_MARGIN: i64 = 1;
+
+    /// Returns the offset to be added to timestamps of estimations.
+    ///
+    /// For `TimestampCorrection::Auto`, if the timestamp is within the time span of GT frames,
+    /// no offset is applied. Otherwise, the offset aligns the timestamp with the first GT frame.
+    ///
+    /// * `timestamp`           - Timestamp of the first estimation.
+    /// * `frame_ground_truths` - List of FrameGroundTruth.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{DateTime, Duration};
+    /// use perception_eval::{config::TimestampCorrection, dataset::FrameGroundTruth};
+    ///
+    /// let frame = FrameGroundTruth::new(
+    ///     DateTime::from_timestamp(1_600_000_000, 0).unwrap().naive_utc(),
+    ///     Vec::new(),
+    /// );
+    /// let sim_time = DateTime::from_timestamp(10, 0).unwrap().naive_utc();
+    ///
+    /// let offset = TimestampCorrection::Auto.resolve(&sim_time, &[frame.clone()]);
+    /// assert_eq!(sim_time + offset, frame.timestamp);
+    ///
+    /// let offset = TimestampCorrection::Offset(0.5).resolve(&sim_time, &[frame]);
+    /// assert_eq!(offset, Duration::milliseconds(500));
+    /// ```
+    pub fn resolve(
+        &self,
+        timestamp: &NaiveDateTime,
+        frame_ground_truths: &[FrameGroundTruth],
+    ) -> Duration {
+        match self {
+            TimestampCorrection::Offset(seconds) => {
+                Duration::microseconds((seconds * 1e6).round() as i64)
+            }
+            TimestampCorrection::Auto => {
+                let first = frame_ground_truths
+                    .iter()
+                    .map(|frame| frame.timestamp)
+                    .min();
+                let last = frame_ground_truths
+                    .iter()
+                    .map(|frame| frame.timestamp)
+                    .max();
+                match (first, last) {
+                    (Some(first), Some(last)) => {
+                        let margin = Duration::seconds(Self::AUTO_MARGIN);
+                        if first - margin <= *timestamp && *timestamp <= last + margin {
+                            Duration::zero()
+                        } else {
+                            first - *timestamp
+                        }
+                    }
+                    _ => Duration::zero(),
+                }
+            }
+        }
+    }
+}
+
+/// Method to compensate the latency of the perception pipeline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyCompensationMethod {
+    /// Timestamps of estimations are shifted back by the latency,
+    /// and estimations are evaluated against the GT frame at the sensor time.
+    #[default]
+    ShiftEstimations,
+    /// GTs at the output time are propagated back to the sensor time with their velocities.
+    /// GTs without velocity are kept as they are.
+    PropagateGroundTruths,
+}
+
+/// Compensation of the known latency of the perception pipeline, where estimations are stamped
+/// at the output time while they represent objects at the sensor time.
+///
+/// * `latency` - Latency from the sensor time to the output time in [s].
+/// * `method`  - Method to compensate the latency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyCompensation {
+    pub latency: f64,
+    pub method: LatencyCompensationMethod,
+}
+
+impl LatencyCompensation {
+    /// Construct `LatencyCompensation`.
+    ///
+    /// * `latency` - Latency from the sensor time to the output time in [s], which must be non-negative.
+    /// * `method`  - Method to compensate the latency.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::{LatencyCompensation, LatencyCompensationMethod};
+    ///
+    /// let compensation =
+    ///     LatencyCompensation::new(0.1, LatencyCompensationMethod::ShiftEstimations).unwrap();
+    /// assert_eq!(compensation.duration().num_milliseconds(), 100);
+    ///
+    /// assert!(LatencyCompensation::new(-0.1, LatencyCompensationMethod::ShiftEstimations).is_err());
+    /// ```
+    pub fn new(latency: f64, method: LatencyCompensationMethod) -> ConfigResult<Self> {
+        if !(latency.is_finite() && 0.0 <= latency) {
+            Err(ConfigError::OutOfRange {
+                name: "latency",
+                value: latency,
+            })?
+        }
+        Ok(Self { latency, method })
+    }
+
+    /// Returns the latency as `Duration`.
+    pub fn duration(&self) -> Duration {
+        Duration::microseconds((self.latency * 1e6).round() as i64)
+    }
+
+    /// Returns the object propagated back by the latency with its velocity.
+    /// If the object has no velocity, returns the object as it is.
+    ///
+    /// * `object`  - Object at the output time.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     config::{LatencyCompensation, LatencyCompensationMethod},
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [10.0, 0.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 4.0, 1.5],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_velocity([10.0, -5.0, 0.0])
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
+    ///
+    /// let compensation =
+    ///     LatencyCompensation::new(0.2, LatencyCompensationMethod::PropagateGroundTruths).unwrap();
+    /// let propagated = compensation.propagate(&object);
+    /// assert_eq!(propagated.position, [8.0, 1.0, 0.0]);
+    /// assert_eq!(propagated.timestamp, object.timestamp - compensation.duration());
+    /// ```
+    pub fn propagate(&self, object: &DynamicObject) -> DynamicObject {
+        let position = match &object.velocity {
+            Some(velocity) => [0, 1, 2].map(|i| object.position[i] - velocity[i] * self.latency),
+            None => object.position,
+        };
+        DynamicObject {
+            timestamp: object.timestamp - self.duration(),
+            position,
+            ..object.to_owned()
+        }
+    }
+}
+
+/// Method to interpolate the precision-recall curve in AP calculation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ApInterpolation {
+    /// Area under the envelope of the curve at every recall point.
+    #[default]
+    #[serde(rename = "all_point")]
+    AllPoint,
+    /// Mean of interpolated precisions at 11 recall points, 0.0, 0.1, ..., 1.0.
+    #[serde(rename = "11_point")]
+    Point11,
+    /// Mean of interpolated precisions at 40 recall points, 1/40, 2/40, ..., 1.0, which is used in KITTI.
+    #[serde(rename = "40_point")]
+    Point40,
+    /// Mean of interpolated precisions at 101 recall points, 0.00, 0.01, ..., 1.00, which is used in COCO.
+    #[serde(rename = "101_point")]
+    Point101,
+}
+
+impl ApInterpolation {
+    /// Returns recall points to sample interpolated precisions.
+    /// For `ApInterpolation::AllPoint`, returns None.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::ApInterpolation;
+    ///
+    /// assert_eq!(ApInterpolation::AllPoint.recall_points(), None);
+    /// assert_eq!(ApInterpolation::Point11.recall_points().unwrap().len(), 11);
+    /// assert_eq!(ApInterpolation::Point40.recall_points().unwrap()[0], 1.0 / 40.0);
+    /// ```
+    pub fn recall_points(&self) -> Option<Vec<f64>> {
+        let (start, num) = match self {
+            ApInterpolation::AllPoint => return None,
+            ApInterpolation::Point11 => (0, 11),
+            ApInterpolation::Point40 => (1, 40),
+            ApInterpolation::Point101 => (0, 101),
+        };
+        let num_steps = (num - 1 + start) as f64;
+        Some((start..start + num).map(|i| i as f64 / num_steps).collect())
+    }
+}
+
+impl Display for ApInterpolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let name = match self {
+            ApInterpolation::AllPoint => "all-point",
+            ApInterpolation::Point11 => "11-point",
+            ApInterpolation::Point40 => "40-point",
+            ApInterpolation::Point101 => "101-point",
         };
-        Ok(ret)
+        write!(f, "{}", name)
     }
 }
 
@@ -181,10 +1215,22 @@ impl FilterParams {
 #[derive(Debug, Clone)]
 pub struct MetricsParams {
     pub(crate) target_labels: Vec<Label>,
+    pub(crate) label_converter: LabelConverter<'static>,
     pub(crate) center_distance_thresholds: Vec<f64>,
     pub(crate) plane_distance_thresholds: Vec<f64>,
     pub(crate) iou2d_thresholds: Vec<f64>,
     pub(crate) iou3d_thresholds: Vec<f64>,
+    pub(crate) tracking_warmup: Option<TrackingWarmup>,
+    pub(crate) max_missed_frames: Option<usize>,
+    pub(crate) plane_distance_config: PlaneDistanceConfig,
+    pub(crate) candidate_pruning: bool,
+    pub(crate) ap_interpolation: ApInterpolation,
+    pub(crate) velocity_thresholds: Option<Vec<f64>>,
+    pub(crate) velocity_position_gate: f64,
+    pub(crate) maneuver_params: Option<ManeuverParams>,
+    pub(crate) alignment_analysis: bool,
+    pub(crate) deterministic_threads: Option<usize>,
+    pub(crate) breakdown_axes: Vec<Arc<dyn BreakdownAxis>>,
 }
 
 impl MetricsParams {
@@ -209,23 +1255,309 @@ impl MetricsParams {
         iou2d_threshold: f64,
         iou3d_threshold: f64,
     ) -> LabelResult<Self> {
-        let label_converter = LabelConverter::new("autoware")?;
-        let target_labels = convert_labels(target_labels, &label_converter)?;
+        let labels = EvaluationLabels::new(target_labels, "autoware")?;
+        Ok(Self::from_labels(
+            &labels,
+            center_distance_threshold,
+            plane_distance_threshold,
+            iou2d_threshold,
+            iou3d_threshold,
+        ))
+    }
+
+    /// Construct `MetricsParams` from shared `EvaluationLabels`.
+    ///
+    /// * `labels`                      - Target labels of evaluation.
+    /// * `center_distance_threshold`   - Center distance threshold.
+    /// * `plane_distance_threshold`    - Plane distance threshold.
+    /// * `iou2d_threshold`             - IoU2D threshold.
+    /// * `iou3d_threshold`             - IoU3D threshold.
+    pub fn from_labels(
+        labels: &EvaluationLabels,
+        center_distance_threshold: f64,
+        plane_distance_threshold: f64,
+        iou2d_threshold: f64,
+        iou3d_threshold: f64,
+    ) -> Self {
+        let target_labels = labels.labels().to_owned();
         let num_target_labels = target_labels.len();
         let center_distance_thresholds = vec![center_distance_threshold; num_target_labels];
         let plane_distance_thresholds = vec![plane_distance_threshold; num_target_labels];
         let iou2d_thresholds = vec![iou2d_threshold; num_target_labels];
         let iou3d_thresholds = vec![iou3d_threshold; num_target_labels];
 
-        let ret = Self {
+        Self {
             target_labels,
+            label_converter: labels.converter().to_owned(),
             center_distance_thresholds,
             plane_distance_thresholds,
             iou2d_thresholds,
             iou3d_thresholds,
-        };
-        Ok(ret)
+            tracking_warmup: None,
+            max_missed_frames: None,
+            plane_distance_config: PlaneDistanceConfig::default(),
+            candidate_pruning: false,
+            ap_interpolation: ApInterpolation::default(),
+            velocity_thresholds: None,
+            velocity_position_gate: DEFAULT_VELOCITY_POSITION_GATE,
+            maneuver_params: None,
+            alignment_analysis: false,
+            breakdown_axes: Vec::new(),
+            deterministic_threads: None,
+        }
+    }
+
+    /// Set the warm-up period excluded from tracking metrics.
+    ///
+    /// * `tracking_warmup` - Warm-up period. If None, all frames are accumulated.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::{MetricsParams, TrackingWarmup};
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_tracking_warmup(Some(TrackingWarmup::Frames(5)));
+    /// ```
+    pub fn with_tracking_warmup(mut self, tracking_warmup: Option<TrackingWarmup>) -> Self {
+        self.tracking_warmup = tracking_warmup;
+        self
+    }
+
+    /// Set the association memory of tracking metrics, that is the maximum number of consecutive
+    /// missed frames before a track is regarded as lost. Re-association after that is not counted as an ID switch.
+    ///
+    /// * `max_missed_frames`   - Maximum number of consecutive missed frames. If None, tracks are never lost.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::MetricsParams;
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_max_missed_frames(Some(1));
+    /// ```
+    pub fn with_max_missed_frames(mut self, max_missed_frames: Option<usize>) -> Self {
+        self.max_missed_frames = max_missed_frames;
+        self
+    }
+
+    /// Set the configuration of plane distance matching.
+    ///
+    /// * `plane_distance_config`   - Configuration of plane distance matching.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{
+    ///     config::MetricsParams,
+    ///     matching::{PlaneDistanceConfig, PointSelection},
+    /// };
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_plane_distance_config(
+    ///         PlaneDistanceConfig::new(PointSelection::NearestCorners, 4).unwrap(),
+    ///     );
+    /// ```
+    pub fn with_plane_distance_config(
+        mut self,
+        plane_distance_config: PlaneDistanceConfig,
+    ) -> Self {
+        self.plane_distance_config = plane_distance_config;
+        self
+    }
+
+    /// Set whether to score only candidate pairs that can be matched within the largest distance threshold.
+    ///
+    /// The radius of candidates is derived from distance thresholds of evaluated matching modes and
+    /// sizes of objects, and results are the same as scoring all pairs.
+    ///
+    /// * `candidate_pruning`   - Whether to prune candidate pairs.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::MetricsParams;
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_candidate_pruning(true);
+    /// ```
+    pub fn with_candidate_pruning(mut self, candidate_pruning: bool) -> Self {
+        self.candidate_pruning = candidate_pruning;
+        self
+    }
+
+    /// Set the method to interpolate the precision-recall curve in AP calculation.
+    ///
+    /// * `ap_interpolation`    - ApInterpolation instance.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::{ApInterpolation, MetricsParams};
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_ap_interpolation(ApInterpolation::Point40);
+    /// ```
+    pub fn with_ap_interpolation(mut self, ap_interpolation: ApInterpolation) -> Self {
+        self.ap_interpolation = ap_interpolation;
+        self
+    }
+
+    /// Enable the velocity matching, which is evaluated in addition to other matching modes.
+    ///
+    /// * `velocity_threshold`  - Threshold of BEV velocity error in [m/s].
+    /// * `position_gate`       - Maximum BEV center distance of matched objects in [m].
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::MetricsParams;
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_velocity_matching(1.0, 4.0);
+    /// ```
+    pub fn with_velocity_matching(mut self, velocity_threshold: f64, position_gate: f64) -> Self {
+        self.velocity_thresholds = Some(vec![velocity_threshold; self.target_labels.len()]);
+        self.velocity_position_gate = position_gate;
+        self
+    }
+
+    /// Set the thresholds to derive maneuvers of GTs, which enables the breakdown of recall conditioned on maneuvers.
+    ///
+    /// * `maneuver_params` - Thresholds to derive maneuvers. If None, the breakdown is disabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{config::MetricsParams, dataset::maneuver::ManeuverParams};
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_maneuver_breakdown(Some(ManeuverParams::default()));
+    /// ```
+    pub fn with_maneuver_breakdown(mut self, maneuver_params: Option<ManeuverParams>) -> Self {
+        self.maneuver_params = maneuver_params;
+        self
+    }
+
+    /// Set whether to analyze distributions of the best-available matching score of each GT regardless of thresholds,
+    /// which are printed as percentile tables in the report.
+    ///
+    /// * `enabled` - Whether to enable the analysis.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::MetricsParams;
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_alignment_analysis(true);
+    /// ```
+    pub fn with_alignment_analysis(mut self, enabled: bool) -> Self {
+        self.alignment_analysis = enabled;
+        self
+    }
+
+    /// Set axes to break metrics down by, whose every combination of bins is scored separately.
+    ///
+    /// * `axes`    - List of axes. If empty, the breakdown contains only one cell of all results.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use perception_eval::{
+    ///     config::MetricsParams,
+    ///     metrics::breakdown::{DistanceAxis, VisibilityAxis},
+    /// };
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_breakdown_axes(vec![
+    ///         Arc::new(DistanceAxis::new(vec![0.0, 30.0, 60.0]).unwrap()),
+    ///         Arc::new(VisibilityAxis::default()),
+    ///     ]);
+    /// ```
+    pub fn with_breakdown_axes(mut self, axes: Vec<Arc<dyn BreakdownAxis>>) -> Self {
+        self.breakdown_axes = axes;
+        self
+    }
+
+    /// Enable the deterministic parallel mode, which evaluates scores of labels across threads and merges them
+    /// in the fixed order of labels. Since the score of each label is computed by one thread,
+    /// metrics are bit-identical to sequential evaluation regardless of the number of threads.
+    ///
+    /// * `num_threads` - Number of threads. If None, labels are evaluated sequentially.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::MetricsParams;
+    ///
+    /// let params = MetricsParams::new(&vec!["Car", "Pedestrian", "Bus"], 1.0, 1.0, 0.5, 0.5)
+    ///     .unwrap()
+    ///     .with_deterministic_parallel(Some(4));
+    /// ```
+    pub fn with_deterministic_parallel(mut self, num_threads: Option<usize>) -> Self {
+        self.deterministic_threads = num_threads;
+        self
+    }
+
+    /// Returns matching modes evaluated for AP and their thresholds,
+    /// which are center distance, plane distance and velocity if enabled.
+    pub(crate) fn evaluated_matchings(&self) -> Vec<(MatchingMode, &Vec<f64>)> {
+        let mut matchings = vec![
+            (
+                MatchingMode::CenterDistance,
+                &self.center_distance_thresholds,
+            ),
+            (MatchingMode::PlaneDistance, &self.plane_distance_thresholds),
+        ];
+        matchings.extend(self.velocity_matching());
+        matchings
+    }
+
+    /// Returns the maximum distance threshold of evaluated matching modes in [m] if candidate pruning is enabled.
+    pub(crate) fn max_matching_distance(&self) -> Option<f64> {
+        self.candidate_pruning.then(|| {
+            let max_velocity_gate = self
+                .velocity_thresholds
+                .as_ref()
+                .map_or(0.0, |_| self.velocity_position_gate);
+            self.center_distance_thresholds
+                .iter()
+                .chain(&self.plane_distance_thresholds)
+                .fold(max_velocity_gate, |acc, &threshold| acc.max(threshold))
+        })
+    }
+
+    /// Returns the velocity matching mode and its thresholds if enabled.
+    pub(crate) fn velocity_matching(&self) -> Option<(MatchingMode, &Vec<f64>)> {
+        self.velocity_thresholds.as_ref().map(|thresholds| {
+            let mode = MatchingMode::Velocity {
+                position_gate: self.velocity_position_gate,
+            };
+            (mode, thresholds)
+        })
+    }
+}
+
+/// Load sample tokens from the text file, which contains one token per line.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// * `path`    - Path of the text file.
+fn load_sample_tokens<P>(path: P) -> ConfigResult<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let reader = BufReader::new(File::open(path.as_ref())?);
+    let mut tokens = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let token = line.trim();
+        if !token.is_empty() && !token.starts_with('#') {
+            tokens.push(token.to_string());
+        }
     }
+    Ok(tokens)
 }
 
 fn load_yaml<T, P>(path: P) -> ConfigResult<T>
@@ -234,13 +1566,9 @@ where
     T: DeserializeOwned,
 {
     let reader = BufReader::new(File::open(path.as_ref())?);
-    let value = serde_yaml::from_reader(reader).map_err(|err| {
-        let msg = format!(
-            "failed to load scenario file {}: {:?}",
-            path.as_ref().display(),
-            err
-        );
-        ConfigError::CorruptedFile(msg)
+    let value = serde_yaml::from_reader(reader).map_err(|source| ConfigError::CorruptedFile {
+        path: path.as_ref().to_path_buf(),
+        source,
     })?;
     Ok(value)
 }
@@ -1,41 +1,272 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{evaluation_task::EvaluationTask, frame_id::FrameID};
+use crate::{
+    evaluation_task::EvaluationTask, frame_id::FrameID, matching::PlaneDistanceConfig,
+    metrics::breakdown::BreakdownAxisParams,
+};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub(super) struct Scenario {
+use super::{
+    load_yaml, ApInterpolation, ConfigError, ConfigResult, DetectionRange, FpSuppressionPolicy,
+    FrameIdPolicy, LatencyCompensationMethod,
+};
+
+/// Scenario file describing datasets and the configuration of evaluation.
+///
+/// Loading and saving keep every field, so that scenarios can be edited programmatically.
+/// Scenarios are constructed in code by `ScenarioBuilder`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct Scenario {
     #[serde(rename = "ScenarioFormatVersion")]
-    pub(super) version: String,
+    pub version: String,
     #[serde(rename = "ScenarioName")]
-    pub(super) name: String,
+    pub name: String,
     #[serde(rename = "ScenarioDescription")]
-    pub(super) description: String,
+    pub description: String,
     #[serde(rename = "SensorModel")]
-    pub(super) sensor_model: String,
+    pub sensor_model: String,
     #[serde(rename = "VehicleModel")]
-    pub(super) vehicle_model: String,
+    pub vehicle_model: String,
     #[serde(rename = "Evaluation")]
-    pub(super) evaluation: Evaluation,
+    pub evaluation: Evaluation,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub(super) struct Evaluation {
+impl Scenario {
+    /// Load the scenario from `.yaml`.
+    ///
+    /// * `path`    - Scenario path of `.yaml`.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::schema::Scenario;
+    ///
+    /// let scenario = Scenario::load("tests/config/perception.yaml").unwrap();
+    ///
+    /// assert_eq!(scenario.evaluation.config.params.max_x_position, 100.0);
+    /// ```
+    pub fn load<P>(path: P) -> ConfigResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        load_yaml(path)
+    }
+
+    /// Save the scenario as `.yaml`, which can be loaded by `Scenario::load()` again.
+    ///
+    /// * `path`    - Output path of `.yaml`.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::config::schema::Scenario;
+    ///
+    /// let mut scenario = Scenario::load("tests/config/perception.yaml").unwrap();
+    /// scenario.evaluation.config.params.max_x_position = 50.0;
+    ///
+    /// let path = std::env::temp_dir().join("perception_eval_scenario_save.yaml");
+    /// scenario.save(&path).unwrap();
+    ///
+    /// assert_eq!(Scenario::load(&path).unwrap(), scenario);
+    /// ```
+    pub fn save<P>(&self, path: P) -> ConfigResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut writer = BufWriter::new(File::create(path.as_ref())?);
+        serde_yaml::to_writer(&mut writer, self).map_err(|source| {
+            ConfigError::SerializeFailed {
+                path: path.as_ref().to_path_buf(),
+                source,
+            }
+        })?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Builder to construct `Scenario` in code.
+///
+/// Format versions are set to the latest, and descriptive fields are empty unless specified.
+///
+/// # Examples
+/// ```
+/// use perception_eval::{
+///     config::schema::{ConfigParams, ScenarioBuilder},
+///     evaluation_task::EvaluationTask,
+///     frame_id::FrameID,
+/// };
+///
+/// let params = ConfigParams::new(
+///     EvaluationTask::Detection,
+///     FrameID::BaseLink,
+///     vec!["car".to_string(), "pedestrian".to_string()],
+///     100.0,
+///     100.0,
+///     [1.0, 2.0, 0.5, 0.5],
+/// );
+/// let scenario = ScenarioBuilder::new("sample")
+///     .dataset("./tests/sample_data", "annotation")
+///     .pass_rate(99.0)
+///     .params(params)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(scenario.name, "sample");
+/// assert_eq!(scenario.evaluation.datasets.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScenarioBuilder {
+    name: String,
+    description: String,
+    sensor_model: String,
+    vehicle_model: String,
+    usecase: UseCase,
+    datasets: Vec<HashMap<String, Dataset>>,
+    pass_rate: f64,
+    params: Option<ConfigParams>,
+}
+
+impl ScenarioBuilder {
+    /// Scenario format version written by the builder.
+    pub const SCENARIO_FORMAT_VERSION: &'static str = "3.0.0";
+    /// Use case format version written by the builder.
+    pub const USECASE_FORMAT_VERSION: &'static str = "0.4.0";
+
+    /// Construct `ScenarioBuilder`.
+    ///
+    /// * `name`    - Name of the scenario.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            description: String::new(),
+            sensor_model: String::new(),
+            vehicle_model: String::new(),
+            usecase: UseCase::Perception,
+            datasets: Vec::new(),
+            pass_rate: 100.0,
+            params: None,
+        }
+    }
+
+    /// Set the description of the scenario.
+    ///
+    /// * `description` - Description of the scenario.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_owned();
+        self
+    }
+
+    /// Set the sensor model.
+    ///
+    /// * `sensor_model`    - Name of the sensor model.
+    pub fn sensor_model(mut self, sensor_model: &str) -> Self {
+        self.sensor_model = sensor_model.to_owned();
+        self
+    }
+
+    /// Set the vehicle model.
+    ///
+    /// * `vehicle_model`   - Name of the vehicle model.
+    pub fn vehicle_model(mut self, vehicle_model: &str) -> Self {
+        self.vehicle_model = vehicle_model.to_owned();
+        self
+    }
+
+    /// Set the use case, which is `UseCase::Perception` by default.
+    ///
+    /// * `usecase` - UseCase instance.
+    pub fn usecase(mut self, usecase: UseCase) -> Self {
+        self.usecase = usecase;
+        self
+    }
+
+    /// Append a dataset.
+    ///
+    /// * `path`    - Root directory path of the dataset.
+    /// * `version` - Version name of the dataset.
+    pub fn dataset(mut self, path: &str, version: &str) -> Self {
+        let dataset = Dataset {
+            version: version.to_owned(),
+            vehicle_id: String::new(),
+            launch_sensing: false,
+            local_map_path: PathBuf::new(),
+        };
+        self.datasets
+            .push(HashMap::from([(path.to_owned(), dataset)]));
+        self
+    }
+
+    /// Set the pass rate, which is 100.0 by default.
+    ///
+    /// * `pass_rate`   - Pass rate in [%].
+    pub fn pass_rate(mut self, pass_rate: f64) -> Self {
+        self.pass_rate = pass_rate;
+        self
+    }
+
+    /// Set the parameters of evaluation.
+    ///
+    /// * `params`  - ConfigParams instance.
+    pub fn params(mut self, params: ConfigParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Build `Scenario`.
+    /// Returns error if no dataset or parameters are specified.
+    pub fn build(self) -> ConfigResult<Scenario> {
+        if self.datasets.is_empty() {
+            return Err(ConfigError::InvalidParameter(
+                "scenario must contain at least one dataset".to_string(),
+            ));
+        }
+        let params = self.params.ok_or(ConfigError::InvalidParameter(
+            "scenario must contain evaluation parameters".to_string(),
+        ))?;
+        Ok(Scenario {
+            version: Self::SCENARIO_FORMAT_VERSION.to_owned(),
+            name: self.name,
+            description: self.description,
+            sensor_model: self.sensor_model,
+            vehicle_model: self.vehicle_model,
+            evaluation: Evaluation {
+                usecase: self.usecase,
+                version: Self::USECASE_FORMAT_VERSION.to_owned(),
+                datasets: self.datasets,
+                conditions: Conditions {
+                    pass_rate: self.pass_rate,
+                },
+                config: EvaluationConfig { params },
+            },
+        })
+    }
+}
+
+/// Evaluation section of the scenario.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct Evaluation {
     #[serde(rename = "UseCaseName")]
-    pub(super) usecase: UseCase,
+    pub usecase: UseCase,
     #[serde(rename = "UseCaseFormatVersion")]
-    pub(super) version: String,
+    pub version: String,
     #[serde(rename = "Datasets")]
-    pub(super) datasets: Vec<HashMap<String, Dataset>>,
+    pub datasets: Vec<HashMap<String, Dataset>>,
     #[serde(rename = "Conditions")]
-    pub(super) conditions: Conditions,
+    pub conditions: Conditions,
     #[serde(rename = "PerceptionEvaluationConfig")]
-    pub(super) config: EvaluationConfig,
+    pub config: EvaluationConfig,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub(super) enum UseCase {
+/// Use case of the scenario.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum UseCase {
     #[serde(rename = "perception")]
     Perception,
     #[serde(rename = "perception_2d")]
@@ -44,52 +275,230 @@ pub(super) enum UseCase {
     TrafficLight,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub(super) struct Dataset {
+/// Dataset entry of the scenario, which is keyed by the dataset path.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct Dataset {
     #[serde(rename = "Version")]
-    pub(super) version: String,
+    pub version: String,
     #[serde(rename = "VehicleId")]
-    pub(super) vehicle_id: String,
+    pub vehicle_id: String,
     #[serde(rename = "LaunchSensing")]
-    pub(super) launch_sensing: bool,
+    pub launch_sensing: bool,
     #[serde(rename = "LocalMapPath")]
-    pub(super) local_map_path: PathBuf,
+    pub local_map_path: PathBuf,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub(super) struct Conditions {
+/// Conditions to judge evaluation results.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct Conditions {
     #[serde(rename = "PassRate")]
-    pub(super) pass_rate: f64,
+    pub pass_rate: f64,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub(super) struct EvaluationConfig {
+/// Wrapper of the parameters of `PerceptionEvaluationConfig`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct EvaluationConfig {
     #[serde(rename = "evaluation_config_dict")]
-    pub(super) params: ConfigParams,
+    pub params: ConfigParams,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub(super) struct ConfigParams {
+/// Parameters of `PerceptionEvaluationConfig`. Optional parameters can be omitted in the scenario.
+///
+/// Parameters are constructed in code by `ConfigParams::new()`, and optional ones can be set afterwards.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct ConfigParams {
     #[serde(with = "evaluation_task_serde")]
-    pub(super) evaluation_task: EvaluationTask,
+    pub evaluation_task: EvaluationTask,
+    #[serde(with = "frame_id_serde")]
+    pub frame_id: FrameID,
+    pub target_labels: Vec<String>,
+    pub max_x_position: f64,
+    pub max_y_position: f64,
+    pub min_point_number: Option<usize>,
+    pub target_uuids: Option<Vec<String>>,
+    pub center_distance_threshold: f64,
+    pub plane_distance_threshold: f64,
+    pub iou_2d_threshold: f64,
+    pub iou_3d_threshold: f64,
+    #[serde(default)]
+    pub warmup_frames: Option<usize>,
+    #[serde(default)]
+    pub warmup_seconds: Option<f64>,
+    #[serde(default)]
+    pub max_missed_frames: Option<usize>,
+    #[serde(default)]
+    pub plane_distance_config: Option<PlaneDistanceConfig>,
+    #[serde(default)]
+    pub audit_trail: bool,
+    #[serde(default)]
+    pub candidate_pruning: bool,
+    #[serde(default)]
+    pub ap_interpolation: ApInterpolation,
+    #[serde(default)]
+    pub timestamp_offset: Option<f64>,
+    #[serde(default)]
+    pub auto_timestamp_offset: bool,
+    #[serde(default)]
+    pub target_sample_tokens: Option<Vec<String>>,
+    #[serde(default)]
+    pub target_sample_tokens_file: Option<PathBuf>,
+    #[serde(default)]
+    pub velocity_threshold: Option<f64>,
+    #[serde(default)]
+    pub velocity_position_gate: Option<f64>,
+    #[serde(default)]
+    pub max_occlusion_ratio: Option<f64>,
+    #[serde(default)]
+    pub occlusion_num_rays: Option<usize>,
+    #[serde(default)]
+    pub estimation_label_remap: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub export_pr_curves: bool,
+    #[serde(default)]
+    pub maneuver_breakdown: bool,
+    #[serde(default)]
+    pub alignment_analysis: bool,
+    #[serde(default)]
+    pub deterministic_threads: Option<usize>,
+    #[serde(default)]
+    pub fp_suppression_policy: FpSuppressionPolicy,
+    #[serde(default)]
+    pub frame_id_policy: FrameIdPolicy,
+    #[serde(default)]
+    pub latency: Option<f64>,
+    #[serde(default)]
+    pub latency_compensation: LatencyCompensationMethod,
+    #[serde(default)]
+    pub camera_models: Vec<CameraModelParams>,
+    #[serde(default)]
+    pub breakdown_axes: Vec<BreakdownAxisParams>,
+    #[serde(default)]
+    pub detection_ranges: Option<HashMap<String, DetectionRange>>,
+}
+
+impl ConfigParams {
+    /// Construct `ConfigParams` with required parameters, where optional parameters are disabled.
+    ///
+    /// * `evaluation_task` - Task of evaluation.
+    /// * `frame_id`        - FrameID that objects are with respect to.
+    /// * `target_labels`   - List of label names should be evaluated.
+    /// * `max_x_position`  - Maximum absolute value in the x direction from ego.
+    /// * `max_y_position`  - Maximum absolute value in the y direction from ego.
+    /// * `thresholds`      - Thresholds of center distance, plane distance, 2D IoU and 3D IoU.
+    pub fn new(
+        evaluation_task: EvaluationTask,
+        frame_id: FrameID,
+        target_labels: Vec<String>,
+        max_x_position: f64,
+        max_y_position: f64,
+        thresholds: [f64; 4],
+    ) -> Self {
+        let [center_distance_threshold, plane_distance_threshold, iou_2d_threshold, iou_3d_threshold] =
+            thresholds;
+        Self {
+            evaluation_task,
+            frame_id,
+            target_labels,
+            max_x_position,
+            max_y_position,
+            min_point_number: None,
+            target_uuids: None,
+            center_distance_threshold,
+            plane_distance_threshold,
+            iou_2d_threshold,
+            iou_3d_threshold,
+            warmup_frames: None,
+            warmup_seconds: None,
+            max_missed_frames: None,
+            plane_distance_config: None,
+            audit_trail: false,
+            candidate_pruning: false,
+            ap_interpolation: ApInterpolation::default(),
+            timestamp_offset: None,
+            auto_timestamp_offset: false,
+            target_sample_tokens: None,
+            target_sample_tokens_file: None,
+            velocity_threshold: None,
+            velocity_position_gate: None,
+            max_occlusion_ratio: None,
+            occlusion_num_rays: None,
+            estimation_label_remap: None,
+            export_pr_curves: false,
+            maneuver_breakdown: false,
+            alignment_analysis: false,
+            deterministic_threads: None,
+            fp_suppression_policy: FpSuppressionPolicy::default(),
+            frame_id_policy: FrameIdPolicy::default(),
+            latency: None,
+            latency_compensation: LatencyCompensationMethod::default(),
+            camera_models: Vec::new(),
+            breakdown_axes: Vec::new(),
+            detection_ranges: None,
+        }
+    }
+}
+
+/// Camera model used for FP suppression of camera frames.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct CameraModelParams {
     #[serde(with = "frame_id_serde")]
-    pub(super) frame_id: FrameID,
-    pub(super) target_labels: Vec<String>,
-    pub(super) max_x_position: f64,
-    pub(super) max_y_position: f64,
-    pub(super) min_point_number: Option<usize>,
-    pub(super) target_uuids: Option<Vec<String>>,
-    pub(super) center_distance_threshold: f64,
-    pub(super) plane_distance_threshold: f64,
-    pub(super) iou_2d_threshold: f64,
-    pub(super) iou_3d_threshold: f64,
+    pub frame_id: FrameID,
+    pub intrinsic: [[f64; 3]; 3],
+    pub image_size: [u32; 2],
+    pub translation: [f64; 3],
+    pub rotation: [f64; 4],
+}
+
+impl CameraModelParams {
+    /// Construct `CameraModelParams`.
+    ///
+    /// * `frame_id`    - FrameID of the camera.
+    /// * `intrinsic`   - Camera intrinsic matrix.
+    /// * `image_size`  - Image size in [px], (width, height) order.
+    /// * `translation` - Translation from the camera to `base_link`.
+    /// * `rotation`    - Rotation from the camera to `base_link`, [w, x, y, z] order.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{config::schema::CameraModelParams, frame_id::FrameID};
+    ///
+    /// let camera = CameraModelParams::new(
+    ///     FrameID::CamFront,
+    ///     [[1000.0, 0.0, 960.0], [0.0, 1000.0, 540.0], [0.0, 0.0, 1.0]],
+    ///     [1920, 1080],
+    ///     [1.5, 0.0, 1.5],
+    ///     [0.5, -0.5, 0.5, -0.5],
+    /// );
+    ///
+    /// assert_eq!(camera.image_size, [1920, 1080]);
+    /// ```
+    pub fn new(
+        frame_id: FrameID,
+        intrinsic: [[f64; 3]; 3],
+        image_size: [u32; 2],
+        translation: [f64; 3],
+        rotation: [f64; 4],
+    ) -> Self {
+        Self {
+            frame_id,
+            intrinsic,
+            image_size,
+            translation,
+            rotation,
+        }
+    }
 }
 
 mod evaluation_task_serde {
     use std::str::FromStr;
 
     use crate::evaluation_task::EvaluationTask;
-    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
 
     pub fn serialize<S>(value: &EvaluationTask, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -104,15 +513,14 @@ mod evaluation_task_serde {
         D: Deserializer<'de>,
     {
         let task_str = String::deserialize(deserializer)?;
-        let task = EvaluationTask::from_str(&task_str).unwrap(); // TODO: update result type
-        Ok(task)
+        EvaluationTask::from_str(&task_str).map_err(D::Error::custom)
     }
 }
 
 mod frame_id_serde {
     use std::str::FromStr;
 
-    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
 
     use crate::frame_id::FrameID;
 
@@ -129,7 +537,30 @@ mod frame_id_serde {
         D: Deserializer<'de>,
     {
         let frame_id_str = String::deserialize(deserializer)?;
-        let frame_id = FrameID::from_str(&frame_id_str).unwrap(); // TODO
-        Ok(frame_id)
+        FrameID::from_str(&frame_id_str).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scenario;
+    use crate::frame_id::FrameID;
+
+    #[test]
+    fn test_scenario_round_trip() {
+        let scenario = Scenario::load("tests/config/perception.yaml").unwrap();
+
+        let mut modified = scenario.clone();
+        modified.name = "modified".to_string();
+        modified.evaluation.config.params.frame_id = FrameID::Map;
+        modified.evaluation.config.params.warmup_frames = Some(3);
+
+        let path = std::env::temp_dir().join("perception_eval_test_scenario_round_trip.yaml");
+        modified.save(&path).unwrap();
+        let reloaded = Scenario::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(reloaded, modified);
+        assert_ne!(reloaded, scenario);
     }
 }
@@ -0,0 +1,6 @@
+pub mod ap;
+#[cfg(not(feature = "std"))]
+mod float;
+pub mod matching;
+pub mod math;
+pub mod point;
@@ -0,0 +1,162 @@
+use alloc::{vec, vec::Vec};
+
+/// Accumulate values in place.
+///
+/// * `values`  - List of values.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::ap::accumulate;
+///
+/// let mut values = [1.0, 0.0, 1.0];
+/// accumulate(&mut values);
+///
+/// assert_eq!(values, [1.0, 1.0, 2.0]);
+/// ```
+pub fn accumulate(values: &mut [f64]) {
+    values.iter_mut().fold(0.0, |acc, x| {
+        *x += acc;
+        *x
+    });
+}
+
+/// Returns lists of precision and recall at each result, which are ordered by confidence.
+///
+/// If the number of GTs is 0, recall is 0.
+///
+/// * `tp_list`             - Accumulated TP values of results.
+/// * `num_ground_truth`    - Number of GTs.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::ap::precision_recall;
+///
+/// let (precision_list, recall_list) = precision_recall(&[1.0, 1.0], 2);
+///
+/// assert_eq!(precision_list, vec![1.0, 0.5]);
+/// assert_eq!(recall_list, vec![0.5, 0.5]);
+/// ```
+pub fn precision_recall(tp_list: &[f64], num_ground_truth: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut precision_list = vec![0.0; tp_list.len()];
+    let mut recall_list = vec![0.0; tp_list.len()];
+    precision_list
+        .iter_mut()
+        .zip(recall_list.iter_mut())
+        .zip(tp_list.iter())
+        .enumerate()
+        .for_each(|(i, ((precision, recall), tp))| {
+            *precision = tp / (1.0 + i as f64);
+            if num_ground_truth > 0 {
+                *recall = tp / num_ground_truth as f64;
+            }
+        });
+    (precision_list, recall_list)
+}
+
+/// Returns lists of interpolated precision and corresponding recall, which are scanned from the last result.
+///
+/// The interpolated precision at each result is the maximum precision of results with the same or larger recall.
+/// Lists are in descending order of recall and end with the point at recall 0, so that the area under the curve
+/// is covered from recall 0 to the maximum recall.
+/// If lists are empty, returns empty lists.
+///
+/// * `precision_list`  - List of precisions.
+/// * `recall_list`     - List of recalls.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::ap::interpolate_precision_recall;
+///
+/// let (max_precision_list, max_recall_list) =
+///     interpolate_precision_recall(&[1.0, 0.5, 2.0 / 3.0], &[0.5, 0.5, 1.0]);
+///
+/// assert_eq!(max_precision_list, vec![2.0 / 3.0, 2.0 / 3.0, 1.0, 1.0]);
+/// assert_eq!(max_recall_list, vec![1.0, 0.5, 0.5, 0.0]);
+/// ```
+pub fn interpolate_precision_recall(
+    precision_list: &[f64],
+    recall_list: &[f64],
+) -> (Vec<f64>, Vec<f64>) {
+    if precision_list.is_empty() || recall_list.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let mut max_precision_list = Vec::with_capacity(precision_list.len() + 1);
+    let mut max_recall_list = Vec::with_capacity(recall_list.len() + 1);
+    let mut max_precision = 0.0_f64;
+    for (precision, recall) in precision_list.iter().zip(recall_list.iter()).rev() {
+        max_precision = max_precision.max(*precision);
+        max_precision_list.push(max_precision);
+        max_recall_list.push(*recall);
+    }
+    max_precision_list.push(max_precision);
+    max_recall_list.push(0.0);
+    (max_precision_list, max_recall_list)
+}
+
+/// Returns all-point interpolated AP, which is the area under the interpolated precision-recall curve.
+/// If lists are empty, returns NaN.
+///
+/// * `max_precision_list`  - List of interpolated precision returned by `interpolate_precision_recall`.
+/// * `max_recall_list`     - List of recall returned by `interpolate_precision_recall`.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::ap::{all_point_ap, interpolate_precision_recall, precision_recall};
+///
+/// // TP, FP and TP for 2 GTs.
+/// let (precision_list, recall_list) = precision_recall(&[1.0, 1.0, 2.0], 2);
+/// let (max_precision_list, max_recall_list) =
+///     interpolate_precision_recall(&precision_list, &recall_list);
+///
+/// // Precision is 1 up to recall 0.5, and 2/3 up to recall 1.
+/// let ap = all_point_ap(&max_precision_list, &max_recall_list);
+/// assert!((ap - 5.0 / 6.0).abs() < 1e-9);
+///
+/// // A single TP for a single GT.
+/// let (max_precision_list, max_recall_list) = interpolate_precision_recall(&[1.0], &[1.0]);
+/// assert_eq!(all_point_ap(&max_precision_list, &max_recall_list), 1.0);
+/// assert!(all_point_ap(&[], &[]).is_nan());
+/// ```
+pub fn all_point_ap(max_precision_list: &[f64], max_recall_list: &[f64]) -> f64 {
+    if max_precision_list.is_empty() {
+        return f64::NAN;
+    }
+    let mut ap = 0.0;
+    for i in 0..max_precision_list.len() - 1 {
+        ap += max_precision_list[i] * (max_recall_list[i] - max_recall_list[i + 1]);
+    }
+    ap
+}
+
+/// Returns AP as the mean of interpolated precisions at sampled recall points.
+/// The interpolated precision at recall `r` is the maximum precision whose recall is not less than `r`.
+/// If lists are empty, returns NaN.
+///
+/// * `precision_list`  - List of precisions.
+/// * `recall_list`     - List of recalls.
+/// * `recall_points`   - List of recall points to sample.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::ap::sampled_ap;
+///
+/// let ap = sampled_ap(&[1.0, 0.5], &[0.5, 0.5], &[0.0, 0.5, 1.0]);
+///
+/// assert_eq!(ap, 2.0 / 3.0);
+/// ```
+pub fn sampled_ap(precision_list: &[f64], recall_list: &[f64], recall_points: &[f64]) -> f64 {
+    if precision_list.is_empty() {
+        return f64::NAN;
+    }
+    recall_points
+        .iter()
+        .map(|r| {
+            precision_list
+                .iter()
+                .zip(recall_list.iter())
+                .filter(|(_, recall)| *recall >= r)
+                .fold(0.0_f64, |acc, (precision, _)| acc.max(*precision))
+        })
+        .sum::<f64>()
+        / recall_points.len() as f64
+}
@@ -0,0 +1,32 @@
+/// Floating point functions which are only provided by `std`, implemented with `libm`.
+///
+/// With `std`, inherent methods of `f64` are used instead, so results do not change.
+pub(crate) trait Float {
+    fn sqrt(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn atan(self) -> Self;
+    fn acos(self) -> Self;
+    fn sin(self) -> Self;
+}
+
+impl Float for f64 {
+    fn sqrt(self) -> Self {
+        libm::sqrt(self)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        libm::pow(self, n as f64)
+    }
+
+    fn atan(self) -> Self {
+        libm::atan(self)
+    }
+
+    fn acos(self) -> Self {
+        libm::acos(self)
+    }
+
+    fn sin(self) -> Self {
+        libm::sin(self)
+    }
+}
@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::point::distance_points_bev;
+
+/// Returns RMS of BEV distances between corresponding points, which is the score of plane distance matching.
+/// If lists are empty, returns NaN.
+///
+/// * `points1` - List of 3D points.
+/// * `points2` - List of 3D points corresponding to `points1`.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::matching::plane_distance;
+///
+/// let points1 = [[1.0, 1.0, 0.0], [1.0, -1.0, 0.0]];
+/// let points2 = [[2.0, 1.0, 0.0], [2.0, -1.0, 1.0]];
+///
+/// assert_eq!(plane_distance(&points1, &points2), 1.0);
+/// ```
+pub fn plane_distance(points1: &[[f64; 3]], points2: &[[f64; 3]]) -> f64 {
+    let sum_squared = points1
+        .iter()
+        .zip(points2.iter())
+        .map(|(point1, point2)| distance_points_bev(point1, point2).powi(2))
+        .sum::<f64>();
+
+    (sum_squared / points1.len() as f64).sqrt()
+}
+
+/// Returns BEV area of the polygon, whose vertices are ordered either clockwise or counterclockwise.
+///
+/// * `polygon` - List of vertices.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::matching::polygon_area_bev;
+///
+/// let square = [[0.0, 0.0, 0.0], [0.0, 2.0, 0.0], [2.0, 2.0, 0.0], [2.0, 0.0, 0.0]];
+///
+/// assert_eq!(polygon_area_bev(&square), 4.0);
+/// ```
+pub fn polygon_area_bev(polygon: &[[f64; 3]]) -> f64 {
+    signed_area(&polygon.iter().map(|p| [p[0], p[1]]).collect::<Vec<_>>()).abs()
+}
+
+/// Returns BEV area of the intersection of two convex polygons, such as footprints of boxes.
+/// Vertices of each polygon are ordered either clockwise or counterclockwise.
+///
+/// * `polygon1`    - List of vertices of the convex polygon.
+/// * `polygon2`    - List of vertices of the convex polygon.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::matching::intersection_area_bev;
+///
+/// let square1 = [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [2.0, 2.0, 0.0], [0.0, 2.0, 0.0]];
+/// let square2 = [[1.0, 1.0, 0.0], [1.0, 3.0, 0.0], [3.0, 3.0, 0.0], [3.0, 1.0, 0.0]];
+///
+/// assert_eq!(intersection_area_bev(&square1, &square2), 1.0);
+/// assert_eq!(intersection_area_bev(&square1, &[]), 0.0);
+/// ```
+pub fn intersection_area_bev(polygon1: &[[f64; 3]], polygon2: &[[f64; 3]]) -> f64 {
+    if polygon1.len() < 3 || polygon2.len() < 3 {
+        return 0.0;
+    }
+    let clip = polygon2.iter().map(|p| [p[0], p[1]]).collect::<Vec<_>>();
+    let orientation = signed_area(&clip).signum();
+    if orientation == 0.0 {
+        return 0.0;
+    }
+
+    // Sutherland-Hodgman clipping of `polygon1` with each edge of `polygon2`.
+    let mut output = polygon1.iter().map(|p| [p[0], p[1]]).collect::<Vec<_>>();
+    for (i, start) in clip.iter().enumerate() {
+        let end = &clip[(i + 1) % clip.len()];
+        // Positive if the point is inside of the edge.
+        let side = |p: &[f64; 2]| {
+            orientation
+                * ((end[0] - start[0]) * (p[1] - start[1])
+                    - (end[1] - start[1]) * (p[0] - start[0]))
+        };
+        let input = core::mem::take(&mut output);
+        for (j, current) in input.iter().enumerate() {
+            let previous = &input[(j + input.len() - 1) % input.len()];
+            let (side_current, side_previous) = (side(current), side(previous));
+            if (0.0 <= side_current) != (0.0 <= side_previous) {
+                let t = side_previous / (side_previous - side_current);
+                output.push([
+                    previous[0] + t * (current[0] - previous[0]),
+                    previous[1] + t * (current[1] - previous[1]),
+                ]);
+            }
+            if 0.0 <= side_current {
+                output.push(*current);
+            }
+        }
+        if output.is_empty() {
+            return 0.0;
+        }
+    }
+    signed_area(&output).abs()
+}
+
+/// Returns the length of the intersection of two ranges, or 0 if they do not overlap.
+///
+/// * `range1`  - Range, [min, max] order.
+/// * `range2`  - Range, [min, max] order.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::matching::intersection_length;
+///
+/// assert_eq!(intersection_length(&[0.0, 2.0], &[1.0, 3.0]), 1.0);
+/// assert_eq!(intersection_length(&[0.0, 1.0], &[2.0, 3.0]), 0.0);
+/// ```
+pub fn intersection_length(range1: &[f64; 2], range2: &[f64; 2]) -> f64 {
+    (range1[1].min(range2[1]) - range1[0].max(range2[0])).max(0.0)
+}
+
+/// Returns the signed area of the polygon, which is positive if vertices are ordered counterclockwise.
+fn signed_area(polygon: &[[f64; 2]]) -> f64 {
+    0.5 * polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(p1, p2)| p1[0] * p2[1] - p2[0] * p1[1])
+        .sum::<f64>()
+}
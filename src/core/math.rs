@@ -0,0 +1,200 @@
+use core::f64::consts::PI;
+
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+
+/// Convert quaternion into euler angle, [roll, pitch, yaw] order.
+///
+/// * `q`   - Quaternion, [w, x, y, z] order.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::math::quaternion2euler;
+///
+/// let q = [1.0, 0.0, 0.0, 0.0];
+/// let euler = quaternion2euler(&q);
+///
+/// assert_eq!(euler, [0.0, 0.0, 0.0]);
+/// ```
+pub fn quaternion2euler(q: &[f64; 4]) -> [f64; 3] {
+    let [q0, q1, q2, q3] = q;
+    let roll = (2.0 * (q0 * q1 + q2 * q3) / (1.0 - 2.0 * (q1.powi(2) + q2.powi(2)))).atan();
+    let pitch = -0.5 * PI
+        + 2.0
+            * ((1.0 + 2.0 * (q0 * q2 - q1 * q3)) / (1.0 - 2.0 * (q0 * q2 - q1 * q3)))
+                .sqrt()
+                .atan();
+    let yaw = (2.0 * (q0 * q3 + q1 * q2) / (1.0 - 2.0 * (q2.powi(2) + q3.powi(2)))).atan();
+    [roll, pitch, yaw]
+}
+
+/// Returns inverse quaternion.
+///
+/// * `q`   - Quaternion, [w, x, y, z] order.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::math::inverse_quaternion;
+///
+/// let q = [1.0, 0.0, 0.0, 0.0];
+/// let q_inv = inverse_quaternion(&q);
+///
+/// assert_eq!(q_inv, [1.0, 0.0, 0.0, 0.0]);
+/// ```
+pub fn inverse_quaternion(q: &[f64; 4]) -> [f64; 4] {
+    let q_norm = q.iter().map(|e| e.powi(2)).sum::<f64>();
+    [
+        q[0] / q_norm,
+        -q[1] / q_norm,
+        -q[2] / q_norm,
+        -q[3] / q_norm,
+    ]
+}
+
+/// Positive translate `xyz1` with  `xyz2`.
+///
+/// * `xyz1`    - 3D position.
+/// * `xyz2`    - 3D position.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::math::translate;
+///
+/// let xyz1 = [1.0, 1.0, 1.0];
+/// let xyz2 = [2.0, 2.0, 2.0];
+///
+/// let ret = translate(&xyz1, &xyz2);
+/// assert_eq!(ret, [3.0, 3.0, 3.0]);
+/// ```
+pub fn translate(xyz1: &[f64; 3], xyz2: &[f64; 3]) -> [f64; 3] {
+    let mut ret = *xyz1;
+    for i in 0..3 {
+        ret[i] += xyz2[i];
+    }
+    ret
+}
+
+/// Negative translate `xyz1` with  `xyz2`.
+///
+/// * `xyz1`    - 3D position.
+/// * `xyz2`    - 3D position.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::math::translate_inv;
+///
+/// let xyz1 = [1.0, 1.0, 1.0];
+/// let xyz2 = [2.0, 2.0, 2.0];
+///
+/// let ret = translate_inv(&xyz1, &xyz2);
+/// assert_eq!(ret, [-1.0, -1.0, -1.0]);
+/// ```
+pub fn translate_inv(xyz1: &[f64; 3], xyz2: &[f64; 3]) -> [f64; 3] {
+    let mut ret = *xyz1;
+    for i in 0..3 {
+        ret[i] -= xyz2[i];
+    }
+    ret
+}
+
+/// Rotate `q1` with input `q2`, which is the Hamilton product `q2 * q1`.
+///
+/// * `q1`   - Quaternion, [w, x, y, z] order.
+/// * `q2`   - Quaternion, [w, x, y, z] order.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::math::rotate_q;
+///
+/// let q1 = [1.0, 0.0, 0.0, 0.0];
+/// let q2 = [1.0, 0.0, 0.0, 0.0];
+///
+/// let ret = rotate_q(&q1, &q2);
+///
+/// assert_eq!(ret, [1.0, 0.0, 0.0, 0.0]);
+///
+/// // Rotating a 90 degree yaw with a 90 degree yaw results in a 180 degree yaw.
+/// let yaw90 = [0.5_f64.sqrt(), 0.0, 0.0, 0.5_f64.sqrt()];
+/// let ret = rotate_q(&yaw90, &yaw90);
+///
+/// assert!(ret[0].abs() < 1e-9);
+/// assert!((ret[3] - 1.0).abs() < 1e-9);
+/// ```
+pub fn rotate_q(q1: &[f64; 4], q2: &[f64; 4]) -> [f64; 4] {
+    let [w1, x1, y1, z1] = q1;
+    let [w2, x2, y2, z2] = q2;
+    [
+        w2 * w1 - x2 * x1 - y2 * y1 - z2 * z1,
+        w2 * x1 + x2 * w1 + y2 * z1 - z2 * y1,
+        w2 * y1 - x2 * z1 + y2 * w1 + z2 * x1,
+        w2 * z1 + x2 * y1 - y2 * x1 + z2 * w1,
+    ]
+}
+
+/// Inverse rotate `q1` with input `q2`.
+///
+/// * `q1`   - Quaternion, [w, x, y, z] order.
+/// * `q2`   - Quaternion, [w, x, y, z] order.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::math::rotate_q_inv;
+///
+/// let q1 = [1.0, 0.0, 0.0, 0.0];
+/// let q2 = [1.0, 0.0, 0.0, 0.0];
+///
+/// let ret = rotate_q_inv(&q1, &q2);
+///
+/// assert_eq!(ret, [1.0, 0.0, 0.0, 0.0]);
+/// ```
+pub fn rotate_q_inv(q1: &[f64; 4], q2: &[f64; 4]) -> [f64; 4] {
+    let q2_inv = inverse_quaternion(q2);
+    rotate_q(q1, &q2_inv)
+}
+
+/// Spherical linear interpolation between two quaternions.
+///
+/// * `q1`      - Quaternion at `t=0`, [w, x, y, z] order.
+/// * `q2`      - Quaternion at `t=1`, [w, x, y, z] order.
+/// * `t`       - Interpolation ratio.
+///
+/// # Examples
+/// ```
+/// use perception_eval::core::math::slerp;
+///
+/// let q1 = [1.0, 0.0, 0.0, 0.0];
+/// let q2 = [0.0, 0.0, 0.0, 1.0];
+///
+/// let ret = slerp(&q1, &q2, 0.5);
+///
+/// assert!((ret[0] - 0.5_f64.sqrt()).abs() < 1e-9);
+/// assert!((ret[3] - 0.5_f64.sqrt()).abs() < 1e-9);
+/// ```
+pub fn slerp(q1: &[f64; 4], q2: &[f64; 4], t: f64) -> [f64; 4] {
+    let mut q2 = *q2;
+    let mut dot = q1.iter().zip(q2.iter()).map(|(a, b)| a * b).sum::<f64>();
+
+    // take the shorter path
+    if dot < 0.0 {
+        q2.iter_mut().for_each(|e| *e = -*e);
+        dot = -dot;
+    }
+
+    let (w1, w2) = if 1.0 - 1e-9 < dot {
+        (1.0 - t, t)
+    } else {
+        let theta = dot.acos();
+        let sin_theta = theta.sin();
+        (
+            ((1.0 - t) * theta).sin() / sin_theta,
+            (t * theta).sin() / sin_theta,
+        )
+    };
+
+    let mut ret = [0.0; 4];
+    for i in 0..4 {
+        ret[i] = w1 * q1[i] + w2 * q2[i];
+    }
+    let norm = ret.iter().map(|e| e.powi(2)).sum::<f64>().sqrt();
+    ret.map(|e| e / norm)
+}
@@ -1,29 +1,282 @@
+pub mod annotation;
+pub mod index;
+pub mod maneuver;
 pub mod nuscenes;
+pub mod quality;
+pub mod stats;
+pub mod suggestion;
+pub mod timeline;
 
-use self::nuscenes::schema::Modality;
-use self::nuscenes::{internal::SampleInternal, NuScenes, WithDataset};
+use self::nuscenes::schema::{Channel, Modality};
+use self::nuscenes::{
+    error::NuScenesError, internal::SampleInternal, load_pointcloud, r#box::NuScenesBox, NuScenes,
+    PointCloudMatrix, WithDataset,
+};
 use crate::{
-    evaluation_task::EvaluationTask, frame_id::FrameID, label::LabelConverter,
-    object::object3d::DynamicObject,
+    evaluation_task::EvaluationTask,
+    frame_id::FrameID,
+    label::{Label, LabelConverter},
+    object::object3d::{DynamicObject, ObjectTolerance},
+    utils::{
+        grid::BevGrid,
+        math::{rotate, translate},
+    },
 };
 use chrono::naive::NaiveDateTime;
 use indicatif::{ProgressBar, ProgressIterator};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::{
+    collections::{HashMap, HashSet},
     error::Error,
     fmt::{Display, Formatter, Result as FormatResult},
+    fs::File,
+    io::BufWriter,
+    path::Path,
 };
 
-pub type DatasetResult<T> = Result<T, Box<dyn Error>>;
+pub type DatasetResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
 
 /// A struct to contain ground truth objects at one frame.
 ///
-/// * `timestamp`   - Timestamp of the frame.
-/// * `objects`     - List of ground truth objects.
-#[derive(Debug, Clone, PartialEq)]
+/// * `timestamp`       - Timestamp of the frame.
+/// * `objects`         - List of ground truth objects.
+/// * `ego_translation` - Translation of ego vehicle in the global coord system, if available.
+/// * `ego_rotation`    - Rotation of ego vehicle in the global coord system as quaternion, if available.
+/// * `sample_token`    - Token of the sample the frame is loaded from, if available.
+/// * `version`         - Version of the dataset the frame is loaded from, if available.
+/// * `raw_pointcloud`  - Reference to the pointcloud of the frame, if available.
+/// * `location`        - Location where the log of the frame was captured, if available.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct FrameGroundTruth {
     pub timestamp: NaiveDateTime,
     pub objects: Vec<DynamicObject>,
+    pub ego_translation: Option<[f64; 3]>,
+    pub ego_rotation: Option<[f64; 4]>,
+    pub sample_token: Option<String>,
+    pub version: Option<String>,
+    pub raw_pointcloud: Option<RawPointCloud>,
+    pub location: Option<String>,
+}
+
+/// Reference to the pointcloud file of a frame, which is loaded lazily.
+///
+/// * `path`        - Path of the pointcloud binary file.
+/// * `translation` - Translation from the sensor to the coord system of GT objects.
+/// * `rotation`    - Rotation from the sensor to the coord system of GT objects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawPointCloud {
+    pub path: PathBuf,
+    pub translation: [f64; 3],
+    pub rotation: [f64; 4],
+}
+
+impl RawPointCloud {
+    /// Load pointcloud expressed in the same coord system as GT objects.
+    pub fn load(&self) -> DatasetResult<PointCloudMatrix> {
+        let mut points = load_pointcloud(&self.path)?;
+        points.row_iter_mut().for_each(|mut row| {
+            let xyz = [row[0] as f64, row[1] as f64, row[2] as f64];
+            let xyz = translate(&rotate(&xyz, &self.rotation), &self.translation);
+            row[0] = xyz[0] as f32;
+            row[1] = xyz[1] as f32;
+            row[2] = xyz[2] as f32;
+        });
+        Ok(points)
+    }
+}
+
+impl FrameGroundTruth {
+    /// Construct `FrameGroundTruth` with objects, where optional metadata are not set.
+    ///
+    /// * `timestamp`   - Timestamp of the frame.
+    /// * `objects`     - List of GT objects.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::dataset::FrameGroundTruth;
+    ///
+    /// let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+    /// let frame = FrameGroundTruth::new(timestamp, vec![])
+    ///     .with_ego_pose([1.0, 2.0, 0.0], [1.0, 0.0, 0.0, 0.0])
+    ///     .with_sample_token("token");
+    ///
+    /// assert_eq!(frame.sample_token, Some("token".to_string()));
+    /// assert_eq!(frame.location, None);
+    /// ```
+    pub fn new(timestamp: NaiveDateTime, objects: Vec<DynamicObject>) -> Self {
+        Self {
+            timestamp,
+            objects,
+            ego_translation: None,
+            ego_rotation: None,
+            sample_token: None,
+            version: None,
+            raw_pointcloud: None,
+            location: None,
+        }
+    }
+
+    /// Set the ego pose of the frame.
+    ///
+    /// * `translation` - Translation of ego in the order of (x, y, z).
+    /// * `rotation`    - Rotation quaternion of ego in the order of (w, x, y, z).
+    pub fn with_ego_pose(mut self, translation: [f64; 3], rotation: [f64; 4]) -> Self {
+        self.ego_translation = Some(translation);
+        self.ego_rotation = Some(rotation);
+        self
+    }
+
+    /// Set the token of the sample the frame is loaded from.
+    ///
+    /// * `sample_token`    - Token of the sample.
+    pub fn with_sample_token(mut self, sample_token: &str) -> Self {
+        self.sample_token = Some(sample_token.to_owned());
+        self
+    }
+
+    /// Set the version of the dataset the frame is loaded from.
+    ///
+    /// * `version` - Version name of the dataset.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_owned());
+        self
+    }
+
+    /// Set the reference to the pointcloud of the frame.
+    ///
+    /// * `raw_pointcloud`  - RawPointCloud instance.
+    pub fn with_raw_pointcloud(mut self, raw_pointcloud: RawPointCloud) -> Self {
+        self.raw_pointcloud = Some(raw_pointcloud);
+        self
+    }
+
+    /// Set the location where the log of the frame was captured.
+    ///
+    /// * `location`    - Name of the location.
+    pub fn with_location(mut self, location: &str) -> Self {
+        self.location = Some(location.to_owned());
+        self
+    }
+
+    /// Returns the frame which has the same metadata but the given objects,
+    /// without cloning objects of this frame.
+    ///
+    /// * `objects` - List of objects of the new frame.
+    pub(crate) fn with_objects(&self, objects: Vec<DynamicObject>) -> Self {
+        Self {
+            timestamp: self.timestamp,
+            objects,
+            ego_translation: self.ego_translation,
+            ego_rotation: self.ego_rotation,
+            sample_token: self.sample_token.to_owned(),
+            version: self.version.to_owned(),
+            raw_pointcloud: self.raw_pointcloud.to_owned(),
+            location: self.location.to_owned(),
+        }
+    }
+
+    /// Returns the nearest object from the point in 3D.
+    /// If `label_filter` is specified, only objects with the labels are considered.
+    ///
+    /// * `point`           - 3D point in the same coord system as objects.
+    /// * `label_filter`    - List of labels to be considered. If None, all objects are considered.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     dataset::FrameGroundTruth, frame_id::FrameID, label::Label, object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("100");
+    /// let mut pedestrian = object.clone();
+    /// pedestrian.position = [5.0, 0.0, 0.0];
+    /// pedestrian.label = Label::Pedestrian;
+    ///
+    /// let frame = FrameGroundTruth::new(object.timestamp, vec![object.clone(), pedestrian.clone()]);
+    ///
+    /// assert_eq!(frame.nearest_object(&[4.0, 0.0, 0.0], None), Some(&pedestrian));
+    /// assert_eq!(frame.nearest_object(&[4.0, 0.0, 0.0], Some(&[Label::Car])), Some(&object));
+    /// ```
+    pub fn nearest_object(
+        &self,
+        point: &[f64; 3],
+        label_filter: Option<&[Label]>,
+    ) -> Option<&DynamicObject> {
+        self.objects
+            .iter()
+            .filter(|object| label_filter.is_none_or(|labels| labels.contains(&object.label)))
+            .min_by(|a, b| a.distance_from(point).total_cmp(&b.distance_from(point)))
+    }
+
+    /// Returns objects whose center is within the radius from the point in 3D.
+    ///
+    /// * `radius`  - Radius in [m].
+    /// * `point`   - 3D point in the same coord system as objects.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     dataset::FrameGroundTruth, frame_id::FrameID, label::Label, object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("100");
+    ///
+    /// let frame = FrameGroundTruth::new(object.timestamp, vec![object.clone()]);
+    ///
+    /// assert_eq!(frame.objects_within(2.0, &[0.0, 0.0, 0.0]), vec![&object]);
+    /// assert!(frame.objects_within(1.0, &[0.0, 0.0, 0.0]).is_empty());
+    /// ```
+    pub fn objects_within(&self, radius: f64, point: &[f64; 3]) -> Vec<&DynamicObject> {
+        self.objects
+            .iter()
+            .filter(|object| object.distance_from(point) <= radius)
+            .collect()
+    }
+
+    /// Returns BEV grid index over object centers, which is useful for repeated queries.
+    /// Indices returned by the grid correspond to `objects`.
+    ///
+    /// * `cell_size`   - Size of each grid cell in [m].
+    pub fn bev_grid(&self, cell_size: f64) -> BevGrid {
+        let centers = self
+            .objects
+            .iter()
+            .map(|object| object.position)
+            .collect::<Vec<_>>();
+        BevGrid::new(&centers, cell_size)
+    }
+
+    /// Returns the translation and rotation of ego in `map`, if both are available.
+    pub fn ego_pose(&self) -> Option<(&[f64; 3], &[f64; 4])> {
+        self.ego_translation
+            .as_ref()
+            .zip(self.ego_rotation.as_ref())
+    }
 }
 
 impl Display for FrameGroundTruth {
@@ -49,24 +302,63 @@ pub fn load_dataset(
     evaluation_task: &EvaluationTask,
     frame_id: &FrameID,
 ) -> DatasetResult<Vec<FrameGroundTruth>> {
+    load_datasets(&[version], data_root, evaluation_task, frame_id)
+}
+
+/// Returns list of `FrameGroundTruth` merged from multiple versions under the same root,
+/// e.g. `v1.0-trainval` and `v1.0-test`.
+///
+/// The version and the sample token of each frame are recorded in `FrameGroundTruth`.
+/// If more than one version is specified, frames are sorted by timestamp.
+/// Returns error if the same sample token is contained in multiple versions.
+///
+/// * `versions`        - List of NuScenes versions of dataset.
+/// * `data_root`       - Root directory path of dataset.
+/// * `evaluation_task` - Task to evaluate.
+/// * `frame_id`        - Frame id where objects are with respect to.
+pub fn load_datasets<S>(
+    versions: &[S],
+    data_root: &PathBuf,
+    evaluation_task: &EvaluationTask,
+    frame_id: &FrameID,
+) -> DatasetResult<Vec<FrameGroundTruth>>
+where
+    S: AsRef<str>,
+{
     log::info!(
         "config: evaluation_task: {}, frame_id: {}",
         evaluation_task,
         frame_id,
     );
 
-    let nusc = NuScenes::load(version, data_root)?;
-    let bar = ProgressBar::new(nusc.sample_map.len() as u64);
-    let datasets = nusc
-        .sample_iter()
-        .progress_with(bar)
-        .map(|sample| sample_to_frame(&nusc, &sample, frame_id))
-        .collect::<DatasetResult<Vec<FrameGroundTruth>>>()?;
+    let mut sources: HashMap<String, &str> = HashMap::new();
+    let mut datasets = Vec::new();
+    for version in versions.iter().map(AsRef::as_ref) {
+        let nusc = NuScenes::load(version, data_root)?;
+        let bar = ProgressBar::new(nusc.sample_map.len() as u64);
+        for sample in nusc.sample_iter().progress_with(bar) {
+            if let Some(other) = sources.insert(sample.token.to_string(), version) {
+                let msg = format!(
+                    "sample token {} is contained in both {} and {}",
+                    sample.token, other, version
+                );
+                return Err(NuScenesError::CorruptedDataset(msg).into());
+            }
+            datasets.push(sample_to_frame(&nusc, &sample, frame_id)?);
+        }
+    }
+
+    if versions.len() > 1 {
+        datasets.sort_by_key(|frame| frame.timestamp);
+    }
     Ok(datasets)
 }
 
 /// Convert NuScenes sample into `FrameGroundTruth` instance.
 ///
+/// For camera frames, annotations are interpolated at the timestamp of the sample data
+/// of the corresponding channel, which is the nearest to the sample timestamp.
+///
 /// TODO: Transform position and rotation into BaseLin
 ///
 /// * `nusc`        - NuScenes instance.
@@ -76,8 +368,38 @@ fn sample_to_frame(
     nusc: &NuScenes,
     sample: &WithDataset<SampleInternal>,
     frame_id: &FrameID,
+) -> DatasetResult<FrameGroundTruth> {
+    match frame_id_to_channel(frame_id) {
+        Some(channel) => camera_sample_to_frame(nusc, sample, frame_id, &channel),
+        None => lidar_sample_to_frame(nusc, sample, frame_id),
+    }
+}
+
+/// Returns the location of the log which the sample belongs to.
+///
+/// * `nusc`        - NuScenes instance.
+/// * `sample`      - Sample annotated in meta data.
+fn sample_location(nusc: &NuScenes, sample: &WithDataset<SampleInternal>) -> Option<String> {
+    nusc.scene_map
+        .get(&sample.scene_token)
+        .and_then(|scene| nusc.log_map.get(&scene.log_token))
+        .map(|log| log.location.to_owned())
+}
+
+/// Convert NuScenes sample into `FrameGroundTruth` instance with lidar annotations.
+///
+/// * `nusc`        - NuScenes instance.
+/// * `sample`      - Sample annotated in meta data.
+/// * `frame_id`    - FrameID instance.
+fn lidar_sample_to_frame(
+    nusc: &NuScenes,
+    sample: &WithDataset<SampleInternal>,
+    frame_id: &FrameID,
 ) -> DatasetResult<FrameGroundTruth> {
     let mut objects: Vec<DynamicObject> = Vec::new();
+    let mut ego_translation = None;
+    let mut ego_rotation = None;
+    let mut raw_pointcloud = None;
 
     // TODO
     // === update objects container ===
@@ -98,46 +420,282 @@ fn sample_to_frame(
             continue;
         }
         let (_, boxes) = nusc.get_sample_data(&sample_data.token, &false)?;
-        boxes.iter().for_each(|nusc_box| {
-            let label = label_converter.convert(&nusc_box.name);
-            objects.push(DynamicObject {
-                timestamp: sample.timestamp.to_owned(),
-                position: nusc_box.position,
-                orientation: nusc_box.orientation,
-                size: nusc_box.size,
-                confidence: 1.0,
-                label,
-                velocity: None,
-                frame_id: frame_id.to_owned(),
-                pointcloud_num: Some(nusc_box.num_lidar_pts),
-                uuid: Some(nusc_box.instance.to_string()),
+        ego_translation = Some(sample_data.ego_pose().translation);
+        ego_rotation = Some(sample_data.ego_pose().rotation);
+        if raw_pointcloud.is_none() {
+            raw_pointcloud = Some(RawPointCloud {
+                path: nusc.dir().join(&sample_data.filename),
+                translation: cs_record.translation,
+                rotation: cs_record.rotation,
             });
-        });
+        }
+        objects.extend(boxes_to_objects(
+            &boxes,
+            &sample.timestamp,
+            frame_id,
+            &label_converter,
+        ));
     }
 
+    // The same annotations are loaded from every lidar at the sample timestamp.
     let ret = FrameGroundTruth {
         timestamp: sample.timestamp,
-        objects,
+        objects: dedup_objects(objects, &ObjectTolerance::default()),
+        ego_translation,
+        ego_rotation,
+        sample_token: Some(sample.token.to_string()),
+        version: Some(nusc.version().to_owned()),
+        raw_pointcloud,
+        location: sample_location(nusc, sample),
+    };
+    Ok(ret)
+}
+
+/// Convert NuScenes sample into `FrameGroundTruth` instance synchronized with camera.
+///
+/// The sample data of the channel whose timestamp is the nearest to the sample is selected,
+/// and boxes are interpolated at its timestamp and expressed in the camera coord system.
+///
+/// * `nusc`        - NuScenes instance.
+/// * `sample`      - Sample annotated in meta data.
+/// * `frame_id`    - FrameID instance.
+/// * `channel`     - Camera channel corresponding to `frame_id`.
+fn camera_sample_to_frame(
+    nusc: &NuScenes,
+    sample: &WithDataset<SampleInternal>,
+    frame_id: &FrameID,
+    channel: &Channel,
+) -> DatasetResult<FrameGroundTruth> {
+    let label_converter = LabelConverter::new("autoware")?;
+
+    let nearest_sample_data = sample
+        .sample_data_iter()
+        .filter(|sample_data| {
+            let cs_record = &nusc.calibrated_sensor_map[&sample_data.calibrated_sensor_token];
+            nusc.sensor_map[&cs_record.sensor_token].channel == *channel
+        })
+        .min_by_key(|sample_data| (sample_data.timestamp - sample.timestamp).abs());
+
+    let ret = match nearest_sample_data {
+        Some(sample_data) => {
+            let (_, boxes) = nusc.get_sample_data_interpolated(&sample_data.token, &true)?;
+            FrameGroundTruth {
+                timestamp: sample_data.timestamp,
+                objects: boxes_to_objects(
+                    &boxes,
+                    &sample_data.timestamp,
+                    frame_id,
+                    &label_converter,
+                ),
+                ego_translation: Some(sample_data.ego_pose().translation),
+                ego_rotation: Some(sample_data.ego_pose().rotation),
+                sample_token: Some(sample.token.to_string()),
+                version: Some(nusc.version().to_owned()),
+                raw_pointcloud: None,
+                location: sample_location(nusc, sample),
+            }
+        }
+        None => {
+            log::warn!(
+                "There is no sample data of {:?} for sample: {}",
+                channel,
+                sample.token
+            );
+            FrameGroundTruth {
+                timestamp: sample.timestamp,
+                objects: Vec::new(),
+                ego_translation: None,
+                ego_rotation: None,
+                sample_token: Some(sample.token.to_string()),
+                version: Some(nusc.version().to_owned()),
+                raw_pointcloud: None,
+                location: sample_location(nusc, sample),
+            }
+        }
     };
     Ok(ret)
 }
 
+/// Convert NuScenes boxes into list of `DynamicObject`.
+///
+/// * `boxes`           - List of NuScenesBox.
+/// * `timestamp`       - Timestamp of objects.
+/// * `frame_id`        - FrameID instance.
+/// * `label_converter` - LabelConverter instance.
+fn boxes_to_objects(
+    boxes: &[NuScenesBox],
+    timestamp: &NaiveDateTime,
+    frame_id: &FrameID,
+    label_converter: &LabelConverter,
+) -> Vec<DynamicObject> {
+    boxes
+        .iter()
+        .map(|nusc_box| DynamicObject {
+            timestamp: timestamp.to_owned(),
+            position: nusc_box.position,
+            orientation: nusc_box.orientation,
+            size: nusc_box.size,
+            confidence: 1.0,
+            label: label_converter.convert(&nusc_box.name),
+            velocity: nusc_box.velocity,
+            frame_id: frame_id.to_owned(),
+            pointcloud_num: Some(nusc_box.num_lidar_pts),
+            uuid: Some(nusc_box.instance.to_string()),
+            class_probabilities: None,
+        })
+        .collect()
+}
+
+/// Remove duplicated objects, which are approximately equal to any preceding object.
+///
+/// Approximately equal objects have the same UUID, so each object is only compared with
+/// preceding objects of the same UUID.
+///
+/// * `objects`     - List of objects.
+/// * `tolerances`  - Per-field tolerances to determine duplication.
+fn dedup_objects(objects: Vec<DynamicObject>, tolerances: &ObjectTolerance) -> Vec<DynamicObject> {
+    let mut ret: Vec<DynamicObject> = Vec::with_capacity(objects.len());
+    let mut indices_by_uuid: HashMap<Option<String>, Vec<usize>> = HashMap::new();
+    for object in objects {
+        let indices = indices_by_uuid.entry(object.uuid.clone()).or_default();
+        if !indices
+            .iter()
+            .any(|&i| ret[i].approx_eq(&object, tolerances))
+        {
+            indices.push(ret.len());
+            ret.push(object);
+        }
+    }
+    ret
+}
+
+/// Summary of loaded dataset, which is saved with evaluation results.
+///
+/// * `versions`                - Versions of the dataset.
+/// * `num_frames`              - Number of frames to be evaluated.
+/// * `num_skipped_frames`      - Number of frames skipped by the sample token filter.
+/// * `missing_sample_tokens`   - Target sample tokens not found in the dataset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    pub versions: Vec<String>,
+    pub num_frames: usize,
+    pub num_skipped_frames: usize,
+    pub missing_sample_tokens: Vec<String>,
+}
+
+impl DatasetManifest {
+    /// Save manifest as JSON.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save<P>(&self, path: P) -> DatasetResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+/// Returns frames whose sample tokens are contained in the target sample tokens,
+/// with the number of skipped frames and target tokens not found in frames.
+///
+/// * `frame_ground_truths` - List of FrameGroundTruth.
+/// * `sample_tokens`       - List of target sample tokens.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::dataset::{filter_frames_by_sample_tokens, FrameGroundTruth};
+///
+/// let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+/// let frames = ["a", "b", "c"]
+///     .iter()
+///     .map(|token| FrameGroundTruth::new(timestamp, Vec::new()).with_sample_token(token))
+///     .collect::<Vec<_>>();
+///
+/// let (frames, num_skipped, missing) =
+///     filter_frames_by_sample_tokens(frames, &["a".to_string(), "d".to_string()]);
+///
+/// assert_eq!(frames.len(), 1);
+/// assert_eq!(num_skipped, 2);
+/// assert_eq!(missing, vec!["d".to_string()]);
+/// ```
+pub fn filter_frames_by_sample_tokens(
+    frame_ground_truths: Vec<FrameGroundTruth>,
+    sample_tokens: &[String],
+) -> (Vec<FrameGroundTruth>, usize, Vec<String>) {
+    let num_frames = frame_ground_truths.len();
+    let target_tokens = sample_tokens
+        .iter()
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+    let filtered = frame_ground_truths
+        .into_iter()
+        .filter(|frame| {
+            frame
+                .sample_token
+                .as_deref()
+                .is_some_and(|token| target_tokens.contains(token))
+        })
+        .collect::<Vec<_>>();
+
+    let found_tokens = filtered
+        .iter()
+        .filter_map(|frame| frame.sample_token.as_deref())
+        .collect::<HashSet<_>>();
+    let missing = sample_tokens
+        .iter()
+        .filter(|token| !found_tokens.contains(token.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        log::warn!("sample tokens not found in dataset: {:?}", missing);
+    }
+
+    let num_skipped = num_frames - filtered.len();
+    (filtered, num_skipped, missing)
+}
+
+/// Returns the camera channel corresponding to the frame id.
+/// If the frame id is not a camera frame, returns None.
+///
+/// * `frame_id`    - FrameID instance.
+fn frame_id_to_channel(frame_id: &FrameID) -> Option<Channel> {
+    match frame_id {
+        FrameID::BaseLink | FrameID::Map => None,
+        FrameID::CamBack => Some(Channel::CamBack),
+        FrameID::CamBackLeft => Some(Channel::CamBackLeft),
+        FrameID::CamBackRight => Some(Channel::CamBackRight),
+        FrameID::CamFront => Some(Channel::CamFront),
+        FrameID::CamFrontLeft => Some(Channel::CamFrontLeft),
+        FrameID::CamFrontRight => Some(Channel::CamFrontRight),
+        FrameID::CamTrafficLightNear => Some(Channel::CamTrafficLightNear),
+        FrameID::CamTrafficLightFar => Some(Channel::CamTrafficLightFar),
+    }
+}
+
 /// Extract `FrameGroundTruth` instance which has nearest timestamp with input timestamp.
 ///
+/// This scans all frames for each call. For repeated lookups, build `index::FrameIndex` once instead.
+///
 /// * `frame_ground_truths` - List of FrameGroundTruth instances.
 /// * `timestamp`           - Target timestamp.
 pub fn get_current_frame(
     frame_ground_truths: &[FrameGroundTruth],
     timestamp: &NaiveDateTime,
 ) -> Option<FrameGroundTruth> {
-    const TIME_THRESHOLD: i64 = 75; // [ms]
+    const TIME_THRESHOLD: i64 = index::DEFAULT_TIME_TOLERANCE_MS; // [ms]
 
     // TODO: update timestamp computation
-    let target_time = timestamp.timestamp_millis();
+    let target_time = timestamp.and_utc().timestamp_millis();
     let (min_index, min_diff_time) = frame_ground_truths.iter().enumerate().fold(
         (usize::MAX, i64::MAX),
         |(a_idx, a), (b_idx, b)| {
-            let diff = (b.timestamp.timestamp_millis() - target_time).abs();
+            let diff = (b.timestamp.and_utc().timestamp_millis() - target_time).abs();
             if diff < a {
                 (b_idx, diff)
             } else {
@@ -159,3 +717,93 @@ pub fn get_current_frame(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{dedup_objects, load_dataset, load_datasets};
+    use crate::{
+        evaluation_task::EvaluationTask,
+        frame_id::FrameID,
+        label::Label,
+        object::object3d::{DynamicObject, ObjectTolerance},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_dedup_objects() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        let transformed = DynamicObject {
+            position: [1.0 + 1e-12, 1.0 - 1e-12, 0.0],
+            orientation: [-1.0, 0.0, 0.0, 0.0],
+            pointcloud_num: Some(500),
+            ..object.clone()
+        };
+        let other = DynamicObject {
+            uuid: Some("112".to_string()),
+            ..object.clone()
+        };
+
+        let objects = dedup_objects(
+            vec![object.clone(), transformed, other.clone()],
+            &ObjectTolerance::default(),
+        );
+        assert_eq!(objects.len(), 2);
+        assert!(objects[0].approx_eq(&object, &ObjectTolerance::default()));
+        assert!(objects[1].approx_eq(&other, &ObjectTolerance::default()));
+    }
+
+    #[test]
+    fn test_raw_pointcloud() {
+        let data_root = "tests/sample_data".into();
+        let frames = load_dataset(
+            "annotation",
+            &data_root,
+            &EvaluationTask::Detection,
+            &FrameID::BaseLink,
+        )
+        .unwrap();
+        let raw_pointcloud = frames
+            .iter()
+            .filter_map(|frame| frame.raw_pointcloud.as_ref())
+            .find(|raw_pointcloud| raw_pointcloud.path.exists())
+            .unwrap();
+        let points = raw_pointcloud.load().unwrap();
+        assert!(0 < points.nrows());
+    }
+
+    #[test]
+    fn test_load_datasets_collision() {
+        let data_root = "tests/sample_data".into();
+        let frames = load_datasets(
+            &["annotation"],
+            &data_root,
+            &EvaluationTask::Detection,
+            &FrameID::BaseLink,
+        )
+        .unwrap();
+        assert!(frames
+            .iter()
+            .all(|frame| frame.version.as_deref() == Some("annotation")
+                && frame.sample_token.is_some()));
+
+        let ret = load_datasets(
+            &["annotation", "annotation"],
+            &data_root,
+            &EvaluationTask::Detection,
+            &FrameID::BaseLink,
+        );
+        assert!(ret.is_err());
+    }
+}
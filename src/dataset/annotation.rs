@@ -0,0 +1,134 @@
+use std::{collections::HashMap, path::Path};
+
+use super::{
+    nuscenes::{schema::VisibilityLevel, NuScenes},
+    DatasetResult,
+};
+
+/// Annotation metadata of GT object, which is not held by `DynamicObject`.
+///
+/// * `visibility`  - Visibility level of the object, such as `v60-80`, if annotated.
+/// * `attributes`  - Names of attributes of the object, such as `vehicle.moving`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectAnnotation {
+    pub visibility: Option<String>,
+    pub attributes: Vec<String>,
+}
+
+/// Annotation metadata of GT objects keyed by the sample token and the instance token,
+/// where the instance token is the uuid of loaded GTs.
+///
+/// # Examples
+/// ```
+/// use perception_eval::dataset::annotation::{AnnotationMap, ObjectAnnotation};
+///
+/// let mut annotations = AnnotationMap::default();
+/// annotations.insert(
+///     "sample",
+///     "instance",
+///     ObjectAnnotation {
+///         visibility: Some("v80-100".to_string()),
+///         attributes: vec!["vehicle.moving".to_string()],
+///     },
+/// );
+///
+/// assert_eq!(annotations.len(), 1);
+/// assert!(annotations.get("sample", "instance").is_some());
+/// assert!(annotations.get("sample", "other").is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationMap {
+    annotations: HashMap<(String, String), ObjectAnnotation>,
+}
+
+impl AnnotationMap {
+    /// Load annotation metadata of all sample annotations in the dataset.
+    ///
+    /// * `version`     - Version name of the dataset.
+    /// * `data_root`   - Root directory path of the dataset.
+    pub fn load<S, P>(version: S, data_root: P) -> DatasetResult<Self>
+    where
+        S: AsRef<str>,
+        P: AsRef<Path>,
+    {
+        let nusc = NuScenes::load(version, data_root)?;
+        let annotations = nusc
+            .sample_annotation_map
+            .values()
+            .map(|sample_annotation| {
+                let visibility = sample_annotation
+                    .visibility_token
+                    .as_ref()
+                    .and_then(|token| nusc.visibility_map.get(token))
+                    .map(|visibility| visibility_name(&visibility.level).to_string());
+                let attributes = sample_annotation
+                    .attribute_tokens
+                    .iter()
+                    .filter_map(|token| nusc.attribute_map.get(token))
+                    .map(|attribute| attribute.name.to_owned())
+                    .collect();
+                let key = (
+                    sample_annotation.sample_token.to_string(),
+                    sample_annotation.instance_token.to_string(),
+                );
+                (
+                    key,
+                    ObjectAnnotation {
+                        visibility,
+                        attributes,
+                    },
+                )
+            })
+            .collect();
+        Ok(Self { annotations })
+    }
+
+    /// Merge annotations of another map, such as one loaded from another version of the dataset.
+    ///
+    /// * `other`   - AnnotationMap instance to be merged.
+    pub fn merge(&mut self, other: Self) {
+        self.annotations.extend(other.annotations);
+    }
+
+    /// Insert the annotation of the object.
+    ///
+    /// * `sample_token`    - Token of the sample.
+    /// * `uuid`            - Instance token of the object.
+    /// * `annotation`      - ObjectAnnotation instance.
+    pub fn insert(&mut self, sample_token: &str, uuid: &str, annotation: ObjectAnnotation) {
+        self.annotations
+            .insert((sample_token.to_owned(), uuid.to_owned()), annotation);
+    }
+
+    /// Returns the annotation of the object in the sample.
+    /// If the object is not annotated in the sample, returns None.
+    ///
+    /// * `sample_token`    - Token of the sample.
+    /// * `uuid`            - Instance token of the object.
+    pub fn get(&self, sample_token: &str, uuid: &str) -> Option<&ObjectAnnotation> {
+        self.annotations
+            .get(&(sample_token.to_owned(), uuid.to_owned()))
+    }
+
+    /// Returns the number of annotated objects.
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    /// Returns whether there is no annotated object.
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+}
+
+/// Returns the name of the visibility level in the nuScenes format.
+///
+/// * `level`   - VisibilityLevel instance.
+fn visibility_name(level: &VisibilityLevel) -> &'static str {
+    match level {
+        VisibilityLevel::None => "v0-40",
+        VisibilityLevel::Partial => "v40-60",
+        VisibilityLevel::Most => "v60-80",
+        VisibilityLevel::Full => "v80-100",
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{naive::NaiveDateTime, Duration};
+
+use super::FrameGroundTruth;
+
+/// Default tolerance to regard a frame as corresponding to a timestamp, 75 [ms].
+pub const DEFAULT_TIME_TOLERANCE_MS: i64 = 75;
+
+/// Sorted index of frame timestamps for nearest timestamp lookup in O(log n).
+///
+/// The index holds positions of frames in the slice it was built from,
+/// so it must be rebuilt when the slice is modified.
+///
+/// * `timestamps`  - Pairs of timestamp in [ms] and position of frame, sorted by timestamp.
+/// * `tolerance`   - Maximum difference of timestamps in [ms] to regard as corresponding.
+///
+/// # Examples
+/// ```
+/// use chrono::{DateTime, Duration};
+/// use perception_eval::dataset::{index::FrameIndex, FrameGroundTruth};
+///
+/// let frames = [300_000, 100_000, 200_000]
+///     .iter()
+///     .map(|&t| FrameGroundTruth::new(
+///         DateTime::from_timestamp_micros(t).unwrap().naive_utc(),
+///         Vec::new(),
+///     ))
+///     .collect::<Vec<_>>();
+///
+/// let index = FrameIndex::new(&frames);
+/// let timestamp = DateTime::from_timestamp_micros(120_000).unwrap().naive_utc();
+/// assert_eq!(index.find(&timestamp), Some(1));
+///
+/// let timestamp = DateTime::from_timestamp_micros(400_000).unwrap().naive_utc();
+/// assert_eq!(index.find(&timestamp), None);
+/// assert_eq!(index.with_tolerance(Duration::milliseconds(150)).find(&timestamp), Some(0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameIndex {
+    timestamps: Vec<(i64, usize)>,
+    tolerance: i64,
+}
+
+impl FrameIndex {
+    /// Construct `FrameIndex` with the default tolerance.
+    ///
+    /// * `frame_ground_truths` - List of FrameGroundTruth instances.
+    pub fn new(frame_ground_truths: &[FrameGroundTruth]) -> Self {
+        let mut timestamps = frame_ground_truths
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| (frame.timestamp.and_utc().timestamp_millis(), i))
+            .collect::<Vec<_>>();
+        timestamps.sort_unstable();
+        Self {
+            timestamps,
+            tolerance: DEFAULT_TIME_TOLERANCE_MS,
+        }
+    }
+
+    /// Set the maximum difference of timestamps to regard as corresponding.
+    ///
+    /// * `tolerance`   - Tolerance of timestamp difference.
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance.num_milliseconds();
+        self
+    }
+
+    /// Returns the number of indexed frames.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Returns whether there is no indexed frame.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Returns the position of the frame which has the nearest timestamp and the difference in [ms].
+    /// If frames have the same difference, the earliest one in the original slice is returned.
+    ///
+    /// * `timestamp`   - Target timestamp.
+    pub fn nearest(&self, timestamp: &NaiveDateTime) -> Option<(usize, i64)> {
+        let target_time = timestamp.and_utc().timestamp_millis();
+        let first_at = |time: i64| self.timestamps.partition_point(|&(t, _)| t < time);
+
+        // Entries are sorted by (timestamp, position), so the first entry of each timestamp
+        // has the earliest position among frames with the same timestamp.
+        let upper = first_at(target_time);
+        let lower = upper.checked_sub(1).map(|i| first_at(self.timestamps[i].0));
+        [lower, Some(upper)]
+            .iter()
+            .flatten()
+            .filter_map(|&i| self.timestamps.get(i))
+            .map(|&(time, i)| (i, (time - target_time).abs()))
+            .min_by_key(|&(i, diff)| (diff, i))
+    }
+
+    /// Returns the position of the frame which has the nearest timestamp within the tolerance.
+    /// If there is no frame within the tolerance, returns None.
+    ///
+    /// * `timestamp`   - Target timestamp.
+    pub fn find(&self, timestamp: &NaiveDateTime) -> Option<usize> {
+        self.nearest(timestamp)
+            .and_then(|(index, diff)| (diff < self.tolerance).then_some(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameIndex;
+    use crate::dataset::{get_current_frame, FrameGroundTruth};
+    use chrono::DateTime;
+
+    #[test]
+    fn test_frame_index() {
+        // Unsorted timestamps with a duplicate.
+        let frames = [500_000, 100_000, 300_000, 100_000, 700_000]
+            .iter()
+            .map(|&t| FrameGroundTruth {
+                timestamp: DateTime::from_timestamp_micros(t).unwrap().naive_utc(),
+                objects: Vec::new(),
+                ego_translation: None,
+                ego_rotation: None,
+                sample_token: None,
+                version: None,
+                raw_pointcloud: None,
+                location: None,
+            })
+            .collect::<Vec<_>>();
+        let index = FrameIndex::new(&frames);
+        assert_eq!(index.len(), 5);
+
+        for t in (0..900_000).step_by(10_000) {
+            let timestamp = DateTime::from_timestamp_micros(t).unwrap().naive_utc();
+            let expected = get_current_frame(&frames, &timestamp);
+            let actual = index.find(&timestamp).map(|i| frames[i].clone());
+            assert_eq!(actual, expected, "timestamp: {}", timestamp);
+        }
+
+        // Equidistant frames resolve to the earliest one in the slice.
+        let timestamp = DateTime::from_timestamp_micros(400_000)
+            .unwrap()
+            .naive_utc();
+        assert_eq!(index.nearest(&timestamp), Some((0, 100)));
+        let timestamp = DateTime::from_timestamp_micros(90_000).unwrap().naive_utc();
+        assert_eq!(index.nearest(&timestamp), Some((1, 10)));
+
+        assert_eq!(FrameIndex::new(&[]).nearest(&timestamp), None);
+    }
+}
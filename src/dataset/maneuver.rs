@@ -0,0 +1,258 @@
+use std::{
+    collections::HashMap,
+    f64::consts::PI,
+    fmt::{Display, Formatter, Result as FormatResult},
+};
+
+use chrono::NaiveDateTime;
+
+use crate::{object::object3d::DynamicObject, utils::point::distance_points_bev};
+
+use super::FrameGroundTruth;
+
+/// Maneuver of GT object derived from kinematics over its annotation chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Maneuver {
+    /// Absolute yaw rate exceeds the threshold.
+    Turning,
+    /// Absolute longitudinal acceleration exceeds the threshold, including braking.
+    Accelerating,
+    /// Object ahead moves laterally into the ego lane.
+    CuttingIn,
+    /// None of the other maneuvers.
+    Steady,
+}
+
+impl Maneuver {
+    /// All maneuvers in order of display.
+    pub const ALL: [Maneuver; 4] = [
+        Maneuver::Turning,
+        Maneuver::Accelerating,
+        Maneuver::CuttingIn,
+        Maneuver::Steady,
+    ];
+}
+
+impl Display for Maneuver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Maneuver flags of GT object at one frame. An object can have multiple maneuvers at once.
+///
+/// * `turning`         - Whether the object is turning.
+/// * `accelerating`    - Whether the object is accelerating or braking.
+/// * `cutting_in`      - Whether the object is cutting into the ego lane.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ManeuverFlags {
+    pub turning: bool,
+    pub accelerating: bool,
+    pub cutting_in: bool,
+}
+
+impl ManeuverFlags {
+    /// Returns whether the object has the maneuver.
+    ///
+    /// * `maneuver`    - Maneuver instance.
+    pub fn contains(&self, maneuver: &Maneuver) -> bool {
+        match maneuver {
+            Maneuver::Turning => self.turning,
+            Maneuver::Accelerating => self.accelerating,
+            Maneuver::CuttingIn => self.cutting_in,
+            Maneuver::Steady => !(self.turning || self.accelerating || self.cutting_in),
+        }
+    }
+}
+
+/// Thresholds to derive maneuver flags.
+///
+/// Positions are regarded as in `base_link`, where x is forward and y is left of the ego vehicle.
+///
+/// * `yaw_rate_threshold`      - Absolute yaw rate in [rad/s] above which an object is turning.
+/// * `acceleration_threshold`  - Absolute acceleration in [m/s^2] above which an object is accelerating.
+/// * `lane_half_width`         - Half width of the ego lane in [m] to determine cutting-in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManeuverParams {
+    pub yaw_rate_threshold: f64,
+    pub acceleration_threshold: f64,
+    pub lane_half_width: f64,
+}
+
+impl Default for ManeuverParams {
+    fn default() -> Self {
+        Self {
+            yaw_rate_threshold: 0.2,
+            acceleration_threshold: 1.0,
+            lane_half_width: 1.75,
+        }
+    }
+}
+
+/// Maneuver flags of GT objects keyed by uuid and timestamp of the frame.
+///
+/// Kinematics of each object is computed with the previous and next annotations of the same uuid.
+/// Objects without uuid or annotated only once have no flags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ManeuverMap {
+    flags: HashMap<(String, NaiveDateTime), ManeuverFlags>,
+}
+
+impl ManeuverMap {
+    /// Construct `ManeuverMap` from the annotation chain of GTs.
+    ///
+    /// * `frame_ground_truths` - List of FrameGroundTruth.
+    /// * `params`              - Thresholds to derive maneuver flags.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{DateTime, Duration};
+    /// use perception_eval::{
+    ///     dataset::{maneuver::{ManeuverMap, ManeuverParams}, FrameGroundTruth},
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let timestamp = DateTime::from_timestamp_micros(0).unwrap().naive_utc();
+    /// // The car keeps 10 [m/s] while its heading changes by 0.1 [rad] every 0.1 [s].
+    /// let frames = (0..3)
+    ///     .map(|i| {
+    ///         let yaw = 0.1 * i as f64;
+    ///         let object = DynamicObject::new(
+    ///             timestamp + Duration::milliseconds(100 * i),
+    ///             FrameID::BaseLink,
+    ///             [10.0 + i as f64, 5.0, 0.0],
+    ///             [(0.5 * yaw).cos(), 0.0, 0.0, (0.5 * yaw).sin()],
+    ///             [4.0, 2.0, 1.5],
+    ///             1.0,
+    ///             Label::Car,
+    ///         )
+    ///         .with_velocity([10.0, 0.0, 0.0])
+    ///         .with_pointcloud_num(1000)
+    ///         .with_uuid("car");
+    ///         FrameGroundTruth::new(object.timestamp, vec![object])
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let maneuvers = ManeuverMap::new(&frames, &ManeuverParams::default());
+    /// let flags = maneuvers.get("car", &frames[1].timestamp).unwrap();
+    /// assert!(flags.turning);
+    /// assert!(!flags.accelerating);
+    /// ```
+    pub fn new(frame_ground_truths: &[FrameGroundTruth], params: &ManeuverParams) -> Self {
+        let mut chains: HashMap<&str, Vec<(NaiveDateTime, &DynamicObject)>> = HashMap::new();
+        frame_ground_truths.iter().for_each(|frame| {
+            frame.objects.iter().for_each(|object| {
+                if let Some(uuid) = &object.uuid {
+                    chains
+                        .entry(uuid.as_str())
+                        .or_default()
+                        .push((frame.timestamp, object));
+                }
+            });
+        });
+
+        let mut flags = HashMap::new();
+        chains.into_iter().for_each(|(uuid, mut chain)| {
+            chain.sort_by_key(|(timestamp, _)| *timestamp);
+            for i in 0..chain.len() {
+                let prev = i.checked_sub(1).map(|j| chain[j]);
+                let next = chain.get(i + 1).copied();
+                if let Some(maneuver) = derive_flags(prev, chain[i], next, params) {
+                    flags.insert((uuid.to_string(), chain[i].0), maneuver);
+                }
+            }
+        });
+        Self { flags }
+    }
+
+    /// Returns maneuver flags of the object at the frame.
+    /// If the object has no flags, returns None.
+    ///
+    /// * `uuid`        - Uuid of the object.
+    /// * `timestamp`   - Timestamp of the frame.
+    pub fn get(&self, uuid: &str, timestamp: &NaiveDateTime) -> Option<ManeuverFlags> {
+        self.flags.get(&(uuid.to_string(), *timestamp)).copied()
+    }
+
+    /// Returns the number of objects with flags.
+    pub fn len(&self) -> usize {
+        self.flags.len()
+    }
+
+    /// Returns whether there is no object with flags.
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+}
+
+/// Derive maneuver flags of the object from its previous and next annotations.
+/// If neither of them exists, returns None.
+///
+/// * `prev`    - Previous annotation with timestamp of the frame.
+/// * `current` - Current annotation with timestamp of the frame.
+/// * `next`    - Next annotation with timestamp of the frame.
+/// * `params`  - Thresholds to derive maneuver flags.
+fn derive_flags(
+    prev: Option<(NaiveDateTime, &DynamicObject)>,
+    current: (NaiveDateTime, &DynamicObject),
+    next: Option<(NaiveDateTime, &DynamicObject)>,
+    params: &ManeuverParams,
+) -> Option<ManeuverFlags> {
+    let seconds = |from: &NaiveDateTime, to: &NaiveDateTime| {
+        (*to - *from).num_microseconds().unwrap_or(i64::MAX) as f64 * 1e-6
+    };
+    let (first, last) = match (prev, next) {
+        (None, None) => return None,
+        (first, last) => (first.unwrap_or(current), last.unwrap_or(current)),
+    };
+    let elapsed = seconds(&first.0, &last.0);
+    if elapsed <= 0.0 {
+        return None;
+    }
+
+    let mut yaw_diff = yaw(first.1) - yaw(last.1);
+    yaw_diff = (yaw_diff + PI).rem_euclid(2.0 * PI) - PI;
+    let turning = params.yaw_rate_threshold < yaw_diff.abs() / elapsed;
+
+    let speed = |object: &DynamicObject| object.velocity.map(|v| v[0].hypot(v[1]));
+    let acceleration = match (speed(first.1), speed(last.1), prev, next) {
+        (Some(first_speed), Some(last_speed), _, _) => Some((last_speed - first_speed) / elapsed),
+        (_, _, Some(prev), Some(next)) => {
+            let prev_elapsed = seconds(&prev.0, &current.0);
+            let next_elapsed = seconds(&current.0, &next.0);
+            let prev_speed =
+                distance_points_bev(&prev.1.position, &current.1.position) / prev_elapsed;
+            let next_speed =
+                distance_points_bev(&current.1.position, &next.1.position) / next_elapsed;
+            Some((next_speed - prev_speed) / (0.5 * elapsed))
+        }
+        _ => None,
+    };
+    let accelerating =
+        acceleration.is_some_and(|acceleration| params.acceleration_threshold < acceleration.abs());
+
+    let cutting_in = match (prev, next) {
+        (Some(prev), Some(next)) => {
+            0.0 < next.1.position[0]
+                && params.lane_half_width <= prev.1.position[1].abs()
+                && next.1.position[1].abs() < params.lane_half_width
+        }
+        _ => false,
+    };
+
+    Some(ManeuverFlags {
+        turning,
+        accelerating,
+        cutting_in,
+    })
+}
+
+/// Returns yaw angle of the object in [-pi, pi].
+///
+/// * `object`  - DynamicObject instance.
+fn yaw(object: &DynamicObject) -> f64 {
+    let [q0, q1, q2, q3] = object.orientation;
+    (2.0 * (q0 * q3 + q1 * q2)).atan2(1.0 - 2.0 * (q2.powi(2) + q3.powi(2)))
+}
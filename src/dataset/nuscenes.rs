@@ -1,8 +1,11 @@
 pub mod r#box;
+pub mod convert;
 pub mod error;
 pub mod internal;
 pub mod iter;
 pub mod iter_impl;
+#[cfg(feature = "parallel-json")]
+pub mod json;
 pub mod schema;
 
 use self::{
@@ -16,14 +19,18 @@ use self::{
     },
 };
 
+use crate::utils::math::slerp;
+use chrono::NaiveDateTime;
 use image::DynamicImage;
 use itertools::Itertools;
 use nalgebra::{Dyn, Matrix, VecStorage, U5};
+use safe_transmute::guard::SingleManyGuard;
 use serde::de::DeserializeOwned;
 use std::{
+    cmp::Ordering,
     collections::{hash_map::Keys as HashMapKeys, HashMap},
     fs::File,
-    io::BufReader,
+    io::{BufReader, Read},
     marker::PhantomData,
     ops::Deref,
     path::{Path, PathBuf},
@@ -32,6 +39,29 @@ use std::{
 
 pub type PointCloudMatrix = Matrix<f32, Dyn, U5, VecStorage<f32, Dyn, U5>>;
 
+/// Load pointcloud from the binary file, which consists of 5 float values for each point,
+/// ordering (x, y, z, intensity, ring index).
+///
+/// * `path`    - Path of the binary file.
+pub fn load_pointcloud<P>(path: P) -> NuScenesResult<PointCloudMatrix>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let mut bytes = vec![];
+    BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+    let values = safe_transmute::transmute_many::<f32, SingleManyGuard>(&bytes)
+        .map_err(|_| NuScenesError::CorruptedFile(path.to_path_buf()))?;
+    if values.len() % 5 != 0 {
+        return Err(NuScenesError::CorruptedFile(path.to_path_buf()));
+    }
+    let n_rows = values.len() / 5;
+
+    // TODO: this step takes one copy of the buffer. try to use more efficient impl.
+    let storage = VecStorage::new(Dyn(n_rows), U5, Vec::from(values));
+    Ok(PointCloudMatrix::from_data(storage))
+}
+
 /// Struct to load NuScenes dataset.
 ///
 /// # Examples
@@ -104,55 +134,55 @@ impl NuScenes {
         // load JSON files
         let attribute_list: Vec<Attribute> = {
             let attribute_path = meta_dir.join("attribute.json");
-            load_json(attribute_path)?
+            load_json_list(attribute_path)?
         };
         let calibrated_sensor_list: Vec<CalibratedSensor> = {
             let calibrated_sensor_path = meta_dir.join("calibrated_sensor.json");
-            load_json(calibrated_sensor_path)?
+            load_json_list(calibrated_sensor_path)?
         };
         let category_list: Vec<Category> = {
             let category_path = meta_dir.join("category.json");
-            load_json(category_path)?
+            load_json_list(category_path)?
         };
         let ego_pose_list: Vec<EgoPose> = {
             let ego_pose_path = meta_dir.join("ego_pose.json");
-            load_json(ego_pose_path)?
+            load_json_list(ego_pose_path)?
         };
         let instance_list: Vec<Instance> = {
             let instance_path = meta_dir.join("instance.json");
-            load_json(instance_path)?
+            load_json_list(instance_path)?
         };
         let log_list: Vec<Log> = {
             let log_path = meta_dir.join("log.json");
-            load_json(log_path)?
+            load_json_list(log_path)?
         };
         let map_list: Vec<Map> = {
             let map_path = meta_dir.join("map.json");
-            load_json(map_path)?
+            load_json_list(map_path)?
         };
         let sample_list: Vec<Sample> = {
             let sample_path = meta_dir.join("sample.json");
-            load_json(sample_path)?
+            load_json_list(sample_path)?
         };
         let sample_annotation_list: Vec<SampleAnnotation> = {
             let sample_annotation_path = meta_dir.join("sample_annotation.json");
-            load_json(sample_annotation_path)?
+            load_json_list(sample_annotation_path)?
         };
         let sample_data_list: Vec<SampleData> = {
             let sample_data_path = meta_dir.join("sample_data.json");
-            load_json(sample_data_path)?
+            load_json_list(sample_data_path)?
         };
         let scene_list: Vec<Scene> = {
             let scene_path = meta_dir.join("scene.json");
-            load_json(scene_path)?
+            load_json_list(scene_path)?
         };
         let sensor_list: Vec<Sensor> = {
             let sensor_path = meta_dir.join("sensor.json");
-            load_json(sensor_path)?
+            load_json_list(sensor_path)?
         };
         let visibility_list: Vec<Visibility> = {
             let visibility_path = meta_dir.join("visibility.json");
-            load_json(visibility_path)?
+            load_json_list(visibility_path)?
         };
 
         // index items by tokens
@@ -534,10 +564,16 @@ impl NuScenes {
             .map(|(sample_token, sample)| {
                 let sample_data_tokens = sample_to_sample_data_groups
                     .remove(&sample_token)
-                    .ok_or(NuScenesError::InternalBug)?;
+                    .ok_or_else(|| {
+                        NuScenesError::CorruptedDataset(format!(
+                            "the sample with token {} has no sample data",
+                            sample_token
+                        ))
+                    })?;
+                // Samples without any annotation are valid.
                 let annotation_tokens = sample_to_annotation_groups
                     .remove(&sample_token)
-                    .ok_or(NuScenesError::InternalBug)?;
+                    .unwrap_or_default();
                 let internal = SampleInternal::from(sample, annotation_tokens, sample_data_tokens);
                 Ok((sample_token, internal))
             })
@@ -594,15 +630,24 @@ impl NuScenes {
                         .sample_tokens
                         .iter()
                         .map(|sample_token| {
-                            let sample = sample_internal_map
-                                .get(sample_token)
-                                .ok_or(NuScenesError::InternalBug)?;
+                            let sample =
+                                sample_internal_map.get(sample_token).ok_or_else(|| {
+                                    NuScenesError::CorruptedDataset(format!(
+                                        "the scene with token {} refers to unknown sample {}",
+                                        scene_token, sample_token
+                                    ))
+                                })?;
                             Ok(sample.timestamp)
                         })
                         .collect::<NuScenesResult<Vec<_>>>()?
                         .into_iter()
                         .min()
-                        .ok_or(NuScenesError::InternalBug)?;
+                        .ok_or_else(|| {
+                            NuScenesError::CorruptedDataset(format!(
+                                "the scene with token {} has no sample",
+                                scene_token
+                            ))
+                        })?;
 
                     Ok((scene_token, timestamp))
                 })
@@ -726,6 +771,42 @@ impl NuScenes {
         sample_data_token: &LongToken,
         use_sensor_frame: &bool,
     ) -> NuScenesResult<(PathBuf, Vec<NuScenesBox>)> {
+        let data_path = self.get_sample_data_path(sample_data_token)?;
+        let boxes = self.get_boxes(sample_data_token)?;
+        let boxes = self.transform_boxes(sample_data_token, boxes, use_sensor_frame)?;
+        Ok((data_path, boxes))
+    }
+
+    /// Returns the data path and boxes which are interpolated at the timestamp of the sample data.
+    ///
+    /// Even for key frames, the timestamp of the sample data can differ from the one of the sample,
+    /// e.g. camera capture timestamps. Boxes are shifted to the sample data timestamp by
+    /// interpolating the motion of each instance between adjacent annotations.
+    ///
+    /// * `sample_data_token`   - Token of the sample data.
+    /// * `use_sensor_frame`    - Whether to transform boxes into the sensor coord system.
+    pub fn get_sample_data_interpolated(
+        &self,
+        sample_data_token: &LongToken,
+        use_sensor_frame: &bool,
+    ) -> NuScenesResult<(PathBuf, Vec<NuScenesBox>)> {
+        let data_path = self.get_sample_data_path(sample_data_token)?;
+        let boxes = self.get_interpolated_boxes(sample_data_token)?;
+        let boxes = self.transform_boxes(sample_data_token, boxes, use_sensor_frame)?;
+        Ok((data_path, boxes))
+    }
+
+    /// Transforms boxes from the global coord system into the ego vehicle or sensor coord system.
+    ///
+    /// * `sample_data_token`   - Token of the sample data.
+    /// * `boxes`               - Boxes in the global coord system.
+    /// * `use_sensor_frame`    - Whether to transform boxes into the sensor coord system.
+    fn transform_boxes(
+        &self,
+        sample_data_token: &LongToken,
+        mut boxes: Vec<NuScenesBox>,
+        use_sensor_frame: &bool,
+    ) -> NuScenesResult<Vec<NuScenesBox>> {
         let sd_record = match self.sample_data_map.get(sample_data_token) {
             Some(record) => record,
             None => {
@@ -761,9 +842,6 @@ impl NuScenes {
             }
         };
 
-        let data_path = self.get_sample_data_path(sample_data_token)?;
-
-        let mut boxes = self.get_boxes(sample_data_token)?;
         boxes.iter_mut().for_each(|nusc_box| {
             // Move box to ego vehicle coord system
             nusc_box.translate_inv(&pose_record.translation);
@@ -776,7 +854,7 @@ impl NuScenes {
             }
         });
 
-        Ok((data_path, boxes))
+        Ok(boxes)
     }
 
     pub fn get_boxes(&self, sample_data_token: &LongToken) -> NuScenesResult<Vec<NuScenesBox>> {
@@ -853,16 +931,149 @@ impl NuScenes {
                 Err(NuScenesError::CorruptedDataset(msg))?
             }
         };
+        let velocity = self.get_box_velocity(record)?;
         Ok(NuScenesBox {
             position: record.translation,
             orientation: record.rotation,
             size: record.size,
+            velocity,
             name: category_name.to_string(),
             instance: record.instance_token.to_owned(),
             num_lidar_pts: record.num_lidar_pts,
             token: record.token.to_owned(),
         })
     }
+
+    /// Returns boxes of the sample which the sample data belongs to,
+    /// interpolated at the timestamp of the sample data.
+    ///
+    /// * `sample_data_token`   - Token of the sample data.
+    pub fn get_interpolated_boxes(
+        &self,
+        sample_data_token: &LongToken,
+    ) -> NuScenesResult<Vec<NuScenesBox>> {
+        let sd_record = match self.sample_data_map.get(sample_data_token) {
+            Some(record) => record,
+            None => {
+                let msg = format!(
+                    "There is not corresponding sample_data for token: {}",
+                    sample_data_token
+                );
+                Err(NuScenesError::CorruptedDataset(msg))?
+            }
+        };
+        let sample_record = match self.sample_map.get(&sd_record.sample_token) {
+            Some(record) => record,
+            None => {
+                let msg = format!(
+                    "There is no corresponding sample for token: {}",
+                    &sd_record.sample_token
+                );
+                Err(NuScenesError::CorruptedDataset(msg))?
+            }
+        };
+        sample_record
+            .annotation_tokens
+            .iter()
+            .map(|token| self.get_box_at(token, &sd_record.timestamp))
+            .collect::<NuScenesResult<Vec<NuScenesBox>>>()
+    }
+
+    /// Returns the box of the sample annotation interpolated at the input timestamp.
+    ///
+    /// The position and rotation are interpolated with the previous or next annotation of
+    /// the same instance. If there is no adjacent annotation in the direction of the timestamp,
+    /// the box of the annotation is returned as it is.
+    ///
+    /// * `sample_annotation_token` - Token of the sample annotation.
+    /// * `timestamp`               - Target timestamp.
+    pub fn get_box_at(
+        &self,
+        sample_annotation_token: &LongToken,
+        timestamp: &NaiveDateTime,
+    ) -> NuScenesResult<NuScenesBox> {
+        let mut nusc_box = self.get_box(sample_annotation_token)?;
+        let record = &self.sample_annotation_map[sample_annotation_token];
+        let sample_timestamp = self.get_sample_timestamp(&record.sample_token)?;
+
+        let adjacent_token = match timestamp.cmp(&sample_timestamp) {
+            Ordering::Greater => record.next.as_ref(),
+            Ordering::Less => record.prev.as_ref(),
+            Ordering::Equal => None,
+        };
+        let adjacent_record = match adjacent_token.and_then(|t| self.sample_annotation_map.get(t)) {
+            Some(adjacent) => adjacent,
+            None => return Ok(nusc_box),
+        };
+        let adjacent_timestamp = self.get_sample_timestamp(&adjacent_record.sample_token)?;
+
+        let total = (adjacent_timestamp - sample_timestamp).num_microseconds();
+        let elapsed = (*timestamp - sample_timestamp).num_microseconds();
+        if let (Some(total), Some(elapsed)) = (total, elapsed) {
+            if total != 0 {
+                let ratio = elapsed as f64 / total as f64;
+                for i in 0..3 {
+                    nusc_box.position[i] +=
+                        (adjacent_record.translation[i] - record.translation[i]) * ratio;
+                }
+                nusc_box.orientation = slerp(&record.rotation, &adjacent_record.rotation, ratio);
+            }
+        }
+        Ok(nusc_box)
+    }
+
+    /// Returns the velocity of the sample annotation in the global coordinate system,
+    /// estimated by the finite difference of the previous and next annotations of the same instance.
+    ///
+    /// If there is no adjacent annotation, or the time difference between them exceeds
+    /// `MAX_VELOCITY_TIME_DIFF` seconds, returns None.
+    ///
+    /// * `record`  - Sample annotation.
+    fn get_box_velocity(&self, record: &SampleAnnotation) -> NuScenesResult<Option<[f64; 3]>> {
+        const MAX_VELOCITY_TIME_DIFF: f64 = 1.5;
+
+        let prev = record
+            .prev
+            .as_ref()
+            .and_then(|t| self.sample_annotation_map.get(t));
+        let next = record
+            .next
+            .as_ref()
+            .and_then(|t| self.sample_annotation_map.get(t));
+        let (first, last) = match (prev, next) {
+            (None, None) => return Ok(None),
+            (first, last) => (first.unwrap_or(record), last.unwrap_or(record)),
+        };
+
+        let first_timestamp = self.get_sample_timestamp(&first.sample_token)?;
+        let last_timestamp = self.get_sample_timestamp(&last.sample_token)?;
+        let time_diff = match (last_timestamp - first_timestamp).num_microseconds() {
+            Some(diff) => diff as f64 * 1e-6,
+            None => return Ok(None),
+        };
+        if time_diff <= 0.0 || MAX_VELOCITY_TIME_DIFF < time_diff {
+            return Ok(None);
+        }
+
+        let mut velocity = [0.0; 3];
+        for (i, v) in velocity.iter_mut().enumerate() {
+            *v = (last.translation[i] - first.translation[i]) / time_diff;
+        }
+        Ok(Some(velocity))
+    }
+
+    fn get_sample_timestamp(&self, sample_token: &LongToken) -> NuScenesResult<NaiveDateTime> {
+        match self.sample_map.get(sample_token) {
+            Some(record) => Ok(record.timestamp),
+            None => {
+                let msg = format!(
+                    "There is no corresponding sample for token: {}",
+                    sample_token
+                );
+                Err(NuScenesError::CorruptedDataset(msg))
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -903,15 +1114,95 @@ impl<'a, T> Deref for WithDataset<'a, T> {
     }
 }
 
+/// Load the JSON table. If compressed variants of the file exist, they are preferred in order of
+/// `<path>.zst` and `<path>.gz`, and the loaded variant is reported.
+///
+/// * `path`    - Path of the uncompressed JSON file.
 fn load_json<T, P>(path: P) -> NuScenesResult<T>
 where
     P: AsRef<Path>,
     T: DeserializeOwned,
 {
-    let reader = BufReader::new(File::open(path.as_ref())?);
-    let value = serde_json::from_reader(reader).map_err(|err| {
-        let msg = format!("failed to load file {}: {:?}", path.as_ref().display(), err);
+    let path = path.as_ref();
+    let zst_path = append_extension(path, "zst");
+    let gz_path = append_extension(path, "gz");
+    let (path, reader): (&Path, Box<dyn Read>) = if zst_path.exists() {
+        let decoder = zstd::Decoder::new(File::open(&zst_path)?)?;
+        (&zst_path, Box::new(decoder))
+    } else if gz_path.exists() {
+        let decoder = flate2::read::GzDecoder::new(BufReader::new(File::open(&gz_path)?));
+        (&gz_path, Box::new(decoder))
+    } else {
+        (path, Box::new(BufReader::new(File::open(path)?)))
+    };
+    log::info!("loading {}", path.display());
+    let value = serde_json::from_reader(BufReader::new(reader)).map_err(|err| {
+        let msg = format!("failed to load file {}: {:?}", path.display(), err);
         NuScenesError::CorruptedDataset(msg)
     })?;
     Ok(value)
 }
+
+/// Load the JSON table of the list of items.
+///
+/// With the `parallel-json` feature, uncompressed files are read into memory and
+/// large ones are deserialized on multiple threads.
+///
+/// * `path`    - Path of the uncompressed JSON file.
+fn load_json_list<T, P>(path: P) -> NuScenesResult<Vec<T>>
+where
+    P: AsRef<Path>,
+    T: DeserializeOwned + Send,
+{
+    #[cfg(feature = "parallel-json")]
+    {
+        let path = path.as_ref();
+        let is_compressed =
+            append_extension(path, "zst").exists() || append_extension(path, "gz").exists();
+        if !is_compressed {
+            log::info!("loading {}", path.display());
+            return json::load_json_array(path);
+        }
+    }
+    load_json(path)
+}
+
+/// Returns the path with the extension appended, e.g. `attribute.json` to `attribute.json.gz`.
+///
+/// * `path`        - Original path.
+/// * `extension`   - Extension to append.
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::load_json;
+    use std::{fs::File, io::Write};
+
+    #[test]
+    fn test_load_compressed_json() {
+        let dir = std::env::temp_dir().join("perception_eval_test_compressed_json");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("table.json");
+        std::fs::write(&path, "[1]").unwrap();
+        assert_eq!(load_json::<Vec<u32>, _>(&path).unwrap(), vec![1]);
+
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(dir.join("table.json.gz")).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(b"[2]").unwrap();
+        encoder.finish().unwrap();
+        assert_eq!(load_json::<Vec<u32>, _>(&path).unwrap(), vec![2]);
+
+        let compressed = zstd::encode_all(&b"[3]"[..], 0).unwrap();
+        std::fs::write(dir.join("table.json.zst"), compressed).unwrap();
+        assert_eq!(load_json::<Vec<u32>, _>(&path).unwrap(), vec![3]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -7,6 +7,7 @@ pub struct NuScenesBox {
     pub position: [f64; 3],
     pub orientation: [f64; 4],
     pub size: [f64; 3],
+    pub velocity: Option<[f64; 3]>,
     pub name: String,
     pub instance: LongToken,
     pub num_lidar_pts: usize,
@@ -48,10 +49,12 @@ impl NuScenesBox {
     pub fn rotate(&mut self, orientation: &[f64; 4]) {
         self.position = rotate(&self.position, orientation);
         self.orientation = rotate_q(&self.orientation, orientation);
+        self.velocity = self.velocity.map(|v| rotate(&v, orientation));
     }
 
     pub fn rotate_inv(&mut self, orientation: &[f64; 4]) {
         self.position = rotate_inv(&self.position, orientation);
         self.orientation = rotate_q_inv(&self.orientation, orientation);
+        self.velocity = self.velocity.map(|v| rotate_inv(&v, orientation));
     }
 }
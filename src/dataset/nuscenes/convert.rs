@@ -0,0 +1,269 @@
+use chrono::NaiveDateTime;
+
+use crate::{
+    frame_id::FrameID,
+    label::{Label, LabelConverter},
+    object::object3d::DynamicObject,
+};
+
+use super::{
+    error::{NuScenesError, NuScenesResult},
+    schema::{LongToken, SampleAnnotation},
+    NuScenes,
+};
+
+impl DynamicObject {
+    /// Convert the object into a new `SampleAnnotation` of the instance in the sample.
+    ///
+    /// The token of the annotation is derived from the instance token and the sample token,
+    /// so that the same pair always has the same token. Links to adjacent annotations,
+    /// attributes and visibility are empty, which should be filled by the caller if needed.
+    /// Use `update_sample_annotation()` to keep them of an existing annotation instead.
+    ///
+    /// The object must be in the `map` coord system, same as sample annotations.
+    ///
+    /// * `instance_token`  - Token of the instance which the object belongs to.
+    /// * `sample_token`    - Token of the sample which the object is annotated at.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDateTime;
+    /// use perception_eval::{
+    ///     dataset::nuscenes::schema::LongToken, frame_id::FrameID, label::Label,
+    ///     object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::default(),
+    ///     FrameID::Map,
+    ///     [10.0, 5.0, 1.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 4.0, 1.5],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(100);
+    /// let instance_token = LongToken::try_from("0123456789abcdef0123456789abcdef").unwrap();
+    /// let sample_token = LongToken::try_from("fedcba9876543210fedcba9876543210").unwrap();
+    ///
+    /// let annotation = object.to_sample_annotation(&instance_token, &sample_token).unwrap();
+    /// assert_eq!(annotation.translation, object.position);
+    /// assert_eq!(annotation.num_lidar_pts, 100);
+    ///
+    /// let restored = DynamicObject::from_sample_annotation(&annotation, object.timestamp, Label::Car);
+    /// assert_eq!(restored.position, object.position);
+    /// assert_eq!(restored.uuid, Some(instance_token.to_string()));
+    /// ```
+    pub fn to_sample_annotation(
+        &self,
+        instance_token: &LongToken,
+        sample_token: &LongToken,
+    ) -> NuScenesResult<SampleAnnotation> {
+        self.check_map_frame()?;
+        Ok(SampleAnnotation {
+            token: annotation_token(instance_token, sample_token),
+            num_lidar_pts: self.pointcloud_num.unwrap_or(0),
+            num_radar_pts: 0,
+            size: self.size,
+            rotation: self.orientation,
+            translation: self.position,
+            sample_token: sample_token.to_owned(),
+            instance_token: instance_token.to_owned(),
+            attribute_tokens: Vec::new(),
+            visibility_token: None,
+            prev: None,
+            next: None,
+        })
+    }
+
+    /// Returns the existing `SampleAnnotation` whose box is replaced with the object,
+    /// such as to write modified GTs.
+    ///
+    /// Tokens, links to adjacent annotations, attributes, visibility and the number of radar points
+    /// are kept. The number of lidar points is replaced only if the object has it.
+    ///
+    /// * `annotation`  - Existing annotation of the object.
+    pub fn update_sample_annotation(
+        &self,
+        annotation: &SampleAnnotation,
+    ) -> NuScenesResult<SampleAnnotation> {
+        self.check_map_frame()?;
+        Ok(SampleAnnotation {
+            num_lidar_pts: self.pointcloud_num.unwrap_or(annotation.num_lidar_pts),
+            size: self.size,
+            rotation: self.orientation,
+            translation: self.position,
+            ..annotation.to_owned()
+        })
+    }
+
+    /// Construct `DynamicObject` in the `map` coord system from `SampleAnnotation`,
+    /// whose uuid is the instance token.
+    ///
+    /// Velocity is not available from a single annotation. Use `NuScenes::get_object()`
+    /// to estimate it from adjacent annotations and to resolve the label from the category.
+    ///
+    /// * `annotation`  - SampleAnnotation instance.
+    /// * `timestamp`   - Timestamp of the sample.
+    /// * `label`       - Label of the instance.
+    pub fn from_sample_annotation(
+        annotation: &SampleAnnotation,
+        timestamp: NaiveDateTime,
+        label: Label,
+    ) -> Self {
+        Self {
+            timestamp,
+            frame_id: FrameID::Map,
+            position: annotation.translation,
+            orientation: annotation.rotation,
+            size: annotation.size,
+            velocity: None,
+            confidence: 1.0,
+            label,
+            pointcloud_num: Some(annotation.num_lidar_pts),
+            uuid: Some(annotation.instance_token.to_string()),
+            class_probabilities: None,
+        }
+    }
+
+    fn check_map_frame(&self) -> NuScenesResult<()> {
+        if self.frame_id != FrameID::Map {
+            let msg = format!(
+                "sample annotation must be in {:?}, but got {:?}",
+                FrameID::Map,
+                self.frame_id
+            );
+            Err(NuScenesError::ParseError(msg))?
+        }
+        Ok(())
+    }
+}
+
+impl NuScenes {
+    /// Returns `DynamicObject` of the sample annotation in the `map` coord system,
+    /// whose label is converted from the category and velocity is estimated from adjacent annotations.
+    ///
+    /// * `sample_annotation_token` - Token of the sample annotation.
+    pub fn get_object(&self, sample_annotation_token: &LongToken) -> NuScenesResult<DynamicObject> {
+        let nusc_box = self.get_box(sample_annotation_token)?;
+        let annotation = &self.sample_annotation_map[sample_annotation_token];
+        let timestamp = self.get_sample_timestamp(&annotation.sample_token)?;
+        let label_converter = LabelConverter::new("autoware").map_err(|err| {
+            NuScenesError::ParseError(format!("cannot construct label converter: {}", err))
+        })?;
+        Ok(DynamicObject {
+            velocity: nusc_box.velocity,
+            ..DynamicObject::from_sample_annotation(
+                annotation,
+                timestamp,
+                label_converter.convert(&nusc_box.name),
+            )
+        })
+    }
+}
+
+/// Returns the annotation token derived from the instance token and the sample token.
+///
+/// * `instance_token`  - Token of the instance.
+/// * `sample_token`    - Token of the sample.
+fn annotation_token(instance_token: &LongToken, sample_token: &LongToken) -> LongToken {
+    // FNV-1a is used instead of `DefaultHasher`, whose output is not stable across releases.
+    let text = format!("{}:{}", instance_token, sample_token);
+    let fnv1a = |offset_basis: u64| {
+        text.bytes().fold(offset_basis, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    };
+    let hex = format!(
+        "{:016x}{:016x}",
+        fnv1a(0xcbf29ce484222325),
+        fnv1a(0x84222325cbf29ce4)
+    );
+    LongToken::try_from(hex.as_str()).expect("hex of 16 bytes must be a valid token")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::annotation_token;
+    use crate::{
+        dataset::nuscenes::{schema::LongToken, NuScenes},
+        frame_id::FrameID,
+        object::object3d::DynamicObject,
+    };
+
+    #[test]
+    fn test_sample_annotation_round_trip() {
+        let nusc = NuScenes::load("annotation", "./tests/sample_data").unwrap();
+        let mut num_checked = 0;
+        for annotation in nusc.sample_annotation_map.values() {
+            let object = nusc.get_object(&annotation.token).unwrap();
+            assert_eq!(object.frame_id, FrameID::Map);
+            assert_eq!(object.uuid, Some(annotation.instance_token.to_string()));
+
+            // Metadata is kept while the box is updated.
+            let moved = DynamicObject {
+                position: [
+                    object.position[0] + 1.0,
+                    object.position[1],
+                    object.position[2],
+                ],
+                ..object.clone()
+            };
+            let updated = moved.update_sample_annotation(annotation).unwrap();
+            assert_eq!(updated.token, annotation.token);
+            assert_eq!(updated.attribute_tokens, annotation.attribute_tokens);
+            assert_eq!(updated.visibility_token, annotation.visibility_token);
+            assert_eq!(updated.prev, annotation.prev);
+            assert_eq!(updated.next, annotation.next);
+            assert_eq!(updated.translation, moved.position);
+
+            // New annotation has the stable token and the same box.
+            let created = object
+                .to_sample_annotation(&annotation.instance_token, &annotation.sample_token)
+                .unwrap();
+            assert_eq!(
+                created.token,
+                annotation_token(&annotation.instance_token, &annotation.sample_token)
+            );
+            assert_ne!(created.token, annotation.instance_token);
+            assert_eq!(created.translation, annotation.translation);
+            assert_eq!(created.rotation, annotation.rotation);
+            assert_eq!(created.size, annotation.size);
+            assert_eq!(created.num_lidar_pts, annotation.num_lidar_pts);
+            let restored = DynamicObject::from_sample_annotation(
+                &created,
+                object.timestamp,
+                object.label.to_owned(),
+            );
+            assert_eq!(
+                restored,
+                DynamicObject {
+                    velocity: None,
+                    ..object.clone()
+                }
+            );
+            num_checked += 1;
+        }
+        assert!(num_checked > 0);
+
+        // Objects in other coord systems are rejected.
+        let annotation = nusc.sample_annotation_map.values().next().unwrap();
+        let object = DynamicObject {
+            frame_id: FrameID::BaseLink,
+            ..nusc.get_object(&annotation.token).unwrap()
+        };
+        assert!(object.update_sample_annotation(annotation).is_err());
+
+        let token = |text: &str| LongToken::try_from(text).unwrap();
+        let instance = token("0123456789abcdef0123456789abcdef");
+        let sample = token("fedcba9876543210fedcba9876543210");
+        assert_eq!(
+            annotation_token(&instance, &sample),
+            annotation_token(&instance, &sample)
+        );
+        assert_ne!(
+            annotation_token(&instance, &sample),
+            annotation_token(&sample, &instance)
+        );
+    }
+}
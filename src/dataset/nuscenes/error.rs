@@ -5,12 +5,11 @@ use thiserror::Error as ThisError;
 pub type NuScenesResult<T> = Result<T, NuScenesError>;
 
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum NuScenesError {
-    #[error("internal error, please report bug")]
-    InternalBug,
     #[error("corrupted file: {0}")]
     CorruptedFile(PathBuf),
-    #[error("corrupted file: {0}")]
+    #[error("corrupted dataset: {0}")]
     CorruptedDataset(String),
     #[error("I/O error: {0}")]
     IoError(#[from] IoError),
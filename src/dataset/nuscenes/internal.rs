@@ -68,11 +68,18 @@ impl InstanceInternal {
         let mut annotation_tokens = vec![];
 
         while let Some(annotation_token) = annotation_token_opt {
-            let annotation = &sample_annotation_map
-                .get(annotation_token)
-                .ok_or(NuScenesError::InternalBug)?;
+            let annotation = &sample_annotation_map.get(annotation_token).ok_or_else(|| {
+                NuScenesError::CorruptedDataset(format!(
+                    "the instance with token {} refers to unknown annotation {}",
+                    token, annotation_token
+                ))
+            })?;
             if annotation_token != &annotation.token {
-                return Err(NuScenesError::InternalBug);
+                let msg = format!(
+                    "the annotation with token {} is stored as {}",
+                    annotation.token, annotation_token
+                );
+                return Err(NuScenesError::CorruptedDataset(msg));
             }
             annotation_tokens.push(annotation_token.clone());
             annotation_token_opt = annotation.next.as_ref();
@@ -131,9 +138,18 @@ impl SceneInternal {
         let mut sample_token_opt = Some(&first_sample_token);
 
         while let Some(sample_token) = sample_token_opt {
-            let sample = &sample_map[sample_token];
+            let sample = sample_map.get(sample_token).ok_or_else(|| {
+                NuScenesError::CorruptedDataset(format!(
+                    "the scene with token {} refers to unknown sample {}",
+                    token, sample_token
+                ))
+            })?;
             if &sample.token != sample_token {
-                return Err(NuScenesError::InternalBug);
+                let msg = format!(
+                    "the sample with token {} is stored as {}",
+                    sample.token, sample_token
+                );
+                return Err(NuScenesError::CorruptedDataset(msg));
             }
             sample_tokens.push(sample_token.clone());
             sample_token_opt = sample.next.as_ref();
@@ -3,11 +3,9 @@ use super::super::{
     internal::SampleInternal,
     iter::Iter,
     schema::{CalibratedSensor, EgoPose, FileFormat, LongToken, SampleData},
-    {LoadedSampleData, PointCloudMatrix, WithDataset},
+    {load_pointcloud, LoadedSampleData, WithDataset},
 };
 // use memmap::MmapOptions;
-use nalgebra::{Dyn, VecStorage, U5};
-use safe_transmute::guard::SingleManyGuard;
 use std::{
     fs::File,
     io::{prelude::*, BufReader, Result as IoResult},
@@ -38,32 +36,17 @@ impl<'a> WithDataset<'a, SampleData> {
                         .ok_or(NuScenesError::CorruptedDataset(format!("the filename {} in sample data with token {} is not valid", filename.display(), self.inner.token)))?;
 
         let data = match self.inner.fileformat {
-            FileFormat::Bin => {
-                match dirname {
-                    "lidar" => {
-                        let bytes = self.load_raw()?;
-                        let values = safe_transmute::transmute_many::<f32, SingleManyGuard>(&bytes)
-                            .map_err(|_| NuScenesError::CorruptedFile(path.clone()))?;
-                        if values.len() % 5 != 0 {
-                            return Err(NuScenesError::CorruptedFile(path));
-                        }
-                        let n_rows = values.len() / 5;
-
-                        // TODO: this step takes one copy of the buffer. try to use more efficient impl.
-                        let storage = VecStorage::new(Dyn(n_rows), U5, Vec::from(values));
-                        let matrix = PointCloudMatrix::from_data(storage);
-                        LoadedSampleData::PointCloud(matrix)
-                    }
-                    _ => {
-                        let msg = format!(
-                            "cannot determine the file format of {} from sample data with token {}",
-                            path.display(),
-                            self.inner.token
-                        );
-                        return Err(NuScenesError::CorruptedDataset(msg));
-                    }
+            FileFormat::Bin => match dirname {
+                "lidar" => LoadedSampleData::PointCloud(load_pointcloud(&path)?),
+                _ => {
+                    let msg = format!(
+                        "cannot determine the file format of {} from sample data with token {}",
+                        path.display(),
+                        self.inner.token
+                    );
+                    return Err(NuScenesError::CorruptedDataset(msg));
                 }
-            }
+            },
             FileFormat::Jpeg | FileFormat::Png => {
                 let image = image::open(path)?;
                 LoadedSampleData::Image(image)
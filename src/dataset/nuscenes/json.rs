@@ -0,0 +1,243 @@
+use std::{ops::Range, path::Path};
+
+use serde::de::DeserializeOwned;
+
+use super::error::{NuScenesError, NuScenesResult};
+
+/// Minimum size of JSON in bytes to be deserialized on multiple threads.
+/// Smaller JSON is deserialized on the current thread, since spawning threads does not pay off.
+pub const PARALLEL_MIN_BYTES: usize = 1 << 20;
+
+/// Returns byte ranges of elements of the top-level JSON array, excluding surrounding whitespace.
+/// If the input is not a well-formed top-level array, returns None.
+///
+/// Only the nesting of arrays, objects and strings is tracked, so that elements themselves
+/// are validated when they are deserialized.
+///
+/// * `bytes`   - JSON text.
+///
+/// # Examples
+/// ```
+/// use perception_eval::dataset::nuscenes::json::split_json_array;
+///
+/// let text = br#" [1, {"a": [2, 3]}, "4,]"] "#;
+/// let ranges = split_json_array(text).unwrap();
+///
+/// assert_eq!(ranges.len(), 3);
+/// assert_eq!(&text[ranges[1].clone()], br#"{"a": [2, 3]}"#);
+/// assert!(split_json_array(b"{}").is_none());
+/// ```
+pub fn split_json_array(bytes: &[u8]) -> Option<Vec<Range<usize>>> {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace())?;
+    if bytes[start] != b'[' {
+        return None;
+    }
+
+    let mut ranges = Vec::new();
+    let mut depth = 0_usize;
+    let mut element_start = start + 1;
+    let mut end = None;
+    let mut i = start + 1;
+    while i < bytes.len() {
+        // Skip bytes which do not change the structure, such as numbers and whitespace.
+        if !STRUCTURAL[bytes[i] as usize] {
+            i += 1;
+            continue;
+        }
+        match bytes[i] {
+            b'"' => i = skip_string(bytes, i + 1)?,
+            b'[' | b'{' => depth += 1,
+            b']' if depth == 0 => {
+                end = Some(i);
+                break;
+            }
+            b'}' if depth == 0 => return None,
+            b']' | b'}' => depth -= 1,
+            b',' if depth == 0 => {
+                ranges.push(trim_range(bytes, element_start..i)?);
+                element_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let end = end?;
+    match trim_range(bytes, element_start..end) {
+        Some(range) => ranges.push(range),
+        // Only `[]` can end without the last element.
+        None if !ranges.is_empty() => return None,
+        None => {}
+    }
+    if !bytes[end + 1..].iter().all(|b| b.is_ascii_whitespace()) {
+        return None;
+    }
+    Some(ranges)
+}
+
+/// Bytes which can change the nesting of arrays, objects and strings, or separate elements.
+const STRUCTURAL: [bool; 256] = {
+    let mut table = [false; 256];
+    let mut i = 0;
+    while i < 6 {
+        table[b"\"[]{},"[i] as usize] = true;
+        i += 1;
+    }
+    table
+};
+
+/// Returns the index of the closing quote of the string starting at `start`,
+/// which is the index next to the opening quote. If the string is not closed, returns None.
+fn skip_string(bytes: &[u8], mut start: usize) -> Option<usize> {
+    loop {
+        let i = start + memchr::memchr2(b'"', b'\\', &bytes[start..])?;
+        if bytes[i] == b'"' {
+            return Some(i);
+        }
+        // Skip the escaped character.
+        start = i + 2;
+        if start > bytes.len() {
+            return None;
+        }
+    }
+}
+
+/// Returns the range excluding surrounding whitespace. If the range is blank, returns None.
+fn trim_range(bytes: &[u8], range: Range<usize>) -> Option<Range<usize>> {
+    let slice = &bytes[range.clone()];
+    let start = slice.iter().position(|b| !b.is_ascii_whitespace())?;
+    let end = slice.iter().rposition(|b| !b.is_ascii_whitespace())? + 1;
+    Some(range.start + start..range.start + end)
+}
+
+/// Deserialize the top-level JSON array on multiple threads,
+/// each of which processes a contiguous chunk of elements.
+/// Elements are returned in the same order as in the array.
+///
+/// If the input is not a well-formed top-level array, it is deserialized on the current thread
+/// so that the error points to the location in the whole input.
+/// Errors of deserialization and panics of threads are returned as `NuScenesError::ParseError`.
+///
+/// * `bytes`       - JSON text.
+/// * `num_threads` - Number of threads.
+///
+/// # Examples
+/// ```
+/// use perception_eval::dataset::nuscenes::json::parse_json_array;
+///
+/// let values: Vec<u32> = parse_json_array(b"[1, 2, 3, 4, 5]", 2).unwrap();
+///
+/// assert_eq!(values, vec![1, 2, 3, 4, 5]);
+/// assert!(parse_json_array::<u32>(b"[1, 2,]", 2).is_err());
+/// ```
+pub fn parse_json_array<T>(bytes: &[u8], num_threads: usize) -> NuScenesResult<Vec<T>>
+where
+    T: DeserializeOwned + Send,
+{
+    let parse_error = |err: serde_json::Error| NuScenesError::ParseError(err.to_string());
+    let ranges = match split_json_array(bytes) {
+        Some(ranges) if !ranges.is_empty() => ranges,
+        _ => return serde_json::from_slice(bytes).map_err(parse_error),
+    };
+    let chunk_size = ranges.len().div_ceil(num_threads.max(1));
+    std::thread::scope(|scope| {
+        let handles = ranges
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|range| serde_json::from_slice::<T>(&bytes[range.clone()]))
+                        .collect::<serde_json::Result<Vec<_>>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        // Join all threads before returning errors, since the scope panics on unjoined panicked threads.
+        let chunk_results = handles
+            .into_iter()
+            .map(|handle| handle.join())
+            .collect::<Vec<_>>();
+        let mut values = Vec::with_capacity(ranges.len());
+        for chunk_result in chunk_results {
+            let chunk_values = chunk_result.map_err(|_| {
+                NuScenesError::ParseError("JSON parsing thread panicked".to_string())
+            })?;
+            values.extend(chunk_values.map_err(parse_error)?);
+        }
+        Ok(values)
+    })
+}
+
+/// Load the JSON array from the file.
+/// The whole file is read into memory, and files larger than `PARALLEL_MIN_BYTES`
+/// are deserialized on all available threads.
+///
+/// * `path`    - Path of the uncompressed JSON file.
+pub(crate) fn load_json_array<T, P>(path: P) -> NuScenesResult<Vec<T>>
+where
+    T: DeserializeOwned + Send,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    let num_threads = if bytes.len() < PARALLEL_MIN_BYTES {
+        1
+    } else {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    };
+    let values = if num_threads == 1 {
+        serde_json::from_slice(&bytes).map_err(|err| NuScenesError::ParseError(err.to_string()))
+    } else {
+        parse_json_array(&bytes, num_threads)
+    };
+    values.map_err(|err| {
+        let msg = format!("failed to load file {}: {}", path.display(), err);
+        NuScenesError::CorruptedDataset(msg)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_json_array, split_json_array};
+    use crate::dataset::nuscenes::error::NuScenesError;
+    use serde::{Deserialize, Deserializer};
+
+    #[test]
+    fn test_split_json_array() {
+        assert_eq!(split_json_array(b" [ ] ").unwrap(), vec![]);
+        assert_eq!(split_json_array(b"[1]").unwrap(), vec![1..2]);
+        // Brackets, commas and escaped quotes in strings are not boundaries.
+        let text = br#"["a\"],[", {"b": "}"}]"#;
+        let ranges = split_json_array(text).unwrap();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(&text[ranges[0].clone()], br#""a\"],[""#);
+        assert_eq!(&text[ranges[1].clone()], br#"{"b": "}"}"#);
+
+        assert!(split_json_array(b"[1,]").is_none());
+        assert!(split_json_array(b"[,1]").is_none());
+        assert!(split_json_array(b"[1] 2").is_none());
+        assert!(split_json_array(b"[1").is_none());
+        assert!(split_json_array(b"[1}]").is_none());
+
+        let values = (0..1000).collect::<Vec<u32>>();
+        let text = serde_json::to_vec_pretty(&values).unwrap();
+        assert_eq!(parse_json_array::<u32>(&text, 7).unwrap(), values);
+    }
+
+    #[test]
+    fn test_parse_json_array_thread_panic() {
+        struct PanickingValue;
+
+        impl<'de> Deserialize<'de> for PanickingValue {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                u32::deserialize(deserializer)?;
+                panic!("failed to deserialize");
+            }
+        }
+
+        assert!(matches!(
+            parse_json_array::<PanickingValue>(b"[1, 2, 3, 4]", 2),
+            Err(NuScenesError::ParseError(_))
+        ));
+    }
+}
@@ -237,7 +237,7 @@ pub enum VisibilityLevel {
     Full,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Channel {
     // camera
     #[serde(rename = "CAM_BACK")]
@@ -572,14 +572,14 @@ mod opt_string_serde {
 }
 
 mod timestamp_serde {
-    use chrono::NaiveDateTime;
+    use chrono::{DateTime, NaiveDateTime};
     use serde::{de::Error as DeserializeError, Deserialize, Deserializer, Serializer};
 
     pub fn serialize<S>(value: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let timestamp = value.timestamp_nanos_opt().unwrap() as f64 / 1_000_000_000.0;
+        let timestamp = value.and_utc().timestamp_nanos_opt().unwrap() as f64 / 1_000_000_000.0;
         serializer.serialize_f64(timestamp)
     }
 
@@ -591,7 +591,7 @@ mod timestamp_serde {
         let timestamp_ns = (timestamp_us * 1000.0) as u64; // in ns
         let secs = timestamp_ns / 1_000_000_000;
         let nsecs = timestamp_ns % 1_000_000_000;
-        let datetime = NaiveDateTime::from_timestamp_opt(secs as i64, nsecs as u32);
+        let datetime = DateTime::from_timestamp(secs as i64, nsecs as u32).map(|dt| dt.naive_utc());
         match datetime {
             Some(value) => Ok(value),
             None => Err(D::Error::custom("Could not load timestamp")),
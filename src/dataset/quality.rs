@@ -0,0 +1,560 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FormatResult},
+    io::BufWriter,
+    path::Path,
+};
+
+use chrono::NaiveDateTime;
+use geo::{Area, BooleanOps, Coord, LineString, Polygon};
+use serde::Serialize;
+
+use crate::{
+    fault::create_file, label::Label, object::object3d::DynamicObject,
+    utils::point::distance_points_bev,
+};
+
+use super::{
+    timeline::{FrameLabel, SceneTimeline},
+    DatasetResult, FrameGroundTruth,
+};
+
+/// Range of plausible object sizes, ordering (length, width, height) in [m].
+///
+/// * `min` - Minimum of sizes.
+/// * `max` - Maximum of sizes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SizeRange {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl SizeRange {
+    /// Construct `SizeRange`.
+    ///
+    /// * `min` - Minimum of sizes.
+    /// * `max` - Maximum of sizes.
+    pub fn new(min: [f64; 3], max: [f64; 3]) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns whether the size is within the range.
+    ///
+    /// * `size`    - Size of object.
+    pub fn contains(&self, size: &[f64; 3]) -> bool {
+        (0..3).all(|i| self.min[i] <= size[i] && size[i] <= self.max[i])
+    }
+}
+
+/// Parameters of heuristics to check the quality of GT annotations.
+///
+/// * `overlap_iou_threshold`   - BEV IoU above which GTs of the same label are regarded as overlapping.
+/// * `max_speed`               - Speed in [m/s] above which an instance is regarded as teleporting between frames.
+/// * `size_ranges`             - Plausible sizes for each label. Labels without range are only checked to be positive.
+///
+/// Positions are compared in the frame of the annotations, so in `base_link`
+/// the speed includes the ego motion and `max_speed` should have margin for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityParams {
+    pub overlap_iou_threshold: f64,
+    pub max_speed: f64,
+    pub size_ranges: HashMap<Label, SizeRange>,
+}
+
+impl Default for QualityParams {
+    fn default() -> Self {
+        let size_ranges = [
+            (Label::Car, [2.5, 1.3, 1.0], [6.5, 2.6, 2.6]),
+            (Label::Truck, [3.5, 1.5, 1.5], [20.0, 3.5, 4.5]),
+            (Label::Bus, [6.0, 2.0, 2.2], [20.0, 3.2, 4.5]),
+            (Label::Bicycle, [1.0, 0.3, 0.6], [2.5, 1.2, 2.2]),
+            (Label::Motorbike, [1.2, 0.4, 0.8], [3.0, 1.3, 2.2]),
+            (Label::Pedestrian, [0.2, 0.2, 0.8], [1.5, 1.5, 2.3]),
+        ]
+        .into_iter()
+        .map(|(label, min, max)| (label, SizeRange::new(min, max)))
+        .collect();
+        Self {
+            overlap_iou_threshold: 0.3,
+            max_speed: 100.0,
+            size_ranges,
+        }
+    }
+}
+
+/// Suspicious GT annotation found by heuristics.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QualityIssue {
+    /// Two GTs of the same label overlap heavily in BEV.
+    Overlap {
+        timestamp: NaiveDateTime,
+        label: String,
+        uuids: [Option<String>; 2],
+        iou: f64,
+        frame: Option<FrameLabel>,
+    },
+    /// GT has a size out of the plausible range of its label.
+    ImplausibleSize {
+        timestamp: NaiveDateTime,
+        label: String,
+        uuid: Option<String>,
+        size: [f64; 3],
+        frame: Option<FrameLabel>,
+    },
+    /// Instance moves faster than `max_speed` between consecutive frames.
+    Teleport {
+        label: String,
+        uuid: String,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        distance: f64,
+        speed: f64,
+        frames: [Option<FrameLabel>; 2],
+    },
+}
+
+impl Display for QualityIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let format_uuid = |uuid: &Option<String>| uuid.to_owned().unwrap_or("-".to_string());
+        let format_time = |timestamp: &NaiveDateTime, frame: &Option<FrameLabel>| match frame {
+            Some(frame) => format!("{} [{}]", timestamp, frame),
+            None => timestamp.to_string(),
+        };
+        match self {
+            Self::Overlap {
+                timestamp,
+                label,
+                uuids,
+                iou,
+                frame,
+            } => write!(
+                f,
+                "[Overlap] timestamp: {}, label: {}, uuids: ({}, {}), iou: {:.3}",
+                format_time(timestamp, frame),
+                label,
+                format_uuid(&uuids[0]),
+                format_uuid(&uuids[1]),
+                iou
+            ),
+            Self::ImplausibleSize {
+                timestamp,
+                label,
+                uuid,
+                size,
+                frame,
+            } => write!(
+                f,
+                "[ImplausibleSize] timestamp: {}, label: {}, uuid: {}, size: ({:.2}, {:.2}, {:.2})",
+                format_time(timestamp, frame),
+                label,
+                format_uuid(uuid),
+                size[0],
+                size[1],
+                size[2]
+            ),
+            Self::Teleport {
+                label,
+                uuid,
+                from,
+                to,
+                distance,
+                speed,
+                frames,
+            } => write!(
+                f,
+                "[Teleport] label: {}, uuid: {}, from: {}, to: {}, distance: {:.3} [m], speed: {:.3} [m/s]",
+                label,
+                uuid,
+                format_time(from, &frames[0]),
+                format_time(to, &frames[1]),
+                distance,
+                speed
+            ),
+        }
+    }
+}
+
+/// Report of suspicious GT annotations, to separate labeling errors from model errors.
+///
+/// * `num_frames`  - Number of checked frames.
+/// * `num_objects` - Number of checked objects.
+/// * `issues`      - List of found issues, ordered by kind and timestamp.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DataQualityReport {
+    pub num_frames: usize,
+    pub num_objects: usize,
+    pub issues: Vec<QualityIssue>,
+}
+
+impl DataQualityReport {
+    /// Construct `DataQualityReport` by checking GTs with heuristics.
+    ///
+    /// * `frame_ground_truths` - List of FrameGroundTruth.
+    /// * `params`              - Parameters of heuristics.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     dataset::{quality::{DataQualityReport, QualityParams}, FrameGroundTruth},
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [40.0, 2.0, 1.5],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("100");
+    /// let frame = FrameGroundTruth::new(object.timestamp, vec![object]);
+    ///
+    /// let report = DataQualityReport::new(&[frame], &QualityParams::default());
+    /// assert_eq!(report.num_implausible_sizes(), 1);
+    /// assert!(report.to_string().contains("[Data Quality]"));
+    /// ```
+    pub fn new(frame_ground_truths: &[FrameGroundTruth], params: &QualityParams) -> Self {
+        Self::from_frames(frame_ground_truths.iter().collect(), params)
+    }
+
+    /// Construct `DataQualityReport` from references of GTs.
+    ///
+    /// * `frames`  - List of references of FrameGroundTruth.
+    /// * `params`  - Parameters of heuristics.
+    pub(crate) fn from_frames(mut frames: Vec<&FrameGroundTruth>, params: &QualityParams) -> Self {
+        frames.sort_by_key(|frame| frame.timestamp);
+
+        let mut issues = Vec::new();
+        frames.iter().for_each(|frame| {
+            issues.extend(find_overlaps(frame, params.overlap_iou_threshold));
+        });
+        frames.iter().for_each(|frame| {
+            issues.extend(
+                frame
+                    .objects
+                    .iter()
+                    .filter(|object| !is_plausible_size(object, &params.size_ranges))
+                    .map(|object| QualityIssue::ImplausibleSize {
+                        timestamp: frame.timestamp,
+                        label: object.label.to_string(),
+                        uuid: object.uuid.to_owned(),
+                        size: object.size,
+                        frame: None,
+                    }),
+            );
+        });
+        issues.extend(find_teleports(&frames, params.max_speed));
+
+        Self {
+            num_frames: frames.len(),
+            num_objects: frames.iter().map(|frame| frame.objects.len()).sum(),
+            issues,
+        }
+    }
+
+    /// Returns the number of overlapping pairs of GTs.
+    pub fn num_overlaps(&self) -> usize {
+        self.count(|issue| matches!(issue, QualityIssue::Overlap { .. }))
+    }
+
+    /// Returns the number of GTs with implausible size.
+    pub fn num_implausible_sizes(&self) -> usize {
+        self.count(|issue| matches!(issue, QualityIssue::ImplausibleSize { .. }))
+    }
+
+    /// Returns the number of teleports of instances between frames.
+    pub fn num_teleports(&self) -> usize {
+        self.count(|issue| matches!(issue, QualityIssue::Teleport { .. }))
+    }
+
+    /// Returns each found issue in a line.
+    pub fn format_issues(&self) -> String {
+        let mut msg = "\n[Data Quality Issues]".to_string();
+        self.issues
+            .iter()
+            .for_each(|issue| msg += &format!("\n{}", issue));
+        msg + "\n"
+    }
+
+    /// Save report as JSON.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save_json<P>(&self, path: P) -> DatasetResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let writer = BufWriter::new(create_file(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Returns the report whose issues are labeled with frames on the timeline.
+    ///
+    /// * `timeline`    - Scene-relative time axis over frames.
+    pub(crate) fn with_timeline(mut self, timeline: &SceneTimeline) -> Self {
+        self.issues.iter_mut().for_each(|issue| match issue {
+            QualityIssue::Overlap {
+                timestamp, frame, ..
+            }
+            | QualityIssue::ImplausibleSize {
+                timestamp, frame, ..
+            } => *frame = timeline.get(timestamp).cloned(),
+            QualityIssue::Teleport {
+                from, to, frames, ..
+            } => *frames = [timeline.get(from).cloned(), timeline.get(to).cloned()],
+        });
+        self
+    }
+
+    fn count(&self, predicate: fn(&QualityIssue) -> bool) -> usize {
+        self.issues.iter().filter(|issue| predicate(issue)).count()
+    }
+}
+
+impl Display for DataQualityReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n".to_string();
+        msg += &format!(
+            "[Data Quality]\nframes: {}, objects: {}",
+            self.num_frames, self.num_objects
+        );
+        msg += &format!(
+            "\n|{0:>16}|{1:^12}|{2:^12}|{3:^12}|",
+            "Issue", "Overlap", "Size", "Teleport"
+        );
+        msg += &format!(
+            "\n|{0:>16}| {1:>10} | {2:>10} | {3:>10} |",
+            "Count",
+            self.num_overlaps(),
+            self.num_implausible_sizes(),
+            self.num_teleports()
+        );
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+/// Returns whether the size of object is positive and within the range of its label.
+///
+/// * `object`      - DynamicObject instance.
+/// * `size_ranges` - Plausible sizes for each label.
+fn is_plausible_size(object: &DynamicObject, size_ranges: &HashMap<Label, SizeRange>) -> bool {
+    if !object.size.iter().all(|s| s.is_finite() && 0.0 < *s) {
+        return false;
+    }
+    size_ranges
+        .get(&object.label)
+        .is_none_or(|range| range.contains(&object.size))
+}
+
+/// Returns pairs of GTs with the same label whose BEV IoU exceeds the threshold in the frame.
+///
+/// * `frame`       - FrameGroundTruth instance.
+/// * `threshold`   - Threshold of BEV IoU.
+fn find_overlaps(frame: &FrameGroundTruth, threshold: f64) -> Vec<QualityIssue> {
+    let footprints = frame
+        .objects
+        .iter()
+        .map(|object| {
+            let coords = object
+                .footprint()
+                .iter()
+                .map(|p| Coord { x: p[0], y: p[1] })
+                .collect::<Vec<_>>();
+            Polygon::new(LineString::from(coords), vec![])
+        })
+        .collect::<Vec<_>>();
+
+    let mut issues = Vec::new();
+    for (i, object1) in frame.objects.iter().enumerate() {
+        for (j, object2) in frame.objects.iter().enumerate().skip(i + 1) {
+            let radius = |object: &DynamicObject| object.size[0].hypot(object.size[1]) * 0.5;
+            if object1.label != object2.label
+                || radius(object1) + radius(object2)
+                    < distance_points_bev(&object1.position, &object2.position)
+            {
+                continue;
+            }
+            let intersection = footprints[i].intersection(&footprints[j]).unsigned_area();
+            let union =
+                footprints[i].unsigned_area() + footprints[j].unsigned_area() - intersection;
+            let iou = if 0.0 < union {
+                intersection / union
+            } else {
+                0.0
+            };
+            if threshold < iou {
+                issues.push(QualityIssue::Overlap {
+                    timestamp: frame.timestamp,
+                    label: object1.label.to_string(),
+                    uuids: [object1.uuid.to_owned(), object2.uuid.to_owned()],
+                    iou,
+                    frame: None,
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// Returns instances which move faster than `max_speed` between consecutive appearances.
+/// Objects without uuid are skipped.
+///
+/// * `frames`      - List of FrameGroundTruth ordered by timestamp.
+/// * `max_speed`   - Maximum plausible speed in [m/s].
+fn find_teleports(frames: &[&FrameGroundTruth], max_speed: f64) -> Vec<QualityIssue> {
+    let mut last_seen: HashMap<&str, (NaiveDateTime, [f64; 3])> = HashMap::new();
+    let mut issues = Vec::new();
+    frames.iter().for_each(|frame| {
+        frame.objects.iter().for_each(|object| {
+            let uuid = match &object.uuid {
+                Some(uuid) => uuid.as_str(),
+                None => return,
+            };
+            if let Some((from, position)) =
+                last_seen.insert(uuid, (frame.timestamp, object.position))
+            {
+                let elapsed = (frame.timestamp - from)
+                    .num_microseconds()
+                    .unwrap_or(i64::MAX) as f64
+                    * 1e-6;
+                let distance = distance_points_bev(&position, &object.position);
+                if 0.0 < elapsed && max_speed < distance / elapsed {
+                    issues.push(QualityIssue::Teleport {
+                        label: object.label.to_string(),
+                        uuid: uuid.to_string(),
+                        from,
+                        to: frame.timestamp,
+                        distance,
+                        speed: distance / elapsed,
+                        frames: [None, None],
+                    });
+                }
+            }
+        });
+    });
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataQualityReport, QualityIssue, QualityParams};
+    use crate::{
+        dataset::{timeline::SceneTimeline, FrameGroundTruth},
+        frame_id::FrameID,
+        label::Label,
+        object::object3d::DynamicObject,
+    };
+    use chrono::{DateTime, Duration};
+
+    #[test]
+    fn test_data_quality_report() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let car = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("car".to_string()),
+            class_probabilities: None,
+        };
+        // Duplicated annotation of the same car.
+        let duplicate = DynamicObject {
+            uuid: Some("duplicate".to_string()),
+            ..car.clone()
+        };
+        // Pedestrian overlapping the car is not regarded as an overlap.
+        let pedestrian = DynamicObject {
+            size: [0.5, 0.5, 1.7],
+            label: Label::Pedestrian,
+            uuid: Some("pedestrian".to_string()),
+            ..car.clone()
+        };
+        let tiny_car = DynamicObject {
+            position: [-20.0, 0.0, 0.0],
+            size: [0.5, 0.5, 0.5],
+            uuid: None,
+            ..car.clone()
+        };
+        // The car jumps 30 [m] in 0.1 [s].
+        let teleported = DynamicObject {
+            position: [40.0, 0.0, 0.0],
+            ..car.clone()
+        };
+
+        let frame = |timestamp, objects| FrameGroundTruth {
+            timestamp,
+            objects,
+            ego_translation: None,
+            ego_rotation: None,
+            sample_token: None,
+            version: None,
+            raw_pointcloud: None,
+            location: None,
+        };
+        let frames = vec![
+            frame(
+                timestamp + Duration::milliseconds(100),
+                vec![teleported, pedestrian.clone()],
+            ),
+            frame(timestamp, vec![car, duplicate, pedestrian, tiny_car]),
+        ];
+
+        let report = DataQualityReport::new(&frames, &QualityParams::default());
+        assert_eq!(report.num_frames, 2);
+        assert_eq!(report.num_objects, 6);
+        assert_eq!(report.num_overlaps(), 1);
+        assert_eq!(report.num_implausible_sizes(), 1);
+        assert_eq!(report.num_teleports(), 1);
+
+        match &report.issues[0] {
+            QualityIssue::Overlap { uuids, iou, .. } => {
+                assert_eq!(uuids[1].as_deref(), Some("duplicate"));
+                assert!((iou - 1.0).abs() < 1e-6);
+            }
+            issue => panic!("unexpected issue: {:?}", issue),
+        }
+        match &report.issues[2] {
+            QualityIssue::Teleport {
+                uuid,
+                distance,
+                speed,
+                ..
+            } => {
+                assert_eq!(uuid, "car");
+                assert_eq!(*distance, 30.0);
+                assert!((speed - 300.0).abs() < 1e-6);
+            }
+            issue => panic!("unexpected issue: {:?}", issue),
+        }
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"kind\":\"teleport\""));
+        assert_eq!(report.format_issues().lines().count(), 5);
+
+        let report = report.with_timeline(&SceneTimeline::new(&frames));
+        match &report.issues[2] {
+            QualityIssue::Teleport { frames, .. } => {
+                assert_eq!(frames[0].as_ref().unwrap().sequence, 0);
+                assert_eq!(frames[1].as_ref().unwrap().sequence, 1);
+                assert!((frames[1].as_ref().unwrap().scene_time - 0.1).abs() < 1e-9);
+            }
+            issue => panic!("unexpected issue: {:?}", issue),
+        }
+        assert!(report.issues[0].to_string().contains("[#0000 t=0.000s]"));
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use serde::Serialize;
+
+use super::{DatasetResult, FrameGroundTruth};
+
+/// Summary statistics of object sizes, ordering (length, width, height) in [m].
+///
+/// * `mean`    - Mean of sizes.
+/// * `std`     - Standard deviation of sizes.
+/// * `min`     - Minimum of sizes.
+/// * `max`     - Maximum of sizes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SizeStatistics {
+    pub mean: [f64; 3],
+    pub std: [f64; 3],
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl SizeStatistics {
+    /// Construct `SizeStatistics` from a non-empty list of sizes.
+    ///
+    /// * `sizes`   - List of sizes.
+    fn new(sizes: &[[f64; 3]]) -> Self {
+        let num = sizes.len() as f64;
+        let mut mean = [0.0; 3];
+        let mut std = [0.0; 3];
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for i in 0..3 {
+            mean[i] = sizes.iter().map(|size| size[i]).sum::<f64>() / num;
+            std[i] = (sizes
+                .iter()
+                .map(|size| (size[i] - mean[i]).powi(2))
+                .sum::<f64>()
+                / num)
+                .sqrt();
+            sizes.iter().for_each(|size| {
+                min[i] = min[i].min(size[i]);
+                max[i] = max[i].max(size[i]);
+            });
+        }
+        Self {
+            mean,
+            std,
+            min,
+            max,
+        }
+    }
+}
+
+/// Aggregate statistics of objects of one label.
+///
+/// * `num_objects` - Number of objects.
+/// * `num_frames`  - Number of frames containing at least one object.
+/// * `frequency`   - Ratio of objects of the label to all objects.
+/// * `size`        - Size statistics. None if the number of objects is less than `min_count`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LabelStatistics {
+    pub num_objects: usize,
+    pub num_frames: usize,
+    pub frequency: f64,
+    pub size: Option<SizeStatistics>,
+}
+
+/// Anonymized aggregate statistics of a dataset for documentation such as dataset cards.
+///
+/// Only aggregated values are kept, so tokens, uuids, timestamps and positions of
+/// annotations are never exported. Size statistics of labels with fewer objects than
+/// `min_count` are withheld, because they could reveal individual annotations.
+///
+/// * `num_frames`  - Number of frames.
+/// * `num_objects` - Number of objects.
+/// * `min_count`   - Minimum number of objects to report size statistics.
+/// * `labels`      - Statistics for each label, ordered by label name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DatasetStatistics {
+    pub num_frames: usize,
+    pub num_objects: usize,
+    pub min_count: usize,
+    pub labels: BTreeMap<String, LabelStatistics>,
+}
+
+impl DatasetStatistics {
+    /// Construct `DatasetStatistics` from loaded GTs.
+    ///
+    /// * `frame_ground_truths` - List of FrameGroundTruth.
+    /// * `min_count`           - Minimum number of objects to report size statistics.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     dataset::{stats::DatasetStatistics, FrameGroundTruth},
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [4.0, 2.0, 1.5],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("100");
+    /// let frame = FrameGroundTruth::new(object.timestamp, vec![object]);
+    ///
+    /// let stats = DatasetStatistics::new(&[frame], 1);
+    /// assert_eq!(stats.labels["Car"].num_objects, 1);
+    /// assert!(stats.to_markdown().contains("| Car |"));
+    /// ```
+    pub fn new(frame_ground_truths: &[FrameGroundTruth], min_count: usize) -> Self {
+        let mut sizes: BTreeMap<String, Vec<[f64; 3]>> = BTreeMap::new();
+        let mut num_frames: BTreeMap<String, usize> = BTreeMap::new();
+        frame_ground_truths.iter().for_each(|frame| {
+            let mut frame_labels = Vec::new();
+            frame.objects.iter().for_each(|object| {
+                let name = object.label.to_string();
+                sizes.entry(name.to_owned()).or_default().push(object.size);
+                if !frame_labels.contains(&name) {
+                    frame_labels.push(name);
+                }
+            });
+            frame_labels
+                .into_iter()
+                .for_each(|name| *num_frames.entry(name).or_default() += 1);
+        });
+
+        let num_objects = sizes.values().map(|sizes| sizes.len()).sum::<usize>();
+        let labels = sizes
+            .into_iter()
+            .map(|(name, sizes)| {
+                let stats = LabelStatistics {
+                    num_objects: sizes.len(),
+                    num_frames: num_frames.get(&name).copied().unwrap_or(0),
+                    frequency: sizes.len() as f64 / num_objects as f64,
+                    size: (min_count <= sizes.len()).then(|| SizeStatistics::new(&sizes)),
+                };
+                (name, stats)
+            })
+            .collect();
+
+        Self {
+            num_frames: frame_ground_truths.len(),
+            num_objects,
+            min_count,
+            labels,
+        }
+    }
+
+    /// Returns statistics formatted as a markdown table.
+    pub fn to_markdown(&self) -> String {
+        let format_size =
+            |values: &[f64; 3]| format!("{:.2} / {:.2} / {:.2}", values[0], values[1], values[2]);
+        let mut msg = format!(
+            "Number of frames: {}, Number of objects: {}\n\n",
+            self.num_frames, self.num_objects
+        );
+        msg += "| Label | Objects | Frames | Frequency | Mean size (l / w / h) | Std size (l / w / h) |\n";
+        msg += "| :---- | ------: | -----: | --------: | :-------------------: | :------------------: |\n";
+        self.labels.iter().for_each(|(name, stats)| {
+            let (mean, std) = match &stats.size {
+                Some(size) => (format_size(&size.mean), format_size(&size.std)),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            msg += &format!(
+                "| {} | {} | {} | {:.3} | {} | {} |\n",
+                name, stats.num_objects, stats.num_frames, stats.frequency, mean, std
+            );
+        });
+        msg
+    }
+
+    /// Save statistics as JSON.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save_json<P>(&self, path: P) -> DatasetResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Save statistics as markdown.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save_markdown<P>(&self, path: P) -> DatasetResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(self.to_markdown().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DatasetStatistics;
+    use crate::{
+        dataset::FrameGroundTruth, frame_id::FrameID, label::Label, object::object3d::DynamicObject,
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_dataset_statistics() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let car = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("secret-instance-token".to_string()),
+            class_probabilities: None,
+        };
+        let large_car = DynamicObject {
+            size: [6.0, 2.0, 1.5],
+            ..car.clone()
+        };
+        let pedestrian = DynamicObject {
+            size: [0.5, 0.5, 1.7],
+            label: Label::Pedestrian,
+            ..car.clone()
+        };
+        let frame = |objects| FrameGroundTruth {
+            timestamp,
+            objects,
+            ego_translation: None,
+            ego_rotation: None,
+            sample_token: Some("secret-sample-token".to_string()),
+            version: None,
+            raw_pointcloud: None,
+            location: None,
+        };
+        let frames = vec![frame(vec![car, pedestrian]), frame(vec![large_car])];
+
+        let stats = DatasetStatistics::new(&frames, 2);
+        assert_eq!(stats.num_frames, 2);
+        assert_eq!(stats.num_objects, 3);
+
+        let car_stats = &stats.labels["Car"];
+        assert_eq!(car_stats.num_objects, 2);
+        assert_eq!(car_stats.num_frames, 2);
+        let size = car_stats.size.as_ref().unwrap();
+        assert_eq!(size.mean, [5.0, 2.0, 1.5]);
+        assert_eq!(size.std, [1.0, 0.0, 0.0]);
+        assert_eq!(size.min[0], 4.0);
+        assert_eq!(size.max[0], 6.0);
+
+        // Size of a single pedestrian is withheld.
+        assert!(stats.labels["Pedestrian"].size.is_none());
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(!json.contains("secret"));
+    }
+}
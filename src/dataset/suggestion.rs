@@ -0,0 +1,382 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use serde::Serialize;
+
+use crate::{
+    config::MetricsParams,
+    label::{EvaluationLabels, LabelResult},
+};
+
+use super::{DatasetResult, FrameGroundTruth};
+
+/// Maximum time span in [s] of three consecutive annotations used to estimate jitter.
+const MAX_JITTER_SPAN_SECONDS: f64 = 1.0;
+
+/// Suggested matching thresholds of one label, derived from GT statistics.
+///
+/// The annotation jitter is the median of BEV deviations of positions from the interpolation
+/// between the previous and the next annotations of the same instance.
+/// The tolerance of center offsets is the larger of a quarter of the BEV diagonal of the median box
+/// and three times the jitter. IoU thresholds are IoU between the median box and its copy
+/// shifted by the tolerance, equally along x and y for 2D, and along x, y and z for 3D.
+///
+/// * `num_objects`         - Number of objects.
+/// * `median_size`         - Median of sizes, ordering (length, width, height) in [m].
+/// * `position_jitter`     - Annotation jitter in [m]. None if no instance is annotated three times in a row.
+/// * `center_distance`     - Suggested center distance threshold in [m].
+/// * `plane_distance`      - Suggested plane distance threshold in [m].
+/// * `iou_2d`              - Suggested IoU2D threshold.
+/// * `iou_3d`              - Suggested IoU3D threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LabelThresholds {
+    pub num_objects: usize,
+    pub median_size: [f64; 3],
+    pub position_jitter: Option<f64>,
+    pub center_distance: f64,
+    pub plane_distance: f64,
+    pub iou_2d: f64,
+    pub iou_3d: f64,
+}
+
+impl LabelThresholds {
+    /// Construct `LabelThresholds` from a non-empty list of sizes and deviations.
+    ///
+    /// * `sizes`       - List of sizes.
+    /// * `deviations`  - List of position deviations.
+    fn new(sizes: &[[f64; 3]], deviations: &mut [f64]) -> Self {
+        let mut median_size = [0.0; 3];
+        for (i, value) in median_size.iter_mut().enumerate() {
+            let mut values = sizes.iter().map(|size| size[i]).collect::<Vec<_>>();
+            *value = median(&mut values).unwrap_or(0.0);
+        }
+        let position_jitter = median(deviations);
+
+        let [length, width, height] = median_size;
+        let tolerance = (0.25 * length.hypot(width)).max(3.0 * position_jitter.unwrap_or(0.0));
+        let plane_distance = (0.5 * length).max(3.0 * position_jitter.unwrap_or(0.0));
+        let shift_2d = tolerance / 2.0_f64.sqrt();
+        let shift_3d = tolerance / 3.0_f64.sqrt();
+        let iou_2d = shifted_iou(&[length, width], shift_2d);
+        let iou_3d = shifted_iou(&[length, width, height], shift_3d);
+
+        Self {
+            num_objects: sizes.len(),
+            median_size,
+            position_jitter,
+            center_distance: round_up(tolerance, 0.1),
+            plane_distance: round_up(plane_distance, 0.1),
+            iou_2d: round_down(iou_2d, 0.05).clamp(0.1, 0.9),
+            iou_3d: round_down(iou_3d, 0.05).clamp(0.1, 0.9),
+        }
+    }
+}
+
+/// Suggestion of per-label matching thresholds for a custom dataset.
+///
+/// Jitter is estimated from positions as they are annotated, so GTs in `map` are preferred,
+/// otherwise motion of ego is also regarded as jitter.
+///
+/// * `num_frames`  - Number of frames.
+/// * `labels`      - Suggested thresholds for each label, ordered by label name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThresholdSuggestion {
+    pub num_frames: usize,
+    pub labels: BTreeMap<String, LabelThresholds>,
+}
+
+impl ThresholdSuggestion {
+    /// Construct `ThresholdSuggestion` from loaded GTs.
+    ///
+    /// * `frame_ground_truths` - List of FrameGroundTruth ordered by timestamp.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{DateTime, Duration};
+    /// use perception_eval::{
+    ///     dataset::{suggestion::ThresholdSuggestion, FrameGroundTruth},
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     object::object3d::DynamicObject,
+    /// };
+    ///
+    /// let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+    /// let frames = (0..3)
+    ///     .map(|i| {
+    ///         let object = DynamicObject::new(
+    ///             timestamp + Duration::milliseconds(100 * i),
+    ///             FrameID::Map,
+    ///             [10.0 + i as f64, 0.0, 0.0],
+    ///             [1.0, 0.0, 0.0, 0.0],
+    ///             [4.0, 2.0, 1.5],
+    ///             1.0,
+    ///             Label::Car,
+    ///         )
+    ///         .with_pointcloud_num(1000)
+    ///         .with_uuid("100");
+    ///         FrameGroundTruth::new(object.timestamp, vec![object])
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let suggestion = ThresholdSuggestion::new(&frames);
+    /// assert_eq!(suggestion.labels["Car"].position_jitter, Some(0.0));
+    /// assert_eq!(suggestion.labels["Car"].plane_distance, 2.0);
+    ///
+    /// let params = suggestion.to_metrics_params().unwrap();
+    /// ```
+    pub fn new(frame_ground_truths: &[FrameGroundTruth]) -> Self {
+        let mut sizes: BTreeMap<String, Vec<[f64; 3]>> = BTreeMap::new();
+        let mut tracks: HashMap<&str, Vec<(f64, [f64; 3])>> = HashMap::new();
+        let mut track_labels: HashMap<&str, String> = HashMap::new();
+        frame_ground_truths.iter().for_each(|frame| {
+            frame.objects.iter().for_each(|object| {
+                let name = object.label.to_string();
+                sizes.entry(name.to_owned()).or_default().push(object.size);
+                if let Some(uuid) = &object.uuid {
+                    let seconds = object.timestamp.and_utc().timestamp_micros() as f64 * 1e-6;
+                    tracks
+                        .entry(uuid)
+                        .or_default()
+                        .push((seconds, object.position));
+                    track_labels.insert(uuid, name);
+                }
+            });
+        });
+
+        let mut deviations: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        tracks.into_iter().for_each(|(uuid, states)| {
+            let label_deviations = deviations.entry(track_labels[uuid].to_owned()).or_default();
+            states.windows(3).for_each(|window| {
+                let [(t0, p0), (t1, p1), (t2, p2)] = [window[0], window[1], window[2]];
+                if t2 - t0 <= 0.0 || MAX_JITTER_SPAN_SECONDS < t2 - t0 {
+                    return;
+                }
+                let ratio = (t1 - t0) / (t2 - t0);
+                let x = p0[0] + (p2[0] - p0[0]) * ratio;
+                let y = p0[1] + (p2[1] - p0[1]) * ratio;
+                label_deviations.push((p1[0] - x).hypot(p1[1] - y));
+            });
+        });
+
+        let labels = sizes
+            .into_iter()
+            .map(|(name, sizes)| {
+                let mut label_deviations = deviations.remove(&name).unwrap_or_default();
+                let thresholds = LabelThresholds::new(&sizes, &mut label_deviations);
+                (name, thresholds)
+            })
+            .collect();
+
+        Self {
+            num_frames: frame_ground_truths.len(),
+            labels,
+        }
+    }
+
+    /// Returns `MetricsParams` whose thresholds are suggested for each label.
+    pub fn to_metrics_params(&self) -> LabelResult<MetricsParams> {
+        let names = self
+            .labels
+            .keys()
+            .map(|name| name as &str)
+            .collect::<Vec<_>>();
+        let labels = EvaluationLabels::new(&names, "autoware")?;
+        let mut params = MetricsParams::from_labels(&labels, 0.0, 0.0, 0.0, 0.0);
+        let thresholds = params
+            .target_labels
+            .iter()
+            .map(|label| &self.labels[&label.to_string()])
+            .collect::<Vec<_>>();
+        params.center_distance_thresholds = thresholds.iter().map(|t| t.center_distance).collect();
+        params.plane_distance_thresholds = thresholds.iter().map(|t| t.plane_distance).collect();
+        params.iou2d_thresholds = thresholds.iter().map(|t| t.iou_2d).collect();
+        params.iou3d_thresholds = thresholds.iter().map(|t| t.iou_3d).collect();
+        Ok(params)
+    }
+
+    /// Returns a snippet of `evaluation_config_dict` in the scenario.
+    ///
+    /// The scenario accepts one threshold for all labels, so thresholds of the label with the most objects
+    /// are written, and thresholds of every label are left as comments.
+    pub fn to_yaml_snippet(&self) -> String {
+        let mut msg = format!("# Suggested thresholds from {} frames.\n", self.num_frames);
+        msg += "# label: center_distance / plane_distance / iou_2d / iou_3d (jitter [m])\n";
+        self.labels.iter().for_each(|(name, thresholds)| {
+            let jitter = thresholds
+                .position_jitter
+                .map_or("-".to_string(), |jitter| format!("{:.3}", jitter));
+            msg += &format!(
+                "# {}: {:.1} / {:.1} / {:.2} / {:.2} ({})\n",
+                name,
+                thresholds.center_distance,
+                thresholds.plane_distance,
+                thresholds.iou_2d,
+                thresholds.iou_3d,
+                jitter
+            );
+        });
+
+        let names = self.labels.keys().cloned().collect::<Vec<_>>();
+        msg += &format!("target_labels: [{}]\n", names.join(", "));
+        // Reversed, so that the first label in name order is taken among ties.
+        if let Some(thresholds) = self.labels.values().rev().max_by_key(|t| t.num_objects) {
+            msg += &format!(
+                "center_distance_threshold: {:.1}\n",
+                thresholds.center_distance
+            );
+            msg += &format!(
+                "plane_distance_threshold: {:.1}\n",
+                thresholds.plane_distance
+            );
+            msg += &format!("iou_2d_threshold: {:.2}\n", thresholds.iou_2d);
+            msg += &format!("iou_3d_threshold: {:.2}\n", thresholds.iou_3d);
+        }
+        msg
+    }
+
+    /// Save the snippet of `evaluation_config_dict` as `.yaml`.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save_yaml_snippet<P>(&self, path: P) -> DatasetResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(self.to_yaml_snippet().as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Returns the median of values, which are sorted in place.
+/// If values are empty, returns None.
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let n = values.len();
+    if n % 2 == 1 {
+        Some(values[n / 2])
+    } else {
+        Some(0.5 * (values[n / 2 - 1] + values[n / 2]))
+    }
+}
+
+/// Returns IoU between the axis-aligned box and its copy shifted by `shift` along every axis.
+///
+/// * `size`    - Size of the box.
+/// * `shift`   - Shift along each axis.
+fn shifted_iou(size: &[f64], shift: f64) -> f64 {
+    let volume = size.iter().product::<f64>();
+    let intersection = size
+        .iter()
+        .map(|length| (length - shift).max(0.0))
+        .product::<f64>();
+    if volume <= 0.0 {
+        return 0.0;
+    }
+    intersection / (2.0 * volume - intersection)
+}
+
+/// Returns the value rounded up to the multiple of the step.
+fn round_up(value: f64, step: f64) -> f64 {
+    ((value / step - 1e-9).ceil() * step * 1e6).round() / 1e6
+}
+
+/// Returns the value rounded down to the multiple of the step.
+fn round_down(value: f64, step: f64) -> f64 {
+    ((value / step + 1e-9).floor() * step * 1e6).round() / 1e6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThresholdSuggestion;
+    use crate::{
+        dataset::FrameGroundTruth, frame_id::FrameID, label::Label, object::object3d::DynamicObject,
+    };
+    use chrono::{DateTime, Duration};
+
+    #[test]
+    fn test_threshold_suggestion() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let car = DynamicObject {
+            timestamp,
+            frame_id: FrameID::Map,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("car".to_string()),
+            class_probabilities: None,
+        };
+        let pedestrian = DynamicObject {
+            size: [0.6, 0.6, 1.7],
+            label: Label::Pedestrian,
+            uuid: Some("pedestrian".to_string()),
+            ..car.clone()
+        };
+
+        // The pedestrian walks straight with lateral zigzag of 0.1 m, while the car moves smoothly.
+        let frames = (0..5)
+            .map(|i| {
+                let timestamp = timestamp + Duration::milliseconds(100 * i);
+                let lateral = if i % 2 == 0 { 0.1 } else { -0.1 };
+                FrameGroundTruth {
+                    timestamp,
+                    objects: vec![
+                        DynamicObject {
+                            timestamp,
+                            position: [10.0 + i as f64, 0.0, 0.0],
+                            ..car.clone()
+                        },
+                        DynamicObject {
+                            timestamp,
+                            position: [5.0 + 0.1 * i as f64, lateral, 0.0],
+                            ..pedestrian.clone()
+                        },
+                    ],
+                    ego_translation: None,
+                    ego_rotation: None,
+                    sample_token: None,
+                    version: None,
+                    raw_pointcloud: None,
+                    location: None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let suggestion = ThresholdSuggestion::new(&frames);
+        assert_eq!(suggestion.num_frames, 5);
+
+        let car = &suggestion.labels["Car"];
+        assert_eq!(car.median_size, [4.0, 2.0, 1.5]);
+        assert_eq!(car.position_jitter, Some(0.0));
+        // A quarter of the BEV diagonal, sqrt(20) / 4 = 1.118.
+        assert_eq!(car.center_distance, 1.2);
+        assert_eq!(car.plane_distance, 2.0);
+
+        let pedestrian = &suggestion.labels["Pedestrian"];
+        assert!((pedestrian.position_jitter.unwrap() - 0.2).abs() < 1e-9);
+        // Jitter dominates the tolerance of small objects.
+        assert_eq!(pedestrian.center_distance, 0.6);
+        assert_eq!(pedestrian.plane_distance, 0.6);
+        assert_eq!(pedestrian.iou_2d, 0.1);
+
+        let params = suggestion.to_metrics_params().unwrap();
+        assert_eq!(params.center_distance_thresholds, vec![1.2, 0.6]);
+
+        let snippet = suggestion.to_yaml_snippet();
+        assert!(snippet.contains("target_labels: [Car, Pedestrian]"));
+        assert!(snippet.contains("center_distance_threshold: 1.2"));
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter, Result as FormatResult},
+};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::FrameGroundTruth;
+
+/// Human-readable reference to a frame, to cross-reference findings with rosbags and the devkit.
+///
+/// * `sequence`        - Sequence number of the frame in the scene, counted from 0 in timestamp order.
+/// * `scene_time`      - Elapsed time from the start of the scene in [s].
+/// * `sample_token`    - Token of the sample the frame is loaded from, if available.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameLabel {
+    pub sequence: usize,
+    pub scene_time: f64,
+    pub sample_token: Option<String>,
+}
+
+impl Display for FrameLabel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "#{:04} t={:.3}s", self.sequence, self.scene_time)?;
+        match &self.sample_token {
+            Some(token) => write!(f, " ({})", token),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Scene-relative time axis over frames, where t=0 is the timestamp of the first frame.
+///
+/// * `start`   - Timestamp of the first frame.
+/// * `labels`  - FrameLabel of each frame keyed by its timestamp.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::dataset::{timeline::SceneTimeline, FrameGroundTruth};
+///
+/// let frames = [300_000, 100_000, 200_000]
+///     .iter()
+///     .map(|&t| {
+///         let timestamp = DateTime::from_timestamp_micros(t).unwrap().naive_utc();
+///         FrameGroundTruth::new(timestamp, Vec::new()).with_sample_token(&format!("token-{}", t))
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let timeline = SceneTimeline::new(&frames);
+/// let label = timeline.get(&frames[0].timestamp).unwrap();
+/// assert_eq!(label.sequence, 2);
+/// assert_eq!(label.to_string(), "#0002 t=0.200s (token-300000)");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneTimeline {
+    start: Option<NaiveDateTime>,
+    labels: BTreeMap<NaiveDateTime, FrameLabel>,
+}
+
+impl SceneTimeline {
+    /// Construct `SceneTimeline` from all frames of the scene.
+    /// If frames have the same timestamp, the first one in the slice is labeled.
+    ///
+    /// * `frame_ground_truths` - List of FrameGroundTruth.
+    pub fn new(frame_ground_truths: &[FrameGroundTruth]) -> Self {
+        let mut frames = frame_ground_truths.iter().collect::<Vec<_>>();
+        frames.sort_by_key(|frame| frame.timestamp);
+        frames.dedup_by_key(|frame| frame.timestamp);
+
+        let start = frames.first().map(|frame| frame.timestamp);
+        let labels = frames
+            .iter()
+            .enumerate()
+            .map(|(sequence, frame)| {
+                let label = FrameLabel {
+                    sequence,
+                    scene_time: elapsed_seconds(&start, &frame.timestamp).unwrap_or(0.0),
+                    sample_token: frame.sample_token.to_owned(),
+                };
+                (frame.timestamp, label)
+            })
+            .collect();
+        Self { start, labels }
+    }
+
+    /// Returns the timestamp of the first frame.
+    /// If there is no frame, returns None.
+    pub fn start(&self) -> Option<NaiveDateTime> {
+        self.start
+    }
+
+    /// Returns the number of labeled frames.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns whether there is no labeled frame.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Returns the label of the frame which has exactly the timestamp.
+    ///
+    /// * `timestamp`   - Timestamp of the frame.
+    pub fn get(&self, timestamp: &NaiveDateTime) -> Option<&FrameLabel> {
+        self.labels.get(timestamp)
+    }
+
+    /// Returns the elapsed time of the timestamp from the start of the scene in [s].
+    /// If there is no frame, returns None.
+    ///
+    /// * `timestamp`   - Any timestamp, which may not correspond to a frame.
+    pub fn scene_time(&self, timestamp: &NaiveDateTime) -> Option<f64> {
+        elapsed_seconds(&self.start, timestamp)
+    }
+}
+
+fn elapsed_seconds(start: &Option<NaiveDateTime>, timestamp: &NaiveDateTime) -> Option<f64> {
+    start.map(|start| (*timestamp - start).num_microseconds().unwrap_or(i64::MAX) as f64 * 1e-6)
+}
@@ -0,0 +1,62 @@
+use std::{
+    fmt::{Display, Formatter, Result as FormatResult},
+    path::PathBuf,
+};
+
+use chrono::NaiveDateTime;
+
+/// Represents problems which the evaluator recovered from, instead of failing.
+///
+/// Objects with diagnostics are excluded from the evaluation,
+/// so that metrics scores are computed over the remaining data.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Diagnostic {
+    /// Estimation rejected before matching.
+    RejectedEstimation {
+        timestamp: NaiveDateTime,
+        uuid: Option<String>,
+        reason: &'static str,
+    },
+    /// GT rejected before matching.
+    RejectedGroundTruth {
+        timestamp: NaiveDateTime,
+        uuid: Option<String>,
+        reason: &'static str,
+    },
+    /// No frame of GTs found for the timestamp.
+    MissingGroundTruth { timestamp: NaiveDateTime },
+    /// Report failed to be written, while the evaluation continued.
+    WriteFailure { path: PathBuf, message: String },
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            Self::RejectedEstimation {
+                timestamp,
+                uuid,
+                reason,
+            } => write!(
+                f,
+                "rejected estimation with {} at {} (uuid: {:?})",
+                reason, timestamp, uuid
+            ),
+            Self::RejectedGroundTruth {
+                timestamp,
+                uuid,
+                reason,
+            } => write!(
+                f,
+                "rejected GT with {} at {} (uuid: {:?})",
+                reason, timestamp, uuid
+            ),
+            Self::MissingGroundTruth { timestamp } => {
+                write!(f, "missing GT frame at {}", timestamp)
+            }
+            Self::WriteFailure { path, message } => {
+                write!(f, "failed to write {}: {}", path.display(), message)
+            }
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use std::cmp::Ordering;
+
+use thiserror::Error as ThisError;
+
+use crate::{
+    config::PerceptionEvaluationConfig,
+    dataset::FrameGroundTruth,
+    manager::PerceptionEvaluationManager,
+    matching::{get_matching_method, MatchingError, MatchingMode, PlaneDistanceConfig},
+    metrics::{error::MetricsError, score::MetricsScore},
+    object::object3d::DynamicObject,
+};
+
+pub type EnsembleResult<T> = Result<T, EnsembleError>;
+
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum EnsembleError {
+    #[error("matching error: {0}")]
+    MatchingError(#[from] MatchingError),
+    #[error("metrics error: {0}")]
+    MetricsError(#[from] MetricsError),
+    #[error("number of frames mismatched: expected {0}, but got {1}")]
+    MismatchedFrames(usize, usize),
+}
+
+/// Represents how to fuse overlapping estimations of multiple sources.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FusionRule {
+    /// Keep only the estimation with the highest confidence among overlapping ones.
+    Nms,
+    /// Average boxes of overlapping estimations weighted by confidence.
+    /// Each cluster has at most one estimation of each source, and confidence is averaged over all sources,
+    /// so estimations missed by some sources are penalized and confidence does not exceed 1.
+    ConfidenceAveraging,
+}
+
+/// Configuration to merge estimations of multiple sources.
+///
+/// Estimations with the same label are regarded as overlapping if they satisfy the threshold
+/// of the matching mode, as same as a pair of estimation and GT.
+///
+/// * `rule`                    - Fusion rule applied to overlapping estimations.
+/// * `matching_mode`           - Matching mode to determine overlapping.
+/// * `threshold`               - Threshold of the matching mode.
+/// * `plane_distance_config`   - Configuration used for `MatchingMode::PlaneDistance`.
+#[derive(Debug, Clone)]
+pub struct FusionConfig {
+    pub rule: FusionRule,
+    pub matching_mode: MatchingMode,
+    pub threshold: f64,
+    pub plane_distance_config: PlaneDistanceConfig,
+}
+
+impl FusionConfig {
+    /// Construct `FusionConfig`.
+    ///
+    /// * `rule`            - Fusion rule applied to overlapping estimations.
+    /// * `matching_mode`   - Matching mode to determine overlapping.
+    /// * `threshold`       - Threshold of the matching mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{
+    ///     ensemble::{FusionConfig, FusionRule},
+    ///     matching::MatchingMode,
+    /// };
+    ///
+    /// let config = FusionConfig::new(FusionRule::Nms, MatchingMode::CenterDistance, 1.0);
+    /// ```
+    pub fn new(rule: FusionRule, matching_mode: MatchingMode, threshold: f64) -> Self {
+        Self {
+            rule,
+            matching_mode,
+            threshold,
+            plane_distance_config: PlaneDistanceConfig::default(),
+        }
+    }
+
+    /// Set the configuration of plane distance matching.
+    ///
+    /// * `plane_distance_config`   - Configuration of plane distance matching.
+    pub fn with_plane_distance_config(
+        mut self,
+        plane_distance_config: PlaneDistanceConfig,
+    ) -> Self {
+        self.plane_distance_config = plane_distance_config;
+        self
+    }
+}
+
+/// Merge estimations of multiple sources at one frame.
+///
+/// All estimations are put together and visited in descending order of confidence.
+/// Each unvisited estimation forms a cluster with unvisited ones overlapping it,
+/// which is fused into one estimation with the rule.
+/// With `FusionRule::ConfidenceAveraging`, a cluster takes at most one estimation of each source,
+/// and the rest of estimations form other clusters.
+///
+/// * `sources` - List of estimations of each source.
+/// * `config`  - Configuration of fusion.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::{
+///     ensemble::{merge_estimations, FusionConfig, FusionRule},
+///     frame_id::FrameID,
+///     label::Label,
+///     matching::MatchingMode,
+///     object::object3d::DynamicObject,
+/// };
+///
+/// let object = DynamicObject::new(
+///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     0.8,
+///     Label::Car,
+/// );
+/// let mut other = object.clone();
+/// other.position = [1.2, 1.0, 0.0];
+/// other.confidence = 0.4;
+///
+/// let config = FusionConfig::new(FusionRule::Nms, MatchingMode::CenterDistance, 1.0);
+/// let merged = merge_estimations(&[vec![object.clone()], vec![other]], &config);
+/// assert_eq!(merged, vec![object]);
+/// ```
+pub fn merge_estimations(
+    sources: &[Vec<DynamicObject>],
+    config: &FusionConfig,
+) -> Vec<DynamicObject> {
+    let matching_method = get_matching_method(&config.matching_mode, &config.plane_distance_config);
+
+    let one_per_source = config.rule == FusionRule::ConfidenceAveraging;
+
+    // Pairs of the index of the source and the estimation.
+    let mut candidates = sources
+        .iter()
+        .enumerate()
+        .flat_map(|(k, objects)| objects.iter().map(move |object| (k, object)))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|(_, a), (_, b)| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut is_merged = vec![false; candidates.len()];
+    let mut ret = Vec::new();
+    for i in 0..candidates.len() {
+        if is_merged[i] {
+            continue;
+        }
+        let (leader_source, leader) = candidates[i];
+        let mut cluster = vec![leader];
+        let mut cluster_sources = vec![leader_source];
+        for j in (i + 1)..candidates.len() {
+            let (source, other) = candidates[j];
+            if !is_merged[j]
+                && (!one_per_source || !cluster_sources.contains(&source))
+                && other.label == leader.label
+                && matching_method.is_better_than(other, leader, &config.threshold)
+            {
+                is_merged[j] = true;
+                cluster.push(other);
+                cluster_sources.push(source);
+            }
+        }
+        let fused = match config.rule {
+            FusionRule::Nms => leader.to_owned(),
+            FusionRule::ConfidenceAveraging => average_objects(&cluster, sources.len()),
+        };
+        ret.push(fused);
+    }
+    ret
+}
+
+/// Returns the object whose box is averaged weighted by confidence.
+/// Orientation, label and uuid are taken from the first object, which has the highest confidence.
+///
+/// * `cluster`     - List of overlapping objects of different sources, the first one is the leader.
+/// * `num_sources` - Number of sources.
+fn average_objects(cluster: &[&DynamicObject], num_sources: usize) -> DynamicObject {
+    let leader = cluster[0];
+    let total_confidence = cluster.iter().map(|object| object.confidence).sum::<f64>();
+    let weights = cluster
+        .iter()
+        .map(|object| {
+            if 0.0 < total_confidence {
+                object.confidence / total_confidence
+            } else {
+                1.0 / cluster.len() as f64
+            }
+        })
+        .collect::<Vec<_>>();
+    let weighted_mean = |values: &[[f64; 3]]| -> [f64; 3] {
+        let mut mean = [0.0; 3];
+        values.iter().zip(&weights).for_each(|(value, weight)| {
+            for i in 0..3 {
+                mean[i] += value[i] * weight;
+            }
+        });
+        mean
+    };
+
+    let positions = cluster.iter().map(|o| o.position).collect::<Vec<_>>();
+    let sizes = cluster.iter().map(|o| o.size).collect::<Vec<_>>();
+    let velocities = cluster
+        .iter()
+        .map(|o| o.velocity)
+        .collect::<Option<Vec<_>>>();
+
+    DynamicObject {
+        position: weighted_mean(&positions),
+        size: weighted_mean(&sizes),
+        velocity: velocities.map(|velocities| weighted_mean(&velocities)),
+        confidence: total_confidence / num_sources.max(1) as f64,
+        ..leader.to_owned()
+    }
+}
+
+/// Scores of an ensemble and each of its sources.
+///
+/// * `fused`   - Score of the merged estimations.
+/// * `sources` - Score of each source in the input order.
+#[derive(Debug)]
+pub struct EnsembleScore {
+    pub fused: MetricsScore,
+    pub sources: Vec<MetricsScore>,
+}
+
+/// Evaluate an ensemble of multiple sources, in addition to each source.
+///
+/// * `config`              - Evaluation configuration.
+/// * `frame_ground_truths` - List of FrameGroundTruth.
+/// * `sources`             - Estimations of each source, `sources[k][i]` corresponds to `frame_ground_truths[i]`.
+/// * `fusion`              - Configuration of fusion.
+///
+/// # Examples
+/// ```
+/// use perception_eval::{
+///     ensemble::{evaluate_ensemble, FusionConfig, FusionRule},
+///     matching::MatchingMode,
+/// };
+///
+/// let quickstart = perception_eval::quickstart();
+/// let sources = vec![quickstart.estimations.clone(), quickstart.estimations.clone()];
+/// let fusion = FusionConfig::new(FusionRule::ConfidenceAveraging, MatchingMode::CenterDistance, 1.0);
+///
+/// let score = evaluate_ensemble(
+///     &quickstart.config,
+///     &quickstart.frame_ground_truths,
+///     &sources,
+///     &fusion,
+/// )
+/// .unwrap();
+/// assert_eq!(score.sources.len(), 2);
+/// ```
+pub fn evaluate_ensemble(
+    config: &PerceptionEvaluationConfig,
+    frame_ground_truths: &[FrameGroundTruth],
+    sources: &[Vec<Vec<DynamicObject>>],
+    fusion: &FusionConfig,
+) -> EnsembleResult<EnsembleScore> {
+    if let Some(source) = sources
+        .iter()
+        .find(|source| source.len() != frame_ground_truths.len())
+    {
+        return Err(EnsembleError::MismatchedFrames(
+            frame_ground_truths.len(),
+            source.len(),
+        ));
+    }
+
+    let source_scores = sources
+        .iter()
+        .map(|source| evaluate_frames(config, frame_ground_truths, source))
+        .collect::<EnsembleResult<Vec<_>>>()?;
+
+    let fused_frames = (0..frame_ground_truths.len())
+        .map(|i| {
+            let frame_sources = sources
+                .iter()
+                .map(|source| source[i].to_owned())
+                .collect::<Vec<_>>();
+            merge_estimations(&frame_sources, fusion)
+        })
+        .collect::<Vec<_>>();
+    let fused_score = evaluate_frames(config, frame_ground_truths, &fused_frames)?;
+
+    Ok(EnsembleScore {
+        fused: fused_score,
+        sources: source_scores,
+    })
+}
+
+/// Evaluate estimations of one source.
+///
+/// * `config`              - Evaluation configuration.
+/// * `frame_ground_truths` - List of FrameGroundTruth.
+/// * `estimations`         - Estimations of each frame.
+fn evaluate_frames(
+    config: &PerceptionEvaluationConfig,
+    frame_ground_truths: &[FrameGroundTruth],
+    estimations: &[Vec<DynamicObject>],
+) -> EnsembleResult<MetricsScore> {
+    let mut manager =
+        PerceptionEvaluationManager::from_frame_ground_truths(config, frame_ground_truths.to_vec());
+    for (frame_ground_truth, estimated_objects) in frame_ground_truths.iter().zip(estimations) {
+        manager.add_frame_result(estimated_objects, frame_ground_truth)?;
+    }
+    Ok(manager.get_metrics_score()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_ensemble, merge_estimations, FusionConfig, FusionRule};
+    use crate::{
+        frame_id::FrameID, label::Label, matching::MatchingMode, object::object3d::DynamicObject,
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_merge_estimations() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 0.6,
+            label: Label::Car,
+            pointcloud_num: None,
+            uuid: Some("a".to_string()),
+            class_probabilities: None,
+        };
+        let overlapped = DynamicObject {
+            position: [1.4, 1.0, 0.0],
+            confidence: 0.2,
+            uuid: Some("b".to_string()),
+            ..object.clone()
+        };
+        let other_label = DynamicObject {
+            label: Label::Pedestrian,
+            confidence: 0.5,
+            ..object.clone()
+        };
+        let sources = vec![
+            vec![object.clone(), other_label.clone()],
+            vec![overlapped.clone()],
+        ];
+
+        let nms = FusionConfig::new(FusionRule::Nms, MatchingMode::CenterDistance, 1.0);
+        let merged = merge_estimations(&sources, &nms);
+        assert_eq!(merged, vec![object.clone(), other_label]);
+
+        let averaging = FusionConfig::new(
+            FusionRule::ConfidenceAveraging,
+            MatchingMode::CenterDistance,
+            1.0,
+        );
+        let merged = merge_estimations(&sources, &averaging);
+        assert_eq!(merged.len(), 2);
+        assert!((merged[0].position[0] - 1.1).abs() < 1e-9);
+        assert!((merged[0].confidence - 0.4).abs() < 1e-9);
+        assert_eq!(merged[0].uuid.as_deref(), Some("a"));
+        // The pedestrian is estimated by only one of two sources.
+        assert!((merged[1].confidence - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_confidence_averaging_one_per_source() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 0.9,
+            label: Label::Car,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        };
+        // The first source has a duplicated estimation of the same object.
+        let duplicated = DynamicObject {
+            position: [1.2, 1.0, 0.0],
+            confidence: 0.8,
+            ..object.clone()
+        };
+        let sources = vec![
+            vec![object.clone(), duplicated.clone()],
+            vec![object.clone()],
+        ];
+
+        let averaging = FusionConfig::new(
+            FusionRule::ConfidenceAveraging,
+            MatchingMode::CenterDistance,
+            1.0,
+        );
+        let merged = merge_estimations(&sources, &averaging);
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().all(|object| object.confidence <= 1.0));
+        assert!((merged[0].confidence - 0.9).abs() < 1e-9);
+        assert_eq!(merged[0].position, object.position);
+        assert!((merged[1].confidence - 0.4).abs() < 1e-9);
+        assert_eq!(merged[1].position, duplicated.position);
+
+        // NMS suppresses overlapping estimations regardless of sources.
+        let nms = FusionConfig::new(FusionRule::Nms, MatchingMode::CenterDistance, 1.0);
+        assert_eq!(merge_estimations(&sources, &nms), vec![object]);
+    }
+
+    #[test]
+    fn test_evaluate_ensemble_mismatched_frames() {
+        let quickstart = crate::quickstart();
+        let sources = vec![quickstart.estimations[..1].to_vec()];
+        let fusion = FusionConfig::new(FusionRule::Nms, MatchingMode::CenterDistance, 1.0);
+        assert!(evaluate_ensemble(
+            &quickstart.config,
+            &quickstart.frame_ground_truths,
+            &sources,
+            &fusion
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use std::error::Error as StdError;
+
+use thiserror::Error as ThisError;
+
+use crate::{
+    audit::AuditError, batch::BatchError, config::ConfigError,
+    dataset::nuscenes::error::NuScenesError, ensemble::EnsembleError,
+    evaluation_task::EvaluationTaskError, frame_id::FrameIdError, label::LabelError,
+    matching::MatchingError, metrics::error::MetricsError, result::spill::SpillError,
+};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Top-level error of the crate, which wraps errors of each module.
+///
+/// The wrapped error is available from `source()`, so the whole chain can be reported.
+///
+/// # Examples
+/// ```
+/// use perception_eval::{config::PerceptionEvaluationConfig, Error};
+/// use std::error::Error as _;
+///
+/// fn load() -> perception_eval::Result<PerceptionEvaluationConfig> {
+///     Ok(PerceptionEvaluationConfig::from("not_exist.yaml", "./work_dir", false)?)
+/// }
+///
+/// let err = load().unwrap_err();
+/// assert!(matches!(err, Error::Config(_)));
+/// // The message of the wrapped error is reported by `source()` only.
+/// assert_eq!(err.to_string(), "config error");
+/// assert!(err.source().is_some());
+/// ```
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("config error")]
+    Config(#[from] ConfigError),
+    #[error("dataset error")]
+    Dataset(#[source] Box<dyn StdError + Send + Sync>),
+    #[error("nuscenes error")]
+    NuScenes(#[from] NuScenesError),
+    #[error("label error")]
+    Label(#[from] LabelError),
+    #[error("frame id error")]
+    FrameId(#[from] FrameIdError),
+    #[error("evaluation task error")]
+    EvaluationTask(#[from] EvaluationTaskError),
+    #[error("matching error")]
+    Matching(#[from] MatchingError),
+    #[error("metrics error")]
+    Metrics(#[from] MetricsError),
+    #[error("audit error")]
+    Audit(#[from] AuditError),
+    #[error("ensemble error")]
+    Ensemble(#[from] EnsembleError),
+    #[error("batch error")]
+    Batch(#[from] BatchError),
+    #[error("spill error")]
+    Spill(#[from] SpillError),
+    #[cfg(feature = "arrow")]
+    #[error("columnar error")]
+    Columnar(#[from] crate::result::columnar::ColumnarError),
+    #[cfg(feature = "rosbag")]
+    #[error("rosbag error")]
+    Rosbag(#[from] crate::rosbag::RosbagError),
+}
+
+impl From<Box<dyn StdError + Send + Sync>> for Error {
+    /// Convert the error returned as `DatasetResult`.
+    fn from(err: Box<dyn StdError + Send + Sync>) -> Self {
+        Self::Dataset(err)
+    }
+}
@@ -6,11 +6,10 @@ pub type EvaluationTaskResult<T> = Result<T, EvaluationTaskError>;
 
 /// Errors that can occur while constructing `EvaluationTask` instance.
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum EvaluationTaskError {
-    #[error("internal error")]
-    InternalError,
-    #[error("value error")]
-    ValueError,
+    #[error("unknown evaluation task: {0}")]
+    UnknownTask(String),
 }
 /// Represents type of evaluation tasks.
 #[derive(Debug, Clone, PartialEq)]
@@ -40,7 +39,7 @@ impl FromStr for EvaluationTask {
             "Detection" | "detection" => Ok(EvaluationTask::Detection),
             "Tracking" | "tracking" => Ok(EvaluationTask::Tracking),
             "Prediction" | "prediction" => Ok(EvaluationTask::Prediction),
-            _ => Err(EvaluationTaskError::ValueError),
+            _ => Err(EvaluationTaskError::UnknownTask(input.to_string())),
         }
     }
 }
@@ -79,7 +78,7 @@ impl EvaluationTask {
 }
 
 /// Convert string task name into `EvaluationTask` instance.
-/// If unexpected task name is input, returns `EvaluationTaskError::ValueError`.
+/// If unexpected task name is input, returns `EvaluationTaskError::UnknownTask`.
 ///
 /// * `task_name`   - Name of task in string.
 ///
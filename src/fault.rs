@@ -0,0 +1,119 @@
+use std::{
+    fs::File,
+    io::{Error as IoError, Result as IoResult},
+    path::Path,
+};
+
+#[cfg(feature = "fault-injection")]
+use std::cell::RefCell;
+
+use crate::object::object3d::DynamicObject;
+
+/// Represents faults to be injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Positions and sizes of estimations and GTs added to the manager are NaN.
+    CorruptedFrame,
+    /// Confidences of estimations added to the manager are NaN.
+    NanScore,
+    /// Lookups of GTs by timestamp find no frame.
+    MissingGroundTruth,
+    /// Files of reports, such as PR curves and exported frame results, fail to be created.
+    DiskError,
+}
+
+#[cfg(feature = "fault-injection")]
+thread_local! {
+    static INJECTED_FAULTS: RefCell<Vec<Fault>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Guard of injected faults, which restores previously injected faults when dropped.
+#[cfg(feature = "fault-injection")]
+#[derive(Debug)]
+#[must_use = "faults are removed as soon as the guard is dropped"]
+pub struct FaultGuard {
+    previous: Vec<Fault>,
+}
+
+#[cfg(feature = "fault-injection")]
+impl Drop for FaultGuard {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+        INJECTED_FAULTS.with(|faults| *faults.borrow_mut() = previous);
+    }
+}
+
+/// Inject faults on the current thread in addition to already injected ones,
+/// until the returned guard is dropped.
+///
+/// * `faults`  - List of faults to be injected.
+#[cfg(feature = "fault-injection")]
+pub fn inject(faults: &[Fault]) -> FaultGuard {
+    INJECTED_FAULTS.with(|injected| {
+        let mut injected = injected.borrow_mut();
+        let previous = injected.clone();
+        injected.extend_from_slice(faults);
+        FaultGuard { previous }
+    })
+}
+
+/// Returns whether the fault is injected on the current thread.
+///
+/// * `fault`   - Fault to be checked.
+pub(crate) fn is_injected(fault: Fault) -> bool {
+    #[cfg(feature = "fault-injection")]
+    {
+        INJECTED_FAULTS.with(|faults| faults.borrow().contains(&fault))
+    }
+    #[cfg(not(feature = "fault-injection"))]
+    {
+        let _ = fault;
+        false
+    }
+}
+
+/// Returns objects with injected faults, or None if no fault of objects is injected.
+///
+/// * `objects`       - List of objects.
+/// * `is_estimation` - Whether objects are estimations, where `Fault::NanScore` is injected.
+pub(crate) fn inject_object_faults(
+    objects: &[DynamicObject],
+    is_estimation: bool,
+) -> Option<Vec<DynamicObject>> {
+    let corrupted = is_injected(Fault::CorruptedFrame);
+    let nan_score = is_estimation && is_injected(Fault::NanScore);
+    if !corrupted && !nan_score {
+        return None;
+    }
+    let objects = objects
+        .iter()
+        .map(|object| {
+            let mut object = object.to_owned();
+            if corrupted {
+                object.position = [f64::NAN; 3];
+                object.size = [f64::NAN; 3];
+            }
+            if nan_score {
+                object.confidence = f64::NAN;
+            }
+            object
+        })
+        .collect();
+    Some(objects)
+}
+
+/// Create a file for writing, which fails if `Fault::DiskError` is injected.
+///
+/// * `path`    - File path.
+pub(crate) fn create_file<P>(path: P) -> IoResult<File>
+where
+    P: AsRef<Path>,
+{
+    if is_injected(Fault::DiskError) {
+        return Err(IoError::other(format!(
+            "injected disk error: {}",
+            path.as_ref().display()
+        )));
+    }
+    File::create(path)
+}
@@ -1,8 +1,14 @@
-use std::collections::HashMap;
+use std::{borrow::Borrow, cmp::Ordering, collections::HashMap, f64::consts::PI};
+
+use geo::{Coord, Intersects, Line, LineString, Polygon};
 
 use crate::{
-    config::FilterParams, label::Label, object::object3d::DynamicObject,
-    result::object::PerceptionResult, threshold::LabelThreshold,
+    config::{CrossCameraFilter, DetectionRange, FilterParams, OcclusionFilter},
+    frame_id::FrameID,
+    label::Label,
+    object::object3d::DynamicObject,
+    result::object::PerceptionResult,
+    threshold::LabelThreshold,
 };
 
 /// Filter objects with `FilterParams`. Returns list of kept objects.
@@ -16,31 +22,29 @@ use crate::{
 /// use chrono::NaiveDateTime;
 /// use perception_eval::{config::FilterParams, filter::filter_objects, frame_id::FrameID, label::Label, object::object3d::DynamicObject};
 ///
-/// let object1 = DynamicObject {
-///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-///     frame_id: FrameID::BaseLink,
-///     position: [1.0, 1.0, 0.0],
-///     orientation: [1.0, 0.0, 0.0, 0.0],
-///     size: [2.0, 1.0, 1.0],
-///     velocity: None,
-///     confidence: 1.0,
-///     label: Label::Car,
-///     pointcloud_num: Some(1000),
-///     uuid: Some("111".to_string()),
-/// };
-///
-/// let object2 = DynamicObject {
-///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-///     frame_id: FrameID::BaseLink,
-///     position: [10.0, 10.0, 0.0],
-///     orientation: [1.0, 0.0, 0.0, 0.0],
-///     size: [2.0, 1.0, 1.0],
-///     velocity: None,
-///     confidence: 1.0,
-///     label: Label::Car,
-///     pointcloud_num: Some(1000),
-///     uuid: Some("111".to_string()),
-/// };
+/// let object1 = DynamicObject::new(
+///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("111");
+///
+/// let object2 = DynamicObject::new(
+///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+///     FrameID::BaseLink,
+///     [10.0, 10.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("111");
 ///
 ///
 /// let objects = vec![object1.clone(), object2];
@@ -54,51 +58,135 @@ pub fn filter_objects(
     is_gt: bool,
     filter_params: &FilterParams,
 ) -> Vec<DynamicObject> {
-    let mut ret = Vec::new();
-    for object in objects {
-        let is_target = if is_gt {
-            is_target_object(
-                object,
-                &filter_params.target_labels,
-                &filter_params.max_x_positions,
-                &filter_params.max_y_positions,
-                &filter_params.min_point_numbers,
-                &filter_params.target_uuids,
-            )
-        } else {
-            is_target_object(
-                object,
-                &filter_params.target_labels,
-                &filter_params.max_x_positions,
-                &filter_params.max_y_positions,
-                &None,
-                &None,
-            )
-        };
+    filter_objects_ref(objects, is_gt, filter_params)
+        .into_iter()
+        .cloned()
+        .collect()
+}
 
-        if is_target {
-            ret.push(object.to_owned());
-        }
-    }
-    ret
+/// Filter objects with `FilterParams`. Returns list of references to kept objects.
+///
+/// This is the borrowing counterpart of `filter_objects()`, which does not clone any object.
+///
+/// * `objects`         - List of `DynamicObject` instances.
+/// * `is_gt`           - Whether input objects are ground truth.
+/// * `filter_params`   - `FilterParam` instance.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::{config::FilterParams, filter::filter_objects_ref, frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+///
+/// let object = DynamicObject::new(
+///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("111");
+///
+/// let objects = vec![object];
+/// let filter_params = FilterParams::new(&vec!["car"], 5.0, 5.0, None, None).unwrap();
+/// let ret = filter_objects_ref(&objects, false, &filter_params);
+///
+/// assert_eq!(ret, vec![&objects[0]]);
+/// ```
+pub fn filter_objects_ref<'a>(
+    objects: &'a [DynamicObject],
+    is_gt: bool,
+    filter_params: &FilterParams,
+) -> Vec<&'a DynamicObject> {
+    filter_objects_with_ego_pose(objects, is_gt, filter_params, None)
+}
+
+/// Filter objects with `FilterParams`, where objects in `map` are transformed into `base_link`
+/// with the ego pose to check detection ranges. Returns list of references to kept objects.
+///
+/// Detection ranges are defined in `base_link`, so that objects in `map` are removed
+/// if the ego pose is not given. Objects in camera frames are not filtered by detection ranges.
+///
+/// * `objects`         - List of `DynamicObject` instances.
+/// * `is_gt`           - Whether input objects are ground truth.
+/// * `filter_params`   - `FilterParam` instance.
+/// * `ego_pose`        - Translation and rotation of ego in `map`, if available.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::{config::FilterParams, filter::filter_objects_with_ego_pose, frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+///
+/// let object = DynamicObject::new(
+///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+///     FrameID::Map,
+///     [101.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("111");
+///
+/// let objects = vec![object];
+/// let filter_params = FilterParams::new(&vec!["car"], 5.0, 5.0, None, None).unwrap();
+/// let ego_pose = ([100.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]);
+/// let ret = filter_objects_with_ego_pose(&objects, false, &filter_params, Some((&ego_pose.0, &ego_pose.1)));
+///
+/// assert_eq!(ret, vec![&objects[0]]);
+/// assert!(filter_objects_with_ego_pose(&objects, false, &filter_params, None).is_empty());
+/// ```
+pub fn filter_objects_with_ego_pose<'a>(
+    objects: &'a [DynamicObject],
+    is_gt: bool,
+    filter_params: &FilterParams,
+    ego_pose: Option<(&[f64; 3], &[f64; 4])>,
+) -> Vec<&'a DynamicObject> {
+    objects
+        .iter()
+        .filter(|object| {
+            if is_gt {
+                is_target_object(
+                    object,
+                    &filter_params.target_labels,
+                    &filter_params.detection_ranges,
+                    &filter_params.min_point_numbers,
+                    &filter_params.target_uuids,
+                    ego_pose,
+                )
+            } else {
+                is_target_object(
+                    object,
+                    &filter_params.target_labels,
+                    &filter_params.detection_ranges,
+                    &None,
+                    &None,
+                    ego_pose,
+                )
+            }
+        })
+        .collect()
 }
 
 /// Returns whether input object is kept.
 ///
 /// * `object`              - DynamicObject instance.
 /// * `target_labels`       - List of `Label` instances.
-/// * `max_x_positions`     - List of maximum x positions for corresponding label.
-/// * `max_y_positions`     - List of maximum y positions for corresponding label.
+/// * `detection_ranges`    - List of detection ranges for corresponding label.
 /// * `min_point_numbers`   - List of minimum number of points the object's box
-///                           must contain for corresponding label.
+///   must contain for corresponding label.
 /// * `target_uuids`        - List of instance IDs to be kept.
+/// * `ego_pose`            - Translation and rotation of ego in `map`, if available.
 fn is_target_object(
     object: &DynamicObject,
     target_labels: &Vec<Label>,
-    max_x_positions: &[f64],
-    max_y_positions: &[f64],
+    detection_ranges: &[DetectionRange],
     min_point_numbers: &Option<Vec<usize>>,
     target_uuids: &Option<Vec<String>>,
+    ego_pose: Option<(&[f64; 3], &[f64; 4])>,
 ) -> bool {
     let label_threshold = LabelThreshold::new(&object.label, target_labels);
 
@@ -110,24 +198,17 @@ fn is_target_object(
         return false;
     }
 
-    // max_x_positions
+    // detection_ranges
     is_target &= {
-        let max_x_position = label_threshold.get_threshold(max_x_positions);
-        object.position[0].abs()
-            < max_x_position.unwrap_or_else(|| {
-                log::error!("There is no corresponding max_x_position");
-                panic!("There is no corresponding max_x_position")
-            })
-    };
-
-    // max_y_positions
-    is_target &= {
-        let max_y_position = label_threshold.get_threshold(max_y_positions);
-        object.position[1].abs()
-            < max_y_position.unwrap_or_else(|| {
-                log::error!("There is no corresponding max_y_position");
-                panic!("There is no corresponding max_y_position")
-            })
+        let detection_range = label_threshold.get_threshold(detection_ranges);
+        is_in_detection_range(
+            object,
+            &detection_range.unwrap_or_else(|| {
+                log::error!("There is no corresponding detection_range");
+                panic!("There is no corresponding detection_range")
+            }),
+            ego_pose,
+        )
     };
 
     // min_point_numbers
@@ -161,28 +242,173 @@ fn is_target_object(
     is_target
 }
 
-/// Returns hashmap that key is `Label` and value is list of objects that have same label.
+/// Returns whether the object is inside the detection range, which is defined in `base_link`.
 ///
-/// * `objects`         - List of objects.
-/// * `target_labels`   - List of target labels.
-#[allow(unused)]
-pub(crate) fn hash_objects(
-    objects: &[DynamicObject],
-    target_labels: &[Label],
-) -> HashMap<Label, Vec<DynamicObject>> {
-    let mut ret: HashMap<Label, Vec<DynamicObject>> = HashMap::new();
+/// Objects in `map` are transformed with the ego pose, and removed if it is not available.
+/// Objects in camera frames are always kept.
+///
+/// * `object`          - DynamicObject instance.
+/// * `detection_range` - DetectionRange instance.
+/// * `ego_pose`        - Translation and rotation of ego in `map`, if available.
+fn is_in_detection_range(
+    object: &DynamicObject,
+    detection_range: &DetectionRange,
+    ego_pose: Option<(&[f64; 3], &[f64; 4])>,
+) -> bool {
+    match object.frame_id {
+        FrameID::BaseLink => detection_range.contains(&object.position),
+        FrameID::Map => ego_pose
+            .and_then(|(translation, rotation)| {
+                object.transform(&FrameID::BaseLink, translation, rotation)
+            })
+            .is_some_and(|object| detection_range.contains(&object.position)),
+        _ => true,
+    }
+}
 
-    target_labels.iter().for_each(|label| {
-        ret.insert(label.to_owned(), Vec::new());
-    });
+/// Returns occlusion ratio of each object seen from the origin in BEV.
+///
+/// Rays are cast from the origin to points evenly sampled between the leftmost and rightmost
+/// corners of the object's footprint, and the ratio of rays crossing the footprint of any
+/// object closer to the origin is returned.
+///
+/// * `objects`     - List of objects in the coord system whose origin is the sensor.
+/// * `num_rays`    - Number of rays cast to each object.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::{filter::occlusion_ratios, frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+///
+/// let near = DynamicObject::new(
+///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+///     FrameID::BaseLink,
+///     [5.0, 0.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 4.0, 2.0],
+///     1.0,
+///     Label::Truck,
+/// );
+/// let mut far = near.clone();
+/// far.position = [15.0, 0.0, 0.0];
+/// far.size = [4.0, 2.0, 1.5];
+/// far.label = Label::Car;
+///
+/// let ratios = occlusion_ratios(&[near, far], 16);
+/// assert_eq!(ratios, vec![0.0, 1.0]);
+/// ```
+pub fn occlusion_ratios<T: Borrow<DynamicObject>>(objects: &[T], num_rays: usize) -> Vec<f64> {
+    let distances = objects
+        .iter()
+        .map(|object| object.borrow().position[0].hypot(object.borrow().position[1]))
+        .collect::<Vec<_>>();
+    let footprints = objects
+        .iter()
+        .map(|object| {
+            let coords = object
+                .borrow()
+                .footprint()
+                .iter()
+                .map(|p| Coord { x: p[0], y: p[1] })
+                .collect::<Vec<_>>();
+            Polygon::new(LineString::from(coords), vec![])
+        })
+        .collect::<Vec<_>>();
+
+    let origin = Coord { x: 0.0, y: 0.0 };
+    objects
+        .iter()
+        .enumerate()
+        .map(|(i, object)| {
+            let occluders = (0..objects.len())
+                .filter(|j| *j != i && distances[*j] < distances[i])
+                .collect::<Vec<_>>();
+            if occluders.is_empty() || num_rays == 0 {
+                return 0.0;
+            }
+            let (left, right) = visible_span(object.borrow());
+            let num_blocked = (0..num_rays)
+                .filter(|k| {
+                    let ratio = (*k as f64 + 0.5) / num_rays as f64;
+                    let target = Coord {
+                        x: right[0] + (left[0] - right[0]) * ratio,
+                        y: right[1] + (left[1] - right[1]) * ratio,
+                    };
+                    let ray = Line::new(origin, target);
+                    occluders.iter().any(|j| ray.intersects(&footprints[*j]))
+                })
+                .count();
+            num_blocked as f64 / num_rays as f64
+        })
+        .collect()
+}
 
-    objects.iter().for_each(|obj| {
-        if let Some(v) = ret.get_mut(&obj.label) {
-            v.push(obj.clone())
+/// Returns the leftmost and rightmost corners of the object's footprint seen from the origin.
+///
+/// * `object`  - DynamicObject instance.
+fn visible_span(object: &DynamicObject) -> ([f64; 3], [f64; 3]) {
+    let center_azimuth = object.position[1].atan2(object.position[0]);
+    let relative_azimuth = |p: &[f64; 3]| -> f64 {
+        let mut diff = p[1].atan2(p[0]) - center_azimuth;
+        if PI < diff {
+            diff -= 2.0 * PI;
+        } else if diff < -PI {
+            diff += 2.0 * PI;
         }
-    });
+        diff
+    };
+    let footprint = object.footprint();
+    let compare = |a: &&[f64; 3], b: &&[f64; 3]| {
+        relative_azimuth(a)
+            .partial_cmp(&relative_azimuth(b))
+            .unwrap_or(Ordering::Equal)
+    };
+    let left = footprint.iter().max_by(compare).unwrap();
+    let right = footprint.iter().min_by(compare).unwrap();
+    (*left, *right)
+}
 
-    ret
+/// Returns GTs except ones heavily occluded by closer GTs and not paired with any estimation.
+///
+/// * `ground_truths`       - List of GT objects.
+/// * `results`             - List of PerceptionResult at the same frame.
+/// * `occlusion_filter`    - OcclusionFilter instance.
+pub(crate) fn filter_occluded_objects<G: Borrow<DynamicObject>>(
+    ground_truths: Vec<G>,
+    results: &[PerceptionResult],
+    occlusion_filter: &OcclusionFilter,
+) -> Vec<G> {
+    let ratios = occlusion_ratios(&ground_truths, occlusion_filter.num_rays);
+    ground_truths
+        .into_iter()
+        .zip(ratios)
+        .filter(|(object, ratio)| {
+            *ratio < occlusion_filter.max_occlusion_ratio
+                || results
+                    .iter()
+                    .any(|result| result.ground_truth_object.as_ref() == Some(object.borrow()))
+        })
+        .map(|(object, _)| object)
+        .collect()
+}
+
+/// Returns results except FPs suppressed by the cross-camera filter.
+/// Only results whose estimation is not paired with any GT are regarded as FPs.
+///
+/// * `results`             - List of PerceptionResult at the same camera frame.
+/// * `cross_camera_filter` - CrossCameraFilter instance.
+pub(crate) fn filter_cross_camera_fp(
+    results: Vec<PerceptionResult>,
+    cross_camera_filter: &CrossCameraFilter,
+) -> Vec<PerceptionResult> {
+    results
+        .into_iter()
+        .filter(|result| {
+            let estimation = &result.estimated_object;
+            result.ground_truth_object.is_some()
+                || !cross_camera_filter.is_suppressed(&estimation.frame_id, &estimation.position)
+        })
+        .collect()
 }
 
 /// Returns hashmap that key is `Label` and value is the number objects that have same label.
@@ -208,15 +434,15 @@ pub(crate) fn hash_num_objects(
     ret
 }
 
-/// Returns hashmap that key is `Label` and value is list of results that estimated object have same label.
+/// Returns hashmap that key is `Label` and value is list of references to results that estimated object have same label.
 ///
 /// * `results`         - List of results.
 /// * `target_labels`   - List of target labels.
-pub(crate) fn hash_results(
-    results: &[PerceptionResult],
+pub(crate) fn hash_results_ref<'a>(
+    results: &'a [PerceptionResult],
     target_labels: &[Label],
-) -> HashMap<Label, Vec<PerceptionResult>> {
-    let mut ret: HashMap<Label, Vec<PerceptionResult>> = HashMap::new();
+) -> HashMap<Label, Vec<&'a PerceptionResult>> {
+    let mut ret: HashMap<Label, Vec<&PerceptionResult>> = HashMap::new();
 
     target_labels.iter().for_each(|label| {
         ret.insert(label.to_owned(), Vec::new());
@@ -224,31 +450,7 @@ pub(crate) fn hash_results(
 
     results.iter().for_each(|result| {
         if let Some(v) = ret.get_mut(&result.estimated_object.label) {
-            v.push(result.clone())
-        }
-    });
-
-    ret
-}
-
-/// Returns hashmap that key is `Label` and value is the number of results that estimated object have same label.
-///
-/// * `results`         - List of results.
-/// * `target_labels`   - List of target labels.
-#[allow(unused)]
-pub(crate) fn hash_num_results(
-    results: &[PerceptionResult],
-    target_labels: &[Label],
-) -> HashMap<Label, usize> {
-    let mut ret: HashMap<Label, usize> = HashMap::new();
-
-    target_labels.iter().for_each(|label| {
-        ret.insert(label.to_owned(), 0);
-    });
-
-    results.iter().for_each(|result| {
-        if let Some(v) = ret.get_mut(&result.estimated_object.label) {
-            *v += 1
+            v.push(result)
         }
     });
 
@@ -258,37 +460,23 @@ pub(crate) fn hash_num_results(
 #[cfg(test)]
 mod tests {
     use crate::{
-        filter::{hash_num_objects, hash_objects, is_target_object},
+        config::{CrossCameraFilter, DetectionRange, FpSuppressionPolicy, OcclusionFilter},
+        filter::{
+            filter_cross_camera_fp, filter_occluded_objects, hash_num_objects, is_target_object,
+            occlusion_ratios,
+        },
         frame_id::FrameID,
         label::Label,
         object::object3d::DynamicObject,
+        result::object::PerceptionResult,
+        utils::projection::CameraModel,
     };
-    use chrono::NaiveDateTime;
-
-    #[test]
-    fn test_hash_objects() {
-        let object = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-            frame_id: FrameID::BaseLink,
-            position: [1.0, 1.0, 0.0],
-            orientation: [1.0, 0.0, 0.0, 0.0],
-            size: [2.0, 1.0, 1.0],
-            velocity: None,
-            confidence: 1.0,
-            label: Label::Car,
-            pointcloud_num: Some(1000),
-            uuid: Some("111".to_string()),
-        };
-
-        let object_map = hash_objects(&vec![object.clone()], &vec![Label::Car, Label::Pedestrian]);
-        assert_eq!(*object_map.get(&Label::Car).unwrap(), vec![object]);
-        assert_eq!(*object_map.get(&Label::Pedestrian).unwrap(), vec![]);
-    }
+    use chrono::DateTime;
 
     #[test]
     fn test_hash_num_objects() {
         let object = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -298,9 +486,10 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
-        let object_num_map = hash_num_objects(&vec![object], &vec![Label::Car, Label::Pedestrian]);
+        let object_num_map = hash_num_objects(&[object], &[Label::Car, Label::Pedestrian]);
         assert_eq!(*object_num_map.get(&Label::Car).unwrap(), 1);
         assert_eq!(*object_num_map.get(&Label::Pedestrian).unwrap(), 0);
     }
@@ -308,7 +497,7 @@ mod tests {
     #[test]
     fn test_is_target_object() {
         let object = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -318,23 +507,171 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
         let target_labels = vec![Label::Car, Label::Pedestrian];
-        let max_x_positions = vec![20.0, 10.0];
-        let max_y_positions = vec![20.0, 10.0];
+        let detection_ranges = vec![
+            DetectionRange::symmetric(20.0, 20.0),
+            DetectionRange::symmetric(10.0, 10.0),
+        ];
         let min_point_numbers = Some(vec![100, 100]);
         let target_uuids = None;
 
         let is_target = is_target_object(
             &object,
             &target_labels,
-            &max_x_positions,
-            &max_y_positions,
+            &detection_ranges,
             &min_point_numbers,
             &target_uuids,
+            None,
         );
 
-        assert_eq!(is_target, true);
+        assert!(is_target);
+
+        // Forward range is longer than backward range.
+        let detection_ranges = vec![
+            DetectionRange::new(50.0, 10.0, 20.0, 20.0),
+            DetectionRange::symmetric(10.0, 10.0),
+        ];
+        let is_target_at = |position: [f64; 3]| {
+            is_target_object(
+                &DynamicObject {
+                    position,
+                    ..object.clone()
+                },
+                &target_labels,
+                &detection_ranges,
+                &None,
+                &None,
+                None,
+            )
+        };
+        assert!(is_target_at([30.0, 0.0, 0.0]));
+        assert!(!is_target_at([-30.0, 0.0, 0.0]));
+        assert!(!is_target_at([0.0, -20.0, 0.0]));
+
+        // Ranges are checked in base_link, where the ego vehicle at (100, 0) heads to -x of map.
+        let map_object = DynamicObject {
+            frame_id: FrameID::Map,
+            position: [70.0, 0.0, 0.0],
+            ..object.clone()
+        };
+        let ego_pose = ([100.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]);
+        let is_target_with = |ego_pose| {
+            is_target_object(
+                &map_object,
+                &target_labels,
+                &detection_ranges,
+                &None,
+                &None,
+                ego_pose,
+            )
+        };
+        assert!(is_target_with(Some((&ego_pose.0, &ego_pose.1))));
+        assert!(!is_target_with(Some((&[40.0, 0.0, 0.0], &ego_pose.1))));
+        assert!(!is_target_with(None));
+    }
+
+    #[test]
+    fn test_filter_occluded_objects() {
+        let near = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [5.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 4.0, 2.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Truck,
+            pointcloud_num: None,
+            uuid: Some("near".to_string()),
+            class_probabilities: None,
+        };
+        let hidden = DynamicObject {
+            position: [15.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            label: Label::Car,
+            uuid: Some("hidden".to_string()),
+            ..near.clone()
+        };
+        let partial = DynamicObject {
+            position: [15.0, 6.0, 0.0],
+            uuid: Some("partial".to_string()),
+            ..hidden.clone()
+        };
+        let ground_truths = vec![near.clone(), hidden.clone(), partial.clone()];
+
+        let ratios = occlusion_ratios(&ground_truths, 16);
+        assert_eq!(ratios[0], 0.0);
+        assert_eq!(ratios[1], 1.0);
+        assert!(0.0 < ratios[2] && ratios[2] < 1.0);
+
+        let filter = OcclusionFilter::new(0.9);
+        let ret = filter_occluded_objects(ground_truths.clone(), &[], &filter);
+        assert_eq!(ret, vec![near.clone(), partial.clone()]);
+
+        // Occluded GT paired with an estimation is kept.
+        let results = vec![PerceptionResult::new(hidden.clone(), Some(hidden.clone()))];
+        let ret = filter_occluded_objects(ground_truths, &results, &filter);
+        assert_eq!(ret, vec![near, hidden, partial]);
+    }
+
+    #[test]
+    fn test_filter_cross_camera_fp() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::CamFront,
+            position: [0.0, 0.0, 10.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        };
+        // The back camera looks in the opposite direction of the front camera.
+        let camera = |frame_id, rotation| CameraModel {
+            frame_id,
+            intrinsic: [[1000.0, 0.0, 800.0], [0.0, 1000.0, 450.0], [0.0, 0.0, 1.0]],
+            image_size: [1600, 900],
+            translation: [0.0, 0.0, 0.0],
+            rotation,
+        };
+        let cameras = vec![
+            camera(FrameID::CamFront, [1.0, 0.0, 0.0, 0.0]),
+            camera(FrameID::CamBack, [0.0, 1.0, 0.0, 0.0]),
+        ];
+
+        let in_view = PerceptionResult::new(object.clone(), None);
+        let behind = PerceptionResult::new(
+            DynamicObject {
+                position: [0.0, 0.0, -10.0],
+                ..object.clone()
+            },
+            None,
+        );
+        let aside = PerceptionResult::new(
+            DynamicObject {
+                position: [100.0, 0.0, 1.0],
+                ..object.clone()
+            },
+            None,
+        );
+        let paired = PerceptionResult::new(aside.estimated_object.clone(), Some(object.clone()));
+        let results = vec![in_view, behind, aside, paired];
+
+        let num_results = |policy| {
+            filter_cross_camera_fp(
+                results.clone(),
+                &CrossCameraFilter::new(policy, cameras.clone()),
+            )
+            .len()
+        };
+        assert_eq!(num_results(FpSuppressionPolicy::Disabled), 4);
+        assert_eq!(num_results(FpSuppressionPolicy::OutOfView), 2);
+        assert_eq!(num_results(FpSuppressionPolicy::VisibleInOtherCameras), 3);
     }
 }
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result as FormatResult};
 use std::str::FromStr;
 use thiserror::Error as ThisError;
@@ -6,15 +7,14 @@ pub type FrameIdResult<T> = Result<T, FrameIdError>;
 
 /// Errors that can occur while constructing `FrameID` instance.
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum FrameIdError {
-    #[error("internal error")]
-    InternalError,
-    #[error("value error: {0}")]
-    ValueError(String),
+    #[error("unknown frame id: {0}")]
+    UnknownFrameId(String),
 }
 
 /// Represents type of sensor frames.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FrameID {
     // 3D
     BaseLink,
@@ -52,7 +52,7 @@ impl FromStr for FrameID {
             "CamFrontRight" | "cam_front_right" => Ok(FrameID::CamFrontRight),
             "CamTrafficLightNear" | "cam_traffic_light_near" => Ok(FrameID::CamTrafficLightNear),
             "CamTrafficLightFar" | "cam_traffic_light_far" => Ok(FrameID::CamTrafficLightFar),
-            _ => Err(FrameIdError::ValueError(input.to_string())),
+            _ => Err(FrameIdError::UnknownFrameId(input.to_string())),
         }
     }
 }
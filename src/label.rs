@@ -3,21 +3,23 @@ use std::{
     fmt::{Display, Formatter, Result as FormatResult},
 };
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error as ThisError;
 
 pub type LabelResult<T> = Result<T, LabelError>;
 
 /// Errors that can occur while constructing `Label` instance.
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum LabelError {
-    #[error("internal error")]
-    InternalError,
-    #[error("value error: {0}")]
-    ValueError(String),
+    #[error("unknown label prefix: {0}")]
+    UnknownLabelPrefix(String),
+    #[error("unknown label name: {0}")]
+    UnknownLabelName(String),
 }
 
 /// Represents name of labels.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Label {
     Unknown,
     Car,
@@ -39,7 +41,7 @@ impl Display for Label {
 /// Use `::new()` method to generate instance.
 ///
 /// * `paris`   - HashMap of pairs, key is name of label in string, value is `Label` instance.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LabelConverter<'a> {
     pairs: HashMap<&'a str, Label>,
 }
@@ -113,7 +115,7 @@ impl<'a> LabelConverter<'a> {
                 pairs.insert("static_object.bicycle_rack", Label::Unknown);
                 pairs.insert("static_object.bollard", Label::Unknown);
             }
-            _ => Err(LabelError::ValueError(label_prefix.to_string()))?,
+            _ => Err(LabelError::UnknownLabelPrefix(label_prefix.to_string()))?,
         }
         let ret = Self { pairs };
         Ok(ret)
@@ -134,15 +136,252 @@ impl<'a> LabelConverter<'a> {
     /// assert_eq!(label, Label::Car);
     /// ```
     pub fn convert(&self, name: &str) -> Label {
-        let lower_name = name.to_lowercase();
-        match self.pairs.get(lower_name.as_str()) {
-            Some(value) => value.to_owned(),
+        match self.try_convert(name) {
+            Some(value) => value,
             None => {
                 log::warn!("unexpected label name: {}, set as Label::Unknown", name);
                 Label::Unknown
             }
         }
     }
+
+    /// Convert string label name into `Label` instance. If the name is unexpected, returns None.
+    ///
+    /// * `name`    - Name of label in string.
+    pub fn try_convert(&self, name: &str) -> Option<Label> {
+        self.pairs.get(name.to_lowercase().as_str()).cloned()
+    }
+}
+
+/// Table to remap label names of estimations into `Label`.
+///
+/// Models trained on a label set different from GTs, e.g. COCO-like `person`, need a mapping
+/// to GT labels. This is separate from `LabelConverter`, which is used for GTs.
+/// Names are compared case-insensitively, and names missing in the table are converted into `Label::Unknown`.
+///
+/// * `pairs`   - HashMap of pairs, key is lowercase name of estimation label, value is `Label` instance.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LabelRemap {
+    pairs: HashMap<String, Label>,
+}
+
+impl LabelRemap {
+    /// Construct `LabelRemap` from pairs of estimation label name and `Label`.
+    ///
+    /// * `pairs`   - Pairs of estimation label name and Label instance.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::label::{Label, LabelRemap};
+    ///
+    /// let remap = LabelRemap::new([("person", Label::Pedestrian), ("motorcycle", Label::Motorbike)]);
+    ///
+    /// assert_eq!(remap.convert("Person"), Label::Pedestrian);
+    /// assert_eq!(remap.convert("traffic light"), Label::Unknown);
+    /// ```
+    pub fn new<I, S>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (S, Label)>,
+        S: AsRef<str>,
+    {
+        let pairs = pairs
+            .into_iter()
+            .map(|(name, label)| (name.as_ref().to_lowercase(), label))
+            .collect();
+        Self { pairs }
+    }
+
+    /// Construct `LabelRemap` from pairs of estimation label name and GT label name.
+    /// GT label names are converted with `LabelConverter`.
+    ///
+    /// * `pairs`           - Pairs of estimation label name and GT label name.
+    /// * `label_prefix`    - Name of label prefix of GTs, e.g. autoware.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use perception_eval::label::{Label, LabelRemap};
+    ///
+    /// let pairs = HashMap::from([("person".to_string(), "pedestrian".to_string())]);
+    /// let remap = LabelRemap::from_names(&pairs, "autoware").unwrap();
+    /// assert_eq!(remap.convert("person"), Label::Pedestrian);
+    ///
+    /// let pairs = HashMap::from([("person".to_string(), "human".to_string())]);
+    /// assert!(LabelRemap::from_names(&pairs, "autoware").is_err());
+    /// ```
+    pub fn from_names(pairs: &HashMap<String, String>, label_prefix: &str) -> LabelResult<Self> {
+        let converter = LabelConverter::new(label_prefix)?;
+        let pairs = pairs
+            .iter()
+            .map(|(name, target)| match converter.try_convert(target) {
+                Some(label) => Ok((name, label)),
+                None => Err(LabelError::UnknownLabelName(target.to_owned())),
+            })
+            .collect::<LabelResult<Vec<_>>>()?;
+        Ok(Self::new(pairs))
+    }
+
+    /// Convert estimation label name into `Label` instance.
+    ///
+    /// * `name`    - Name of estimation label in string.
+    pub fn convert(&self, name: &str) -> Label {
+        match self.pairs.get(&name.to_lowercase()) {
+            Some(label) => label.to_owned(),
+            None => {
+                log::warn!(
+                    "estimation label name: {} is not remapped, set as Label::Unknown",
+                    name
+                );
+                Label::Unknown
+            }
+        }
+    }
+
+    /// Convert class probabilities keyed by estimation label names into ones keyed by `Label`.
+    /// Probabilities of names remapped into the same label are summed up.
+    ///
+    /// * `probabilities`   - Class probabilities keyed by estimation label names.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use perception_eval::label::{Label, LabelRemap};
+    ///
+    /// let remap = LabelRemap::new([("car", Label::Car), ("van", Label::Car), ("person", Label::Pedestrian)]);
+    /// let probabilities = HashMap::from([
+    ///     ("car".to_string(), 0.5),
+    ///     ("van".to_string(), 0.25),
+    ///     ("person".to_string(), 0.25),
+    /// ]);
+    ///
+    /// let probabilities = remap.convert_probabilities(&probabilities);
+    /// assert_eq!(probabilities[&Label::Car], 0.75);
+    /// assert_eq!(probabilities[&Label::Pedestrian], 0.25);
+    /// ```
+    pub fn convert_probabilities(
+        &self,
+        probabilities: &HashMap<String, f64>,
+    ) -> HashMap<Label, f64> {
+        let mut ret = HashMap::new();
+        probabilities.iter().for_each(|(name, probability)| {
+            *ret.entry(self.convert(name)).or_insert(0.0) += probability;
+        });
+        ret
+    }
+
+    /// Returns the number of pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns whether there is no pair.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+/// Target labels of evaluation shared by `FilterParams` and `MetricsParams`.
+///
+/// Labels are converted once with a single `LabelConverter`, so that every list of thresholds
+/// indexed by label has the same ordering. Duplicated labels are removed keeping the first one.
+/// The converter is kept to convert estimation labels in the same way.
+///
+/// * `labels`      - List of Label instances.
+/// * `converter`   - LabelConverter instance used to convert labels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationLabels {
+    labels: Vec<Label>,
+    converter: LabelConverter<'static>,
+}
+
+impl EvaluationLabels {
+    /// Construct `EvaluationLabels` from string labels.
+    ///
+    /// * `target_labels`   - List of string labels.
+    /// * `label_prefix`    - Name of label prefix, e.g. autoware.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::label::{EvaluationLabels, Label};
+    ///
+    /// let labels = EvaluationLabels::new(&["car", "vehicle.car", "pedestrian"], "autoware").unwrap();
+    ///
+    /// assert_eq!(labels.labels(), &[Label::Car, Label::Pedestrian]);
+    /// assert_eq!(labels.index_of(&Label::Pedestrian), Some(1));
+    /// ```
+    pub fn new(target_labels: &[&str], label_prefix: &str) -> LabelResult<Self> {
+        let converter = LabelConverter::new(label_prefix)?;
+        Ok(Self::from_converter(target_labels, &converter))
+    }
+
+    /// Construct `EvaluationLabels` from string labels converted with `LabelConverter`,
+    /// which is useful to resolve other labels in the same way.
+    ///
+    /// * `target_labels`   - List of string labels.
+    /// * `converter`       - LabelConverter instance.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::label::{EvaluationLabels, Label, LabelConverter};
+    ///
+    /// let converter = LabelConverter::new("autoware").unwrap();
+    /// let labels = EvaluationLabels::from_converter(&["car", "pedestrian"], &converter);
+    ///
+    /// assert_eq!(labels.index_of(&converter.convert("vehicle.car")), Some(0));
+    /// ```
+    pub fn from_converter(target_labels: &[&str], converter: &LabelConverter<'static>) -> Self {
+        let labels = target_labels
+            .iter()
+            .map(|name| converter.convert(name))
+            .collect();
+        Self::from_labels(labels, converter)
+    }
+
+    /// Construct `EvaluationLabels` from `Label` instances.
+    ///
+    /// * `labels`      - List of Label instances.
+    /// * `converter`   - LabelConverter instance used to convert other labels.
+    pub fn from_labels(labels: Vec<Label>, converter: &LabelConverter<'static>) -> Self {
+        let mut unique: Vec<Label> = Vec::with_capacity(labels.len());
+        for label in labels {
+            if unique.contains(&label) {
+                log::warn!("duplicated target label: {}, ignored", label);
+            } else {
+                unique.push(label);
+            }
+        }
+        Self {
+            labels: unique,
+            converter: converter.to_owned(),
+        }
+    }
+
+    /// Returns list of labels.
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Returns the converter used to convert labels.
+    pub fn converter(&self) -> &LabelConverter<'static> {
+        &self.converter
+    }
+
+    /// Returns the number of labels.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns whether there is no label.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Returns the index of the label. If the label is not a target, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn index_of(&self, label: &Label) -> Option<usize> {
+        self.labels.iter().position(|l| l == label)
+    }
 }
 
 /// Convert input string labels into Label objects.
@@ -1,13 +1,102 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or `libm` feature must be enabled");
+
+extern crate alloc;
+
+/// Stable public surface of the crate, which follows semantic versioning.
+///
+/// Modules hidden from the documentation, such as `config`, `dataset`, `manager` and `metrics`,
+/// are still public but experimental, so that their paths and items may change in minor releases.
+/// Prefer importing supported items from this module.
+///
+/// # Examples
+/// ```
+/// use perception_eval::api::{quickstart, MatchingMode};
+///
+/// let quickstart = quickstart();
+///
+/// let mut manager = quickstart.manager();
+/// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+///     manager.add_frame_result(estimations, frame).unwrap();
+/// }
+/// let score = manager.get_metrics_score().unwrap();
+/// assert!(score.mean_ap(&MatchingMode::CenterDistance).is_some());
+/// ```
+#[cfg(feature = "std")]
+pub mod api;
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+#[doc(hidden)]
 pub mod config;
+pub mod core;
+#[cfg(feature = "std")]
+#[doc(hidden)]
 pub mod dataset;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "std")]
+pub mod ensemble;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
 pub mod evaluation_task;
+/// Fault injection to verify that the evaluator degrades gracefully.
+///
+/// This module is public only if the `fault-injection` feature is enabled,
+/// otherwise every injection point is a no-op.
+/// Injected faults are local to the current thread, so that tests running in parallel do not interfere.
+///
+/// # Examples
+/// ```
+/// use perception_eval::fault::{inject, Fault};
+///
+/// let quickstart = perception_eval::quickstart();
+/// let manager = quickstart.manager();
+/// let timestamp = quickstart.frame_ground_truths[0].timestamp;
+///
+/// {
+///     let _guard = inject(&[Fault::MissingGroundTruth]);
+///     assert!(manager.get_frame_ground_truth(&timestamp).is_none());
+/// }
+/// assert!(manager.get_frame_ground_truth(&timestamp).is_some());
+/// ```
+#[cfg(feature = "fault-injection")]
+pub mod fault;
+#[cfg(all(feature = "std", not(feature = "fault-injection")))]
+pub(crate) mod fault;
+#[cfg(feature = "std")]
 pub mod filter;
+#[cfg(feature = "std")]
 pub mod frame_id;
+#[cfg(feature = "std")]
 pub mod label;
+#[cfg(feature = "std")]
+#[doc(hidden)]
 pub mod manager;
+#[cfg(feature = "std")]
 pub mod matching;
+#[cfg(feature = "std")]
+#[doc(hidden)]
 pub mod metrics;
+#[cfg(feature = "std")]
 pub mod object;
+#[cfg(feature = "std")]
+pub mod quickstart;
+#[cfg(feature = "std")]
 pub mod result;
+#[cfg(feature = "rosbag")]
+pub mod rosbag;
+#[cfg(feature = "std")]
 pub mod threshold;
+#[cfg(feature = "std")]
 pub mod utils;
+
+#[cfg(feature = "std")]
+pub use self::error::{Error, Result};
+#[cfg(feature = "std")]
+pub use self::quickstart::quickstart;
@@ -1,24 +1,61 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    cell::{OnceCell, RefCell},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration as StdDuration,
+};
 
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 
 use crate::{
-    config::PerceptionEvaluationConfig,
-    dataset::{get_current_frame, load_dataset, DatasetResult, FrameGroundTruth},
+    audit::{get_audit_records, write_audit_records},
+    config::{
+        FrameIdPolicy, LatencyCompensation, LatencyCompensationMethod, PerceptionEvaluationConfig,
+    },
+    dataset::{
+        annotation::AnnotationMap,
+        filter_frames_by_sample_tokens,
+        index::FrameIndex,
+        load_datasets,
+        maneuver::ManeuverMap,
+        quality::{DataQualityReport, QualityParams},
+        timeline::SceneTimeline,
+        DatasetManifest, DatasetResult, FrameGroundTruth,
+    },
+    diagnostics::Diagnostic,
     evaluation_task::EvaluationTask,
-    filter::{filter_objects, hash_num_objects, hash_results},
+    fault::{inject_object_faults, is_injected, Fault},
+    filter::{filter_cross_camera_fp, filter_objects_with_ego_pose, filter_occluded_objects},
     label::Label,
-    matching::{MatchingMode, MatchingResult},
+    matching::{MatchingError, MatchingMode, MatchingResult},
     metrics::{
-        error::{MetricsError, MetricsResult},
+        breakdown::BreakdownReport,
+        coverage::InstanceCoverage,
+        error::MetricsResult,
+        latency::LatencyReport,
+        preview::ScorePreview,
+        region::{RegionBreakdown, RegionGrouping},
         score::MetricsScore,
+        summary::{ErrorRateSummary, OrientationSummary},
+        tracking::{IdSwitchCounter, TrackSmoothness},
     },
-    object::object3d::DynamicObject,
+    object::object3d::{DynamicObject, OrientationValidity},
     result::{
-        frame::PerceptionFrameResult, object::get_perception_results, object::PerceptionResult,
+        frame::PerceptionFrameResult,
+        kitti::KittiWriter,
+        object::get_perception_results_within,
+        spill::{FrameResultStore, SpillResult},
+    },
+    utils::{
+        color::{object_color, ColorLegend, ObjectKind},
+        pointcloud::{crop_points_in_box, write_pcd, write_pcd_with_color},
     },
 };
 
+#[cfg(feature = "arrow")]
+use crate::result::columnar::{ColumnarResult, ResultRecordWriter};
+
 /// Manager of perception evaluation.
 ///
 /// In order to construct, use the `::new()` method.
@@ -27,11 +64,37 @@ use crate::{
 /// with the `add_frame_result()` method.
 ///
 /// The `get_metrics_score()` method calculates a total metrics score with stacked `frame_results` till that time.
+///
+/// If spilling is enabled with the `with_spill()` method, `frame_results` only holds frames
+/// which have not been spilled to disk yet.
 #[derive(Debug, Clone)]
 pub struct PerceptionEvaluationManager<'a> {
     pub config: &'a PerceptionEvaluationConfig,
-    pub frame_ground_truths: Vec<FrameGroundTruth>,
+    pub manifest: DatasetManifest,
+    frame_ground_truths: Vec<FrameGroundTruth>,
     pub frame_results: Vec<PerceptionFrameResult>,
+    uncompensated_frame_results: Vec<PerceptionFrameResult>,
+    timestamp_offset: OnceCell<Duration>,
+    frame_index: FrameIndex,
+    spill: Option<Spill>,
+    num_uncompensable_frames: usize,
+    memory_bytes: usize,
+    orientation_summary: OrientationSummary,
+    timeline: SceneTimeline,
+    annotations: AnnotationMap,
+    diagnostics: RefCell<Vec<Diagnostic>>,
+}
+
+/// Temporary on-disk stores of frame results which exceed the memory limit.
+///
+/// * `store`               - Store of frame results.
+/// * `uncompensated_store` - Store of frame results without latency compensation, if it is configured.
+/// * `max_memory_bytes`    - Maximum bytes of frame results kept in memory.
+#[derive(Debug, Clone)]
+struct Spill {
+    store: FrameResultStore,
+    uncompensated_store: Option<FrameResultStore>,
+    max_memory_bytes: usize,
 }
 
 impl<'a> PerceptionEvaluationManager<'a> {
@@ -49,7 +112,7 @@ impl<'a> PerceptionEvaluationManager<'a> {
     /// };  
     /// use std::error::Error;
     ///
-    /// type Result<T> = std::result::Result<T, Box<dyn Error>>;
+    /// type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
     ///
     /// fn main() -> Result<()> {
     ///     let scenario = "tests/config/perception.yaml";
@@ -65,23 +128,213 @@ impl<'a> PerceptionEvaluationManager<'a> {
     /// }
     /// ```
     pub fn from(config: &'a PerceptionEvaluationConfig) -> DatasetResult<Self> {
-        let frame_ground_truths = load_dataset(
-            &config.version,
+        let frame_ground_truths = load_datasets(
+            &config.versions,
             &config.dataset_path,
             &config.evaluation_task,
             &config.frame_id,
         )?;
 
-        let ret = Self {
+        let mut ret = Self::from_frame_ground_truths(config, frame_ground_truths);
+        if config
+            .metrics_params
+            .breakdown_axes
+            .iter()
+            .any(|axis| axis.requires_annotations())
+        {
+            for version in &config.versions {
+                ret.annotations
+                    .merge(AnnotationMap::load(version, &config.dataset_path)?);
+            }
+        }
+        ret.manifest.save(config.result_dir.join("manifest.json"))?;
+        Ok(ret)
+    }
+
+    /// Construct `PerceptionEvaluationManager` from GTs already loaded in memory.
+    ///
+    /// Unlike `::from()`, neither the dataset nor the manifest is accessed on disk.
+    ///
+    /// * `config`              - Evaluation configuration.
+    /// * `frame_ground_truths` - List of FrameGroundTruth.
+    pub fn from_frame_ground_truths(
+        config: &'a PerceptionEvaluationConfig,
+        frame_ground_truths: Vec<FrameGroundTruth>,
+    ) -> Self {
+        // Scene time is counted from the first loaded frame, even if it is not a target.
+        let timeline = SceneTimeline::new(&frame_ground_truths);
+        let (frame_ground_truths, num_skipped_frames, missing_sample_tokens) =
+            match &config.target_sample_tokens {
+                Some(sample_tokens) => {
+                    filter_frames_by_sample_tokens(frame_ground_truths, sample_tokens)
+                }
+                None => (frame_ground_truths, 0, Vec::new()),
+            };
+        log::info!(
+            "number of frames: {}, skipped: {}",
+            frame_ground_truths.len(),
+            num_skipped_frames
+        );
+
+        let manifest = DatasetManifest {
+            versions: config.versions.to_owned(),
+            num_frames: frame_ground_truths.len(),
+            num_skipped_frames,
+            missing_sample_tokens,
+        };
+
+        let frame_index = FrameIndex::new(&frame_ground_truths);
+
+        Self {
             config,
+            manifest,
             frame_ground_truths,
             frame_results: Vec::new(),
+            uncompensated_frame_results: Vec::new(),
+            timestamp_offset: OnceCell::new(),
+            frame_index,
+            spill: None,
+            num_uncompensable_frames: 0,
+            memory_bytes: 0,
+            orientation_summary: OrientationSummary::default(),
+            timeline,
+            annotations: AnnotationMap::default(),
+            diagnostics: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns loaded GT frames to be evaluated.
+    pub fn frame_ground_truths(&self) -> &[FrameGroundTruth] {
+        &self.frame_ground_truths
+    }
+
+    /// Replace GT frames to be evaluated, and rebuild the index for timestamp lookups.
+    ///
+    /// * `frame_ground_truths` - List of FrameGroundTruth.
+    ///
+    /// # Examples
+    /// ```
+    /// let quickstart = perception_eval::quickstart();
+    ///
+    /// let mut manager = quickstart.manager();
+    /// let frames = quickstart.frame_ground_truths[..1].to_vec();
+    /// manager.set_frame_ground_truths(frames);
+    ///
+    /// assert_eq!(manager.frame_ground_truths().len(), 1);
+    /// assert!(manager
+    ///     .get_frame_ground_truth(&quickstart.frame_ground_truths[1].timestamp)
+    ///     .is_none());
+    /// ```
+    pub fn set_frame_ground_truths(&mut self, frame_ground_truths: Vec<FrameGroundTruth>) {
+        self.frame_index = FrameIndex::new(&frame_ground_truths);
+        self.frame_ground_truths = frame_ground_truths;
+    }
+
+    /// Set annotation metadata of GTs, such as visibility and attributes, used by breakdown axes.
+    ///
+    /// Unlike `::from()`, `::from_frame_ground_truths()` does not load them from the dataset.
+    ///
+    /// * `annotations` - Annotation metadata of GTs.
+    pub fn with_annotations(mut self, annotations: AnnotationMap) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Enable spilling accumulated frame results to disk, in order to bound memory usage.
+    ///
+    /// Once frame results in memory are estimated to exceed `max_memory_bytes`,
+    /// they are moved to a temporary file under `dir`, and streamed back while computing scores.
+    /// If spilling fails, frame results are kept in memory.
+    ///
+    /// * `dir`                 - Directory to place the temporary file. Memory-backed filesystems such as tmpfs should be avoided.
+    /// * `max_memory_bytes`    - Maximum bytes of frame results kept in memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::matching::MatchingMode;
+    ///
+    /// let quickstart = perception_eval::quickstart();
+    ///
+    /// let mut manager = quickstart.manager().with_spill("work_dir/spill", 0).unwrap();
+    /// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+    ///     manager.add_frame_result(estimations, frame).unwrap();
+    /// }
+    /// assert!(manager.frame_results.is_empty());
+    /// assert_eq!(manager.num_frame_results(), quickstart.frame_ground_truths.len());
+    ///
+    /// let score = manager.get_metrics_score().unwrap();
+    /// let expected = quickstart.run().unwrap();
+    /// assert_eq!(
+    ///     score.mean_ap(&MatchingMode::CenterDistance),
+    ///     expected.mean_ap(&MatchingMode::CenterDistance)
+    /// );
+    /// ```
+    pub fn with_spill<P>(mut self, dir: P, max_memory_bytes: usize) -> SpillResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let uncompensated_store = match self.config.latency_compensation {
+            Some(_) => Some(FrameResultStore::new(dir.as_ref())?),
+            None => None,
         };
-        Ok(ret)
+        self.spill = Some(Spill {
+            store: FrameResultStore::new(dir)?,
+            uncompensated_store,
+            max_memory_bytes,
+        });
+        Ok(self)
+    }
+
+    /// Returns the number of accumulated frame results, including spilled ones.
+    pub fn num_frame_results(&self) -> usize {
+        let num_spilled = self.spill.as_ref().map_or(0, |spill| spill.store.len());
+        num_spilled + self.frame_results.len()
+    }
+
+    /// Convert label name of estimation into `Label` instance.
+    ///
+    /// If `estimation_label_remap` is configured, the name is remapped with the table.
+    /// Otherwise, the name is converted with the converter of target labels in the config.
+    ///
+    /// * `name`    - Name of estimation label in string.
+    pub fn convert_estimation_label(&self, name: &str) -> Label {
+        match &self.config.estimation_label_remap {
+            Some(remap) => remap.convert(name),
+            None => self.config.metrics_params.label_converter.convert(name),
+        }
+    }
+
+    /// Convert class probabilities of estimation keyed by label names into ones keyed by `Label`.
+    /// Probabilities of names converted into the same label are summed up.
+    ///
+    /// * `probabilities`   - Class probabilities keyed by estimation label names.
+    pub fn convert_estimation_probabilities(
+        &self,
+        probabilities: &HashMap<String, f64>,
+    ) -> HashMap<Label, f64> {
+        let mut ret = HashMap::new();
+        probabilities.iter().for_each(|(name, probability)| {
+            *ret.entry(self.convert_estimation_label(name))
+                .or_insert(0.0) += probability;
+        });
+        ret
     }
 
     /// Add estimated objects and ground truths at current frame.
     ///
+    /// If `timestamp_correction` is configured, timestamps of estimated objects are corrected.
+    /// Labels of estimated objects with class probabilities are replaced with their argmax.
+    /// Near-unit orientation quaternions of estimated objects are normalized, and objects with invalid ones,
+    /// such as zero or non-finite quaternions, are rejected and counted in the report.
+    /// Estimations and GTs with non-finite positions, sizes, velocities or confidences are rejected.
+    /// Rejected objects are reported in `diagnostics()`.
+    /// If `frame_id_policy` is enabled, objects in other frames than the configured one
+    /// fail the frame, or are transformed with the ego pose of the frame.
+    /// If `latency_compensation` is configured, frames are also evaluated without compensation,
+    /// which are reported in `get_latency_report()`.
+    /// If `occlusion_filter` is configured, heavily occluded GTs without paired estimation are ignored.
+    /// If `cross_camera_filter` is configured, FPs are suppressed according to its policy.
+    ///
     /// * `estimated_objects`   - List of estimated objects.
     /// * `frame_ground_truth`  - Set of GTs that has the nearest timestamp.
     pub fn add_frame_result(
@@ -89,82 +342,874 @@ impl<'a> PerceptionEvaluationManager<'a> {
         estimated_objects: &[DynamicObject],
         frame_ground_truth: &FrameGroundTruth,
     ) -> MatchingResult<()> {
-        let filtered_estimations =
-            filter_objects(estimated_objects, false, &self.config.filter_params);
-        let filtered_frame_ground_truth = self.filter_frame_ground_truth(frame_ground_truth);
+        let injected_estimations = inject_object_faults(estimated_objects, true);
+        let estimated_objects = injected_estimations.as_deref().unwrap_or(estimated_objects);
+        let preprocessed_ground_truth = self.preprocess_ground_truth(frame_ground_truth)?;
+        let frame_ground_truth = preprocessed_ground_truth
+            .as_ref()
+            .unwrap_or(frame_ground_truth);
 
-        let results =
-            get_perception_results(&filtered_estimations, &filtered_frame_ground_truth.objects);
+        let offset = estimated_objects
+            .first()
+            .map(|object| self.timestamp_offset(&object.timestamp))
+            .unwrap_or_else(Duration::zero);
+        let needs_relabel = estimated_objects.iter().any(|object| {
+            object
+                .argmax_label()
+                .is_some_and(|label| label != object.label)
+        });
+        let validities = estimated_objects
+            .iter()
+            .map(|object| object.orientation_validity())
+            .collect::<Vec<_>>();
+        self.orientation_summary.add_frame(&validities);
+        let needs_reorientation = validities
+            .iter()
+            .any(|validity| *validity != OrientationValidity::Valid);
+        let needs_rejection = estimated_objects.iter().any(|object| !object.is_finite());
+        let corrected_objects;
+        let estimated_objects =
+            if offset.is_zero() && !needs_relabel && !needs_reorientation && !needs_rejection {
+                estimated_objects
+            } else {
+                corrected_objects = estimated_objects
+                    .iter()
+                    .zip(&validities)
+                    .filter_map(|(object, validity)| {
+                        if !object.is_finite() {
+                            self.report(Diagnostic::RejectedEstimation {
+                                timestamp: object.timestamp,
+                                uuid: object.uuid.to_owned(),
+                                reason: "non-finite values",
+                            });
+                            return None;
+                        }
+                        let orientation = match validity {
+                            OrientationValidity::Valid => object.orientation,
+                            OrientationValidity::NearUnit(orientation) => *orientation,
+                            OrientationValidity::Invalid => {
+                                self.report(Diagnostic::RejectedEstimation {
+                                    timestamp: object.timestamp,
+                                    uuid: object.uuid.to_owned(),
+                                    reason: "invalid orientation",
+                                });
+                                return None;
+                            }
+                        };
+                        let object = DynamicObject {
+                            timestamp: object.timestamp + offset,
+                            orientation,
+                            ..object.to_owned()
+                        };
+                        Some(object.with_argmax_label())
+                    })
+                    .collect::<Vec<_>>();
+                &corrected_objects
+            };
+
+        let aligned_estimations = self.align_frame_id(estimated_objects, frame_ground_truth)?;
+        let estimated_objects = aligned_estimations.as_deref().unwrap_or(estimated_objects);
+
+        let frame_result = match &self.config.latency_compensation {
+            Some(compensation) => {
+                let compensated =
+                    self.compensate_latency(compensation, estimated_objects, frame_ground_truth)?;
+                let uncompensated =
+                    self.evaluate_frame(estimated_objects, frame_ground_truth, false)?;
+                self.memory_bytes += uncompensated.estimated_bytes();
+                self.uncompensated_frame_results.push(uncompensated);
+                match compensated {
+                    Some((estimations, ground_truth)) => {
+                        Some(self.evaluate_frame(&estimations, &ground_truth, true)?)
+                    }
+                    None => {
+                        self.num_uncompensable_frames += 1;
+                        None
+                    }
+                }
+            }
+            None => Some(self.evaluate_frame(estimated_objects, frame_ground_truth, true)?),
+        };
+        if let Some(frame_result) = frame_result {
+            self.memory_bytes += frame_result.estimated_bytes();
+            self.frame_results.push(frame_result);
+        }
+        self.spill_frame_results();
+        Ok(())
+    }
+
+    /// Returns the result of matching estimations with GTs at one frame,
+    /// where both are filtered with `FilterParams`.
+    ///
+    /// * `estimated_objects`   - List of preprocessed estimated objects.
+    /// * `frame_ground_truth`  - Set of preprocessed GTs at the frame.
+    /// * `audit`               - Whether to write audit records of the frame, if configured.
+    fn evaluate_frame(
+        &self,
+        estimated_objects: &[DynamicObject],
+        frame_ground_truth: &FrameGroundTruth,
+        audit: bool,
+    ) -> MatchingResult<PerceptionFrameResult> {
+        let filtered_estimations = filter_objects_with_ego_pose(
+            estimated_objects,
+            false,
+            &self.config.filter_params,
+            frame_ground_truth.ego_pose(),
+        );
+        let filtered_ground_truths = filter_objects_with_ego_pose(
+            &frame_ground_truth.objects,
+            true,
+            &self.config.filter_params,
+            frame_ground_truth.ego_pose(),
+        );
+
+        let mut results = get_perception_results_within(
+            &filtered_estimations,
+            &filtered_ground_truths,
+            self.config.metrics_params.max_matching_distance(),
+        );
+
+        if let Some(cross_camera_filter) = &self.config.filter_params.cross_camera_filter {
+            results = filter_cross_camera_fp(results, cross_camera_filter);
+        }
+
+        let filtered_ground_truths = match &self.config.filter_params.occlusion_filter {
+            Some(occlusion_filter) => {
+                filter_occluded_objects(filtered_ground_truths, &results, occlusion_filter)
+            }
+            None => filtered_ground_truths,
+        };
+
+        if let Some(audit_log) = self.config.audit_log.as_ref().filter(|_| audit) {
+            let mut records = self
+                .config
+                .metrics_params
+                .evaluated_matchings()
+                .into_iter()
+                .flat_map(|(matching_mode, thresholds)| {
+                    get_audit_records(
+                        &frame_ground_truth.timestamp,
+                        &filtered_estimations,
+                        &filtered_ground_truths,
+                        &results,
+                        &self.config.filter_params.target_labels,
+                        &matching_mode,
+                        thresholds,
+                        &self.config.metrics_params.plane_distance_config,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let frame = self.timeline.get(&frame_ground_truth.timestamp).cloned();
+            records
+                .iter_mut()
+                .for_each(|record| record.frame = frame.clone());
+            if let Err(err) = write_audit_records(audit_log, &records) {
+                self.report(Diagnostic::WriteFailure {
+                    path: audit_log.to_owned(),
+                    message: err.to_string(),
+                });
+            }
+        }
 
-        let frame_result = PerceptionFrameResult::new(
+        PerceptionFrameResult::new(
             results,
-            filtered_frame_ground_truth,
+            frame_ground_truth.with_objects(filtered_ground_truths.into_iter().cloned().collect()),
             &self.config.filter_params.target_labels,
             MatchingMode::PlaneDistance,
             &self.config.metrics_params.plane_distance_thresholds,
-        )?;
-        self.frame_results.push(frame_result);
-        Ok(())
+            &self.config.metrics_params.plane_distance_config,
+        )
+    }
+
+    /// Returns GTs with injected faults, without ones with non-finite values and in the configured frame_id,
+    /// or None if the frame is kept as it is.
+    ///
+    /// * `frame_ground_truth`  - Set of GTs at one frame.
+    fn preprocess_ground_truth(
+        &self,
+        frame_ground_truth: &FrameGroundTruth,
+    ) -> MatchingResult<Option<FrameGroundTruth>> {
+        let injected_ground_truth = inject_object_faults(&frame_ground_truth.objects, false)
+            .map(|objects| frame_ground_truth.with_objects(objects));
+        let frame_ground_truth = injected_ground_truth.as_ref().unwrap_or(frame_ground_truth);
+        let finite_ground_truth = frame_ground_truth
+            .objects
+            .iter()
+            .any(|object| !object.is_finite())
+            .then(|| self.reject_non_finite_ground_truths(frame_ground_truth));
+        let frame_ground_truth = finite_ground_truth.as_ref().unwrap_or(frame_ground_truth);
+        let aligned_ground_truth = self
+            .align_frame_id(&frame_ground_truth.objects, frame_ground_truth)?
+            .map(|objects| frame_ground_truth.with_objects(objects));
+        Ok(aligned_ground_truth
+            .or(finite_ground_truth)
+            .or(injected_ground_truth))
+    }
+
+    /// Returns estimations and GTs compensated for the latency of the perception pipeline.
+    ///
+    /// For `LatencyCompensationMethod::ShiftEstimations`, if no GT frame is found at the sensor time,
+    /// the missing frame is reported and None is returned.
+    ///
+    /// * `compensation`        - Latency compensation.
+    /// * `estimated_objects`   - List of preprocessed estimated objects.
+    /// * `frame_ground_truth`  - Set of preprocessed GTs at the output time of estimations.
+    fn compensate_latency(
+        &self,
+        compensation: &LatencyCompensation,
+        estimated_objects: &[DynamicObject],
+        frame_ground_truth: &FrameGroundTruth,
+    ) -> MatchingResult<Option<(Vec<DynamicObject>, FrameGroundTruth)>> {
+        match compensation.method {
+            LatencyCompensationMethod::ShiftEstimations => {
+                let latency = compensation.duration();
+                let estimated_objects = estimated_objects
+                    .iter()
+                    .map(|object| DynamicObject {
+                        timestamp: object.timestamp - latency,
+                        ..object.to_owned()
+                    })
+                    .collect();
+                let timestamp = frame_ground_truth.timestamp - latency;
+                let Some(frame) = self.find_frame_ground_truth(&timestamp) else {
+                    self.report(Diagnostic::MissingGroundTruth { timestamp });
+                    return Ok(None);
+                };
+                let frame_ground_truth = self.preprocess_ground_truth(&frame)?.unwrap_or(frame);
+                Ok(Some((estimated_objects, frame_ground_truth)))
+            }
+            LatencyCompensationMethod::PropagateGroundTruths => {
+                let objects = frame_ground_truth
+                    .objects
+                    .iter()
+                    .map(|object| compensation.propagate(object))
+                    .collect();
+                let frame_ground_truth = frame_ground_truth.with_objects(objects);
+                Ok(Some((estimated_objects.to_owned(), frame_ground_truth)))
+            }
+        }
     }
 
     /// Returns `FrameGroundTruth` that has the nearest timestamp to the current timestamp.
     ///
+    /// If `timestamp_correction` is configured, the timestamp is corrected before searching.
+    ///
+    /// Frames are looked up with the index of `frame_ground_truths()` in O(log n).
+    ///
     /// * `timestamp`   - Current timestamp.
     pub fn get_frame_ground_truth(&self, timestamp: &NaiveDateTime) -> Option<FrameGroundTruth> {
-        get_current_frame(&self.frame_ground_truths, timestamp)
+        let timestamp = *timestamp + self.timestamp_offset(timestamp);
+        let frame = if is_injected(Fault::MissingGroundTruth) {
+            None
+        } else {
+            self.find_frame_ground_truth(&timestamp)
+        };
+        if frame.is_none() {
+            self.report(Diagnostic::MissingGroundTruth { timestamp });
+        }
+        frame
+    }
+
+    /// Returns `FrameGroundTruth` that has the nearest timestamp within the tolerance.
+    ///
+    /// * `timestamp`   - Timestamp of the dataset clock.
+    fn find_frame_ground_truth(&self, timestamp: &NaiveDateTime) -> Option<FrameGroundTruth> {
+        self.frame_index
+            .find(timestamp)
+            .map(|i| self.frame_ground_truths[i].to_owned())
+    }
+
+    /// Returns diagnostics of problems which the manager recovered from, such as rejected objects,
+    /// missing GT frames and reports failed to be written, in the order of occurrence.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.borrow().to_owned()
+    }
+
+    /// Log the diagnostic and keep it to be returned by `diagnostics()`.
+    ///
+    /// * `diagnostic`  - Diagnostic instance.
+    fn report(&self, diagnostic: Diagnostic) {
+        log::warn!("{}", diagnostic);
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    /// Returns the offset added to timestamps of estimations.
+    ///
+    /// The offset is resolved at the first call and reused for the following frames.
+    ///
+    /// * `timestamp`   - Timestamp of estimation used to resolve the offset.
+    fn timestamp_offset(&self, timestamp: &NaiveDateTime) -> Duration {
+        *self
+            .timestamp_offset
+            .get_or_init(|| match &self.config.timestamp_correction {
+                Some(correction) => {
+                    let offset = correction.resolve(timestamp, &self.frame_ground_truths);
+                    log::info!(
+                        "timestamp correction: {:?}, applied offset: {} [us]",
+                        correction,
+                        offset.num_microseconds().unwrap_or(i64::MAX)
+                    );
+                    offset
+                }
+                None => Duration::zero(),
+            })
     }
 
     /// Returns the `MetricsScore` that calculated metrics score with having been accumulated frame results till that time.
+    ///
+    /// If the maneuver breakdown is enabled, recall is also broken down by maneuvers of GTs.
+    /// If the alignment analysis is enabled, distributions of the best-available matching score are also reported.
+    /// If `export_pr_curves` is enabled, raw data of PR curves are saved under `result_dir/pr_curves`.
     pub fn get_metrics_score(&self) -> MetricsResult<MetricsScore> {
-        let target_labels = &self.config.metrics_params.target_labels;
         let mut score = MetricsScore::new(&self.config.metrics_params);
-        let mut scene_results: HashMap<Label, Vec<PerceptionResult>> = HashMap::new();
-        let mut num_scene_gt = HashMap::new();
+        let all_frame_results = self.load_frame_results()?;
 
-        target_labels.iter().for_each(|label| {
-            scene_results.insert(label.to_owned(), Vec::new());
-            num_scene_gt.insert(label.to_owned(), 0);
-        });
+        let frame_results = self.without_tracking_warmup(&all_frame_results);
+        let frame_results = frame_results.iter().copied();
 
-        self.frame_results.iter().for_each(|frame| {
-            let mut result_map = hash_results(frame.results(), target_labels);
-            let num_gt_map = hash_num_objects(&frame.frame_ground_truth().objects, target_labels);
-            target_labels.iter().for_each(|label| {
-                if let Some(results) = scene_results.get_mut(label) {
-                    if let Some(result) = result_map.get_mut(label) {
-                        results.append(result)
-                    }
-                };
-                if let Some(num_gts) = num_scene_gt.get_mut(label) {
-                    if let Some(num_gt) = num_gt_map.get(label) {
-                        *num_gts += num_gt
-                    }
+        score.evaluate_frames(&self.config.evaluation_task, frame_results.clone())?;
+        if let Some(maneuver_params) = &self.config.metrics_params.maneuver_params {
+            // Maneuvers are derived over all loaded GTs, so that evaluated frames have their neighbors.
+            let maneuvers = ManeuverMap::new(&self.frame_ground_truths, maneuver_params);
+            score.evaluate_maneuvers(frame_results.clone(), &maneuvers);
+        }
+        if self.config.metrics_params.alignment_analysis {
+            score.evaluate_alignment(frame_results);
+        }
+        score.evaluate_error_rates(&all_frame_results);
+        score.set_orientation_summary(&self.orientation_summary);
+        score.evaluate_data_quality(
+            &all_frame_results,
+            &QualityParams::default(),
+            &self.timeline,
+        );
+        if self.config.export_pr_curves {
+            let paths = score.save_pr_curves(self.config.result_dir.join("pr_curves"))?;
+            log::info!("exported {} PR curves", paths.len());
+        }
+        Ok(score)
+    }
+
+    /// Returns the metrics score over frames sampled from accumulated frame results within the time budget,
+    /// and the fraction of evaluated frames. This is intended for interactive tools
+    /// which need a rough score before `get_metrics_score()` finishes.
+    ///
+    /// The preview contains AP based scores only, and PR curves are not exported.
+    /// At least `MIN_PREVIEW_FRAMES` frames are evaluated even if the budget is exceeded.
+    ///
+    /// * `budget`  - Time budget of the preview.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let quickstart = perception_eval::quickstart();
+    ///
+    /// let mut manager = quickstart.manager();
+    /// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+    ///     manager.add_frame_result(estimations, frame).unwrap();
+    /// }
+    /// let preview = manager.get_metrics_score_preview(Duration::from_millis(100)).unwrap();
+    ///
+    /// assert!(0.0 < preview.coverage() && preview.coverage() <= 1.0);
+    /// println!("{}", preview);
+    /// ```
+    pub fn get_metrics_score_preview(&self, budget: StdDuration) -> MetricsResult<ScorePreview> {
+        let all_frame_results = self.load_frame_results()?;
+
+        let frame_results = self.without_tracking_warmup(&all_frame_results);
+
+        ScorePreview::new(self.config, &frame_results, budget)
+    }
+
+    /// Returns metrics scores with and without latency compensation over accumulated frame results,
+    /// or None if `latency_compensation` is not configured.
+    ///
+    /// Frames without GTs at the sensor time are excluded from the compensated score,
+    /// and counted in the report.
+    pub fn get_latency_report(&self) -> MetricsResult<Option<LatencyReport>> {
+        let Some(compensation) = &self.config.latency_compensation else {
+            return Ok(None);
+        };
+        let compensated = self.load_frame_results()?;
+        let uncompensated = load_spilled_frame_results(
+            self.spill
+                .as_ref()
+                .and_then(|spill| spill.uncompensated_store.as_ref()),
+            &self.uncompensated_frame_results,
+        )?;
+        LatencyReport::new(
+            self.config,
+            compensation,
+            &self.without_tracking_warmup(&compensated),
+            &self.without_tracking_warmup(&uncompensated),
+        )
+        .map(|report| Some(report.with_num_skipped_frames(self.num_uncompensable_frames)))
+    }
+
+    /// Returns the metrics score aggregated for each region over accumulated frame results.
+    ///
+    /// * `grouping`    - How to group frames into regions.
+    pub fn get_region_breakdown(
+        &self,
+        grouping: &RegionGrouping,
+    ) -> MetricsResult<RegionBreakdown> {
+        RegionBreakdown::new(self.config, &self.load_frame_results()?, grouping)
+    }
+
+    /// Returns the metrics score for every combination of bins of breakdown axes over accumulated frame results.
+    pub fn get_breakdown(&self) -> MetricsResult<BreakdownReport> {
+        BreakdownReport::new(
+            self.config,
+            &self.load_frame_results()?,
+            &self.config.metrics_params.breakdown_axes,
+            &self.frame_ground_truths,
+            &self.annotations,
+        )
+    }
+
+    /// Returns the counter of ID switches over accumulated frame results except warm-up frames,
+    /// with the association memory configured by `max_missed_frames`.
+    ///
+    /// Spilled frame results are streamed from disk without being loaded into memory at once.
+    pub fn get_id_switches(&self) -> SpillResult<IdSwitchCounter> {
+        let mut counter = IdSwitchCounter::new(self.config.metrics_params.max_missed_frames);
+        self.for_each_tracking_frame(|frame_result| {
+            counter.add_frame_result(frame_result);
+        })?;
+        Ok(counter)
+    }
+
+    /// Returns the fraction of frames where each GT instance is detected over accumulated frame results.
+    ///
+    /// Spilled frame results are streamed from disk without being loaded into memory at once.
+    pub fn get_instance_coverage(&self) -> SpillResult<InstanceCoverage> {
+        let mut coverage = InstanceCoverage::new();
+        for frame_result in self.iter_frame_results()? {
+            coverage.add_frame_result(&*frame_result?);
+        }
+        Ok(coverage)
+    }
+
+    /// Returns the smoothness of matched tracks over accumulated frame results except warm-up frames.
+    ///
+    /// Spilled frame results are streamed from disk without being loaded into memory at once.
+    pub fn get_track_smoothness(&self) -> SpillResult<TrackSmoothness> {
+        let mut smoothness = TrackSmoothness::new();
+        self.for_each_tracking_frame(|frame_result| {
+            smoothness.add_frame_result(frame_result);
+        })?;
+        Ok(smoothness)
+    }
+
+    /// Returns the report of suspicious annotations over all loaded GTs,
+    /// including frames which have not been evaluated yet.
+    ///
+    /// * `params`  - Parameters of heuristics.
+    pub fn get_data_quality_report(&self, params: &QualityParams) -> DataQualityReport {
+        DataQualityReport::new(&self.frame_ground_truths, params).with_timeline(&self.timeline)
+    }
+
+    /// Returns the scene-relative time axis over all loaded frames,
+    /// including frames excluded by `target_sample_tokens`.
+    pub fn timeline(&self) -> &SceneTimeline {
+        &self.timeline
+    }
+
+    /// Export per-result records of accumulated frame results to a Parquet file,
+    /// and returns the number of written rows.
+    ///
+    /// Spilled frame results are streamed from disk without being loaded into memory at once.
+    /// See `ResultRecordWriter` for columns of records.
+    ///
+    /// * `path`    - Path of the Parquet file.
+    ///
+    /// # Examples
+    /// ```
+    /// let quickstart = perception_eval::quickstart();
+    ///
+    /// let mut manager = quickstart.manager();
+    /// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+    ///     manager.add_frame_result(estimations, frame).unwrap();
+    /// }
+    /// let num_rows = manager.export_parquet("work_dir/results.parquet").unwrap();
+    /// assert!(num_rows > 0);
+    /// ```
+    #[cfg(feature = "arrow")]
+    pub fn export_parquet<P>(&self, path: P) -> ColumnarResult<usize>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let mut writer =
+            ResultRecordWriter::create(path, &self.config.metrics_params.plane_distance_config)?;
+        let mut write_frame = |frame_result: &PerceptionFrameResult| {
+            let frame_label = self
+                .timeline
+                .get(&frame_result.frame_ground_truth().timestamp);
+            writer.write_frame(frame_result, frame_label)
+        };
+        for frame_result in self.iter_frame_results()? {
+            write_frame(&*frame_result?)?;
+        }
+        writer.finish()
+    }
+
+    /// Export accumulated frame results in the KITTI format under the directory,
+    /// and returns the number of exported frames. See `KittiWriter` for the layout of files.
+    ///
+    /// Spilled frame results are streamed from disk without being loaded into memory at once.
+    ///
+    /// * `dir` - Root directory path.
+    pub fn export_kitti<P>(&self, dir: P) -> DatasetResult<usize>
+    where
+        P: AsRef<Path>,
+    {
+        let mut writer = KittiWriter::create(dir.as_ref())?;
+        let mut write_frame = |frame_result: &PerceptionFrameResult| {
+            let frame_label = self
+                .timeline
+                .get(&frame_result.frame_ground_truth().timestamp);
+            writer.write_frame(frame_result, frame_label)
+        };
+        for frame_result in self.iter_frame_results()? {
+            write_frame(&*frame_result?)?;
+        }
+        let num_frames = writer.finish()?;
+        log::info!(
+            "exported {} frames in the KITTI format to {}",
+            num_frames,
+            dir.as_ref().display()
+        );
+        Ok(num_frames)
+    }
+
+    /// Export pointclouds cropped around FN GTs to PCD files under `viz_dir/fn_crops`,
+    /// and returns paths of exported files.
+    ///
+    /// Nothing is exported unless `load_raw_data` is enabled.
+    /// Frames without the reference to the pointcloud are skipped.
+    /// File names are prefixed with the sequence number of the frame in the scene.
+    /// Points of GTs with uuid are painted with the color in the legend of `export_color_legend()`.
+    ///
+    /// * `margin`  - Margin added to each side of GT boxes in [m].
+    pub fn export_fn_pointclouds(&self, margin: f64) -> DatasetResult<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        if !self.config.load_raw_data {
+            log::warn!("load_raw_data is disabled, skip exporting pointclouds of FNs");
+            return Ok(paths);
+        }
+
+        let out_dir = self.config.viz_dir.join("fn_crops");
+        for frame_result in self.load_frame_results()?.iter() {
+            let frame_ground_truth = frame_result.frame_ground_truth();
+            let fn_objects = frame_result.fn_objects();
+            let raw_pointcloud = match &frame_ground_truth.raw_pointcloud {
+                Some(raw_pointcloud) if !fn_objects.is_empty() => raw_pointcloud,
+                _ => continue,
+            };
+            let points = raw_pointcloud.load()?;
+            let frame_name = match &frame_ground_truth.sample_token {
+                Some(token) => token.to_owned(),
+                None => frame_ground_truth
+                    .timestamp
+                    .and_utc()
+                    .timestamp_micros()
+                    .to_string(),
+            };
+            // Prefix the sequence number so that files are listed in the scene order.
+            let frame_name = match self.timeline.get(&frame_ground_truth.timestamp) {
+                Some(label) => format!("{:04}_{}", label.sequence, frame_name),
+                None => frame_name,
+            };
+            for (i, object) in fn_objects.iter().enumerate() {
+                let object_name = match &object.uuid {
+                    Some(uuid) => uuid.to_owned(),
+                    None => i.to_string(),
                 };
-            });
+                let path = out_dir.join(format!(
+                    "{}_{}_{}.pcd",
+                    frame_name, object.label, object_name
+                ));
+                let cropped = crop_points_in_box(&points, object, margin);
+                match &object.uuid {
+                    Some(uuid) => write_pcd_with_color(
+                        &path,
+                        &cropped,
+                        object_color(ObjectKind::GroundTruth, uuid),
+                    )?,
+                    None => write_pcd(&path, &cropped)?,
+                }
+                paths.push(path);
+            }
+        }
+        log::info!(
+            "exported {} pointclouds of FNs to {}",
+            paths.len(),
+            out_dir.display()
+        );
+        Ok(paths)
+    }
+
+    /// Export the legend mapping GT instances and estimated tracks to their colors
+    /// to `viz_dir/color_legend.json`, and returns the path of the exported file.
+    ///
+    /// Colors only depend on uuids, so that they are consistent across frames of visualization outputs.
+    ///
+    /// # Examples
+    /// ```
+    /// let quickstart = perception_eval::quickstart();
+    ///
+    /// let mut manager = quickstart.manager();
+    /// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+    ///     manager.add_frame_result(estimations, frame).unwrap();
+    /// }
+    /// let path = manager.export_color_legend().unwrap();
+    /// assert!(path.ends_with("color_legend.json"));
+    /// ```
+    pub fn export_color_legend(&self) -> DatasetResult<PathBuf> {
+        let legend = ColorLegend::new(&self.load_frame_results()?);
+        let path = self.config.viz_dir.join("color_legend.json");
+        legend.save_json(&path)?;
+        log::info!(
+            "exported colors of {} objects to {}",
+            legend.len(),
+            path.display()
+        );
+        Ok(path)
+    }
+
+    /// Move frame results in memory to the spill store, if they exceed the memory limit.
+    fn spill_frame_results(&mut self) {
+        let Some(spill) = &mut self.spill else {
+            return;
+        };
+        if self.memory_bytes <= spill.max_memory_bytes {
+            return;
+        }
+        let spilled = spill.store.append(&self.frame_results).and_then(|_| {
+            match &mut spill.uncompensated_store {
+                Some(store) => store.append(&self.uncompensated_frame_results),
+                None => Ok(()),
+            }
         });
+        match spilled {
+            Ok(()) => {
+                log::debug!(
+                    "spilled {} frame results to {}",
+                    self.frame_results.len(),
+                    spill.store.path().display()
+                );
+                self.frame_results.clear();
+                self.uncompensated_frame_results.clear();
+                self.memory_bytes = 0;
+            }
+            Err(err) => log::error!("failed to spill frame results: {}", err),
+        }
+    }
 
-        match self.config.evaluation_task {
-            EvaluationTask::Detection => score.evaluate_detection(&scene_results, &num_scene_gt),
-            _ => Err(MetricsError::NotImplementedError(
-                self.config.evaluation_task.clone(),
-            ))?,
+    /// Returns all accumulated frame results in order, where spilled ones are read back from disk.
+    ///
+    /// This is for scores such as AP which need results of all frames at once.
+    /// Otherwise, use `iter_frame_results()` not to load them into memory at once.
+    fn load_frame_results(&self) -> SpillResult<Cow<'_, [PerceptionFrameResult]>> {
+        load_spilled_frame_results(
+            self.spill.as_ref().map(|spill| &spill.store),
+            &self.frame_results,
+        )
+    }
+
+    /// Returns the iterator over all accumulated frame results in order,
+    /// where spilled ones are streamed back from disk one by one.
+    fn iter_frame_results(
+        &self,
+    ) -> SpillResult<impl Iterator<Item = SpillResult<Cow<'_, PerceptionFrameResult>>>> {
+        let spilled = match &self.spill {
+            Some(spill) => Some(spill.store.iter()?),
+            None => None,
+        };
+        Ok(spilled
+            .into_iter()
+            .flatten()
+            .map(|frame_result| frame_result.map(Cow::Owned))
+            .chain(
+                self.frame_results
+                    .iter()
+                    .map(|frame_result| Ok(Cow::Borrowed(frame_result))),
+            ))
+    }
+
+    /// Call the function with each accumulated frame result in order except warm-up frames of tracking metrics,
+    /// where spilled ones are streamed back from disk one by one.
+    ///
+    /// * `f`   - Function called with each frame result.
+    fn for_each_tracking_frame<F>(&self, mut f: F) -> SpillResult<()>
+    where
+        F: FnMut(&PerceptionFrameResult),
+    {
+        let mut scene_start = SceneStart::default();
+        for (i, frame_result) in self.iter_frame_results()?.enumerate() {
+            let frame_result = frame_result?;
+            if !self.is_tracking_warmup_frame(i, &frame_result, &mut scene_start) {
+                f(&frame_result);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns frame results excluding warm-up frames of tracking metrics.
+    ///
+    /// * `frame_results`   - List of frame results in order.
+    fn without_tracking_warmup<'b>(
+        &self,
+        frame_results: &'b [PerceptionFrameResult],
+    ) -> Vec<&'b PerceptionFrameResult> {
+        let mut scene_start = SceneStart::default();
+        frame_results
+            .iter()
+            .enumerate()
+            .filter(|(i, frame)| !self.is_tracking_warmup_frame(*i, frame, &mut scene_start))
+            .map(|(_, frame)| frame)
+            .collect()
+    }
+
+    /// Returns whether the frame should be excluded from tracking metrics as warm-up,
+    /// which is measured from the start of the scene of the frame.
+    ///
+    /// * `frame_index`     - Index of the frame in accumulated frame results.
+    /// * `frame`           - Frame result.
+    /// * `scene_start`     - Start of the scene of frames visited in order.
+    fn is_tracking_warmup_frame(
+        &self,
+        frame_index: usize,
+        frame: &PerceptionFrameResult,
+        scene_start: &mut SceneStart,
+    ) -> bool {
+        if self.config.evaluation_task != EvaluationTask::Tracking {
+            return false;
+        }
+        match &self.config.metrics_params.tracking_warmup {
+            Some(warmup) => {
+                let (scene_frame_index, elapsed) =
+                    scene_start.visit(frame_index, frame.frame_ground_truth());
+                warmup.is_warming_up(scene_frame_index, elapsed)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns objects in the configured frame_id following `FrameIdPolicy`,
+    /// or None if all objects are already in the frame or the policy is disabled.
+    ///
+    /// * `objects`             - List of objects at the frame.
+    /// * `frame_ground_truth`  - Set of GTs at the frame, which has the ego pose.
+    fn align_frame_id(
+        &self,
+        objects: &[DynamicObject],
+        frame_ground_truth: &FrameGroundTruth,
+    ) -> MatchingResult<Option<Vec<DynamicObject>>> {
+        let expected = &self.config.frame_id;
+        let Some(object) = objects.iter().find(|object| object.frame_id != *expected) else {
+            return Ok(None);
+        };
+        match self.config.frame_id_policy {
+            FrameIdPolicy::Disabled => Ok(None),
+            FrameIdPolicy::Strict => Err(MatchingError::InconsistentFrameId {
+                expected: expected.to_owned(),
+                actual: object.frame_id.to_owned(),
+            }),
+            FrameIdPolicy::Transform => objects
+                .iter()
+                .map(|object| {
+                    let transformed =
+                        frame_ground_truth
+                            .ego_pose()
+                            .and_then(|(translation, rotation)| {
+                                object.transform(expected, translation, rotation)
+                            });
+                    transformed.ok_or_else(|| MatchingError::MissingTransform {
+                        from: object.frame_id.to_owned(),
+                        to: expected.to_owned(),
+                    })
+                })
+                .collect::<MatchingResult<Vec<_>>>()
+                .map(Some),
         }
-        Ok(score)
     }
 
-    /// Filter `FrameGroundTruth` with `FilterParams`.
+    /// Returns `FrameGroundTruth` without GTs which have non-finite values, and reports them.
     ///
     /// * `frame_ground_truth`  - Set of GTs at one frame.
-    fn filter_frame_ground_truth(&self, frame_ground_truth: &FrameGroundTruth) -> FrameGroundTruth {
-        let filtered_gt = filter_objects(
-            &frame_ground_truth.objects,
-            true,
-            &self.config.filter_params,
-        );
+    fn reject_non_finite_ground_truths(
+        &self,
+        frame_ground_truth: &FrameGroundTruth,
+    ) -> FrameGroundTruth {
+        let objects = frame_ground_truth
+            .objects
+            .iter()
+            .filter(|object| {
+                let is_finite = object.is_finite();
+                if !is_finite {
+                    self.report(Diagnostic::RejectedGroundTruth {
+                        timestamp: object.timestamp,
+                        uuid: object.uuid.to_owned(),
+                        reason: "non-finite values",
+                    });
+                }
+                is_finite
+            })
+            .cloned()
+            .collect();
+        frame_ground_truth.with_objects(objects)
+    }
+}
+
+/// Start of the scene of frames visited in order, from which warm-up of tracking metrics is measured.
+#[derive(Debug, Default)]
+struct SceneStart {
+    index: usize,
+    timestamp: NaiveDateTime,
+    last_frame: Option<FrameGroundTruth>,
+}
 
-        FrameGroundTruth {
-            timestamp: frame_ground_truth.timestamp.to_owned(),
-            objects: filtered_gt,
+impl SceneStart {
+    /// Visit the next frame, and returns the index of the frame from the start of its scene
+    /// and the elapsed time from the start of its scene in [s].
+    ///
+    /// * `frame_index` - Index of the frame in visited frames.
+    /// * `frame`       - Set of GTs at the frame.
+    fn visit(&mut self, frame_index: usize, frame: &FrameGroundTruth) -> (usize, f64) {
+        let is_same_scene = self
+            .last_frame
+            .as_ref()
+            .is_some_and(|last_frame| ErrorRateSummary::is_same_scene(last_frame, frame));
+        if !is_same_scene {
+            self.index = frame_index;
+            self.timestamp = frame.timestamp;
+        }
+        self.last_frame = Some(frame.with_objects(Vec::new()));
+
+        let elapsed = (frame.timestamp - self.timestamp)
+            .num_microseconds()
+            .unwrap_or(i64::MAX) as f64
+            * 1e-6;
+        (frame_index - self.index, elapsed)
+    }
+}
+
+/// Returns spilled frame results followed by ones in memory, where spilled ones are streamed back from disk.
+///
+/// * `store`           - Store of spilled frame results, if spilling is enabled.
+/// * `frame_results`   - List of frame results in memory.
+fn load_spilled_frame_results<'b>(
+    store: Option<&FrameResultStore>,
+    frame_results: &'b [PerceptionFrameResult],
+) -> SpillResult<Cow<'b, [PerceptionFrameResult]>> {
+    match store {
+        Some(store) if !store.is_empty() => {
+            let mut spilled = store.iter()?.collect::<SpillResult<Vec<_>>>()?;
+            spilled.extend_from_slice(frame_results);
+            Ok(Cow::Owned(spilled))
         }
+        _ => Ok(Cow::Borrowed(frame_results)),
     }
 }
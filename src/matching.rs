@@ -1,27 +1,64 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, f64::consts::PI};
 
-use crate::utils::point::{distance_points_bev, get_point_left_right};
+use crate::{
+    core::matching::{intersection_length, plane_distance},
+    frame_id::FrameID,
+    utils::point::{distance_points_bev, get_point_left_right},
+};
 
 use super::object::object3d::DynamicObject;
 use geo::{polygon, Area, BooleanOps, Coord, Polygon};
+use serde::{Deserialize, Serialize};
 use thiserror::Error as ThisError;
 
 pub type MatchingResult<T> = Result<T, MatchingError>;
 
+/// Represents errors that occur while matching objects.
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum MatchingError {
-    #[error("internal error")]
-    InternalError,
-    #[error("value error")]
-    ValueError,
+    #[error("invalid plane distance config: {num_points} points with {selection:?}")]
+    InvalidPlaneDistanceConfig {
+        selection: PointSelection,
+        num_points: usize,
+    },
+    #[error("inconsistent frame_id: expected {expected:?}, but got {actual:?}")]
+    InconsistentFrameId { expected: FrameID, actual: FrameID },
+    #[error("missing transform from {from:?} to {to:?}")]
+    MissingTransform { from: FrameID, to: FrameID },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MatchingMode {
     CenterDistance,
     PlaneDistance,
     Iou2d,
     Iou3d,
+    /// Velocity-vector similarity between objects whose BEV centers are within `position_gate` [m].
+    Velocity {
+        position_gate: f64,
+    },
+}
+
+/// Returns the matching method corresponding to the matching mode.
+///
+/// * `matching_mode`           - MatchingMode instance.
+/// * `plane_distance_config`   - Configuration used for `MatchingMode::PlaneDistance`.
+pub(crate) fn get_matching_method(
+    matching_mode: &MatchingMode,
+    plane_distance_config: &PlaneDistanceConfig,
+) -> Box<dyn MatchingMethod> {
+    match matching_mode {
+        MatchingMode::CenterDistance => Box::new(CenterDistanceMatching),
+        MatchingMode::PlaneDistance => Box::new(PlaneDistanceMatching {
+            config: plane_distance_config.to_owned(),
+        }),
+        MatchingMode::Iou2d => Box::new(Iou2dMatching),
+        MatchingMode::Iou3d => Box::new(Iou3dMatching),
+        MatchingMode::Velocity { position_gate } => Box::new(VelocityMatching {
+            position_gate: *position_gate,
+        }),
+    }
 }
 
 pub(crate) trait MatchingMethod {
@@ -37,6 +74,15 @@ pub(crate) trait MatchingMethod {
         ground_truth_object: &DynamicObject,
         threshold: &f64,
     ) -> bool;
+
+    /// Returns the cost to pair objects, where the pair with the smallest cost is assigned first.
+    fn pairing_cost(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+    ) -> f64 {
+        self.calculate_matching_score(estimated_object, ground_truth_object)
+    }
 }
 
 /// Matching object with euclidean distance of center of objects.
@@ -63,37 +109,197 @@ impl MatchingMethod for CenterDistanceMatching {
     }
 }
 
+/// Matching object with BEV euclidean distance of velocity vectors of objects.
+///
+/// Radar detections localize poorly but measure velocity well, so the position is only used
+/// to gate candidates coarsely. If the BEV distance of centers exceeds `position_gate`,
+/// or either object does not have velocity, the score is infinity.
+///
+/// * `position_gate`   - Maximum BEV distance of centers in [m].
 #[derive(Debug, Clone)]
-pub struct PlaneDistanceMatching;
+pub struct VelocityMatching {
+    pub position_gate: f64,
+}
 
-impl MatchingMethod for PlaneDistanceMatching {
+impl MatchingMethod for VelocityMatching {
     fn calculate_matching_score(
         &self,
         estimated_object: &DynamicObject,
         ground_truth_object: &DynamicObject,
     ) -> f64 {
+        let distance =
+            distance_points_bev(&estimated_object.position, &ground_truth_object.position);
+        if self.position_gate < distance {
+            return f64::INFINITY;
+        }
+        match (&estimated_object.velocity, &ground_truth_object.velocity) {
+            (Some(est_velocity), Some(gt_velocity)) => {
+                distance_points_bev(est_velocity, gt_velocity)
+            }
+            _ => f64::INFINITY,
+        }
+    }
+
+    fn is_better_than(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+        threshold: &f64,
+    ) -> bool {
+        let velocity_error = self.calculate_matching_score(estimated_object, ground_truth_object);
+        velocity_error < *threshold
+    }
+}
+
+/// Represents how to select the footprint points used for plane distance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointSelection {
+    /// Use the nearest corners of footprint from the origin.
+    NearestCorners,
+    /// Use points evenly sampled on the nearest face of footprint from the origin.
+    NearestFace,
+}
+
+/// Configuration of plane distance matching.
+///
+/// * `selection`   - How to select points.
+/// * `num_points`  - Number of points, `1..=4` for `NearestCorners` and `2..` for `NearestFace`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaneDistanceConfig {
+    pub selection: PointSelection,
+    pub num_points: usize,
+}
+
+impl Default for PlaneDistanceConfig {
+    /// Returns the configuration which uses the two nearest corners, same as the python perception_eval.
+    fn default() -> Self {
+        Self {
+            selection: PointSelection::NearestCorners,
+            num_points: 2,
+        }
+    }
+}
+
+impl PlaneDistanceConfig {
+    /// Construct `PlaneDistanceConfig`.
+    ///
+    /// * `selection`   - How to select points.
+    /// * `num_points`  - Number of points.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::matching::{PlaneDistanceConfig, PointSelection};
+    ///
+    /// let config = PlaneDistanceConfig::new(PointSelection::NearestCorners, 4).unwrap();
+    /// assert!(PlaneDistanceConfig::new(PointSelection::NearestCorners, 5).is_err());
+    /// assert!(PlaneDistanceConfig::new(PointSelection::NearestFace, 1).is_err());
+    /// ```
+    pub fn new(selection: PointSelection, num_points: usize) -> MatchingResult<Self> {
+        let config = Self {
+            selection,
+            num_points,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Returns `MatchingError::InvalidPlaneDistanceConfig` if the number of points is invalid for the selection.
+    pub fn validate(&self) -> MatchingResult<()> {
+        let is_valid = match self.selection {
+            PointSelection::NearestCorners => (1..=4).contains(&self.num_points),
+            PointSelection::NearestFace => 2 <= self.num_points,
+        };
+        if is_valid {
+            Ok(())
+        } else {
+            Err(MatchingError::InvalidPlaneDistanceConfig {
+                selection: self.selection.to_owned(),
+                num_points: self.num_points,
+            })
+        }
+    }
+
+    /// Returns points of the object's footprint used to compute plane distance,
+    /// which are ordered from left to right seen from the origin.
+    ///
+    /// * `object`  - DynamicObject instance.
+    fn select_points(&self, object: &DynamicObject) -> Vec<[f64; 3]> {
         let sort_func = |p1: &[f64; 3], p2: &[f64; 3]| -> Ordering {
             let d1 = p1[0].hypot(p1[1]);
             let d2 = p2[0].hypot(p2[1]);
             d1.partial_cmp(&d2).unwrap()
         };
 
-        let mut est_footprint = estimated_object.footprint();
-        est_footprint.sort_by(sort_func);
-
-        let mut gt_footprint = ground_truth_object.footprint();
-        gt_footprint.sort_by(sort_func);
+        let mut footprint = object.footprint();
+        footprint.sort_by(sort_func);
+
+        match self.selection {
+            PointSelection::NearestCorners => {
+                let mut points = footprint[..self.num_points].to_vec();
+                sort_left_right(&mut points, &object.position);
+                points
+            }
+            PointSelection::NearestFace => {
+                let (left, right) = get_point_left_right(&footprint[0], &footprint[1]);
+                let num_segments = (self.num_points - 1) as f64;
+                (0..self.num_points)
+                    .map(|i| {
+                        let ratio = i as f64 / num_segments;
+                        [
+                            left[0] + (right[0] - left[0]) * ratio,
+                            left[1] + (right[1] - left[1]) * ratio,
+                            left[2] + (right[2] - left[2]) * ratio,
+                        ]
+                    })
+                    .collect()
+            }
+        }
+    }
+}
 
-        let (est_left_point, est_right_point) =
-            get_point_left_right(&est_footprint[0], &est_footprint[1]);
+/// Sort points from left to right seen from the origin,
+/// with respect to the azimuth of `center` to avoid wrapping around at the back.
+///
+/// * `points`  - List of points.
+/// * `center`  - Reference point.
+fn sort_left_right(points: &mut [[f64; 3]], center: &[f64; 3]) {
+    let center_azimuth = center[1].atan2(center[0]);
+    let relative_azimuth = |p: &[f64; 3]| -> f64 {
+        let mut diff = p[1].atan2(p[0]) - center_azimuth;
+        if PI < diff {
+            diff -= 2.0 * PI;
+        } else if diff < -PI {
+            diff += 2.0 * PI;
+        }
+        diff
+    };
+    points.sort_by(|p1, p2| {
+        relative_azimuth(p2)
+            .partial_cmp(&relative_azimuth(p1))
+            .unwrap()
+    });
+}
 
-        let (gt_left_point, gt_right_point) =
-            get_point_left_right(&gt_footprint[0], &gt_footprint[1]);
+/// Matching object with the distance between the nearest planes of objects.
+///
+/// Points used to compute the distance are configured by `PlaneDistanceConfig`,
+/// and the score is RMS of distances between corresponding points.
+#[derive(Debug, Clone, Default)]
+pub struct PlaneDistanceMatching {
+    pub config: PlaneDistanceConfig,
+}
 
-        let distance_left = distance_points_bev(est_left_point, gt_left_point).abs();
-        let distance_right = distance_points_bev(est_right_point, gt_right_point).abs();
+impl MatchingMethod for PlaneDistanceMatching {
+    fn calculate_matching_score(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+    ) -> f64 {
+        let est_points = self.config.select_points(estimated_object);
+        let gt_points = self.config.select_points(ground_truth_object);
 
-        ((distance_left.powi(2) + distance_right.powi(2)) / 2.0).sqrt()
+        plane_distance(&est_points, &gt_points)
     }
 
     fn is_better_than(
@@ -169,6 +375,135 @@ impl MatchingMethod for Iou3dMatching {
     }
 }
 
+/// Direction in which the score of the custom matching function is regarded as better.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreDirection {
+    /// Smaller score is better, e.g. distance. Pairs whose score is less than the threshold are matched.
+    LowerIsBetter,
+    /// Larger score is better, e.g. IoU. Pairs whose score is greater than the threshold are matched.
+    HigherIsBetter,
+}
+
+/// Matching object with a user-defined score function, without implementing a new matching mode.
+///
+/// * `func`        - Function returning the score of estimated and ground truth objects.
+/// * `direction`   - Direction in which the score is better.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::{
+///     frame_id::FrameID,
+///     label::Label,
+///     matching::{CustomMatching, ScoreDirection},
+///     object::object3d::DynamicObject,
+/// };
+///
+/// let object = DynamicObject::new(
+///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("111");
+///
+/// // Lateral distance, which is tolerant of longitudinal errors.
+/// let matching = CustomMatching::new(
+///     |est: &DynamicObject, gt: &DynamicObject| (est.position[1] - gt.position[1]).abs(),
+///     ScoreDirection::LowerIsBetter,
+/// );
+/// let mut far = object.clone();
+/// far.position = [5.0, 1.5, 0.0];
+///
+/// assert_eq!(matching.score(&far, &object), 0.5);
+/// assert!(matching.is_matched(&far, &object, &1.0));
+/// ```
+pub struct CustomMatching<F> {
+    func: F,
+    direction: ScoreDirection,
+}
+
+impl<F> CustomMatching<F>
+where
+    F: Fn(&DynamicObject, &DynamicObject) -> f64,
+{
+    /// Construct `CustomMatching`.
+    ///
+    /// * `func`        - Function returning the score of estimated and ground truth objects.
+    /// * `direction`   - Direction in which the score is better.
+    pub fn new(func: F, direction: ScoreDirection) -> Self {
+        Self { func, direction }
+    }
+
+    /// Returns the score of estimated and ground truth objects.
+    ///
+    /// * `estimated_object`    - Estimated object.
+    /// * `ground_truth_object` - Ground truth object.
+    pub fn score(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+    ) -> f64 {
+        (self.func)(estimated_object, ground_truth_object)
+    }
+
+    /// Returns whether the score of estimated and ground truth objects is better than the threshold.
+    ///
+    /// * `estimated_object`    - Estimated object.
+    /// * `ground_truth_object` - Ground truth object.
+    /// * `threshold`           - Threshold of the score.
+    pub fn is_matched(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+        threshold: &f64,
+    ) -> bool {
+        let score = self.score(estimated_object, ground_truth_object);
+        match self.direction {
+            ScoreDirection::LowerIsBetter => score < *threshold,
+            ScoreDirection::HigherIsBetter => *threshold < score,
+        }
+    }
+}
+
+impl<F> MatchingMethod for CustomMatching<F>
+where
+    F: Fn(&DynamicObject, &DynamicObject) -> f64,
+{
+    fn calculate_matching_score(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+    ) -> f64 {
+        self.score(estimated_object, ground_truth_object)
+    }
+
+    fn is_better_than(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+        threshold: &f64,
+    ) -> bool {
+        self.is_matched(estimated_object, ground_truth_object, threshold)
+    }
+
+    fn pairing_cost(
+        &self,
+        estimated_object: &DynamicObject,
+        ground_truth_object: &DynamicObject,
+    ) -> f64 {
+        let score = self.score(estimated_object, ground_truth_object);
+        match self.direction {
+            ScoreDirection::LowerIsBetter => score,
+            ScoreDirection::HigherIsBetter => -score,
+        }
+    }
+}
+
 fn get_intersection_area(
     estimated_object: &DynamicObject,
     ground_truth_object: &DynamicObject,
@@ -210,30 +545,13 @@ fn get_intersection_height(
     estimated_object: &DynamicObject,
     ground_truth_object: &DynamicObject,
 ) -> f64 {
-    let min_z = {
-        [
-            estimated_object.position[2] - estimated_object.size[2] * 0.5,
-            ground_truth_object.position[2] - ground_truth_object.size[2] * 0.5,
-        ]
-        .into_iter()
-        .min_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap()
-    };
-
-    let max_z = {
+    let z_range = |object: &DynamicObject| {
         [
-            estimated_object.position[2] + estimated_object.size[2] * 0.5,
-            ground_truth_object.position[2] + ground_truth_object.size[2] * 0.5,
+            object.position[2] - object.size[2] * 0.5,
+            object.position[2] + object.size[2] * 0.5,
         ]
-        .into_iter()
-        .max_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap()
     };
-
-    [0.0, max_z - min_z]
-        .into_iter()
-        .max_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap()
+    intersection_length(&z_range(estimated_object), &z_range(ground_truth_object))
 }
 
 fn get_intersection_volume(
@@ -247,15 +565,16 @@ fn get_intersection_volume(
 #[cfg(test)]
 mod tests {
     use super::{
-        CenterDistanceMatching, Iou2dMatching, Iou3dMatching, MatchingMethod, PlaneDistanceMatching,
+        CenterDistanceMatching, Iou2dMatching, Iou3dMatching, MatchingMethod, PlaneDistanceConfig,
+        PlaneDistanceMatching, PointSelection, VelocityMatching,
     };
     use crate::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
-    use chrono::NaiveDateTime;
+    use chrono::DateTime;
 
     #[test]
     fn test_center_distance_matching() {
         let estimation = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -265,10 +584,11 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
         let ground_truth = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -278,19 +598,20 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("100".to_string()),
+            class_probabilities: None,
         };
 
         let ans_score = CenterDistanceMatching.calculate_matching_score(&estimation, &ground_truth);
         assert_eq!(ans_score, 0.0);
 
         let ans_is_better = CenterDistanceMatching.is_better_than(&estimation, &ground_truth, &1.0);
-        assert_eq!(ans_is_better, true);
+        assert!(ans_is_better);
     }
 
     #[test]
     fn test_plane_distance_matching() {
         let estimation = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -300,10 +621,11 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
         let ground_truth = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -313,19 +635,64 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("100".to_string()),
+            class_probabilities: None,
         };
 
-        let ans_score = PlaneDistanceMatching.calculate_matching_score(&estimation, &ground_truth);
+        let ans_score =
+            PlaneDistanceMatching::default().calculate_matching_score(&estimation, &ground_truth);
         assert_eq!(ans_score, 0.0);
 
-        let ans_is_better = PlaneDistanceMatching.is_better_than(&estimation, &ground_truth, &1.0);
-        assert_eq!(ans_is_better, true);
+        let ans_is_better =
+            PlaneDistanceMatching::default().is_better_than(&estimation, &ground_truth, &1.0);
+        assert!(ans_is_better);
+    }
+
+    #[test]
+    fn test_plane_distance_matching_with_config() {
+        let estimation = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [10.5, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+
+        let ground_truth = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("100".to_string()),
+            class_probabilities: None,
+        };
+
+        // Same translation for all points, so every configuration yields the offset.
+        for config in [
+            PlaneDistanceConfig::default(),
+            PlaneDistanceConfig::new(PointSelection::NearestCorners, 4).unwrap(),
+            PlaneDistanceConfig::new(PointSelection::NearestFace, 5).unwrap(),
+        ] {
+            let matching = PlaneDistanceMatching { config };
+            let ans_score = matching.calculate_matching_score(&estimation, &ground_truth);
+            assert!((ans_score - 0.5).abs() < 1e-9);
+        }
     }
 
     #[test]
     fn test_iou2d_matching() {
         let estimation = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -335,10 +702,11 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
         let ground_truth = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -348,19 +716,55 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("100".to_string()),
+            class_probabilities: None,
         };
 
         let ans_score = Iou2dMatching.calculate_matching_score(&estimation, &ground_truth);
         assert_eq!(ans_score, 1.0);
 
         let ans_is_better = Iou2dMatching.is_better_than(&estimation, &ground_truth, &0.5);
-        assert_eq!(ans_is_better, true);
+        assert!(ans_is_better);
+    }
+
+    #[test]
+    fn test_matching_rotated_boxes() {
+        // Boxes heading away from the origin at 45 deg, and GT is ahead of the estimation by 1.5m.
+        let yaw = std::f64::consts::FRAC_PI_4;
+        let heading = [yaw.cos(), yaw.sin()];
+        let estimation = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [10.0 * heading[0], 10.0 * heading[1], 0.0],
+            orientation: [(0.5 * yaw).cos(), 0.0, 0.0, (0.5 * yaw).sin()],
+            size: [1.0, 4.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+
+        let ground_truth = DynamicObject {
+            position: [11.5 * heading[0], 11.5 * heading[1], 0.0],
+            uuid: Some("100".to_string()),
+            ..estimation.clone()
+        };
+
+        // Rear corners are the nearest ones, which are shifted along the heading.
+        let plane_distance = PlaneDistanceMatching::default();
+        let ans_score = plane_distance.calculate_matching_score(&estimation, &ground_truth);
+        assert!((ans_score - 1.5).abs() < 1e-9);
+
+        // Boxes overlap by 2.5m of their length.
+        let ans_score = Iou2dMatching.calculate_matching_score(&estimation, &ground_truth);
+        assert!((ans_score - 2.5 / 5.5).abs() < 1e-9);
     }
 
     #[test]
     fn test_iou3d_matching() {
         let estimation = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -370,10 +774,11 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
         let ground_truth = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -383,12 +788,65 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("100".to_string()),
+            class_probabilities: None,
         };
 
         let ans_score = Iou3dMatching.calculate_matching_score(&estimation, &ground_truth);
         assert_eq!(ans_score, 1.0);
 
         let ans_is_better = Iou3dMatching.is_better_than(&estimation, &ground_truth, &0.5);
-        assert_eq!(ans_is_better, true);
+        assert!(ans_is_better);
+
+        // Only the overlapping height of 0.5 contributes to the intersection.
+        let shifted = DynamicObject {
+            position: [1.0, 1.0, 0.5],
+            ..estimation
+        };
+        let ans_score = Iou3dMatching.calculate_matching_score(&shifted, &ground_truth);
+        assert!((ans_score - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_velocity_matching() {
+        let ground_truth = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: Some([5.0, 0.0, 0.0]),
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("100".to_string()),
+            class_probabilities: None,
+        };
+        let estimation = DynamicObject {
+            position: [11.5, 0.5, 0.0],
+            velocity: Some([5.3, 0.4, 0.0]),
+            uuid: Some("111".to_string()),
+            ..ground_truth.clone()
+        };
+        let matching = VelocityMatching { position_gate: 2.0 };
+
+        let ans_score = matching.calculate_matching_score(&estimation, &ground_truth);
+        assert!((ans_score - 0.5).abs() < 1e-9);
+        assert!(matching.is_better_than(&estimation, &ground_truth, &1.0));
+        assert!(!matching.is_better_than(&estimation, &ground_truth, &0.4));
+
+        let far = DynamicObject {
+            position: [13.0, 0.0, 0.0],
+            ..estimation.clone()
+        };
+        assert_eq!(
+            matching.calculate_matching_score(&far, &ground_truth),
+            f64::INFINITY
+        );
+
+        let without_velocity = DynamicObject {
+            velocity: None,
+            ..estimation
+        };
+        assert!(!matching.is_better_than(&without_velocity, &ground_truth, &1.0));
     }
 }
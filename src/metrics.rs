@@ -1,4 +1,18 @@
+pub(crate) mod alignment;
+pub mod bootstrap;
+pub mod breakdown;
+pub(crate) mod calibration;
+pub(crate) mod classification;
+pub mod coverage;
+pub mod custom;
 pub(crate) mod detection;
 pub(crate) mod error;
-pub(crate) mod score;
+pub mod jitter;
+pub mod latency;
+pub(crate) mod maneuver;
+pub mod preview;
+pub mod region;
+pub mod score;
+pub(crate) mod summary;
 pub(crate) mod tp_metrics;
+pub mod tracking;
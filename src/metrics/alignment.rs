@@ -0,0 +1,272 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use crate::{
+    label::Label,
+    matching::{get_matching_method, MatchingMode, PlaneDistanceConfig},
+    result::frame::PerceptionFrameResult,
+};
+
+/// Matching modes whose score distributions are reported.
+pub const ALIGNMENT_MODES: [MatchingMode; 4] = [
+    MatchingMode::CenterDistance,
+    MatchingMode::PlaneDistance,
+    MatchingMode::Iou2d,
+    MatchingMode::Iou3d,
+];
+
+/// Percentiles printed in the report.
+pub const ALIGNMENT_PERCENTILES: [f64; 5] = [5.0, 25.0, 50.0, 75.0, 95.0];
+
+/// Distribution of the best-available matching score of each GT regardless of thresholds,
+/// which reveals systematic misalignment between the estimation source and GTs, such as a constant offset.
+///
+/// For each GT, the best score over estimations of the same label in the frame is collected.
+/// GTs in frames without such estimation are not counted.
+///
+/// * `target_labels`   - List of Label instances.
+/// * `scores`          - Sorted best scores for each matching mode and label.
+#[derive(Debug, Clone)]
+pub struct AlignmentReport {
+    target_labels: Vec<Label>,
+    scores: Vec<Vec<Vec<f64>>>,
+}
+
+impl AlignmentReport {
+    /// Construct `AlignmentReport` from accumulated frame results.
+    ///
+    /// * `frame_results`           - List of PerceptionFrameResult.
+    /// * `target_labels`           - List of Label instances.
+    /// * `plane_distance_config`   - Configuration used for `MatchingMode::PlaneDistance`.
+    pub(crate) fn new<'a>(
+        frame_results: impl IntoIterator<Item = &'a PerceptionFrameResult>,
+        target_labels: &[Label],
+        plane_distance_config: &PlaneDistanceConfig,
+    ) -> Self {
+        let methods = ALIGNMENT_MODES
+            .iter()
+            .map(|mode| get_matching_method(mode, plane_distance_config))
+            .collect::<Vec<_>>();
+        let mut scores = vec![vec![Vec::new(); target_labels.len()]; ALIGNMENT_MODES.len()];
+        frame_results.into_iter().for_each(|frame| {
+            frame
+                .frame_ground_truth()
+                .objects
+                .iter()
+                .for_each(|ground_truth| {
+                    let label_index =
+                        match target_labels.iter().position(|l| *l == ground_truth.label) {
+                            Some(index) => index,
+                            None => return,
+                        };
+                    let estimations = frame
+                        .results()
+                        .iter()
+                        .map(|result| &result.estimated_object)
+                        .filter(|estimation| estimation.label == ground_truth.label)
+                        .collect::<Vec<_>>();
+                    if estimations.is_empty() {
+                        return;
+                    }
+                    ALIGNMENT_MODES.iter().zip(&methods).enumerate().for_each(
+                        |(i, (mode, method))| {
+                            let values = estimations.iter().map(|estimation| {
+                                method.calculate_matching_score(estimation, ground_truth)
+                            });
+                            let best = if is_lower_better(mode) {
+                                values.fold(f64::INFINITY, f64::min)
+                            } else {
+                                values.fold(f64::NEG_INFINITY, f64::max)
+                            };
+                            scores[i][label_index].push(best);
+                        },
+                    );
+                });
+        });
+        scores
+            .iter_mut()
+            .flatten()
+            .for_each(|values| values.sort_by(|a, b| a.total_cmp(b)));
+
+        Self {
+            target_labels: target_labels.to_owned(),
+            scores,
+        }
+    }
+
+    /// Returns sorted best scores of GTs for the matching mode and the label.
+    /// If the matching mode is not reported or the label is not a target, returns None.
+    ///
+    /// * `matching_mode`   - MatchingMode instance.
+    /// * `label`           - Label instance.
+    pub fn scores(&self, matching_mode: &MatchingMode, label: &Label) -> Option<&[f64]> {
+        let (i, j) = self.index(matching_mode, label)?;
+        Some(&self.scores[i][j])
+    }
+
+    /// Returns the percentile of best scores, linearly interpolated between closest ranks.
+    /// If the matching mode is not reported, the label is not a target or there is no GT, returns None.
+    ///
+    /// * `matching_mode`   - MatchingMode instance.
+    /// * `label`           - Label instance.
+    /// * `percentile`      - Percentile in [0, 100].
+    pub fn percentile(
+        &self,
+        matching_mode: &MatchingMode,
+        label: &Label,
+        percentile: f64,
+    ) -> Option<f64> {
+        let values = self.scores(matching_mode, label)?;
+        if values.is_empty() {
+            return None;
+        }
+        let rank = percentile.clamp(0.0, 100.0) / 100.0 * (values.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        Some(values[lower] + (values[upper] - values[lower]) * (rank - lower as f64))
+    }
+
+    fn index(&self, matching_mode: &MatchingMode, label: &Label) -> Option<(usize, usize)> {
+        let i = ALIGNMENT_MODES.iter().position(|m| m == matching_mode)?;
+        let j = self.target_labels.iter().position(|l| l == label)?;
+        Some((i, j))
+    }
+}
+
+/// Returns whether the lower score means the better match.
+///
+/// * `matching_mode`   - MatchingMode instance.
+fn is_lower_better(matching_mode: &MatchingMode) -> bool {
+    matches!(
+        matching_mode,
+        MatchingMode::CenterDistance | MatchingMode::PlaneDistance
+    )
+}
+
+impl Display for AlignmentReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n[Alignment]".to_string();
+        ALIGNMENT_MODES.iter().for_each(|mode| {
+            msg += &format!("\n|{0:>16}|{1:^8}|", format!("{:?}", mode), "GTs");
+            ALIGNMENT_PERCENTILES
+                .iter()
+                .for_each(|p| msg += &format!("{0:^10}|", format!("p{}", p)));
+            self.target_labels.iter().for_each(|label| {
+                let num_gt = self.scores(mode, label).map_or(0, |values| values.len());
+                msg += &format!("\n|{0:>16}|{1:>7} |", label.to_string(), num_gt);
+                ALIGNMENT_PERCENTILES
+                    .iter()
+                    .for_each(|p| match self.percentile(mode, label, *p) {
+                        Some(value) => msg += &format!(" {0:>8.3} |", value),
+                        None => msg += &format!(" {0:>8} |", "-"),
+                    });
+            });
+        });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AlignmentReport;
+    use crate::{
+        dataset::FrameGroundTruth,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_alignment_report() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let ground_truth = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("100".to_string()),
+            class_probabilities: None,
+        };
+        // Estimations have a constant offset of 0.5m, far beyond the matching threshold.
+        // The pedestrian without estimation and the FP far away do not affect the distribution.
+        let frame_results = (0..5)
+            .map(|i| {
+                let ground_truths = vec![
+                    DynamicObject {
+                        position: [10.0 + i as f64, 0.0, 0.0],
+                        ..ground_truth.clone()
+                    },
+                    DynamicObject {
+                        label: Label::Pedestrian,
+                        position: [5.0, 5.0, 0.0],
+                        ..ground_truth.clone()
+                    },
+                ];
+                let estimations = vec![
+                    DynamicObject {
+                        position: [10.5 + i as f64, 0.0, 0.0],
+                        ..ground_truth.clone()
+                    },
+                    DynamicObject {
+                        position: [50.0, 50.0, 0.0],
+                        ..ground_truth.clone()
+                    },
+                ];
+                PerceptionFrameResult::new(
+                    get_perception_results(&estimations, &ground_truths),
+                    FrameGroundTruth {
+                        timestamp,
+                        objects: ground_truths,
+                        ego_translation: None,
+                        ego_rotation: None,
+                        sample_token: None,
+                        version: None,
+                        raw_pointcloud: None,
+                        location: None,
+                    },
+                    &[Label::Car, Label::Pedestrian],
+                    MatchingMode::CenterDistance,
+                    &[0.1, 0.1],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let report = AlignmentReport::new(
+            &frame_results,
+            &[Label::Car, Label::Pedestrian],
+            &PlaneDistanceConfig::default(),
+        );
+        let center_distances = report
+            .scores(&MatchingMode::CenterDistance, &Label::Car)
+            .unwrap();
+        assert_eq!(center_distances.len(), 5);
+        assert!(center_distances
+            .iter()
+            .all(|value| (value - 0.5).abs() < 1e-9));
+        let iou = |p| {
+            report
+                .percentile(&MatchingMode::Iou3d, &Label::Car, p)
+                .unwrap()
+        };
+        assert!(0.0 < iou(50.0) && iou(50.0) < 1.0);
+        assert!((iou(5.0) - iou(95.0)).abs() < 1e-9);
+        assert_eq!(
+            report.percentile(&MatchingMode::CenterDistance, &Label::Pedestrian, 50.0),
+            None
+        );
+        assert!(report
+            .scores(&MatchingMode::Velocity { position_gate: 1.0 }, &Label::Car)
+            .is_none());
+        assert!(report.to_string().contains("[Alignment]"));
+    }
+}
@@ -0,0 +1,339 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    config::PerceptionEvaluationConfig, matching::MatchingMode,
+    result::frame::PerceptionFrameResult,
+};
+
+use super::{
+    error::{MetricsError, MetricsResult},
+    score::MetricsScore,
+};
+
+/// Configuration of bootstrap resampling to compare two runs.
+///
+/// * `num_samples`         - Number of bootstrap samples.
+/// * `confidence_level`    - Confidence level of the interval in (0, 1).
+/// * `seed`                - Seed of random number generator.
+/// * `matching_mode`       - MatchingMode of mAP to be compared.
+/// * `scene_indices`       - Index of scene for each frame. If None, frames are resampled individually.
+#[derive(Debug, Clone)]
+pub struct BootstrapConfig {
+    num_samples: usize,
+    confidence_level: f64,
+    seed: u64,
+    matching_mode: MatchingMode,
+    scene_indices: Option<Vec<usize>>,
+}
+
+impl BootstrapConfig {
+    /// Construct `BootstrapConfig`.
+    ///
+    /// * `num_samples`         - Number of bootstrap samples, which must be positive.
+    /// * `confidence_level`    - Confidence level of the interval in (0, 1).
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{matching::MatchingMode, metrics::bootstrap::BootstrapConfig};
+    ///
+    /// let config = BootstrapConfig::new(1000, 0.95)
+    ///     .unwrap()
+    ///     .with_seed(42)
+    ///     .with_matching_mode(MatchingMode::CenterDistance);
+    /// ```
+    pub fn new(num_samples: usize, confidence_level: f64) -> MetricsResult<Self> {
+        if num_samples == 0 {
+            Err(MetricsError::InvalidParameter(
+                "num_samples must be positive".to_string(),
+            ))?
+        }
+        if !(confidence_level > 0.0 && confidence_level < 1.0) {
+            Err(MetricsError::InvalidParameter(format!(
+                "confidence_level must be in (0, 1), but got {}",
+                confidence_level
+            )))?
+        }
+        Ok(Self {
+            num_samples,
+            confidence_level,
+            seed: 0,
+            matching_mode: MatchingMode::PlaneDistance,
+            scene_indices: None,
+        })
+    }
+
+    /// Set the seed of random number generator.
+    ///
+    /// * `seed`    - Seed value.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the matching mode of mAP to be compared.
+    ///
+    /// * `matching_mode`   - MatchingMode instance.
+    pub fn with_matching_mode(mut self, matching_mode: MatchingMode) -> Self {
+        self.matching_mode = matching_mode;
+        self
+    }
+
+    /// Set the index of scene for each frame, then scenes are resampled instead of frames.
+    ///
+    /// * `scene_indices`   - Index of scene for each frame.
+    pub fn with_scene_indices(mut self, scene_indices: Vec<usize>) -> Self {
+        self.scene_indices = Some(scene_indices);
+        self
+    }
+}
+
+/// Result of bootstrap comparison of mAP between two runs, which is `mAP(a) - mAP(b)`.
+///
+/// * `matching_mode`       - MatchingMode of compared mAP.
+/// * `difference`          - Observed difference of mAP.
+/// * `lower`               - Lower bound of the confidence interval.
+/// * `upper`               - Upper bound of the confidence interval.
+/// * `confidence_level`    - Confidence level of the interval.
+/// * `p_value`             - Two-sided p-value of the null hypothesis that there is no difference.
+/// * `num_samples`         - Number of valid bootstrap samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootstrapSummary {
+    pub matching_mode: MatchingMode,
+    pub difference: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence_level: f64,
+    pub p_value: f64,
+    pub num_samples: usize,
+}
+
+impl Display for BootstrapSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        writeln!(
+            f,
+            "\n[Bootstrap: {:?}]\nmAP difference: {:.4} ({:.0}% CI: [{:.4}, {:.4}]), p-value: {:.4}, samples: {}\n",
+            self.matching_mode,
+            self.difference,
+            self.confidence_level * 100.0,
+            self.lower,
+            self.upper,
+            self.p_value,
+            self.num_samples
+        )
+    }
+}
+
+/// Compare mAP of two runs with paired bootstrap resampling.
+///
+/// Both runs must contain frame results of the same frames in the same order.
+/// Frames, or scenes if `scene_indices` is set, are resampled with replacement,
+/// and the same resampled frames are used for both runs.
+/// The p-value is twice the smaller fraction of samples on either side of zero.
+///
+/// * `config`          - Evaluation configuration used for both runs.
+/// * `frame_results_a` - Frame results of the run A.
+/// * `frame_results_b` - Frame results of the run B.
+/// * `bootstrap`       - Configuration of bootstrap resampling.
+pub fn compare_runs(
+    config: &PerceptionEvaluationConfig,
+    frame_results_a: &[PerceptionFrameResult],
+    frame_results_b: &[PerceptionFrameResult],
+    bootstrap: &BootstrapConfig,
+) -> MetricsResult<BootstrapSummary> {
+    if frame_results_a.len() != frame_results_b.len() {
+        Err(MetricsError::InvalidParameter(format!(
+            "number of frames must be same, but got {} and {}",
+            frame_results_a.len(),
+            frame_results_b.len()
+        )))?
+    }
+    let units = resampling_units(frame_results_a.len(), &bootstrap.scene_indices)?;
+
+    let mean_ap = |frame_results: &[PerceptionFrameResult],
+                   indices: &[usize]|
+     -> MetricsResult<Option<f64>> {
+        let mut score = MetricsScore::new(&config.metrics_params);
+        score.evaluate_frames(
+            &config.evaluation_task,
+            indices.iter().map(|i| &frame_results[*i]),
+        )?;
+        Ok(score.mean_ap(&bootstrap.matching_mode))
+    };
+    let difference = |indices: &[usize]| -> MetricsResult<Option<f64>> {
+        let ret = match (
+            mean_ap(frame_results_a, indices)?,
+            mean_ap(frame_results_b, indices)?,
+        ) {
+            (Some(a), Some(b)) => Some(a - b),
+            _ => None,
+        };
+        Ok(ret)
+    };
+
+    let all_indices = (0..frame_results_a.len()).collect::<Vec<_>>();
+    let observed = difference(&all_indices)?.ok_or_else(|| {
+        MetricsError::InvalidParameter("there is no valid AP to be compared".to_string())
+    })?;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(bootstrap.seed);
+    let mut samples = Vec::with_capacity(bootstrap.num_samples);
+    for _ in 0..bootstrap.num_samples {
+        let indices = (0..units.len())
+            .flat_map(|_| units[rng.gen_range(0..units.len())].iter().copied())
+            .collect::<Vec<_>>();
+        if let Some(diff) = difference(&indices)? {
+            samples.push(diff);
+        }
+    }
+    if samples.is_empty() {
+        Err(MetricsError::InvalidParameter(
+            "there is no valid bootstrap sample".to_string(),
+        ))?
+    }
+    samples.sort_by(|a, b| a.total_cmp(b));
+
+    let alpha = 1.0 - bootstrap.confidence_level;
+    let num_samples = samples.len();
+    let percentile = |q: f64| samples[((q * num_samples as f64) as usize).min(num_samples - 1)];
+    let num_lower = samples.iter().filter(|diff| **diff <= 0.0).count();
+    let num_upper = samples.iter().filter(|diff| **diff >= 0.0).count();
+    let p_value = (2.0 * num_lower.min(num_upper) as f64 / num_samples as f64).min(1.0);
+
+    Ok(BootstrapSummary {
+        matching_mode: bootstrap.matching_mode.to_owned(),
+        difference: observed,
+        lower: percentile(alpha * 0.5),
+        upper: percentile(1.0 - alpha * 0.5),
+        confidence_level: bootstrap.confidence_level,
+        p_value,
+        num_samples,
+    })
+}
+
+/// Returns list of frame indices for each resampling unit.
+///
+/// * `num_frames`      - Number of frames.
+/// * `scene_indices`   - Index of scene for each frame. If None, each frame is a unit.
+fn resampling_units(
+    num_frames: usize,
+    scene_indices: &Option<Vec<usize>>,
+) -> MetricsResult<Vec<Vec<usize>>> {
+    if num_frames == 0 {
+        Err(MetricsError::InvalidParameter(
+            "there is no frame to be resampled".to_string(),
+        ))?
+    }
+    match scene_indices {
+        Some(scene_indices) => {
+            if scene_indices.len() != num_frames {
+                Err(MetricsError::InvalidParameter(format!(
+                    "length of scene_indices must be {}, but got {}",
+                    num_frames,
+                    scene_indices.len()
+                )))?
+            }
+            let mut scenes = scene_indices.to_owned();
+            scenes.sort_unstable();
+            scenes.dedup();
+            Ok(scenes
+                .iter()
+                .map(|scene| {
+                    (0..num_frames)
+                        .filter(|i| scene_indices[*i] == *scene)
+                        .collect()
+                })
+                .collect())
+        }
+        None => Ok((0..num_frames).map(|i| vec![i]).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_runs, BootstrapConfig};
+    use crate::{
+        config::{ApInterpolation, FilterParams, MetricsParams, PerceptionEvaluationConfig},
+        dataset::FrameGroundTruth,
+        evaluation_task::EvaluationTask,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_compare_runs() {
+        let config = PerceptionEvaluationConfig::new(
+            vec!["annotation".to_string()],
+            "tests/sample_data",
+            EvaluationTask::Detection,
+            FrameID::BaseLink,
+            "work_dir",
+            FilterParams::new(&vec!["Car"], 100.0, 100.0, None, None).unwrap(),
+            MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5)
+                .unwrap()
+                .with_ap_interpolation(ApInterpolation::Point101),
+        );
+
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let ground_truth = DynamicObject::new(
+            timestamp,
+            FrameID::BaseLink,
+            [1.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [2.0, 1.0, 1.0],
+            1.0,
+            Label::Car,
+        )
+        .with_pointcloud_num(1000)
+        .with_uuid("100");
+        let make_frames = |offset: f64| {
+            (0..10)
+                .map(|i| {
+                    let estimation = DynamicObject {
+                        position: [1.0 + offset * (i % 2) as f64, 1.0, 0.0],
+                        ..ground_truth.clone()
+                    };
+                    let ground_truths = vec![ground_truth.clone()];
+                    PerceptionFrameResult::new(
+                        get_perception_results(&[estimation], &ground_truths),
+                        FrameGroundTruth::new(timestamp, ground_truths),
+                        &[Label::Car],
+                        MatchingMode::PlaneDistance,
+                        &[1.0],
+                        &PlaneDistanceConfig::default(),
+                    )
+                    .unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let frame_results_a = make_frames(0.0);
+        let frame_results_b = make_frames(10.0);
+        let bootstrap = BootstrapConfig::new(200, 0.9)
+            .unwrap()
+            .with_seed(1)
+            .with_matching_mode(MatchingMode::CenterDistance);
+
+        let summary =
+            compare_runs(&config, &frame_results_a, &frame_results_b, &bootstrap).unwrap();
+        assert!(summary.difference > 0.0);
+        assert!(summary.lower <= summary.difference && summary.difference <= summary.upper);
+        assert!(summary.lower > 0.0);
+        assert!(summary.p_value < 0.05);
+
+        let same = compare_runs(&config, &frame_results_a, &frame_results_a, &bootstrap).unwrap();
+        assert_eq!(same.difference, 0.0);
+        assert_eq!(same.p_value, 1.0);
+
+        let scenes = BootstrapConfig::new(10, 0.9)
+            .unwrap()
+            .with_scene_indices(vec![0; 5]);
+        assert!(compare_runs(&config, &frame_results_a, &frame_results_b, &scenes).is_err());
+    }
+}
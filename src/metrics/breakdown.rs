@@ -0,0 +1,773 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    f64::consts::PI,
+    fmt::{Debug, Display, Formatter, Result as FormatResult},
+    sync::Arc,
+};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::PerceptionEvaluationConfig,
+    dataset::{
+        annotation::{AnnotationMap, ObjectAnnotation},
+        FrameGroundTruth,
+    },
+    frame_id::FrameID,
+    matching::MatchingMode,
+    object::object3d::DynamicObject,
+    result::{frame::PerceptionFrameResult, object::PerceptionResult},
+    utils::point::distance_points_bev,
+};
+
+use super::{
+    error::{MetricsError, MetricsResult},
+    score::MetricsScore,
+};
+
+/// Visibility levels of nuScenes in ascending order.
+pub const VISIBILITY_LEVELS: [&str; 4] = ["v0-40", "v40-60", "v60-80", "v80-100"];
+
+/// Context of the frame which objects are binned in.
+///
+/// * `frame`       - GTs and metadata of the frame.
+/// * `ego_speed`   - Speed of the ego vehicle in [m/s], if it can be derived from ego translations.
+/// * `annotations` - Annotation metadata of GTs.
+#[derive(Debug, Clone, Copy)]
+pub struct BreakdownContext<'a> {
+    pub frame: &'a FrameGroundTruth,
+    pub ego_speed: Option<f64>,
+    pub annotations: &'a AnnotationMap,
+}
+
+impl BreakdownContext<'_> {
+    /// Returns the annotation metadata of the object in the frame.
+    /// If the frame has no sample token, the object has no uuid or it is not annotated, returns None.
+    ///
+    /// * `object`  - DynamicObject instance.
+    pub fn annotation(&self, object: &DynamicObject) -> Option<&ObjectAnnotation> {
+        let sample_token = self.frame.sample_token.as_ref()?;
+        let uuid = object.uuid.as_ref()?;
+        self.annotations.get(sample_token, uuid)
+    }
+}
+
+/// Axis to break metrics down by, which assigns each object to one of its bins.
+///
+/// Results are binned by their GTs if paired, or else by their estimations.
+/// Objects which do not belong to any bin are excluded from the breakdown.
+pub trait BreakdownAxis: Debug + Send + Sync {
+    /// Returns the name of the axis.
+    fn name(&self) -> &str;
+
+    /// Returns names of bins in order.
+    fn bins(&self) -> Vec<String>;
+
+    /// Returns the index of the bin which the object belongs to.
+    /// If the object does not belong to any bin, returns None.
+    ///
+    /// * `object`  - DynamicObject instance.
+    /// * `context` - Context of the frame.
+    fn bin(&self, object: &DynamicObject, context: &BreakdownContext) -> Option<usize>;
+
+    /// Returns whether the axis requires annotation metadata loaded by `AnnotationMap`.
+    fn requires_annotations(&self) -> bool {
+        false
+    }
+}
+
+/// Axis of BEV distance bands from the ego vehicle.
+/// Objects in the `map` frame are measured from the ego translation.
+///
+/// * `bands`   - Ascending edges of bands in [m].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceAxis {
+    bands: Vec<f64>,
+}
+
+impl DistanceAxis {
+    /// Construct `DistanceAxis`.
+    ///
+    /// * `bands`   - Ascending edges of bands in [m], e.g. `[0, 30, 60]` for two bands.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::metrics::breakdown::{BreakdownAxis, DistanceAxis};
+    ///
+    /// let axis = DistanceAxis::new(vec![0.0, 30.0, 60.0]).unwrap();
+    /// assert_eq!(axis.bins(), vec!["[0, 30)", "[30, 60)"]);
+    ///
+    /// assert!(DistanceAxis::new(vec![30.0, 0.0]).is_err());
+    /// ```
+    pub fn new(bands: Vec<f64>) -> MetricsResult<Self> {
+        Ok(Self {
+            bands: validate_bands("distance", bands)?,
+        })
+    }
+}
+
+impl BreakdownAxis for DistanceAxis {
+    fn name(&self) -> &str {
+        "distance"
+    }
+
+    fn bins(&self) -> Vec<String> {
+        band_names(&self.bands)
+    }
+
+    fn bin(&self, object: &DynamicObject, context: &BreakdownContext) -> Option<usize> {
+        let distance = match object.frame_id {
+            FrameID::Map => {
+                distance_points_bev(&object.position, context.frame.ego_translation.as_ref()?)
+            }
+            _ => object.distance_bev(),
+        };
+        band_index(&self.bands, distance)
+    }
+}
+
+/// Axis of azimuth sectors around the ego vehicle, starting from -180 [deg] counterclockwise.
+/// Objects in the `map` frame are not binned, since the heading of the ego vehicle is unknown.
+///
+/// * `num_sectors` - Number of sectors with the same angle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AzimuthAxis {
+    num_sectors: usize,
+}
+
+impl AzimuthAxis {
+    /// Construct `AzimuthAxis`.
+    ///
+    /// * `num_sectors` - Number of sectors with the same angle, which must be positive.
+    pub fn new(num_sectors: usize) -> MetricsResult<Self> {
+        if num_sectors == 0 {
+            return Err(MetricsError::InvalidParameter(
+                "azimuth axis requires at least one sector".to_string(),
+            ));
+        }
+        Ok(Self { num_sectors })
+    }
+}
+
+impl BreakdownAxis for AzimuthAxis {
+    fn name(&self) -> &str {
+        "azimuth"
+    }
+
+    fn bins(&self) -> Vec<String> {
+        let width = 360.0 / self.num_sectors as f64;
+        (0..self.num_sectors)
+            .map(|i| {
+                let start = -180.0 + width * i as f64;
+                format!("[{}, {})", start, start + width)
+            })
+            .collect()
+    }
+
+    fn bin(&self, object: &DynamicObject, _context: &BreakdownContext) -> Option<usize> {
+        if object.frame_id == FrameID::Map {
+            return None;
+        }
+        let azimuth = object.position[1].atan2(object.position[0]);
+        let index = ((azimuth + PI) / (2.0 * PI) * self.num_sectors as f64).floor() as usize;
+        Some(index.min(self.num_sectors - 1))
+    }
+}
+
+/// Axis of visibility levels of GTs.
+/// FPs without paired GTs are not binned, since estimations have no visibility.
+///
+/// * `levels`  - Names of visibility levels, such as `v60-80`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisibilityAxis {
+    levels: Vec<String>,
+}
+
+impl VisibilityAxis {
+    /// Construct `VisibilityAxis`.
+    ///
+    /// * `levels`  - Names of visibility levels, such as `v60-80`.
+    pub fn new(levels: Vec<String>) -> MetricsResult<Self> {
+        if levels.is_empty() {
+            return Err(MetricsError::InvalidParameter(
+                "visibility axis requires at least one level".to_string(),
+            ));
+        }
+        Ok(Self { levels })
+    }
+}
+
+impl Default for VisibilityAxis {
+    fn default() -> Self {
+        Self {
+            levels: VISIBILITY_LEVELS.map(String::from).to_vec(),
+        }
+    }
+}
+
+impl BreakdownAxis for VisibilityAxis {
+    fn name(&self) -> &str {
+        "visibility"
+    }
+
+    fn bins(&self) -> Vec<String> {
+        self.levels.to_owned()
+    }
+
+    fn bin(&self, object: &DynamicObject, context: &BreakdownContext) -> Option<usize> {
+        let visibility = context.annotation(object)?.visibility.as_ref()?;
+        self.levels.iter().position(|level| level == visibility)
+    }
+
+    fn requires_annotations(&self) -> bool {
+        true
+    }
+}
+
+/// Axis of attributes of GTs, such as `vehicle.moving` and `vehicle.parked`.
+/// An object with multiple listed attributes belongs to the bin of the first one.
+/// FPs without paired GTs are not binned, since estimations have no attribute.
+///
+/// * `attributes`  - Names of attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeAxis {
+    attributes: Vec<String>,
+}
+
+impl AttributeAxis {
+    /// Construct `AttributeAxis`.
+    ///
+    /// * `attributes`  - Names of attributes.
+    pub fn new(attributes: Vec<String>) -> MetricsResult<Self> {
+        if attributes.is_empty() {
+            return Err(MetricsError::InvalidParameter(
+                "attribute axis requires at least one attribute".to_string(),
+            ));
+        }
+        Ok(Self { attributes })
+    }
+}
+
+impl BreakdownAxis for AttributeAxis {
+    fn name(&self) -> &str {
+        "attribute"
+    }
+
+    fn bins(&self) -> Vec<String> {
+        self.attributes.to_owned()
+    }
+
+    fn bin(&self, object: &DynamicObject, context: &BreakdownContext) -> Option<usize> {
+        let annotation = context.annotation(object)?;
+        self.attributes
+            .iter()
+            .position(|attribute| annotation.attributes.contains(attribute))
+    }
+
+    fn requires_annotations(&self) -> bool {
+        true
+    }
+}
+
+/// Axis of speed bands of the ego vehicle, which bins all objects in the frame together.
+///
+/// * `bands`   - Ascending edges of bands in [m/s].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EgoSpeedAxis {
+    bands: Vec<f64>,
+}
+
+impl EgoSpeedAxis {
+    /// Construct `EgoSpeedAxis`.
+    ///
+    /// * `bands`   - Ascending edges of bands in [m/s].
+    pub fn new(bands: Vec<f64>) -> MetricsResult<Self> {
+        Ok(Self {
+            bands: validate_bands("ego speed", bands)?,
+        })
+    }
+}
+
+impl BreakdownAxis for EgoSpeedAxis {
+    fn name(&self) -> &str {
+        "ego_speed"
+    }
+
+    fn bins(&self) -> Vec<String> {
+        band_names(&self.bands)
+    }
+
+    fn bin(&self, _object: &DynamicObject, context: &BreakdownContext) -> Option<usize> {
+        band_index(&self.bands, context.ego_speed?)
+    }
+}
+
+/// Parameters of built-in breakdown axes, which are specified as `breakdown_axes` in the scenario.
+///
+/// ```yaml
+/// breakdown_axes:
+///   - type: distance
+///     bands: [0.0, 30.0, 60.0, 100.0]
+///   - type: visibility
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BreakdownAxisParams {
+    Distance {
+        bands: Vec<f64>,
+    },
+    Azimuth {
+        num_sectors: usize,
+    },
+    Visibility {
+        #[serde(default)]
+        levels: Option<Vec<String>>,
+    },
+    Attribute {
+        attributes: Vec<String>,
+    },
+    EgoSpeed {
+        bands: Vec<f64>,
+    },
+}
+
+impl BreakdownAxisParams {
+    /// Build the axis from parameters.
+    /// Returns error if parameters are invalid.
+    pub fn build(&self) -> MetricsResult<Arc<dyn BreakdownAxis>> {
+        let axis: Arc<dyn BreakdownAxis> = match self {
+            Self::Distance { bands } => Arc::new(DistanceAxis::new(bands.to_owned())?),
+            Self::Azimuth { num_sectors } => Arc::new(AzimuthAxis::new(*num_sectors)?),
+            Self::Visibility { levels: None } => Arc::new(VisibilityAxis::default()),
+            Self::Visibility {
+                levels: Some(levels),
+            } => Arc::new(VisibilityAxis::new(levels.to_owned())?),
+            Self::Attribute { attributes } => Arc::new(AttributeAxis::new(attributes.to_owned())?),
+            Self::EgoSpeed { bands } => Arc::new(EgoSpeedAxis::new(bands.to_owned())?),
+        };
+        Ok(axis)
+    }
+}
+
+/// Validate that bands have at least two finite edges in strictly ascending order.
+///
+/// * `name`    - Name of the axis.
+/// * `bands`   - Edges of bands.
+fn validate_bands(name: &str, bands: Vec<f64>) -> MetricsResult<Vec<f64>> {
+    if bands.len() < 2
+        || bands.iter().any(|edge| !edge.is_finite())
+        || bands.windows(2).any(|pair| pair[0] >= pair[1])
+    {
+        return Err(MetricsError::InvalidParameter(format!(
+            "{} bands must have at least two finite edges in ascending order: {:?}",
+            name, bands
+        )));
+    }
+    Ok(bands)
+}
+
+/// Returns the index of the band containing the value, where each band is closed on the left.
+///
+/// * `bands`   - Ascending edges of bands.
+/// * `value`   - Value to be binned.
+fn band_index(bands: &[f64], value: f64) -> Option<usize> {
+    bands
+        .windows(2)
+        .position(|pair| pair[0] <= value && value < pair[1])
+}
+
+/// Returns names of bands, such as `[0, 30)`.
+///
+/// * `bands`   - Ascending edges of bands.
+fn band_names(bands: &[f64]) -> Vec<String> {
+    bands
+        .windows(2)
+        .map(|pair| format!("[{}, {})", pair[0], pair[1]))
+        .collect()
+}
+
+/// Returns the speed of the ego vehicle at each frame, derived from ego translations of
+/// the frame and its previous frame, or its next frame for the first frame.
+///
+/// * `frame_ground_truths` - List of FrameGroundTruth.
+fn ego_speeds(frame_ground_truths: &[FrameGroundTruth]) -> HashMap<NaiveDateTime, f64> {
+    let mut frames = frame_ground_truths
+        .iter()
+        .filter_map(|frame| {
+            frame
+                .ego_translation
+                .as_ref()
+                .map(|translation| (frame.timestamp, translation))
+        })
+        .collect::<Vec<_>>();
+    frames.sort_by_key(|(timestamp, _)| *timestamp);
+    frames.dedup_by_key(|(timestamp, _)| *timestamp);
+
+    let speed = |(t1, p1): &(NaiveDateTime, &[f64; 3]), (t2, p2): &(NaiveDateTime, &[f64; 3])| {
+        let elapsed = (*t2 - *t1).num_microseconds()? as f64 * 1e-6;
+        Some(distance_points_bev(p1, p2) / elapsed)
+    };
+    frames
+        .iter()
+        .enumerate()
+        .filter_map(|(i, frame)| {
+            let value = match i {
+                0 => speed(frame, frames.get(1)?),
+                _ => speed(&frames[i - 1], frame),
+            }?;
+            Some((frame.0, value))
+        })
+        .collect()
+}
+
+/// Metrics score for every combination of bins of configured axes.
+///
+/// Cells are ordered by bin indices of axes, and cells without any GT or result are omitted.
+/// If no axis is configured, the only cell contains all results and GTs.
+///
+/// * `axes`    - Name and bin names of each axis.
+/// * `cells`   - Bin indices, the number of GTs and the score of each cell.
+#[derive(Debug, Clone)]
+pub struct BreakdownReport {
+    axes: Vec<(String, Vec<String>)>,
+    cells: Vec<(Vec<usize>, usize, MetricsScore)>,
+}
+
+impl BreakdownReport {
+    /// Construct `BreakdownReport` from accumulated frame results.
+    ///
+    /// * `config`              - Evaluation configuration.
+    /// * `frame_results`       - List of PerceptionFrameResult.
+    /// * `axes`                - List of axes to break metrics down by.
+    /// * `frame_ground_truths` - All loaded GTs, which are used to derive ego speeds.
+    /// * `annotations`         - Annotation metadata of GTs.
+    pub fn new(
+        config: &PerceptionEvaluationConfig,
+        frame_results: &[PerceptionFrameResult],
+        axes: &[Arc<dyn BreakdownAxis>],
+        frame_ground_truths: &[FrameGroundTruth],
+        annotations: &AnnotationMap,
+    ) -> MetricsResult<Self> {
+        let ego_speeds = ego_speeds(frame_ground_truths);
+        let mut members: BTreeMap<Vec<usize>, Vec<PerceptionFrameResult>> = BTreeMap::new();
+        frame_results.iter().for_each(|frame_result| {
+            let frame = frame_result.frame_ground_truth();
+            let context = BreakdownContext {
+                frame,
+                ego_speed: ego_speeds.get(&frame.timestamp).copied(),
+                annotations,
+            };
+            let cell_of = |object: &DynamicObject| -> Option<Vec<usize>> {
+                axes.iter().map(|axis| axis.bin(object, &context)).collect()
+            };
+            let result_cell_of = |result: &PerceptionResult| {
+                cell_of(
+                    result
+                        .ground_truth_object
+                        .as_ref()
+                        .unwrap_or(&result.estimated_object),
+                )
+            };
+
+            let mut cells = frame
+                .objects
+                .iter()
+                .filter_map(cell_of)
+                .chain(frame_result.results().iter().filter_map(result_cell_of))
+                .collect::<Vec<_>>();
+            cells.sort();
+            cells.dedup();
+            cells.into_iter().for_each(|cell| {
+                let filtered = frame_result.retain(
+                    |result| result_cell_of(result).as_ref() == Some(&cell),
+                    |object| cell_of(object).as_ref() == Some(&cell),
+                );
+                members.entry(cell).or_default().push(filtered);
+            });
+        });
+
+        let cells = members
+            .into_iter()
+            .map(|(cell, frames)| {
+                let num_gt = frames
+                    .iter()
+                    .map(|frame| frame.frame_ground_truth().objects.len())
+                    .sum();
+                let mut score = MetricsScore::new(&config.metrics_params);
+                score.evaluate_frames(&config.evaluation_task, &frames)?;
+                Ok((cell, num_gt, score))
+            })
+            .collect::<MetricsResult<Vec<_>>>()?;
+        let axes = axes
+            .iter()
+            .map(|axis| (axis.name().to_owned(), axis.bins()))
+            .collect();
+
+        Ok(Self { axes, cells })
+    }
+
+    /// Returns names of axes.
+    pub fn axes(&self) -> Vec<&str> {
+        self.axes.iter().map(|(name, _)| name as &str).collect()
+    }
+
+    /// Returns names of bins of the axis.
+    /// If the axis is not configured, returns None.
+    ///
+    /// * `axis`    - Name of the axis.
+    pub fn bins(&self, axis: &str) -> Option<&[String]> {
+        self.axes
+            .iter()
+            .find(|(name, _)| name == axis)
+            .map(|(_, bins)| bins as &[String])
+    }
+
+    /// Returns bin names of cells in order.
+    pub fn cells(&self) -> Vec<Vec<&str>> {
+        self.cells
+            .iter()
+            .map(|(cell, _, _)| self.bin_names(cell))
+            .collect()
+    }
+
+    /// Returns the number of GTs in the cell.
+    /// If the cell has no GT or result, returns None.
+    ///
+    /// * `bins`    - Bin names of the cell in the order of axes.
+    pub fn num_gt(&self, bins: &[&str]) -> Option<usize> {
+        self.get(bins).map(|(_, num_gt, _)| *num_gt)
+    }
+
+    /// Returns the metrics score of the cell.
+    /// If the cell has no GT or result, returns None.
+    ///
+    /// * `bins`    - Bin names of the cell in the order of axes.
+    pub fn score(&self, bins: &[&str]) -> Option<&MetricsScore> {
+        self.get(bins).map(|(_, _, score)| score)
+    }
+
+    fn get(&self, bins: &[&str]) -> Option<&(Vec<usize>, usize, MetricsScore)> {
+        self.cells
+            .iter()
+            .find(|(cell, _, _)| self.bin_names(cell) == bins)
+    }
+
+    fn bin_names(&self, cell: &[usize]) -> Vec<&str> {
+        self.axes
+            .iter()
+            .zip(cell)
+            .map(|((_, bins), index)| &bins[*index] as &str)
+            .collect()
+    }
+}
+
+impl Display for BreakdownReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let modes = [MatchingMode::CenterDistance, MatchingMode::PlaneDistance];
+        let mut msg = "\n[Breakdown]\n|".to_string();
+        self.axes
+            .iter()
+            .for_each(|(name, _)| msg += &format!("{0:>16}|", name));
+        msg += &format!("{0:^8}|", "GTs");
+        modes
+            .iter()
+            .for_each(|mode| msg += &format!("{0:^16}|", format!("mAP {:?}", mode)));
+
+        self.cells.iter().for_each(|(cell, num_gt, score)| {
+            msg += "\n|";
+            self.bin_names(cell)
+                .iter()
+                .for_each(|bin| msg += &format!("{0:>16}|", bin));
+            msg += &format!("{0:>7} |", num_gt);
+            modes.iter().for_each(|mode| match score.mean_ap(mode) {
+                Some(value) => msg += &format!(" {0:>14.3} |", value),
+                None => msg += &format!(" {0:>14} |", "-"),
+            });
+        });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{
+        AzimuthAxis, BreakdownAxis, BreakdownAxisParams, BreakdownReport, DistanceAxis,
+        EgoSpeedAxis, VisibilityAxis,
+    };
+    use crate::{
+        config::{
+            ApInterpolation, FilterParams, FrameIdPolicy, MetricsParams, PerceptionEvaluationConfig,
+        },
+        dataset::{
+            annotation::{AnnotationMap, ObjectAnnotation},
+            FrameGroundTruth,
+        },
+        evaluation_task::EvaluationTask,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::{DateTime, Duration};
+
+    #[test]
+    fn test_breakdown_report() {
+        let axes = serde_yaml::from_str::<Vec<BreakdownAxisParams>>(
+            "
+            - type: distance
+              bands: [0.0, 30.0, 60.0]
+            - type: visibility
+            - type: ego_speed
+              bands: [0.0, 5.0, 20.0]
+            ",
+        )
+        .unwrap()
+        .iter()
+        .map(|params| params.build().unwrap())
+        .collect::<Vec<Arc<dyn BreakdownAxis>>>();
+        let config = PerceptionEvaluationConfig {
+            versions: vec!["annotation".to_string()],
+            dataset_path: "tests/sample_data".into(),
+            evaluation_task: EvaluationTask::Detection,
+            frame_id: FrameID::BaseLink,
+            result_dir: "work_dir".into(),
+            log_dir: "work_dir/log".into(),
+            viz_dir: "work_dir/visualize".into(),
+            filter_params: FilterParams::new(&vec!["Car"], 100.0, 100.0, None, None).unwrap(),
+            metrics_params: MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5)
+                .unwrap()
+                .with_ap_interpolation(ApInterpolation::Point11)
+                .with_breakdown_axes(axes),
+            load_raw_data: false,
+            audit_log: None,
+            timestamp_correction: None,
+            target_sample_tokens: None,
+            estimation_label_remap: None,
+            export_pr_curves: false,
+            frame_id_policy: FrameIdPolicy::Disabled,
+            latency_compensation: None,
+        };
+
+        let start = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let near = DynamicObject {
+            timestamp: start,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 4.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("near".to_string()),
+            class_probabilities: None,
+        };
+        let far = DynamicObject {
+            position: [40.0, 0.0, 0.0],
+            uuid: Some("far".to_string()),
+            ..near.clone()
+        };
+        // The near car is detected, while the far car is mislocated by 5 [m].
+        let estimations = vec![
+            near.clone(),
+            DynamicObject {
+                position: [45.0, 0.0, 0.0],
+                ..far.clone()
+            },
+        ];
+        let mut annotations = AnnotationMap::default();
+        // The ego vehicle moves 10 [m] per second.
+        let frame_ground_truths = (0..2)
+            .map(|i| {
+                let sample_token = format!("sample-{}", i);
+                [("near", "v80-100"), ("far", "v0-40")]
+                    .iter()
+                    .for_each(|(uuid, visibility)| {
+                        annotations.insert(
+                            &sample_token,
+                            uuid,
+                            ObjectAnnotation {
+                                visibility: Some(visibility.to_string()),
+                                attributes: Vec::new(),
+                            },
+                        )
+                    });
+                FrameGroundTruth {
+                    timestamp: start + Duration::seconds(i),
+                    objects: vec![near.clone(), far.clone()],
+                    ego_translation: Some([10.0 * i as f64, 0.0, 0.0]),
+                    ego_rotation: None,
+                    sample_token: Some(sample_token),
+                    version: None,
+                    raw_pointcloud: None,
+                    location: None,
+                }
+            })
+            .collect::<Vec<_>>();
+        let frame_results = frame_ground_truths
+            .iter()
+            .map(|frame| {
+                PerceptionFrameResult::new(
+                    get_perception_results(&estimations, &frame.objects),
+                    frame.clone(),
+                    &[Label::Car],
+                    MatchingMode::CenterDistance,
+                    &[1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let report = BreakdownReport::new(
+            &config,
+            &frame_results,
+            &config.metrics_params.breakdown_axes,
+            &frame_ground_truths,
+            &annotations,
+        )
+        .unwrap();
+        assert_eq!(report.axes(), vec!["distance", "visibility", "ego_speed"]);
+        assert_eq!(
+            report.cells(),
+            vec![
+                vec!["[0, 30)", "v80-100", "[5, 20)"],
+                vec!["[30, 60)", "v0-40", "[5, 20)"],
+            ]
+        );
+        assert_eq!(report.num_gt(&["[0, 30)", "v80-100", "[5, 20)"]), Some(2));
+        let ap = |bins: &[&str]| {
+            report
+                .score(bins)
+                .unwrap()
+                .mean_ap(&MatchingMode::CenterDistance)
+                .unwrap()
+        };
+        assert_eq!(ap(&["[30, 60)", "v0-40", "[5, 20)"]), 0.0);
+        assert!(ap(&["[0, 30)", "v80-100", "[5, 20)"]) > 0.0);
+        assert!(report.score(&["[0, 30)", "v0-40", "[5, 20)"]).is_none());
+        assert!(report.to_string().contains("[Breakdown]"));
+
+        // Without annotations, no object belongs to visibility bins.
+        let report = BreakdownReport::new(
+            &config,
+            &frame_results,
+            &[Arc::new(VisibilityAxis::default())],
+            &frame_ground_truths,
+            &AnnotationMap::default(),
+        )
+        .unwrap();
+        assert!(report.cells().is_empty());
+
+        let azimuth = AzimuthAxis::new(4).unwrap();
+        assert_eq!(azimuth.bins()[2], "[0, 90)");
+        assert!(AzimuthAxis::new(0).is_err());
+        assert!(DistanceAxis::new(vec![0.0]).is_err());
+        assert!(EgoSpeedAxis::new(vec![0.0, f64::INFINITY]).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use crate::{label::Label, matching::MatchingMode};
+
+use super::detection::DecisionRecords;
+
+/// Operating point of the detector when estimations below the confidence threshold are dropped.
+///
+/// * `confidence`  - Minimum confidence of kept estimations.
+/// * `precision`   - Precision of kept estimations.
+/// * `recall`      - Recall of kept estimations. 0.0 if there is no GT.
+/// * `num_tp`      - Number of kept estimations determined as TP.
+/// * `num_fp`      - Number of kept estimations determined as FP.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatingPoint {
+    pub confidence: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub num_tp: usize,
+    pub num_fp: usize,
+}
+
+/// Minimum confidence threshold of each label achieving the target precision,
+/// and the recall achievable at that operating point, to support selecting deployment thresholds.
+///
+/// Since recall never increases with the threshold, the minimum threshold maximizes recall
+/// among thresholds achieving the target precision.
+///
+/// * `matching_mode`       - MatchingMode used to determine TP and FP.
+/// * `target_precision`    - Target precision in (0, 1].
+/// * `target_labels`       - List of Label instances.
+/// * `points`              - Operating point of each label. None if no threshold achieves the target.
+#[derive(Debug, Clone)]
+pub struct ConfidenceCalibration {
+    matching_mode: MatchingMode,
+    target_precision: f64,
+    target_labels: Vec<Label>,
+    points: Vec<Option<OperatingPoint>>,
+}
+
+impl ConfidenceCalibration {
+    /// Construct `ConfidenceCalibration` from raw TP/FP decisions.
+    ///
+    /// * `matching_mode`       - MatchingMode used to determine TP and FP.
+    /// * `target_precision`    - Target precision in (0, 1].
+    /// * `target_labels`       - List of Label instances.
+    /// * `decisions`           - TP/FP decisions of each label.
+    /// * `num_gt`              - Number of GTs of each label.
+    pub(crate) fn new(
+        matching_mode: &MatchingMode,
+        target_precision: f64,
+        target_labels: &[Label],
+        decisions: &[DecisionRecords],
+        num_gt: &[usize],
+    ) -> Self {
+        let points = decisions
+            .iter()
+            .zip(num_gt)
+            .map(|(decisions, num_gt)| find_operating_point(decisions, *num_gt, target_precision))
+            .collect();
+        Self {
+            matching_mode: matching_mode.to_owned(),
+            target_precision,
+            target_labels: target_labels.to_owned(),
+            points,
+        }
+    }
+
+    /// Returns the target precision.
+    pub fn target_precision(&self) -> f64 {
+        self.target_precision
+    }
+
+    /// Returns the operating point of the label at its minimum confidence threshold.
+    /// If the label is not a target or no threshold achieves the target precision, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn operating_point(&self, label: &Label) -> Option<&OperatingPoint> {
+        let index = self.target_labels.iter().position(|l| l == label)?;
+        self.points[index].as_ref()
+    }
+
+    /// Returns the minimum confidence threshold of the label achieving the target precision.
+    /// If the label is not a target or no threshold achieves the target precision, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn min_confidence(&self, label: &Label) -> Option<f64> {
+        self.operating_point(label).map(|point| point.confidence)
+    }
+}
+
+/// Returns the operating point at the minimum confidence threshold achieving the target precision.
+/// Estimations with the same confidence are kept or dropped together.
+///
+/// * `decisions`           - TP/FP decisions of results.
+/// * `num_gt`              - Number of GTs.
+/// * `target_precision`    - Target precision.
+fn find_operating_point(
+    decisions: &DecisionRecords,
+    num_gt: usize,
+    target_precision: f64,
+) -> Option<OperatingPoint> {
+    let mut order = (0..decisions.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| decisions.confidence[b].total_cmp(&decisions.confidence[a]));
+
+    let mut num_tp = 0;
+    let mut num_fp = 0;
+    let mut best = None;
+    for (i, &index) in order.iter().enumerate() {
+        if decisions.is_tp[index] {
+            num_tp += 1;
+        } else {
+            num_fp += 1;
+        }
+        let confidence = decisions.confidence[index];
+        let is_last_of_ties = order
+            .get(i + 1)
+            .is_none_or(|&next| decisions.confidence[next] != confidence);
+        if !is_last_of_ties {
+            continue;
+        }
+        let precision = num_tp as f64 / (num_tp + num_fp) as f64;
+        if precision >= target_precision {
+            best = Some(OperatingPoint {
+                confidence,
+                precision,
+                recall: if num_gt == 0 {
+                    0.0
+                } else {
+                    num_tp as f64 / num_gt as f64
+                },
+                num_tp,
+                num_fp,
+            });
+        }
+    }
+    best
+}
+
+impl Display for ConfidenceCalibration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = format!(
+            "\n[Confidence Calibration] ({:?}, target precision: {:.3})",
+            self.matching_mode, self.target_precision
+        );
+        msg += &format!(
+            "\n|{0:>10}|{1:^12}|{2:^12}|{3:^12}|",
+            "Label", "Confidence", "Precision", "Recall"
+        );
+        self.target_labels
+            .iter()
+            .zip(&self.points)
+            .for_each(|(label, point)| match point {
+                Some(point) => {
+                    msg += &format!(
+                        "\n|{0:>10}| {1:>10.3} | {2:>10.3} | {3:>10.3} |",
+                        label.to_string(),
+                        point.confidence,
+                        point.precision,
+                        point.recall
+                    )
+                }
+                None => {
+                    msg += &format!(
+                        "\n|{0:>10}| {1:>10} | {1:>10} | {1:>10} |",
+                        label.to_string(),
+                        "-"
+                    )
+                }
+            });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfidenceCalibration;
+    use crate::{label::Label, matching::MatchingMode, metrics::detection::DecisionRecords};
+
+    #[test]
+    fn test_confidence_calibration() {
+        let records = |values: &[(f64, bool)]| DecisionRecords {
+            confidence: values.iter().map(|(confidence, _)| *confidence).collect(),
+            is_tp: values.iter().map(|(_, is_tp)| *is_tp).collect(),
+            matching_score: vec![None; values.len()],
+            gt_uuid: vec![None; values.len()],
+        };
+        // Precision is 1.0 down to 0.7, 0.75 at 0.6, 0.5 at 0.5 and 0.57 at 0.4.
+        let car = records(&[
+            (0.5, false),
+            (0.9, true),
+            (0.4, true),
+            (0.7, true),
+            (0.6, false),
+            (0.8, true),
+            (0.5, false),
+        ]);
+        // TP and FP with the same confidence are dropped together.
+        let pedestrian = records(&[(0.9, true), (0.9, false)]);
+
+        let calibration = ConfidenceCalibration::new(
+            &MatchingMode::CenterDistance,
+            0.75,
+            &[Label::Car, Label::Pedestrian, Label::Bus],
+            &[car, pedestrian, records(&[])],
+            &[6, 1, 0],
+        );
+        let point = calibration.operating_point(&Label::Car).unwrap();
+        assert_eq!(point.confidence, 0.6);
+        assert_eq!(point.precision, 0.75);
+        assert_eq!(point.recall, 0.5);
+        assert_eq!((point.num_tp, point.num_fp), (3, 1));
+        assert_eq!(calibration.min_confidence(&Label::Pedestrian), None);
+        assert_eq!(calibration.min_confidence(&Label::Bus), None);
+        assert_eq!(calibration.min_confidence(&Label::Bicycle), None);
+        assert!(calibration.to_string().contains("[Confidence Calibration]"));
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use crate::{label::Label, result::object::PerceptionResult};
+
+/// Lower bound of probability to avoid infinite log-loss.
+const MIN_PROBABILITY: f64 = 1e-15;
+
+type ScoreFn = fn(&SoftClassificationScore, &Label) -> Option<f64>;
+
+/// Soft classification score over pairs of estimation and GT,
+/// where the estimation has class probabilities.
+///
+/// Scores are aggregated for each label of GT.
+///
+/// * `target_labels`   - List of Label instances.
+/// * `num_pairs`       - Number of evaluated pairs for each label.
+/// * `log_loss`        - Sum of log-loss for each label.
+/// * `brier_score`     - Sum of Brier score for each label.
+#[derive(Debug, Clone)]
+pub struct SoftClassificationScore {
+    target_labels: Vec<Label>,
+    num_pairs: Vec<usize>,
+    log_loss: Vec<f64>,
+    brier_score: Vec<f64>,
+}
+
+impl SoftClassificationScore {
+    /// Construct `SoftClassificationScore` from results.
+    ///
+    /// Probabilities of labels missing in the map are regarded as 0.
+    /// Brier score is computed over the union of target labels and labels in the map.
+    ///
+    /// * `results`         - List of PerceptionResult.
+    /// * `target_labels`   - List of Label instances.
+    pub(crate) fn new<'a, I>(results: I, target_labels: &[Label]) -> Self
+    where
+        I: IntoIterator<Item = &'a PerceptionResult>,
+    {
+        let mut num_pairs = vec![0; target_labels.len()];
+        let mut log_loss = vec![0.0; target_labels.len()];
+        let mut brier_score = vec![0.0; target_labels.len()];
+
+        results.into_iter().for_each(|result| {
+            let (probabilities, gt_label) = match (
+                &result.estimated_object.class_probabilities,
+                &result.ground_truth_object,
+            ) {
+                (Some(probabilities), Some(gt)) => (probabilities, &gt.label),
+                _ => return,
+            };
+            let i = match target_labels.iter().position(|label| label == gt_label) {
+                Some(i) => i,
+                None => return,
+            };
+
+            let probability = |label: &Label| probabilities.get(label).copied().unwrap_or(0.0);
+            let squared_error = |label: &Label| {
+                let target = if label == gt_label { 1.0 } else { 0.0 };
+                (probability(label) - target).powi(2)
+            };
+
+            num_pairs[i] += 1;
+            log_loss[i] -= probability(gt_label).max(MIN_PROBABILITY).ln();
+            brier_score[i] += target_labels.iter().map(squared_error).sum::<f64>()
+                + probabilities
+                    .keys()
+                    .filter(|label| !target_labels.contains(label))
+                    .map(squared_error)
+                    .sum::<f64>();
+        });
+
+        Self {
+            target_labels: target_labels.to_vec(),
+            num_pairs,
+            log_loss,
+            brier_score,
+        }
+    }
+
+    /// Returns the total number of evaluated pairs.
+    pub fn num_pairs(&self) -> usize {
+        self.num_pairs.iter().sum()
+    }
+
+    /// Returns mean log-loss of the label.
+    /// If there is no pair, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn log_loss(&self, label: &Label) -> Option<f64> {
+        let i = self.index_of(label)?;
+        mean(self.log_loss[i], self.num_pairs[i])
+    }
+
+    /// Returns mean Brier score of the label.
+    /// If there is no pair, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn brier_score(&self, label: &Label) -> Option<f64> {
+        let i = self.index_of(label)?;
+        mean(self.brier_score[i], self.num_pairs[i])
+    }
+
+    /// Returns mean log-loss over all pairs.
+    /// If there is no pair, returns None.
+    pub fn mean_log_loss(&self) -> Option<f64> {
+        mean(self.log_loss.iter().sum(), self.num_pairs())
+    }
+
+    /// Returns mean Brier score over all pairs.
+    /// If there is no pair, returns None.
+    pub fn mean_brier_score(&self) -> Option<f64> {
+        mean(self.brier_score.iter().sum(), self.num_pairs())
+    }
+
+    fn index_of(&self, label: &Label) -> Option<usize> {
+        self.target_labels.iter().position(|l| l == label)
+    }
+}
+
+impl Display for SoftClassificationScore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n".to_string();
+        msg += &format!("[Soft Classification]\nnum pairs: {}", self.num_pairs());
+
+        msg += &format!("\n|{0:>10}|", "Label");
+        self.target_labels
+            .iter()
+            .for_each(|label| msg += &format!("{0:^12}|", label.to_string()));
+
+        let rows: [(&str, ScoreFn); 2] =
+            [("LogLoss", Self::log_loss), ("Brier", Self::brier_score)];
+        rows.iter().for_each(|(name, func)| {
+            msg += &format!("\n|{0:>10}|", name);
+            self.target_labels
+                .iter()
+                .for_each(|label| match func(self, label) {
+                    Some(value) => msg += &format!(" {0:>10.3} |", value),
+                    None => msg += &format!(" {0:>10} |", "-"),
+                });
+        });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+fn mean(sum: f64, num: usize) -> Option<f64> {
+    if num == 0 {
+        None
+    } else {
+        Some(sum / num as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SoftClassificationScore;
+    use crate::{
+        frame_id::FrameID, label::Label, object::object3d::DynamicObject,
+        result::object::PerceptionResult,
+    };
+    use chrono::DateTime;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_soft_classification_score() {
+        let ground_truth = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: None,
+            class_probabilities: None,
+        };
+        let estimation = DynamicObject {
+            class_probabilities: Some(HashMap::from([(Label::Car, 0.5), (Label::Truck, 0.5)])),
+            ..ground_truth.clone()
+        };
+        let without_probabilities = ground_truth.clone();
+        let results = vec![
+            PerceptionResult::new(estimation.clone(), Some(ground_truth.clone())),
+            PerceptionResult::new(without_probabilities, Some(ground_truth)),
+            PerceptionResult::new(estimation, None),
+        ];
+
+        let score = SoftClassificationScore::new(&results, &[Label::Car, Label::Pedestrian]);
+        assert_eq!(score.num_pairs(), 1);
+        assert!((score.log_loss(&Label::Car).unwrap() - 0.5f64.ln().abs()).abs() < 1e-9);
+        // (0.5 - 1)^2 + (0 - 0)^2 + (0.5 - 0)^2 for Car, Pedestrian and Truck.
+        assert!((score.brier_score(&Label::Car).unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(score.log_loss(&Label::Pedestrian), None);
+    }
+}
@@ -0,0 +1,351 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FormatResult},
+    io::{BufWriter, Result as IoResult},
+    path::Path,
+};
+
+use serde::Serialize;
+
+use crate::{fault::create_file, label::Label, result::frame::PerceptionFrameResult};
+
+/// Default number of bins of the coverage histogram.
+pub const DEFAULT_COVERAGE_BINS: usize = 10;
+
+/// Number of worst-covered instances shown by `Display`.
+const NUM_DISPLAYED_INSTANCES: usize = 10;
+
+/// Detection coverage of one GT instance.
+///
+/// * `uuid`            - Instance ID of the GT.
+/// * `label`           - Label of the GT at the first frame.
+/// * `num_frames`      - Number of frames where the GT is evaluated.
+/// * `num_detected`    - Number of frames where the GT is paired as TP.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InstanceRecord {
+    pub uuid: String,
+    pub label: Label,
+    pub num_frames: usize,
+    pub num_detected: usize,
+}
+
+impl InstanceRecord {
+    /// Returns the fraction of frames where the GT is detected in [0, 1].
+    pub fn coverage(&self) -> f64 {
+        self.num_detected as f64 / self.num_frames as f64
+    }
+}
+
+/// Per-instance recall, which is the fraction of frames where each GT instance is detected as TP.
+///
+/// Unlike frame-level recall, systematically missed objects, such as children or dark vehicles,
+/// stand out as instances with low coverage even if they are a small part of all GTs.
+///
+/// GTs without uuid are ignored.
+///
+/// * `instances`   - Coverage record for each GT uuid.
+#[derive(Debug, Clone, Default)]
+pub struct InstanceCoverage {
+    instances: HashMap<String, InstanceRecord>,
+}
+
+impl InstanceCoverage {
+    /// Construct `InstanceCoverage`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct `InstanceCoverage` from frame results.
+    ///
+    /// * `frame_results`   - List of PerceptionFrameResult.
+    pub fn from_frame_results(frame_results: &[PerceptionFrameResult]) -> Self {
+        let mut coverage = Self::new();
+        frame_results.iter().for_each(|frame_result| {
+            coverage.add_frame_result(frame_result);
+        });
+        coverage
+    }
+
+    /// Accumulate GTs evaluated at one frame and whether they are detected.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult at one frame.
+    pub fn add_frame_result(&mut self, frame_result: &PerceptionFrameResult) -> &mut Self {
+        let detected = frame_result
+            .tp_results()
+            .iter()
+            .filter_map(|result| result.ground_truth_object.as_ref()?.uuid.as_deref())
+            .collect::<Vec<_>>();
+        frame_result
+            .frame_ground_truth()
+            .objects
+            .iter()
+            .for_each(|object| {
+                let uuid = match &object.uuid {
+                    Some(uuid) => uuid,
+                    None => return,
+                };
+                let record =
+                    self.instances
+                        .entry(uuid.to_owned())
+                        .or_insert_with(|| InstanceRecord {
+                            uuid: uuid.to_owned(),
+                            label: object.label.to_owned(),
+                            num_frames: 0,
+                            num_detected: 0,
+                        });
+                record.num_frames += 1;
+                if detected.contains(&uuid.as_str()) {
+                    record.num_detected += 1;
+                }
+            });
+        self
+    }
+
+    /// Returns the number of GT instances.
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns whether there is no GT instance.
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Returns the coverage record of the GT instance.
+    /// If the instance has never been evaluated, returns None.
+    ///
+    /// * `uuid`    - Instance ID of the GT.
+    pub fn get(&self, uuid: &str) -> Option<&InstanceRecord> {
+        self.instances.get(uuid)
+    }
+
+    /// Returns the number of instances in each bin of coverage, where `[0, 1]` is divided equally.
+    /// Instances fully covered are counted in the last bin.
+    ///
+    /// * `num_bins`    - Number of bins, which must be positive.
+    /// * `label`       - Label of instances to be counted. If None, all instances are counted.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::metrics::coverage::InstanceCoverage;
+    ///
+    /// let quickstart = perception_eval::quickstart();
+    ///
+    /// let mut manager = quickstart.manager();
+    /// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+    ///     manager.add_frame_result(estimations, frame).unwrap();
+    /// }
+    /// let coverage = manager.get_instance_coverage().unwrap();
+    /// let histogram = coverage.histogram(10, None);
+    ///
+    /// assert_eq!(histogram.iter().sum::<usize>(), coverage.len());
+    /// ```
+    pub fn histogram(&self, num_bins: usize, label: Option<&Label>) -> Vec<usize> {
+        let mut histogram = vec![0; num_bins];
+        self.instances
+            .values()
+            .filter(|record| label.is_none_or(|label| record.label == *label))
+            .for_each(|record| {
+                let bin = (record.coverage() * num_bins as f64) as usize;
+                histogram[bin.min(num_bins - 1)] += 1;
+            });
+        histogram
+    }
+
+    /// Returns instances ordered from the worst coverage, where ties are ordered
+    /// from the longest observed instance.
+    ///
+    /// * `num_instances`   - Maximum number of returned instances.
+    /// * `min_frames`      - Minimum number of frames of returned instances,
+    ///   to exclude instances only briefly observed.
+    pub fn worst_instances(&self, num_instances: usize, min_frames: usize) -> Vec<&InstanceRecord> {
+        let mut records = self
+            .instances
+            .values()
+            .filter(|record| min_frames <= record.num_frames)
+            .collect::<Vec<_>>();
+        records.sort_by(|a, b| {
+            a.coverage()
+                .total_cmp(&b.coverage())
+                .then(b.num_frames.cmp(&a.num_frames))
+                .then(a.uuid.cmp(&b.uuid))
+        });
+        records.truncate(num_instances);
+        records
+    }
+
+    /// Save the histogram with `DEFAULT_COVERAGE_BINS` bins and all instances
+    /// ordered from the worst coverage as JSON.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save_json<P>(&self, path: P) -> IoResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            histogram: Vec<usize>,
+            instances: Vec<&'a InstanceRecord>,
+        }
+
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let report = Report {
+            histogram: self.histogram(DEFAULT_COVERAGE_BINS, None),
+            instances: self.worst_instances(self.len(), 0),
+        };
+        let writer = BufWriter::new(create_file(path)?);
+        serde_json::to_writer_pretty(writer, &report)?;
+        Ok(())
+    }
+}
+
+impl Display for InstanceCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n[Instance Coverage]".to_string();
+        msg += &format!("\n|{0:^13}|{1:^10}|", "Coverage", "Instances");
+        let bin_width = 1.0 / DEFAULT_COVERAGE_BINS as f64;
+        self.histogram(DEFAULT_COVERAGE_BINS, None)
+            .iter()
+            .enumerate()
+            .for_each(|(i, num_instances)| {
+                msg += &format!(
+                    "\n| {0:.1} - {1:.1}{2}|{3:>9} |",
+                    i as f64 * bin_width,
+                    (i + 1) as f64 * bin_width,
+                    if i + 1 == DEFAULT_COVERAGE_BINS {
+                        "]"
+                    } else {
+                        ")"
+                    },
+                    num_instances
+                );
+            });
+
+        msg += "\n[Worst Covered Instances]";
+        msg += &format!(
+            "\n|{0:>34}|{1:>12}|{2:^10}|{3:^10}|",
+            "UUID", "Label", "Frames", "Coverage"
+        );
+        self.worst_instances(NUM_DISPLAYED_INSTANCES, 0)
+            .iter()
+            .for_each(|record| {
+                msg += &format!(
+                    "\n|{0:>34}|{1:>12}|{2:>9} |{3:>9.3} |",
+                    record.uuid,
+                    record.label.to_string(),
+                    record.num_frames,
+                    record.coverage()
+                );
+            });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstanceCoverage;
+    use crate::{
+        dataset::FrameGroundTruth,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_instance_coverage() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let object = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 4.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: None,
+            class_probabilities: None,
+        };
+        let car = DynamicObject {
+            uuid: Some("car".to_string()),
+            ..object.clone()
+        };
+        let child = DynamicObject {
+            position: [-10.0, 0.0, 0.0],
+            size: [0.5, 0.5, 1.0],
+            label: Label::Pedestrian,
+            uuid: Some("child".to_string()),
+            ..object.clone()
+        };
+        let anonymous = DynamicObject {
+            position: [0.0, 10.0, 0.0],
+            ..object.clone()
+        };
+
+        // The car is detected at 3 of 4 frames, while the child is never detected.
+        let frames = [
+            (vec![&car, &child, &anonymous], true),
+            (vec![&car, &child, &anonymous], true),
+            (vec![&car], false),
+            (vec![&car], true),
+        ];
+        let frame_results = frames
+            .iter()
+            .map(|(ground_truths, detected)| {
+                let ground_truths = ground_truths
+                    .iter()
+                    .map(|object| (*object).to_owned())
+                    .collect::<Vec<_>>();
+                let estimations = if *detected {
+                    vec![object.clone()]
+                } else {
+                    vec![]
+                };
+                PerceptionFrameResult::new(
+                    get_perception_results(&estimations, &ground_truths),
+                    FrameGroundTruth {
+                        timestamp,
+                        objects: ground_truths,
+                        ego_translation: None,
+                        ego_rotation: None,
+                        sample_token: None,
+                        version: None,
+                        raw_pointcloud: None,
+                        location: None,
+                    },
+                    &[Label::Car, Label::Pedestrian],
+                    MatchingMode::CenterDistance,
+                    &[1.0, 1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let coverage = InstanceCoverage::from_frame_results(&frame_results);
+        assert_eq!(coverage.len(), 2);
+        let record = coverage.get("car").unwrap();
+        assert_eq!((record.num_frames, record.num_detected), (4, 3));
+        assert_eq!(record.coverage(), 0.75);
+        assert_eq!(coverage.get("child").unwrap().coverage(), 0.0);
+
+        assert_eq!(coverage.histogram(4, None), vec![1, 0, 0, 1]);
+        assert_eq!(coverage.histogram(2, Some(&Label::Car)), vec![0, 1]);
+        assert_eq!(coverage.histogram(2, Some(&Label::Bicycle)), vec![0, 0]);
+
+        let worst = coverage.worst_instances(1, 0);
+        assert_eq!(worst.len(), 1);
+        assert_eq!(worst[0].uuid, "child");
+        assert_eq!(worst[0].label, Label::Pedestrian);
+        let worst = coverage.worst_instances(10, 3);
+        assert_eq!(worst.len(), 1);
+        assert_eq!(worst[0].uuid, "car");
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FormatResult},
+};
+
+use crate::{
+    config::ApInterpolation,
+    filter::{hash_num_objects, hash_results_ref},
+    label::Label,
+    matching::{CustomMatching, PlaneDistanceConfig},
+    object::object3d::DynamicObject,
+    result::{frame::PerceptionFrameResult, object::PerceptionResult},
+    threshold::get_label_threshold,
+};
+
+use super::{
+    detection::Ap,
+    tp_metrics::{TPMetricsAP, TPMetricsAPH},
+};
+
+/// AP and APH scores for each label calculated with the user-defined matching function.
+///
+/// * `target_labels`       - List of Label instances.
+/// * `thresholds`          - List of thresholds of the score for each label.
+/// * `ap_interpolation`    - Method to interpolate the precision-recall curve.
+/// * `ap`                  - AP for each label.
+/// * `aph`                 - APH for each label.
+#[derive(Debug, Clone)]
+pub struct CustomMatchingScore {
+    target_labels: Vec<Label>,
+    thresholds: Vec<f64>,
+    ap_interpolation: ApInterpolation,
+    ap: Vec<f64>,
+    aph: Vec<f64>,
+}
+
+impl CustomMatchingScore {
+    /// Construct `CustomMatchingScore` from accumulated frame results.
+    ///
+    /// Frame results are usually constructed by `PerceptionFrameResult::with_custom_matching()`
+    /// with the same matching function, so that pairs are assigned consistently with the scores.
+    ///
+    /// * `frame_results`       - List of PerceptionFrameResult.
+    /// * `target_labels`       - List of Label instances.
+    /// * `matching`            - CustomMatching instance.
+    /// * `matching_thresholds` - List of thresholds of the score for each target label.
+    /// * `ap_interpolation`    - Method to interpolate the precision-recall curve.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     config::ApInterpolation,
+    ///     dataset::FrameGroundTruth,
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     matching::{CustomMatching, ScoreDirection},
+    ///     metrics::custom::CustomMatchingScore,
+    ///     object::object3d::DynamicObject,
+    ///     result::frame::PerceptionFrameResult,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [10.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [4.0, 2.0, 1.5],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
+    /// let frame_ground_truth = FrameGroundTruth::new(object.timestamp, vec![object.clone()]);
+    ///
+    /// // Distance error relative to the range of GT, which tolerates errors of far objects.
+    /// let matching = CustomMatching::new(
+    ///     |est: &DynamicObject, gt: &DynamicObject| {
+    ///         est.distance_from(&gt.position) / gt.distance_from(&[0.0, 0.0, 0.0])
+    ///     },
+    ///     ScoreDirection::LowerIsBetter,
+    /// );
+    /// let mut estimation = object;
+    /// estimation.position = [11.0, 1.0, 0.0];
+    /// let frame_result = PerceptionFrameResult::with_custom_matching(
+    ///     &[estimation],
+    ///     frame_ground_truth,
+    ///     &[Label::Car],
+    ///     &matching,
+    ///     &[0.2],
+    /// );
+    ///
+    /// let score = CustomMatchingScore::new(
+    ///     &[frame_result],
+    ///     &[Label::Car],
+    ///     &matching,
+    ///     &[0.2],
+    ///     &ApInterpolation::Point11,
+    /// );
+    /// assert_eq!(score.ap(&Label::Car), Some(1.0));
+    /// ```
+    pub fn new<F>(
+        frame_results: &[PerceptionFrameResult],
+        target_labels: &[Label],
+        matching: &CustomMatching<F>,
+        matching_thresholds: &[f64],
+        ap_interpolation: &ApInterpolation,
+    ) -> Self
+    where
+        F: Fn(&DynamicObject, &DynamicObject) -> f64,
+    {
+        let mut results_map = target_labels
+            .iter()
+            .map(|label| (label.to_owned(), Vec::<&PerceptionResult>::new()))
+            .collect::<HashMap<_, _>>();
+        let mut num_gt_map = target_labels
+            .iter()
+            .map(|label| (label.to_owned(), 0))
+            .collect::<HashMap<_, _>>();
+        frame_results.iter().for_each(|frame| {
+            let mut frame_results_map = hash_results_ref(frame.results(), target_labels);
+            let frame_num_gt_map =
+                hash_num_objects(&frame.frame_ground_truth().objects, target_labels);
+            target_labels.iter().for_each(|label| {
+                if let (Some(results), Some(frame_results)) =
+                    (results_map.get_mut(label), frame_results_map.get_mut(label))
+                {
+                    results.append(frame_results);
+                }
+                if let (Some(num_gt), Some(frame_num_gt)) =
+                    (num_gt_map.get_mut(label), frame_num_gt_map.get(label))
+                {
+                    *num_gt += frame_num_gt;
+                }
+            });
+        });
+
+        let plane_distance_config = PlaneDistanceConfig::default();
+        let mut ap = Vec::with_capacity(target_labels.len());
+        let mut aph = Vec::with_capacity(target_labels.len());
+        target_labels.iter().for_each(|label| {
+            let threshold =
+                get_label_threshold(label, target_labels, matching_thresholds).unwrap_or(f64::NAN);
+            let is_correct =
+                |result: &PerceptionResult| result.is_result_correct_by(matching, &threshold);
+            let calculator = Ap::new(
+                &results_map[label],
+                &num_gt_map[label],
+                &plane_distance_config,
+                ap_interpolation,
+            );
+            ap.push(calculator.calculate_ap_by(TPMetricsAP, is_correct));
+            aph.push(calculator.calculate_ap_by(TPMetricsAPH, is_correct));
+        });
+
+        Self {
+            target_labels: target_labels.to_owned(),
+            thresholds: matching_thresholds.to_owned(),
+            ap_interpolation: ap_interpolation.to_owned(),
+            ap,
+            aph,
+        }
+    }
+
+    /// Returns AP of the label.
+    /// If the label is not a target, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn ap(&self, label: &Label) -> Option<f64> {
+        let index = self.target_labels.iter().position(|l| l == label)?;
+        Some(self.ap[index])
+    }
+
+    /// Returns APH of the label.
+    /// If the label is not a target, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn aph(&self, label: &Label) -> Option<f64> {
+        let index = self.target_labels.iter().position(|l| l == label)?;
+        Some(self.aph[index])
+    }
+}
+
+impl Display for CustomMatchingScore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n".to_string();
+        msg += &format!("[Custom] (AP interpolation: {})\n", self.ap_interpolation);
+        [("AP", &self.ap), ("APH", &self.aph)]
+            .iter()
+            .for_each(|(key, values)| {
+                msg += &format!(
+                    "m{}: {:.3} ",
+                    key,
+                    values.iter().sum::<f64>() / values.len() as f64
+                )
+            });
+
+        msg += &format!("\n|{0:>10}|", "Label");
+        self.target_labels
+            .iter()
+            .zip(self.thresholds.iter())
+            .for_each(|(label, threshold)| msg += &format!("{0:^10}({1:<.3}) |", label, threshold));
+
+        [("AP", &self.ap), ("APH", &self.aph)]
+            .iter()
+            .for_each(|(key, values)| {
+                msg += &format!("\n|{0:>10}|", key);
+                values
+                    .iter()
+                    .for_each(|value| msg += &format!(" {0:>10.3} | ", value));
+            });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
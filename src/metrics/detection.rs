@@ -1,5 +1,16 @@
-use super::tp_metrics::{TPMetrics, TPMetricsAP, TPMetricsAPH};
-use crate::{label::Label, matching::MatchingMode, result::object::PerceptionResult};
+use super::{
+    error::{MetricsError, MetricsResult},
+    tp_metrics::{TPMetrics, TPMetricsAP, TPMetricsAPH},
+};
+use crate::{
+    config::ApInterpolation,
+    core::ap::{
+        accumulate, all_point_ap, interpolate_precision_recall, precision_recall, sampled_ap,
+    },
+    label::Label,
+    matching::{get_matching_method, MatchingMode, MatchingResult, PlaneDistanceConfig},
+    result::object::PerceptionResult,
+};
 use std::{
     collections::HashMap,
     fmt::{Display, Formatter, Result as FormatResult},
@@ -11,7 +22,59 @@ pub(crate) struct DetectionMetricsScore {
     pub(crate) target_labels: Vec<Label>,
     pub(crate) matching_mode: MatchingMode,
     pub(crate) thresholds: Vec<f64>,
+    pub(crate) ap_interpolation: ApInterpolation,
     pub(crate) scores: HashMap<String, Vec<f64>>,
+    pub(crate) pr_curves: Vec<Vec<PrPoint>>,
+    pub(crate) decisions: Vec<DecisionRecords>,
+    pub(crate) num_gt: Vec<usize>,
+}
+
+/// Point of the precision-recall curve at one result, which backs AP.
+///
+/// * `confidence`  - Confidence of the estimation.
+/// * `precision`   - Precision accumulated till the result.
+/// * `recall`      - Recall accumulated till the result.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PrPoint {
+    pub(crate) confidence: f64,
+    pub(crate) precision: f64,
+    pub(crate) recall: f64,
+}
+
+/// Raw TP/FP decisions of results backing AP, stored as aligned vectors
+/// so that custom metrics can be computed by external tools without re-implementing matching.
+///
+/// * `confidence`      - Confidence of the estimation.
+/// * `is_tp`           - Whether the result is TP with the matching threshold.
+/// * `matching_score`  - Matching score between the estimation and the paired GT. None for the result without GT.
+/// * `gt_uuid`         - UUID of the paired GT. None for the result without GT or the GT without UUID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecisionRecords {
+    pub confidence: Vec<f64>,
+    pub is_tp: Vec<bool>,
+    pub matching_score: Vec<Option<f64>>,
+    pub gt_uuid: Vec<Option<String>>,
+}
+
+impl DecisionRecords {
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.confidence.len()
+    }
+
+    /// Returns whether there is no record.
+    pub fn is_empty(&self) -> bool {
+        self.confidence.is_empty()
+    }
+}
+
+/// Scores of one label evaluated by `DetectionMetricsScore`.
+struct LabelScore {
+    ap: f64,
+    aph: f64,
+    pr_curve: Vec<PrPoint>,
+    decisions: DecisionRecords,
+    num_gt: usize,
 }
 
 impl DetectionMetricsScore {
@@ -22,47 +85,132 @@ impl DetectionMetricsScore {
     /// * `target_labels`       - List of Label instances.
     /// * `matching_mode`       - MatchingMode instance.
     /// * `matching_thresholds` - List of matching thresholds.
+    /// * `plane_distance_config` - Configuration used for `MatchingMode::PlaneDistance`.
+    /// * `ap_interpolation`    - Method to interpolate the precision-recall curve.
+    /// * `num_threads`         - Number of threads to evaluate labels on. If None, labels are evaluated sequentially.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        results_map: &HashMap<Label, Vec<PerceptionResult>>,
+        results_map: &HashMap<Label, Vec<&PerceptionResult>>,
         num_gt_map: &HashMap<Label, usize>,
         target_labels: &Vec<Label>,
         matching_mode: &MatchingMode,
         matching_thresholds: &Vec<f64>,
-    ) -> Self {
-        let mut scores = HashMap::new();
-        let num_targets = target_labels.len();
-        let mut ap_list = vec![0.0; num_targets];
-        let mut aph_list = vec![0.0; num_targets];
-        for (i, (target_label, threshold)) in target_labels
-            .iter()
-            .zip(matching_thresholds.iter())
-            .enumerate()
-        {
+        plane_distance_config: &PlaneDistanceConfig,
+        ap_interpolation: &ApInterpolation,
+        num_threads: Option<usize>,
+    ) -> MetricsResult<Self> {
+        let evaluate_label = |(target_label, threshold): (&Label, &f64)| {
             let results = results_map.get(target_label).unwrap();
             let num_gt = num_gt_map.get(target_label).unwrap();
-            ap_list[i] =
-                Ap::new(results, num_gt).calculate_ap(TPMetricsAP, matching_mode, threshold);
-            aph_list[i] =
-                Ap::new(results, num_gt).calculate_ap(TPMetricsAPH, matching_mode, threshold);
+            let ap = Ap::new(results, num_gt, plane_distance_config, ap_interpolation);
+            Ok(LabelScore {
+                ap: ap.calculate_ap(TPMetricsAP, matching_mode, threshold),
+                aph: ap.calculate_ap(TPMetricsAPH, matching_mode, threshold),
+                pr_curve: ap.calculate_pr_curve(matching_mode, threshold),
+                decisions: ap.calculate_decisions(matching_mode, threshold)?,
+                num_gt: *num_gt,
+            })
+        };
+        let jobs = target_labels
+            .iter()
+            .zip(matching_thresholds.iter())
+            .collect::<Vec<_>>();
+        // Each label is evaluated by one thread and merged in the order of labels,
+        // so that scores are the same as sequential evaluation.
+        let label_scores = match num_threads {
+            Some(num_threads) if !jobs.is_empty() => {
+                let chunk_size = jobs.len().div_ceil(num_threads.max(1));
+                std::thread::scope(|scope| {
+                    let handles = jobs
+                        .chunks(chunk_size)
+                        .map(|chunk| {
+                            scope.spawn(|| {
+                                chunk
+                                    .iter()
+                                    .copied()
+                                    .map(evaluate_label)
+                                    .collect::<MatchingResult<Vec<_>>>()
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    // Join all threads before returning errors, since the scope panics on unjoined panicked threads.
+                    let chunk_results = handles
+                        .into_iter()
+                        .map(|handle| handle.join())
+                        .collect::<Vec<_>>();
+                    chunk_results
+                        .into_iter()
+                        .map(|chunk_result| {
+                            Ok(chunk_result.map_err(|_| MetricsError::ThreadPanicked)??)
+                        })
+                        .collect::<MetricsResult<Vec<_>>>()
+                })?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+            }
+            _ => jobs
+                .into_iter()
+                .map(evaluate_label)
+                .collect::<MatchingResult<Vec<_>>>()?,
+        };
+
+        let mut scores = HashMap::new();
+        let mut ap_list = Vec::with_capacity(label_scores.len());
+        let mut aph_list = Vec::with_capacity(label_scores.len());
+        let mut pr_curves = Vec::with_capacity(label_scores.len());
+        let mut decisions = Vec::with_capacity(label_scores.len());
+        let mut num_gt_list = Vec::with_capacity(label_scores.len());
+        for label_score in label_scores {
+            ap_list.push(label_score.ap);
+            aph_list.push(label_score.aph);
+            pr_curves.push(label_score.pr_curve);
+            decisions.push(label_score.decisions);
+            num_gt_list.push(label_score.num_gt);
         }
 
         scores.insert(String::from("AP"), ap_list);
         scores.insert(String::from("APH"), aph_list);
 
         // TODO: Refactor DO NOT USE to_owned()
-        Self {
+        Ok(Self {
             target_labels: target_labels.to_owned(),
             matching_mode: matching_mode.to_owned(),
             thresholds: matching_thresholds.to_owned(),
+            ap_interpolation: ap_interpolation.to_owned(),
             scores,
-        }
+            pr_curves,
+            decisions,
+            num_gt: num_gt_list,
+        })
+    }
+}
+
+impl DetectionMetricsScore {
+    /// Returns one-line summary of mean scores over labels.
+    pub(crate) fn format_compact(&self) -> String {
+        let mut msg = format!("[{:?}]", self.matching_mode);
+        let mut keys = self.scores.keys().collect::<Vec<_>>();
+        keys.sort();
+        keys.iter().for_each(|key| {
+            let values = &self.scores[*key];
+            msg += &format!(
+                " m{}: {:.3}",
+                key,
+                values.iter().sum::<f64>() / values.len() as f64
+            )
+        });
+        msg
     }
 }
 
 impl Display for DetectionMetricsScore {
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         let mut msg = "\n".to_string();
-        msg += &format!("[{:?}]\n", self.matching_mode);
+        msg += &format!(
+            "[{:?}] (AP interpolation: {})\n",
+            self.matching_mode, self.ap_interpolation
+        );
 
         self.scores.iter().for_each(|(key, values)| {
             msg += &format!(
@@ -94,19 +242,37 @@ impl Display for DetectionMetricsScore {
 /// Manager to calculate Average Precision (AP) and Average Precision Heading (APH) for each set of labels.
 #[derive(Debug)]
 pub(super) struct Ap<'a> {
-    results: &'a Vec<PerceptionResult>,
+    results: Vec<&'a PerceptionResult>,
     num_ground_truth: &'a usize,
+    plane_distance_config: &'a PlaneDistanceConfig,
+    ap_interpolation: &'a ApInterpolation,
 }
 
 impl<'a> Ap<'a> {
-    /// Construct `Ap`  instance.
+    /// Construct `Ap`  instance. Results are sorted in descending order of confidence,
+    /// and results with the same confidence keep the given order.
     ///
     /// * `results`             - List of PerceptionResult.
     /// * `num_ground_truth`    - Number of GTs.
-    pub(super) fn new(results: &'a Vec<PerceptionResult>, num_ground_truth: &'a usize) -> Self {
+    /// * `plane_distance_config` - Configuration used for `MatchingMode::PlaneDistance`.
+    /// * `ap_interpolation`    - Method to interpolate the precision-recall curve.
+    pub(super) fn new(
+        results: &'a [&'a PerceptionResult],
+        num_ground_truth: &'a usize,
+        plane_distance_config: &'a PlaneDistanceConfig,
+        ap_interpolation: &'a ApInterpolation,
+    ) -> Self {
+        let mut results = results.to_vec();
+        results.sort_by(|a, b| {
+            b.estimated_object
+                .confidence
+                .total_cmp(&a.estimated_object.confidence)
+        });
         Self {
             results,
             num_ground_truth,
+            plane_distance_config,
+            ap_interpolation,
         }
     }
 
@@ -124,70 +290,85 @@ impl<'a> Ap<'a> {
     where
         T: TPMetrics,
     {
-        let (tp_list, _) = self.calculate_tp_fp(tp_metrics, matching_mode, threshold);
-        let (precision_list, recall_list) = self.calculate_precision_recall(&tp_list);
-        let (max_precision_list, max_recall_list) =
-            self.interpolate_precision_recall(precision_list, recall_list);
+        self.calculate_ap_by(tp_metrics, |result| {
+            result
+                .is_result_correct_with(matching_mode, threshold, self.plane_distance_config)
+                .unwrap()
+        })
+    }
 
-        if max_precision_list.is_empty() {
-            f64::NAN
-        } else {
-            let mut ap = 0.0;
-            for i in 0..max_precision_list.len() - 1 {
-                ap += max_precision_list[i] * (max_recall_list[i] - max_recall_list[i + 1]);
-            }
-            ap
+    /// Calculate AP or APH score with the function to determine whether the result is correct.
+    ///
+    /// * `tp_metrics`  - TP metrics. `TPMetricsAP` or `TPMetricsAPH`.
+    /// * `is_correct`  - Function returning whether the result is TP.
+    pub(super) fn calculate_ap_by<T, C>(&self, tp_metrics: T, is_correct: C) -> f64
+    where
+        T: TPMetrics,
+        C: Fn(&PerceptionResult) -> bool,
+    {
+        let (tp_list, _) = self.calculate_tp_fp_by(tp_metrics, is_correct);
+        let (precision_list, recall_list) = precision_recall(&tp_list, *self.num_ground_truth);
+        if let Some(recall_points) = self.ap_interpolation.recall_points() {
+            return sampled_ap(&precision_list, &recall_list, &recall_points);
         }
+        let (max_precision_list, max_recall_list) =
+            interpolate_precision_recall(&precision_list, &recall_list);
+        all_point_ap(&max_precision_list, &max_recall_list)
     }
 
-    /// Interpolate precision and recall values.
+    /// Calculate points of the precision-recall curve used for AP, in descending order of confidence.
     ///
-    /// * `precision_list`  - List of precisions.
-    /// * `recall_list`     - List of recalls.
-    fn interpolate_precision_recall(
+    /// * `matching_mode`   - MatchingMode instance.
+    /// * `threshold`       - Matching threshold.
+    pub(super) fn calculate_pr_curve(
         &self,
-        precision_list: Vec<f64>,
-        recall_list: Vec<f64>,
-    ) -> (Vec<f64>, Vec<f64>) {
-        if self.results.is_empty() && *self.num_ground_truth == 0 {
-            (Vec::new(), Vec::new())
-        } else {
-            let mut max_precision_list = vec![*precision_list.last().unwrap()];
-            let mut max_recall_list = vec![*recall_list.last().unwrap()];
-            for i in 0..recall_list.len() - 1 {
-                if max_precision_list.last().unwrap() < &precision_list[i] {
-                    max_precision_list.push(precision_list[i]);
-                    max_recall_list.push(recall_list[i]);
-                }
-            }
-            (max_precision_list, max_recall_list)
-        }
+        matching_mode: &MatchingMode,
+        threshold: &f64,
+    ) -> Vec<PrPoint> {
+        let (tp_list, _) = self.calculate_tp_fp(TPMetricsAP, matching_mode, threshold);
+        let (precision_list, recall_list) = precision_recall(&tp_list, *self.num_ground_truth);
+        self.results
+            .iter()
+            .zip(precision_list.into_iter().zip(recall_list))
+            .map(|(result, (precision, recall))| PrPoint {
+                confidence: result.estimated_object.confidence,
+                precision,
+                recall,
+            })
+            .collect()
     }
 
-    /// Compute precision and recall values.
+    /// Collect TP/FP decisions used for AP, in descending order of confidence.
     ///
-    /// * `tp_list` - List of TP values.
-    fn calculate_precision_recall(&self, tp_list: &[f64]) -> (Vec<f64>, Vec<f64>) {
-        if self.results.is_empty() && *self.num_ground_truth == 0 {
-            (Vec::new(), Vec::new())
-        } else {
-            let num_results = self.results.len();
-            let mut precision_list = vec![0.0; num_results];
-            let mut recall_list = vec![0.0; num_results];
-
-            precision_list
-                .iter_mut()
-                .zip(recall_list.iter_mut())
-                .zip(tp_list.iter())
-                .enumerate()
-                .for_each(|(i, ((precision, recall), tp))| {
-                    *precision = tp / (1.0 + i as f64);
-                    if *self.num_ground_truth > 0 {
-                        *recall = tp / *self.num_ground_truth as f64;
-                    }
-                });
-            (precision_list, recall_list)
+    /// * `matching_mode`   - MatchingMode instance.
+    /// * `threshold`       - Matching threshold.
+    pub(super) fn calculate_decisions(
+        &self,
+        matching_mode: &MatchingMode,
+        threshold: &f64,
+    ) -> MatchingResult<DecisionRecords> {
+        let matching_method = get_matching_method(matching_mode, self.plane_distance_config);
+        let mut decisions = DecisionRecords::default();
+        for result in &self.results {
+            let ground_truth = result.ground_truth_object.as_ref();
+            decisions
+                .confidence
+                .push(result.estimated_object.confidence);
+            decisions.is_tp.push(result.is_result_correct_with(
+                matching_mode,
+                threshold,
+                self.plane_distance_config,
+            )?);
+            decisions.matching_score.push(
+                ground_truth.map(|gt| {
+                    matching_method.calculate_matching_score(&result.estimated_object, gt)
+                }),
+            );
+            decisions
+                .gt_uuid
+                .push(ground_truth.and_then(|gt| gt.uuid.to_owned()));
         }
+        Ok(decisions)
     }
 
     /// Compute TP and FP values.
@@ -203,6 +384,22 @@ impl<'a> Ap<'a> {
     ) -> (Vec<f64>, Vec<f64>)
     where
         T: TPMetrics,
+    {
+        self.calculate_tp_fp_by(tp_metrics, |result| {
+            result
+                .is_result_correct_with(matching_mode, threshold, self.plane_distance_config)
+                .unwrap()
+        })
+    }
+
+    /// Compute TP and FP values with the function to determine whether the result is correct.
+    ///
+    /// * `tp_metrics`  - TP metrics.
+    /// * `is_correct`  - Function returning whether the result is TP.
+    fn calculate_tp_fp_by<T, C>(&self, tp_metrics: T, is_correct: C) -> (Vec<f64>, Vec<f64>)
+    where
+        T: TPMetrics,
+        C: Fn(&PerceptionResult) -> bool,
     {
         if self.results.is_empty() && *self.num_ground_truth == 0 {
             (Vec::new(), Vec::new())
@@ -212,24 +409,68 @@ impl<'a> Ap<'a> {
             let mut fp_list = vec![0.0; num_results];
 
             self.results.iter().enumerate().for_each(|(i, result)| {
-                if result.is_result_correct(matching_mode, threshold).unwrap() {
+                if is_correct(result) {
                     tp_list[i] = tp_metrics.get_value(result);
                 } else {
                     fp_list[i] = 1.0;
                 }
             });
 
-            tp_list.iter_mut().fold(0.0, |acc, x| {
-                *x += acc;
-                *x
-            });
-
-            fp_list.iter_mut().fold(0.0, |acc, x| {
-                *x += acc;
-                *x
-            });
+            accumulate(&mut tp_list);
+            accumulate(&mut fp_list);
 
             (tp_list, fp_list)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Ap;
+    use crate::{
+        config::ApInterpolation,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        metrics::tp_metrics::TPMetricsAP,
+        object::object3d::DynamicObject,
+        result::object::PerceptionResult,
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_ap_interpolation() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        let tp_result = PerceptionResult::new(object.clone(), Some(object.clone()));
+        let fp_result = PerceptionResult::new(object, None);
+        let results = vec![&tp_result, &fp_result];
+
+        let num_ground_truth = 2;
+        let plane_distance_config = PlaneDistanceConfig::default();
+        let calculate_ap = |ap_interpolation: &ApInterpolation| {
+            Ap::new(
+                &results,
+                &num_ground_truth,
+                &plane_distance_config,
+                ap_interpolation,
+            )
+            .calculate_ap(TPMetricsAP, &MatchingMode::CenterDistance, &1.0)
+        };
+
+        assert_eq!(calculate_ap(&ApInterpolation::Point11), 6.0 / 11.0);
+        assert_eq!(calculate_ap(&ApInterpolation::Point40), 20.0 / 40.0);
+        assert_eq!(calculate_ap(&ApInterpolation::Point101), 51.0 / 101.0);
+    }
+}
@@ -1,14 +1,25 @@
+use std::io::Error as IoError;
+
 use thiserror::Error as ThisError;
 
-use crate::evaluation_task::EvaluationTask;
+use crate::{evaluation_task::EvaluationTask, matching::MatchingError, result::spill::SpillError};
 
 pub type MetricsResult<T> = Result<T, MetricsError>;
 
 /// Represents error that occurs while computing metrics score.
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum MetricsError {
-    #[error("internal error, please report bug")]
-    InternalBug,
     #[error("not implemented error: {0}")]
     NotImplementedError(EvaluationTask),
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+    #[error("I/O error: {0}")]
+    IoError(#[from] IoError),
+    #[error("spill error: {0}")]
+    SpillError(#[from] SpillError),
+    #[error("matching error: {0}")]
+    MatchingError(#[from] MatchingError),
+    #[error("evaluation thread panicked")]
+    ThreadPanicked,
 }
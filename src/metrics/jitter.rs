@@ -0,0 +1,415 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    config::PerceptionEvaluationConfig,
+    matching::MatchingMode,
+    object::object3d::DynamicObject,
+    result::{frame::PerceptionFrameResult, object::get_perception_results_within},
+};
+
+use super::{
+    error::{MetricsError, MetricsResult},
+    score::MetricsScore,
+};
+
+/// Configuration of random GT jitter to evaluate robustness of metrics to annotation noise.
+///
+/// * `num_trials`          - Number of jittered re-evaluations.
+/// * `position_sigma`      - Standard deviation of position noise in [m], applied to x and y.
+/// * `yaw_sigma`           - Standard deviation of yaw noise in [rad].
+/// * `timestamp_sigma`     - Standard deviation of timestamp noise in [s].
+/// * `seed`                - Seed of random number generator.
+/// * `matching_mode`       - MatchingMode of mAP to be reported.
+#[derive(Debug, Clone)]
+pub struct JitterConfig {
+    num_trials: usize,
+    position_sigma: f64,
+    yaw_sigma: f64,
+    timestamp_sigma: f64,
+    seed: u64,
+    matching_mode: MatchingMode,
+}
+
+impl JitterConfig {
+    /// Construct `JitterConfig` without any noise.
+    ///
+    /// * `num_trials`  - Number of jittered re-evaluations, which must be larger than 1.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{matching::MatchingMode, metrics::jitter::JitterConfig};
+    ///
+    /// let config = JitterConfig::new(100)
+    ///     .unwrap()
+    ///     .with_position_sigma(0.1)
+    ///     .unwrap()
+    ///     .with_yaw_sigma(0.02)
+    ///     .unwrap()
+    ///     .with_seed(42)
+    ///     .with_matching_mode(MatchingMode::CenterDistance);
+    /// ```
+    pub fn new(num_trials: usize) -> MetricsResult<Self> {
+        if num_trials < 2 {
+            Err(MetricsError::InvalidParameter(format!(
+                "num_trials must be larger than 1, but got {}",
+                num_trials
+            )))?
+        }
+        Ok(Self {
+            num_trials,
+            position_sigma: 0.0,
+            yaw_sigma: 0.0,
+            timestamp_sigma: 0.0,
+            seed: 0,
+            matching_mode: MatchingMode::PlaneDistance,
+        })
+    }
+
+    /// Set the standard deviation of position noise in [m].
+    ///
+    /// * `sigma`   - Non-negative standard deviation.
+    pub fn with_position_sigma(mut self, sigma: f64) -> MetricsResult<Self> {
+        self.position_sigma = validate_sigma("position_sigma", sigma)?;
+        Ok(self)
+    }
+
+    /// Set the standard deviation of yaw noise in [rad].
+    ///
+    /// * `sigma`   - Non-negative standard deviation.
+    pub fn with_yaw_sigma(mut self, sigma: f64) -> MetricsResult<Self> {
+        self.yaw_sigma = validate_sigma("yaw_sigma", sigma)?;
+        Ok(self)
+    }
+
+    /// Set the standard deviation of timestamp noise in [s].
+    /// GTs are moved along their velocities by the sampled time offset,
+    /// and GTs without velocity are not affected.
+    ///
+    /// * `sigma`   - Non-negative standard deviation.
+    pub fn with_timestamp_sigma(mut self, sigma: f64) -> MetricsResult<Self> {
+        self.timestamp_sigma = validate_sigma("timestamp_sigma", sigma)?;
+        Ok(self)
+    }
+
+    /// Set the seed of random number generator.
+    ///
+    /// * `seed`    - Seed value.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the matching mode of mAP to be reported.
+    ///
+    /// * `matching_mode`   - MatchingMode instance.
+    pub fn with_matching_mode(mut self, matching_mode: MatchingMode) -> Self {
+        self.matching_mode = matching_mode;
+        self
+    }
+
+    /// Returns a copy of the object with sampled noise.
+    ///
+    /// * `object`  - GT object.
+    /// * `rng`     - Random number generator.
+    fn apply<R: Rng>(&self, object: &DynamicObject, rng: &mut R) -> DynamicObject {
+        let dx = sample_normal(rng, self.position_sigma);
+        let dy = sample_normal(rng, self.position_sigma);
+        let dyaw = sample_normal(rng, self.yaw_sigma);
+        let dt = sample_normal(rng, self.timestamp_sigma);
+
+        let [vx, vy, vz] = object.velocity.unwrap_or([0.0; 3]);
+        let [x, y, z] = object.position;
+        let position = [x + dx + vx * dt, y + dy + vy * dt, z + vz * dt];
+
+        // Rotate around z-axis of the frame, i.e. q' = q_z(dyaw) * q.
+        let (s, c) = (0.5 * dyaw).sin_cos();
+        let [w, qx, qy, qz] = object.orientation;
+        let orientation = [
+            c * w - s * qz,
+            c * qx - s * qy,
+            c * qy + s * qx,
+            c * qz + s * w,
+        ];
+
+        DynamicObject {
+            position,
+            orientation,
+            ..object.to_owned()
+        }
+    }
+}
+
+/// Result of jittered re-evaluations of mAP.
+///
+/// * `matching_mode`   - MatchingMode of reported mAP.
+/// * `nominal`         - mAP without jitter.
+/// * `mean`            - Mean of mAP over jittered trials.
+/// * `std`             - Sample standard deviation of mAP over jittered trials.
+/// * `min`             - Minimum of mAP over jittered trials.
+/// * `max`             - Maximum of mAP over jittered trials.
+/// * `num_trials`      - Number of trials which have valid mAP.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JitterSummary {
+    pub matching_mode: MatchingMode,
+    pub nominal: f64,
+    pub mean: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+    pub num_trials: usize,
+}
+
+impl Display for JitterSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        writeln!(
+            f,
+            "\n[Jitter: {:?}]\nmAP: {:.4} (jittered: {:.4} ± {:.4}, range: [{:.4}, {:.4}]), trials: {}\n",
+            self.matching_mode,
+            self.nominal,
+            self.mean,
+            self.std,
+            self.min,
+            self.max,
+            self.num_trials
+        )
+    }
+}
+
+/// Re-evaluate the same estimations against randomly jittered GTs, and report statistics of mAP.
+///
+/// Estimations are taken from results of each frame and paired with jittered GTs again,
+/// same as `PerceptionEvaluationManager::add_frame_result()`.
+///
+/// * `config`          - Evaluation configuration used for the run.
+/// * `frame_results`   - Frame results of the run.
+/// * `jitter`          - Configuration of jitter.
+pub fn evaluate_jitter(
+    config: &PerceptionEvaluationConfig,
+    frame_results: &[PerceptionFrameResult],
+    jitter: &JitterConfig,
+) -> MetricsResult<JitterSummary> {
+    if frame_results.is_empty() {
+        Err(MetricsError::InvalidParameter(
+            "there is no frame to be jittered".to_string(),
+        ))?
+    }
+    let estimations = frame_results
+        .iter()
+        .map(|frame| {
+            frame
+                .results()
+                .iter()
+                .map(|result| result.estimated_object.to_owned())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mean_ap = |frame_results: &[PerceptionFrameResult]| -> MetricsResult<Option<f64>> {
+        let mut score = MetricsScore::new(&config.metrics_params);
+        score.evaluate_frames(&config.evaluation_task, frame_results.iter())?;
+        Ok(score.mean_ap(&jitter.matching_mode))
+    };
+
+    let nominal = mean_ap(frame_results)?.ok_or_else(|| {
+        MetricsError::InvalidParameter("there is no valid AP to be jittered".to_string())
+    })?;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(jitter.seed);
+    let mut samples = Vec::with_capacity(jitter.num_trials);
+    for _ in 0..jitter.num_trials {
+        let jittered_results = frame_results
+            .iter()
+            .zip(estimations.iter())
+            .map(|(frame, estimations)| {
+                let frame_ground_truth = frame.frame_ground_truth();
+                let objects = frame_ground_truth
+                    .objects
+                    .iter()
+                    .map(|object| jitter.apply(object, &mut rng))
+                    .collect::<Vec<_>>();
+                let results = get_perception_results_within(
+                    estimations,
+                    &objects,
+                    config.metrics_params.max_matching_distance(),
+                );
+                PerceptionFrameResult::new(
+                    results,
+                    frame_ground_truth.with_objects(objects),
+                    &config.filter_params.target_labels,
+                    MatchingMode::PlaneDistance,
+                    &config.metrics_params.plane_distance_thresholds,
+                    &config.metrics_params.plane_distance_config,
+                )
+                .map_err(|err| MetricsError::InvalidParameter(err.to_string()))
+            })
+            .collect::<MetricsResult<Vec<_>>>()?;
+        if let Some(ap) = mean_ap(&jittered_results)? {
+            samples.push(ap);
+        }
+    }
+    if samples.len() < 2 {
+        Err(MetricsError::InvalidParameter(
+            "there are not enough valid jittered trials".to_string(),
+        ))?
+    }
+
+    let num_trials = samples.len();
+    let mean = samples.iter().sum::<f64>() / num_trials as f64;
+    let variance =
+        samples.iter().map(|ap| (ap - mean).powi(2)).sum::<f64>() / (num_trials - 1) as f64;
+
+    Ok(JitterSummary {
+        matching_mode: jitter.matching_mode.to_owned(),
+        nominal,
+        mean,
+        std: variance.sqrt(),
+        min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+        max: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        num_trials,
+    })
+}
+
+/// Returns the standard deviation if it is non-negative and finite.
+///
+/// * `name`    - Name of the parameter.
+/// * `sigma`   - Standard deviation.
+fn validate_sigma(name: &str, sigma: f64) -> MetricsResult<f64> {
+    if sigma.is_finite() && sigma >= 0.0 {
+        Ok(sigma)
+    } else {
+        Err(MetricsError::InvalidParameter(format!(
+            "{} must be non-negative, but got {}",
+            name, sigma
+        )))
+    }
+}
+
+/// Returns a sample of the zero-mean normal distribution with Box-Muller transform.
+///
+/// * `rng`     - Random number generator.
+/// * `sigma`   - Standard deviation.
+fn sample_normal<R: Rng>(rng: &mut R, sigma: f64) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    sigma * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_jitter, JitterConfig};
+    use crate::{
+        config::{
+            ApInterpolation, FilterParams, FrameIdPolicy, MetricsParams, PerceptionEvaluationConfig,
+        },
+        dataset::FrameGroundTruth,
+        evaluation_task::EvaluationTask,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_evaluate_jitter() {
+        let config = PerceptionEvaluationConfig {
+            versions: vec!["annotation".to_string()],
+            dataset_path: "tests/sample_data".into(),
+            evaluation_task: EvaluationTask::Detection,
+            frame_id: FrameID::BaseLink,
+            result_dir: "work_dir".into(),
+            log_dir: "work_dir/log".into(),
+            viz_dir: "work_dir/visualize".into(),
+            filter_params: FilterParams::new(&vec!["Car"], 100.0, 100.0, None, None).unwrap(),
+            metrics_params: MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5)
+                .unwrap()
+                .with_ap_interpolation(ApInterpolation::Point101),
+            load_raw_data: false,
+            audit_log: None,
+            timestamp_correction: None,
+            target_sample_tokens: None,
+            estimation_label_remap: None,
+            export_pr_curves: false,
+            frame_id_policy: FrameIdPolicy::Disabled,
+            latency_compensation: None,
+        };
+
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let ground_truth = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: Some([10.0, 0.0, 0.0]),
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("100".to_string()),
+            class_probabilities: None,
+        };
+        let frame_results = (0..10)
+            .map(|i| {
+                let estimation = DynamicObject {
+                    position: [10.0 + 0.1 * i as f64, 1.0, 0.0],
+                    ..ground_truth.clone()
+                };
+                let ground_truths = vec![ground_truth.clone()];
+                PerceptionFrameResult::new(
+                    get_perception_results(&[estimation], &ground_truths),
+                    FrameGroundTruth {
+                        timestamp,
+                        objects: ground_truths,
+                        ego_translation: None,
+                        ego_rotation: None,
+                        sample_token: None,
+                        version: None,
+                        raw_pointcloud: None,
+                        location: None,
+                    },
+                    &[Label::Car],
+                    MatchingMode::PlaneDistance,
+                    &[1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // Without noise, every trial reproduces the nominal mAP.
+        let jitter = JitterConfig::new(5)
+            .unwrap()
+            .with_matching_mode(MatchingMode::CenterDistance);
+        let summary = evaluate_jitter(&config, &frame_results, &jitter).unwrap();
+        assert_eq!(summary.nominal, 1.0);
+        assert_eq!(summary.mean, 1.0);
+        assert_eq!(summary.std, 0.0);
+        assert_eq!(summary.num_trials, 5);
+
+        let jitter = JitterConfig::new(20)
+            .unwrap()
+            .with_position_sigma(0.5)
+            .unwrap()
+            .with_yaw_sigma(0.1)
+            .unwrap()
+            .with_timestamp_sigma(0.05)
+            .unwrap()
+            .with_seed(42)
+            .with_matching_mode(MatchingMode::CenterDistance);
+        let summary = evaluate_jitter(&config, &frame_results, &jitter).unwrap();
+        assert!(summary.std > 0.0);
+        assert!(summary.mean < summary.nominal);
+        assert!(summary.min <= summary.mean && summary.mean <= summary.max);
+
+        // Same seed reproduces the same statistics.
+        let reproduced = evaluate_jitter(&config, &frame_results, &jitter).unwrap();
+        assert_eq!(reproduced, summary);
+
+        assert!(JitterConfig::new(1).is_err());
+        assert!(JitterConfig::new(2).unwrap().with_yaw_sigma(-1.0).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use crate::{
+    config::{LatencyCompensation, PerceptionEvaluationConfig},
+    matching::MatchingMode,
+    result::frame::PerceptionFrameResult,
+};
+
+use super::{error::MetricsResult, score::MetricsScore};
+
+/// Metrics scores with and without latency compensation, which separate errors caused by
+/// the latency of the perception pipeline from errors of estimations themselves.
+///
+/// * `compensation`    - Latency compensation applied to the compensated score.
+/// * `num_frames`          - Number of frames evaluated with compensation.
+/// * `num_skipped_frames`  - Number of frames excluded from `compensated`, which have no GT at the sensor time.
+/// * `compensated`     - Metrics score with latency compensation.
+/// * `uncompensated`   - Metrics score without latency compensation.
+#[derive(Debug, Clone)]
+pub struct LatencyReport {
+    pub compensation: LatencyCompensation,
+    pub num_frames: usize,
+    pub num_skipped_frames: usize,
+    pub compensated: MetricsScore,
+    pub uncompensated: MetricsScore,
+}
+
+impl LatencyReport {
+    /// Construct `LatencyReport` from frame results evaluated with and without compensation.
+    ///
+    /// * `config`          - Evaluation configuration.
+    /// * `compensation`    - Latency compensation applied to `compensated`.
+    /// * `compensated`     - List of PerceptionFrameResult with latency compensation.
+    /// * `uncompensated`   - List of PerceptionFrameResult without latency compensation.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{
+    ///     config::{LatencyCompensation, LatencyCompensationMethod},
+    ///     manager::PerceptionEvaluationManager,
+    ///     matching::MatchingMode,
+    /// };
+    ///
+    /// let quickstart = perception_eval::quickstart();
+    /// let mut config = quickstart.config.clone();
+    /// config.latency_compensation = Some(
+    ///     LatencyCompensation::new(0.3, LatencyCompensationMethod::PropagateGroundTruths).unwrap(),
+    /// );
+    /// let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+    ///     &config,
+    ///     quickstart.frame_ground_truths.clone(),
+    /// );
+    /// for (i, frame) in quickstart.frame_ground_truths.iter().enumerate().skip(3) {
+    ///     // Estimations of 3 frames (0.3 [s]) before are published with the current timestamp.
+    ///     let estimations = quickstart.estimations[i - 3]
+    ///         .iter()
+    ///         .map(|object| {
+    ///             let mut object = object.clone();
+    ///             object.timestamp = frame.timestamp;
+    ///             object
+    ///         })
+    ///         .collect::<Vec<_>>();
+    ///     manager.add_frame_result(&estimations, frame).unwrap();
+    /// }
+    ///
+    /// let report = manager.get_latency_report().unwrap().unwrap();
+    /// assert_eq!(report.num_frames, quickstart.frame_ground_truths.len() - 3);
+    /// assert!(0.0 < report.gain(&MatchingMode::CenterDistance).unwrap());
+    /// ```
+    pub fn new(
+        config: &PerceptionEvaluationConfig,
+        compensation: &LatencyCompensation,
+        compensated: &[&PerceptionFrameResult],
+        uncompensated: &[&PerceptionFrameResult],
+    ) -> MetricsResult<Self> {
+        let evaluate = |frame_results: &[&PerceptionFrameResult]| -> MetricsResult<MetricsScore> {
+            let mut score = MetricsScore::new(&config.metrics_params);
+            score.evaluate_frames(&config.evaluation_task, frame_results.iter().copied())?;
+            Ok(score)
+        };
+        Ok(Self {
+            compensation: compensation.to_owned(),
+            num_frames: compensated.len(),
+            num_skipped_frames: 0,
+            compensated: evaluate(compensated)?,
+            uncompensated: evaluate(uncompensated)?,
+        })
+    }
+
+    /// Set the number of frames excluded from the compensated score.
+    ///
+    /// * `num_skipped_frames`  - Number of frames without GT at the sensor time.
+    pub fn with_num_skipped_frames(mut self, num_skipped_frames: usize) -> Self {
+        self.num_skipped_frames = num_skipped_frames;
+        self
+    }
+
+    /// Returns the improvement of mAP by latency compensation.
+    /// If mAP of the matching mode is not evaluated, returns None.
+    ///
+    /// * `matching_mode`   - MatchingMode instance.
+    pub fn gain(&self, matching_mode: &MatchingMode) -> Option<f64> {
+        Some(self.compensated.mean_ap(matching_mode)? - self.uncompensated.mean_ap(matching_mode)?)
+    }
+}
+
+impl Display for LatencyReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n[Latency Compensation]".to_string();
+        msg += &format!(
+            "\nLatency: {:.3} [s], Method: {:?}, Frames: {}, Skipped: {}",
+            self.compensation.latency,
+            self.compensation.method,
+            self.num_frames,
+            self.num_skipped_frames
+        );
+        msg += &format!(
+            "\n|{0:^16}|{1:^10}|{2:^10}|{3:^10}|",
+            "mAP", "Without", "With", "Gain"
+        );
+        let format_value = |value: Option<f64>| match value {
+            Some(value) => format!("{0:>9.3} ", value),
+            None => format!("{0:>9} ", "-"),
+        };
+        [MatchingMode::CenterDistance, MatchingMode::PlaneDistance]
+            .iter()
+            .for_each(|mode| {
+                msg += &format!(
+                    "\n|{0:>16}|{1}|{2}|{3}|",
+                    format!("{:?}", mode),
+                    format_value(self.uncompensated.mean_ap(mode)),
+                    format_value(self.compensated.mean_ap(mode)),
+                    format_value(self.gain(mode))
+                );
+            });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::{LatencyCompensation, LatencyCompensationMethod},
+        manager::PerceptionEvaluationManager,
+        matching::MatchingMode,
+        object::object3d::DynamicObject,
+        quickstart,
+    };
+
+    #[test]
+    fn test_shift_estimations() {
+        const DELAY_FRAMES: usize = 3;
+        let quickstart = quickstart();
+        let mut config = quickstart.config.clone();
+        config.latency_compensation = Some(
+            LatencyCompensation::new(0.3, LatencyCompensationMethod::ShiftEstimations).unwrap(),
+        );
+        let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+            &config,
+            quickstart.frame_ground_truths.clone(),
+        );
+        let mut expected = quickstart.manager();
+        for (i, frame) in quickstart
+            .frame_ground_truths
+            .iter()
+            .enumerate()
+            .skip(DELAY_FRAMES)
+        {
+            let estimations = &quickstart.estimations[i - DELAY_FRAMES];
+            let delayed = estimations
+                .iter()
+                .map(|object| DynamicObject {
+                    timestamp: frame.timestamp,
+                    ..object.clone()
+                })
+                .collect::<Vec<_>>();
+            manager.add_frame_result(&delayed, frame).unwrap();
+            expected
+                .add_frame_result(
+                    estimations,
+                    &quickstart.frame_ground_truths[i - DELAY_FRAMES],
+                )
+                .unwrap();
+        }
+
+        // Delayed estimations are evaluated against GT frames at the sensor time.
+        let report = manager.get_latency_report().unwrap().unwrap();
+        let expected = expected.get_metrics_score().unwrap();
+        for mode in [MatchingMode::CenterDistance, MatchingMode::PlaneDistance] {
+            assert_eq!(report.compensated.mean_ap(&mode), expected.mean_ap(&mode));
+            assert!(0.0 < report.gain(&mode).unwrap());
+        }
+        assert_eq!(
+            manager.frame_results[0].frame_ground_truth().timestamp,
+            quickstart.frame_ground_truths[0].timestamp
+        );
+        assert!(manager.diagnostics().is_empty());
+
+        // No report without compensation.
+        assert!(quickstart.manager().get_latency_report().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_shift_estimations_without_ground_truth() {
+        const DELAY_FRAMES: usize = 3;
+        let quickstart = quickstart();
+        let mut config = quickstart.config.clone();
+        config.latency_compensation = Some(
+            LatencyCompensation::new(0.3, LatencyCompensationMethod::ShiftEstimations).unwrap(),
+        );
+        let evaluate = |spill: bool| {
+            let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+                &config,
+                quickstart.frame_ground_truths.clone(),
+            );
+            if spill {
+                manager = manager
+                    .with_spill(std::env::temp_dir().join("perception_eval_latency"), 0)
+                    .unwrap();
+            }
+            for (i, frame) in quickstart.frame_ground_truths.iter().enumerate() {
+                let delayed = quickstart.estimations[i.saturating_sub(DELAY_FRAMES)]
+                    .iter()
+                    .map(|object| DynamicObject {
+                        timestamp: frame.timestamp,
+                        ..object.clone()
+                    })
+                    .collect::<Vec<_>>();
+                manager.add_frame_result(&delayed, frame).unwrap();
+            }
+            let num_diagnostics = manager.diagnostics().len();
+            (
+                manager.get_latency_report().unwrap().unwrap(),
+                num_diagnostics,
+            )
+        };
+
+        // First frames have no GT at the sensor time, so they are excluded from the compensated score.
+        let (report, num_diagnostics) = evaluate(false);
+        let num_frames = quickstart.frame_ground_truths.len();
+        assert_eq!(report.num_frames, num_frames - DELAY_FRAMES);
+        assert_eq!(report.num_skipped_frames, DELAY_FRAMES);
+        assert_eq!(num_diagnostics, DELAY_FRAMES);
+
+        // Frame results without compensation are also spilled, which gives the same report.
+        let (spilled, _) = evaluate(true);
+        assert_eq!(spilled.num_frames, report.num_frames);
+        for mode in [MatchingMode::CenterDistance, MatchingMode::PlaneDistance] {
+            assert_eq!(
+                spilled.compensated.mean_ap(&mode),
+                report.compensated.mean_ap(&mode)
+            );
+            assert_eq!(
+                spilled.uncompensated.mean_ap(&mode),
+                report.uncompensated.mean_ap(&mode)
+            );
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use crate::{
+    dataset::maneuver::{Maneuver, ManeuverMap},
+    label::Label,
+    object::object3d::DynamicObject,
+    result::frame::PerceptionFrameResult,
+};
+
+/// Breakdown of detection counts of GTs conditioned on their maneuvers.
+///
+/// GTs without maneuver flags, i.e. without uuid or annotated only once, are not counted.
+///
+/// * `target_labels`   - List of Label instances.
+/// * `num_tp`          - Number of GTs detected as TP for each maneuver and label.
+/// * `num_gt`          - Number of GTs for each maneuver and label.
+#[derive(Debug, Clone)]
+pub struct ManeuverBreakdown {
+    target_labels: Vec<Label>,
+    num_tp: Vec<Vec<usize>>,
+    num_gt: Vec<Vec<usize>>,
+}
+
+impl ManeuverBreakdown {
+    /// Construct `ManeuverBreakdown` from accumulated frame results.
+    ///
+    /// * `frame_results`   - List of PerceptionFrameResult.
+    /// * `maneuvers`       - Maneuver flags of GTs.
+    /// * `target_labels`   - List of Label instances.
+    pub(crate) fn new<'a>(
+        frame_results: impl IntoIterator<Item = &'a PerceptionFrameResult>,
+        maneuvers: &ManeuverMap,
+        target_labels: &[Label],
+    ) -> Self {
+        let num_maneuvers = Maneuver::ALL.len();
+        let mut num_tp = vec![vec![0; target_labels.len()]; num_maneuvers];
+        let mut num_gt = vec![vec![0; target_labels.len()]; num_maneuvers];
+        frame_results.into_iter().for_each(|frame| {
+            let timestamp = frame.frame_ground_truth().timestamp;
+            let mut count = |object: &DynamicObject, is_tp: bool| {
+                let label_index = match target_labels.iter().position(|l| *l == object.label) {
+                    Some(index) => index,
+                    None => return,
+                };
+                let flags = match object
+                    .uuid
+                    .as_ref()
+                    .and_then(|uuid| maneuvers.get(uuid, &timestamp))
+                {
+                    Some(flags) => flags,
+                    None => return,
+                };
+                Maneuver::ALL
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, maneuver)| flags.contains(maneuver))
+                    .for_each(|(i, _)| {
+                        num_gt[i][label_index] += 1;
+                        if is_tp {
+                            num_tp[i][label_index] += 1;
+                        }
+                    });
+            };
+            frame
+                .tp_results()
+                .iter()
+                .filter_map(|result| result.ground_truth_object.as_ref())
+                .for_each(|object| count(object, true));
+            frame
+                .fn_objects()
+                .iter()
+                .for_each(|object| count(object, false));
+        });
+
+        Self {
+            target_labels: target_labels.to_owned(),
+            num_tp,
+            num_gt,
+        }
+    }
+
+    /// Returns the number of GTs with the maneuver for the label.
+    /// If the label is not a target, returns None.
+    ///
+    /// * `maneuver`    - Maneuver instance.
+    /// * `label`       - Label instance.
+    pub fn num_gt(&self, maneuver: &Maneuver, label: &Label) -> Option<usize> {
+        let (i, j) = self.index(maneuver, label)?;
+        Some(self.num_gt[i][j])
+    }
+
+    /// Returns the number of GTs with the maneuver detected as TP for the label.
+    /// If the label is not a target, returns None.
+    ///
+    /// * `maneuver`    - Maneuver instance.
+    /// * `label`       - Label instance.
+    pub fn num_tp(&self, maneuver: &Maneuver, label: &Label) -> Option<usize> {
+        let (i, j) = self.index(maneuver, label)?;
+        Some(self.num_tp[i][j])
+    }
+
+    /// Returns recall of GTs with the maneuver for the label.
+    /// If the label is not a target or there is no such GT, returns None.
+    ///
+    /// * `maneuver`    - Maneuver instance.
+    /// * `label`       - Label instance.
+    pub fn recall(&self, maneuver: &Maneuver, label: &Label) -> Option<f64> {
+        let (i, j) = self.index(maneuver, label)?;
+        if self.num_gt[i][j] == 0 {
+            None
+        } else {
+            Some(self.num_tp[i][j] as f64 / self.num_gt[i][j] as f64)
+        }
+    }
+
+    fn index(&self, maneuver: &Maneuver, label: &Label) -> Option<(usize, usize)> {
+        let i = Maneuver::ALL.iter().position(|m| m == maneuver)?;
+        let j = self.target_labels.iter().position(|l| l == label)?;
+        Some((i, j))
+    }
+}
+
+impl Display for ManeuverBreakdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n[Maneuver]".to_string();
+        msg += &format!("\n|{0:>12}|", "Recall");
+        self.target_labels
+            .iter()
+            .for_each(|label| msg += &format!("{0:^12}|", label.to_string()));
+
+        Maneuver::ALL.iter().for_each(|maneuver| {
+            msg += &format!("\n|{0:>12}|", maneuver.to_string());
+            self.target_labels
+                .iter()
+                .for_each(|label| match self.recall(maneuver, label) {
+                    Some(value) => msg += &format!(" {0:>10.3} |", value),
+                    None => msg += &format!(" {0:>10} |", "-"),
+                });
+        });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ManeuverBreakdown;
+    use crate::{
+        dataset::{
+            maneuver::{Maneuver, ManeuverMap, ManeuverParams},
+            FrameGroundTruth,
+        },
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::{DateTime, Duration};
+
+    #[test]
+    fn test_maneuver_breakdown() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let object = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: Some([10.0, 0.0, 0.0]),
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("cut-in".to_string()),
+            class_probabilities: None,
+        };
+
+        // "cut-in" moves from the next lane into the ego lane, "steady" keeps its lane.
+        let frame_ground_truths = (0..3)
+            .map(|i| {
+                let timestamp = timestamp + Duration::milliseconds(500 * i);
+                let cut_in = DynamicObject {
+                    timestamp,
+                    position: [10.0 + 5.0 * i as f64, 3.0 - 1.5 * i as f64, 0.0],
+                    ..object.clone()
+                };
+                let steady = DynamicObject {
+                    timestamp,
+                    position: [30.0 + 5.0 * i as f64, 0.0, 0.0],
+                    uuid: Some("steady".to_string()),
+                    ..object.clone()
+                };
+                FrameGroundTruth {
+                    timestamp,
+                    objects: vec![cut_in, steady],
+                    ego_translation: None,
+                    ego_rotation: None,
+                    sample_token: None,
+                    version: None,
+                    raw_pointcloud: None,
+                    location: None,
+                }
+            })
+            .collect::<Vec<_>>();
+        let maneuvers = ManeuverMap::new(&frame_ground_truths, &ManeuverParams::default());
+
+        // Only "steady" is detected at every frame.
+        let frame_results = frame_ground_truths
+            .iter()
+            .map(|frame| {
+                let estimations = vec![frame.objects[1].clone()];
+                let results = get_perception_results(&estimations, &frame.objects);
+                PerceptionFrameResult::new(
+                    results,
+                    frame.clone(),
+                    &[Label::Car],
+                    MatchingMode::CenterDistance,
+                    &[1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let breakdown = ManeuverBreakdown::new(&frame_results, &maneuvers, &[Label::Car]);
+        assert_eq!(breakdown.num_gt(&Maneuver::CuttingIn, &Label::Car), Some(1));
+        assert_eq!(
+            breakdown.recall(&Maneuver::CuttingIn, &Label::Car),
+            Some(0.0)
+        );
+        assert_eq!(breakdown.num_gt(&Maneuver::Steady, &Label::Car), Some(5));
+        assert_eq!(breakdown.num_tp(&Maneuver::Steady, &Label::Car), Some(3));
+        assert_eq!(breakdown.recall(&Maneuver::Turning, &Label::Car), None);
+        assert_eq!(
+            breakdown.num_gt(&Maneuver::Steady, &Label::Pedestrian),
+            None
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    fmt::{Display, Formatter, Result as FormatResult},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::PerceptionEvaluationConfig, matching::MatchingMode,
+    result::frame::PerceptionFrameResult,
+};
+
+use super::{error::MetricsResult, score::MetricsScore};
+
+/// Minimum number of frames evaluated for a preview, even if the time budget is exceeded.
+pub const MIN_PREVIEW_FRAMES: usize = 8;
+
+/// Metrics score computed over a sampled subset of accumulated frames within a time budget.
+///
+/// * `score`               - MetricsScore over sampled frames, which contains AP based scores only.
+/// * `num_frames`          - Number of sampled frames.
+/// * `num_total_frames`    - Number of all frames to be evaluated.
+/// * `elapsed`             - Elapsed time to compute the preview.
+#[derive(Debug, Clone)]
+pub struct ScorePreview {
+    pub score: MetricsScore,
+    pub num_frames: usize,
+    pub num_total_frames: usize,
+    pub elapsed: Duration,
+}
+
+impl ScorePreview {
+    /// Compute the preview over frames sampled at a regular stride, which is halved while
+    /// the evaluation of the next denser subset is expected to finish within the time budget.
+    /// Since the cost of each subset is doubled, the expected elapsed time is at most twice
+    /// the time to evaluate the returned subset.
+    ///
+    /// * `config`          - Evaluation configuration.
+    /// * `frame_results`   - List of PerceptionFrameResult to be evaluated.
+    /// * `budget`          - Time budget of the preview.
+    pub fn new(
+        config: &PerceptionEvaluationConfig,
+        frame_results: &[&PerceptionFrameResult],
+        budget: Duration,
+    ) -> MetricsResult<Self> {
+        let start = Instant::now();
+        let num_total_frames = frame_results.len();
+        let mut stride = 1;
+        while MIN_PREVIEW_FRAMES <= num_total_frames.div_ceil(2 * stride) {
+            stride *= 2;
+        }
+        loop {
+            let step_start = Instant::now();
+            let frames = frame_results.iter().step_by(stride).copied();
+            let num_frames = frames.len();
+            let mut score = MetricsScore::new(&config.metrics_params);
+            score.evaluate_frames(&config.evaluation_task, frames)?;
+
+            let expected = start.elapsed() + 2 * step_start.elapsed();
+            if stride == 1 || budget < expected {
+                return Ok(Self {
+                    score,
+                    num_frames,
+                    num_total_frames,
+                    elapsed: start.elapsed(),
+                });
+            }
+            stride /= 2;
+        }
+    }
+
+    /// Returns the fraction of sampled frames in [0, 1].
+    /// If there is no frame to be evaluated, returns 1.
+    pub fn coverage(&self) -> f64 {
+        if self.num_total_frames == 0 {
+            1.0
+        } else {
+            self.num_frames as f64 / self.num_total_frames as f64
+        }
+    }
+
+    /// Returns whether all frames are evaluated, where the score equals to the full score.
+    pub fn is_complete(&self) -> bool {
+        self.num_frames == self.num_total_frames
+    }
+}
+
+impl Display for ScorePreview {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n[Preview]".to_string();
+        msg += &format!(
+            "\nFrames: {} / {} ({:.1}%), Elapsed: {:.3}[s]",
+            self.num_frames,
+            self.num_total_frames,
+            100.0 * self.coverage(),
+            self.elapsed.as_secs_f64()
+        );
+        [MatchingMode::CenterDistance, MatchingMode::PlaneDistance]
+            .iter()
+            .for_each(|mode| match self.score.mean_ap(mode) {
+                Some(value) => msg += &format!("\nmAP {:?}: {:.3}", mode, value),
+                None => msg += &format!("\nmAP {:?}: -", mode),
+            });
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScorePreview, MIN_PREVIEW_FRAMES};
+    use crate::{
+        matching::MatchingMode,
+        quickstart::quickstart,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_score_preview() {
+        let quickstart = quickstart();
+        let config = &quickstart.config;
+        let frame_results = quickstart
+            .frame_ground_truths
+            .iter()
+            .zip(&quickstart.estimations)
+            .map(|(frame, estimations)| {
+                PerceptionFrameResult::new(
+                    get_perception_results(estimations, &frame.objects),
+                    frame.clone(),
+                    &config.filter_params.target_labels,
+                    MatchingMode::PlaneDistance,
+                    &config.metrics_params.plane_distance_thresholds,
+                    &config.metrics_params.plane_distance_config,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let frame_results = frame_results.iter().cycle().take(40).collect::<Vec<_>>();
+
+        // Only the sparsest subset is evaluated without any budget.
+        let preview = ScorePreview::new(config, &frame_results, Duration::ZERO).unwrap();
+        assert_eq!(preview.num_total_frames, 40);
+        assert_eq!(preview.num_frames, 10);
+        assert!(MIN_PREVIEW_FRAMES <= preview.num_frames);
+        assert!(!preview.is_complete());
+        assert_eq!(preview.coverage(), 0.25);
+
+        // All frames are evaluated with enough budget.
+        let preview = ScorePreview::new(config, &frame_results, Duration::from_secs(3600)).unwrap();
+        assert_eq!(preview.num_frames, 40);
+        assert!(preview.is_complete());
+        assert_eq!(preview.coverage(), 1.0);
+
+        let empty = ScorePreview::new(config, &[], Duration::ZERO).unwrap();
+        assert_eq!(empty.num_frames, 0);
+        assert_eq!(empty.coverage(), 1.0);
+        assert!(empty.is_complete());
+    }
+}
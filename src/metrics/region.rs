@@ -0,0 +1,329 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use geo::{Contains, Coord, LineString, Point, Polygon};
+
+use crate::{
+    config::PerceptionEvaluationConfig, matching::MatchingMode,
+    result::frame::PerceptionFrameResult,
+};
+
+use super::{error::MetricsResult, score::MetricsScore};
+
+/// Name of the region for frames without location or outside of all geofences.
+pub const UNKNOWN_REGION: &str = "unknown";
+
+/// Named polygon region in the global coord system.
+///
+/// * `name`    - Name of the region.
+/// * `polygon` - Vertices of the polygon in BEV, (x, y) order.
+#[derive(Debug, Clone)]
+pub struct Geofence {
+    pub name: String,
+    polygon: Polygon<f64>,
+}
+
+impl Geofence {
+    /// Construct `Geofence`.
+    ///
+    /// * `name`        - Name of the region.
+    /// * `vertices`    - Vertices of the polygon in BEV, (x, y) order.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::metrics::region::Geofence;
+    ///
+    /// let fence = Geofence::new("crossing", &[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]);
+    ///
+    /// assert!(fence.contains(&[5.0, 5.0, 0.0]));
+    /// assert!(!fence.contains(&[15.0, 5.0, 0.0]));
+    /// ```
+    pub fn new(name: &str, vertices: &[[f64; 2]]) -> Self {
+        let exterior = vertices
+            .iter()
+            .map(|[x, y]| Coord { x: *x, y: *y })
+            .collect::<LineString<f64>>();
+        Self {
+            name: name.to_owned(),
+            polygon: Polygon::new(exterior, vec![]),
+        }
+    }
+
+    /// Returns whether the position is inside the region in BEV.
+    ///
+    /// * `position`    - Position in the global coord system.
+    pub fn contains(&self, position: &[f64; 3]) -> bool {
+        self.polygon.contains(&Point::new(position[0], position[1]))
+    }
+}
+
+/// Represents how to group frames into regions.
+#[derive(Debug, Clone)]
+pub enum RegionGrouping {
+    /// Group by the location of the log, such as `singapore-onenorth`.
+    Location,
+    /// Group by geofences containing the ego position. Geofences may overlap,
+    /// and a frame is counted for every geofence containing it.
+    Geofences(Vec<Geofence>),
+}
+
+impl RegionGrouping {
+    /// Returns names of regions which the frame belongs to.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult at one frame.
+    fn regions(&self, frame_result: &PerceptionFrameResult) -> Vec<String> {
+        let frame = frame_result.frame_ground_truth();
+        let regions = match self {
+            Self::Location => frame.location.iter().cloned().collect::<Vec<_>>(),
+            Self::Geofences(fences) => match &frame.ego_translation {
+                Some(translation) => fences
+                    .iter()
+                    .filter(|fence| fence.contains(translation))
+                    .map(|fence| fence.name.to_owned())
+                    .collect(),
+                None => Vec::new(),
+            },
+        };
+        if regions.is_empty() {
+            vec![UNKNOWN_REGION.to_string()]
+        } else {
+            regions
+        }
+    }
+}
+
+/// Metrics score aggregated for each region, which highlights geographic weak spots.
+///
+/// Regions are ordered by name for `RegionGrouping::Location`, and by the order of geofences
+/// for `RegionGrouping::Geofences`. Regions without any frame are omitted, and `UNKNOWN_REGION` comes last.
+///
+/// * `regions`     - Name of the region, the number of frames and the score for each region.
+#[derive(Debug, Clone)]
+pub struct RegionBreakdown {
+    regions: Vec<(String, usize, MetricsScore)>,
+}
+
+impl RegionBreakdown {
+    /// Construct `RegionBreakdown` from accumulated frame results.
+    ///
+    /// * `config`          - Evaluation configuration.
+    /// * `frame_results`   - List of PerceptionFrameResult.
+    /// * `grouping`        - How to group frames into regions.
+    pub fn new(
+        config: &PerceptionEvaluationConfig,
+        frame_results: &[PerceptionFrameResult],
+        grouping: &RegionGrouping,
+    ) -> MetricsResult<Self> {
+        let mut names = match grouping {
+            RegionGrouping::Location => Vec::new(),
+            RegionGrouping::Geofences(fences) => {
+                fences.iter().map(|fence| fence.name.to_owned()).collect()
+            }
+        };
+        let mut members: Vec<Vec<&PerceptionFrameResult>> = vec![Vec::new(); names.len()];
+        frame_results.iter().for_each(|frame_result| {
+            grouping
+                .regions(frame_result)
+                .into_iter()
+                .for_each(|region| {
+                    let index = match names.iter().position(|name| *name == region) {
+                        Some(index) => index,
+                        None => {
+                            names.push(region);
+                            members.push(Vec::new());
+                            names.len() - 1
+                        }
+                    };
+                    members[index].push(frame_result);
+                });
+        });
+
+        let mut regions = names
+            .into_iter()
+            .zip(members)
+            .filter(|(_, frames)| !frames.is_empty())
+            .map(|(name, frames)| {
+                let mut score = MetricsScore::new(&config.metrics_params);
+                score.evaluate_frames(&config.evaluation_task, frames.iter().copied())?;
+                Ok((name, frames.len(), score))
+            })
+            .collect::<MetricsResult<Vec<_>>>()?;
+        if let RegionGrouping::Location = grouping {
+            regions.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+        }
+        regions.sort_by_key(|(name, _, _)| name == UNKNOWN_REGION);
+        Ok(Self { regions })
+    }
+
+    /// Returns names of regions.
+    pub fn regions(&self) -> Vec<&str> {
+        self.regions
+            .iter()
+            .map(|(name, _, _)| name as &str)
+            .collect()
+    }
+
+    /// Returns the number of frames in the region.
+    /// If the region has no frame, returns None.
+    ///
+    /// * `region`  - Name of the region.
+    pub fn num_frames(&self, region: &str) -> Option<usize> {
+        self.get(region).map(|(_, num_frames, _)| *num_frames)
+    }
+
+    /// Returns the metrics score of the region.
+    /// If the region has no frame, returns None.
+    ///
+    /// * `region`  - Name of the region.
+    pub fn score(&self, region: &str) -> Option<&MetricsScore> {
+        self.get(region).map(|(_, _, score)| score)
+    }
+
+    fn get(&self, region: &str) -> Option<&(String, usize, MetricsScore)> {
+        self.regions.iter().find(|(name, _, _)| name == region)
+    }
+}
+
+impl Display for RegionBreakdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let modes = [MatchingMode::CenterDistance, MatchingMode::PlaneDistance];
+        let mut msg = "\n[Region]".to_string();
+        msg += &format!("\n|{0:>24}|{1:^8}|", "Region", "Frames");
+        modes
+            .iter()
+            .for_each(|mode| msg += &format!("{0:^16}|", format!("mAP {:?}", mode)));
+
+        self.regions.iter().for_each(|(name, num_frames, score)| {
+            msg += &format!("\n|{0:>24}|{1:>7} |", name, num_frames);
+            modes.iter().for_each(|mode| match score.mean_ap(mode) {
+                Some(value) => msg += &format!(" {0:>14.3} |", value),
+                None => msg += &format!(" {0:>14} |", "-"),
+            });
+        });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Geofence, RegionBreakdown, RegionGrouping, UNKNOWN_REGION};
+    use crate::{
+        config::{
+            ApInterpolation, FilterParams, FrameIdPolicy, MetricsParams, PerceptionEvaluationConfig,
+        },
+        dataset::FrameGroundTruth,
+        evaluation_task::EvaluationTask,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_region_breakdown() {
+        let config = PerceptionEvaluationConfig {
+            versions: vec!["annotation".to_string()],
+            dataset_path: "tests/sample_data".into(),
+            evaluation_task: EvaluationTask::Detection,
+            frame_id: FrameID::BaseLink,
+            result_dir: "work_dir".into(),
+            log_dir: "work_dir/log".into(),
+            viz_dir: "work_dir/visualize".into(),
+            filter_params: FilterParams::new(&vec!["Car"], 100.0, 100.0, None, None).unwrap(),
+            metrics_params: MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5)
+                .unwrap()
+                .with_ap_interpolation(ApInterpolation::Point11),
+            load_raw_data: false,
+            audit_log: None,
+            timestamp_correction: None,
+            target_sample_tokens: None,
+            estimation_label_remap: None,
+            export_pr_curves: false,
+            frame_id_policy: FrameIdPolicy::Disabled,
+            latency_compensation: None,
+        };
+
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let ground_truth = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [4.0, 2.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("100".to_string()),
+            class_probabilities: None,
+        };
+        // The car is mislocated in "boston", while detected in "singapore".
+        let frames = [
+            (Some("singapore"), Some([5.0, 5.0, 0.0]), true),
+            (Some("boston"), Some([50.0, 5.0, 0.0]), false),
+            (None, None, true),
+        ];
+        let frame_results = frames
+            .iter()
+            .map(|(location, ego_translation, detected)| {
+                let offset = if *detected { 0.0 } else { 5.0 };
+                let estimations = vec![DynamicObject {
+                    position: [10.0 + offset, 1.0, 0.0],
+                    ..ground_truth.clone()
+                }];
+                let ground_truths = vec![ground_truth.clone()];
+                PerceptionFrameResult::new(
+                    get_perception_results(&estimations, &ground_truths),
+                    FrameGroundTruth {
+                        timestamp,
+                        objects: ground_truths,
+                        ego_translation: *ego_translation,
+                        ego_rotation: None,
+                        sample_token: None,
+                        version: None,
+                        raw_pointcloud: None,
+                        location: location.map(|location| location.to_string()),
+                    },
+                    &[Label::Car],
+                    MatchingMode::CenterDistance,
+                    &[1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let breakdown =
+            RegionBreakdown::new(&config, &frame_results, &RegionGrouping::Location).unwrap();
+        assert_eq!(
+            breakdown.regions(),
+            vec!["boston", "singapore", UNKNOWN_REGION]
+        );
+        assert_eq!(breakdown.num_frames("singapore"), Some(1));
+        let ap = |region| {
+            breakdown
+                .score(region)
+                .unwrap()
+                .mean_ap(&MatchingMode::CenterDistance)
+        };
+        assert_eq!(ap("boston"), Some(0.0));
+        assert!(ap("singapore").unwrap() > ap("boston").unwrap());
+        assert!(breakdown.to_string().contains("singapore"));
+
+        let fences = vec![
+            Geofence::new(
+                "west",
+                &[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]],
+            ),
+            Geofence::new("nowhere", &[[100.0, 0.0], [110.0, 0.0], [110.0, 10.0]]),
+        ];
+        let breakdown =
+            RegionBreakdown::new(&config, &frame_results, &RegionGrouping::Geofences(fences))
+                .unwrap();
+        assert_eq!(breakdown.regions(), vec!["west", UNKNOWN_REGION]);
+        assert_eq!(breakdown.num_frames(UNKNOWN_REGION), Some(2));
+        assert_eq!(breakdown.num_frames("nowhere"), None);
+    }
+}
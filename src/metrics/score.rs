@@ -1,27 +1,80 @@
 use std::{
+    borrow::Cow,
     collections::HashMap,
     fmt::{Display, Formatter, Result as FormatResult},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
 };
 
 use crate::{
-    config::MetricsParams, label::Label, matching::MatchingMode, result::object::PerceptionResult,
+    config::MetricsParams,
+    dataset::{
+        maneuver::ManeuverMap,
+        quality::{DataQualityReport, QualityParams},
+        timeline::SceneTimeline,
+    },
+    evaluation_task::EvaluationTask,
+    fault::create_file,
+    filter::{hash_num_objects, hash_results_ref},
+    label::Label,
+    matching::MatchingMode,
+    result::{frame::PerceptionFrameResult, object::PerceptionResult},
 };
 
-use super::detection::DetectionMetricsScore;
+use super::{
+    alignment::AlignmentReport,
+    calibration::ConfidenceCalibration,
+    classification::SoftClassificationScore,
+    detection::{DecisionRecords, DetectionMetricsScore},
+    error::{MetricsError, MetricsResult},
+    maneuver::ManeuverBreakdown,
+    summary::{ErrorRateSummary, OrientationSummary},
+};
 
 #[derive(Debug, Clone)]
 pub struct MetricsScore {
     params: MetricsParams,
     scores: Vec<DetectionMetricsScore>,
+    summary: Option<ErrorRateSummary>,
+    soft_classification: Option<SoftClassificationScore>,
+    data_quality: Option<DataQualityReport>,
+    maneuver: Option<ManeuverBreakdown>,
+    alignment: Option<AlignmentReport>,
+    orientation: Option<OrientationSummary>,
+}
+
+/// Level of detail of formatted `MetricsScore`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Verbosity {
+    /// One-line summary of mean scores for each matching mode.
+    Compact,
+    /// Score tables for each matching mode, error rate summary and data quality.
+    #[default]
+    Standard,
+    /// Standard output with breakdown of the number of FP and FN for each label and found data quality issues.
+    Verbose,
+}
+
+/// Options to format `MetricsScore`.
+///
+/// * `verbosity`   - Level of detail.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormatOptions {
+    pub verbosity: Verbosity,
+}
+
+impl FormatOptions {
+    /// Construct `FormatOptions`.
+    ///
+    /// * `verbosity`   - Level of detail.
+    pub fn new(verbosity: Verbosity) -> Self {
+        Self { verbosity }
+    }
 }
 
 impl Display for MetricsScore {
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
-        let mut msg = "\n".to_string();
-        self.scores
-            .iter()
-            .for_each(|score| msg += &format!("{}", score));
-        write!(f, "{}", msg)
+        write!(f, "{}", self.format_with(&FormatOptions::default()))
     }
 }
 
@@ -31,33 +84,407 @@ impl MetricsScore {
         Self {
             params: params.to_owned(),
             scores,
+            summary: None,
+            soft_classification: None,
+            data_quality: None,
+            maneuver: None,
+            alignment: None,
+            orientation: None,
         }
     }
 
-    pub(crate) fn evaluate_detection(
+    /// Returns formatted scores with the level of detail specified by options.
+    /// `Display` is same as formatting with default options.
+    ///
+    /// * `options` - Options to format.
+    pub fn format_with(&self, options: &FormatOptions) -> String {
+        if options.verbosity == Verbosity::Compact {
+            return self
+                .scores
+                .iter()
+                .map(|score| score.format_compact())
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
+        let mut msg = "\n".to_string();
+        self.scores
+            .iter()
+            .for_each(|score| msg += &format!("{}", score));
+        // Data quality comes before the summary, so that verbose output extends standard output.
+        if let Some(data_quality) = &self.data_quality {
+            msg += &format!("{}", data_quality);
+        }
+        if let Some(summary) = &self.summary {
+            msg += &format!("{}", summary);
+            if options.verbosity == Verbosity::Verbose {
+                msg += &summary.format_counts();
+            }
+        }
+        if let Some(soft_classification) = &self.soft_classification {
+            msg += &format!("{}", soft_classification);
+        }
+        if let Some(maneuver) = &self.maneuver {
+            msg += &format!("{}", maneuver);
+        }
+        if let Some(alignment) = &self.alignment {
+            msg += &format!("{}", alignment);
+        }
+        if let Some(orientation) = &self.orientation {
+            msg += &format!("{}", orientation);
+        }
+        match &self.data_quality {
+            Some(data_quality)
+                if options.verbosity == Verbosity::Verbose && !data_quality.issues.is_empty() =>
+            {
+                msg += &data_quality.format_issues()
+            }
+            _ => {}
+        }
+        msg
+    }
+
+    /// Returns summary of FP and FN rates normalized by driven distance and time.
+    pub fn summary(&self) -> Option<&ErrorRateSummary> {
+        self.summary.as_ref()
+    }
+
+    /// Returns soft classification score over pairs whose estimation has class probabilities.
+    /// If there is no such pair, returns None.
+    pub fn soft_classification(&self) -> Option<&SoftClassificationScore> {
+        self.soft_classification.as_ref()
+    }
+
+    /// Returns report of suspicious GT annotations in evaluated frames.
+    pub fn data_quality(&self) -> Option<&DataQualityReport> {
+        self.data_quality.as_ref()
+    }
+
+    /// Returns breakdown of recall conditioned on maneuvers of GTs.
+    /// If the maneuver breakdown is not enabled in `MetricsParams`, returns None.
+    pub fn maneuver(&self) -> Option<&ManeuverBreakdown> {
+        self.maneuver.as_ref()
+    }
+
+    /// Returns distributions of the best-available matching score of each GT.
+    /// If the alignment analysis is not enabled in `MetricsParams`, returns None.
+    pub fn alignment(&self) -> Option<&AlignmentReport> {
+        self.alignment.as_ref()
+    }
+
+    /// Returns counts of estimations whose orientation was normalized or rejected at ingestion.
+    /// If the score is not computed by `PerceptionEvaluationManager`, returns None.
+    pub fn orientation(&self) -> Option<&OrientationSummary> {
+        self.orientation.as_ref()
+    }
+
+    /// Save raw data of precision-recall curves backing AP into a CSV file for each label,
+    /// and returns paths of saved files.
+    ///
+    /// Each row is a point in descending order of confidence as used for AP, with columns
+    /// `matching_mode,confidence,precision,recall,threshold`.
+    ///
+    /// * `dir` - Directory to save CSV files.
+    pub fn save_pr_curves<P>(&self, dir: P) -> MetricsResult<Vec<PathBuf>>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let mut paths = Vec::new();
+        for (i, label) in self.params.target_labels.iter().enumerate() {
+            let path = dir.as_ref().join(format!("{}.csv", label));
+            let mut writer = BufWriter::new(create_file(&path)?);
+            writeln!(
+                writer,
+                "matching_mode,confidence,precision,recall,threshold"
+            )?;
+            for score in &self.scores {
+                let matching_mode = match score.matching_mode {
+                    MatchingMode::Velocity { .. } => "Velocity".to_string(),
+                    ref mode => format!("{:?}", mode),
+                };
+                let threshold = score.thresholds[i];
+                for point in &score.pr_curves[i] {
+                    writeln!(
+                        writer,
+                        "{},{},{},{},{}",
+                        matching_mode, point.confidence, point.precision, point.recall, threshold
+                    )?;
+                }
+            }
+            writer.flush()?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Returns raw TP/FP decisions of all accumulated results for the label and the matching mode,
+    /// in the same order as used for AP.
+    /// If the label is not a target or the matching mode has not been evaluated, returns None.
+    ///
+    /// * `label`           - Label instance.
+    /// * `matching_mode`   - MatchingMode instance.
+    pub fn decisions(
+        &self,
+        label: &Label,
+        matching_mode: &MatchingMode,
+    ) -> Option<&DecisionRecords> {
+        let index = self.params.target_labels.iter().position(|l| l == label)?;
+        self.scores
+            .iter()
+            .find(|score| score.matching_mode == *matching_mode)
+            .and_then(|score| score.decisions.get(index))
+    }
+
+    /// Save raw TP/FP decisions into a CSV file for each label, and returns paths of saved files.
+    ///
+    /// Each row is a result in the same order as used for AP, with columns
+    /// `matching_mode,confidence,is_tp,matching_score,gt_uuid`.
+    /// `matching_score` and `gt_uuid` are empty for the result without GT,
+    /// and `gt_uuid` is quoted if it contains commas, quotes or line breaks.
+    ///
+    /// * `dir` - Directory to save CSV files.
+    pub fn save_decisions<P>(&self, dir: P) -> MetricsResult<Vec<PathBuf>>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let mut paths = Vec::new();
+        for (i, label) in self.params.target_labels.iter().enumerate() {
+            let path = dir.as_ref().join(format!("{}.csv", label));
+            let mut writer = BufWriter::new(create_file(&path)?);
+            writeln!(
+                writer,
+                "matching_mode,confidence,is_tp,matching_score,gt_uuid"
+            )?;
+            for score in &self.scores {
+                let matching_mode = match score.matching_mode {
+                    MatchingMode::Velocity { .. } => "Velocity".to_string(),
+                    ref mode => format!("{:?}", mode),
+                };
+                let decisions = &score.decisions[i];
+                for j in 0..decisions.len() {
+                    writeln!(
+                        writer,
+                        "{},{},{},{},{}",
+                        matching_mode,
+                        decisions.confidence[j],
+                        decisions.is_tp[j],
+                        decisions.matching_score[j]
+                            .map(|value| value.to_string())
+                            .unwrap_or_default(),
+                        quote_csv_field(decisions.gt_uuid[j].as_deref().unwrap_or_default())
+                    )?;
+                }
+            }
+            writer.flush()?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Returns the minimum confidence threshold of each label achieving the target precision,
+    /// and the recall achievable at that operating point, over all accumulated results.
+    ///
+    /// * `matching_mode`       - MatchingMode used to determine TP and FP.
+    /// * `target_precision`    - Target precision in (0, 1], e.g. 0.95.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{label::Label, matching::MatchingMode};
+    ///
+    /// let quickstart = perception_eval::quickstart();
+    ///
+    /// let mut manager = quickstart.manager();
+    /// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+    ///     manager.add_frame_result(estimations, frame).unwrap();
+    /// }
+    /// let score = manager.get_metrics_score().unwrap();
+    ///
+    /// let calibration = score.calibrate_confidence(&MatchingMode::CenterDistance, 0.95).unwrap();
+    /// if let Some(point) = calibration.operating_point(&Label::Car) {
+    ///     assert!(point.precision >= 0.95);
+    /// }
+    /// assert!(score.calibrate_confidence(&MatchingMode::CenterDistance, 1.5).is_err());
+    /// ```
+    pub fn calibrate_confidence(
+        &self,
+        matching_mode: &MatchingMode,
+        target_precision: f64,
+    ) -> MetricsResult<ConfidenceCalibration> {
+        if !(0.0 < target_precision && target_precision <= 1.0) {
+            return Err(MetricsError::InvalidParameter(format!(
+                "target precision must be in (0, 1]: {}",
+                target_precision
+            )));
+        }
+        let score = self
+            .scores
+            .iter()
+            .find(|score| score.matching_mode == *matching_mode)
+            .ok_or(MetricsError::InvalidParameter(format!(
+                "matching mode has not been evaluated: {:?}",
+                matching_mode
+            )))?;
+        Ok(ConfidenceCalibration::new(
+            matching_mode,
+            target_precision,
+            &self.params.target_labels,
+            &score.decisions,
+            &score.num_gt,
+        ))
+    }
+
+    /// Returns matching modes evaluated for AP, in the order of evaluation.
+    pub fn matching_modes(&self) -> Vec<MatchingMode> {
+        self.scores
+            .iter()
+            .map(|score| score.matching_mode.to_owned())
+            .collect()
+    }
+
+    /// Returns mean AP over target labels calculated with the matching mode.
+    /// Labels whose AP is NaN, which have neither results nor GTs, are ignored.
+    /// If the matching mode has not been evaluated or there is no valid AP, returns None.
+    ///
+    /// * `matching_mode`   - MatchingMode instance.
+    pub fn mean_ap(&self, matching_mode: &MatchingMode) -> Option<f64> {
+        let score = self
+            .scores
+            .iter()
+            .find(|score| score.matching_mode == *matching_mode)?;
+        let ap_list = score
+            .scores
+            .get("AP")?
+            .iter()
+            .filter(|ap| !ap.is_nan())
+            .collect::<Vec<_>>();
+        if ap_list.is_empty() {
+            None
+        } else {
+            Some(ap_list.iter().copied().sum::<f64>() / ap_list.len() as f64)
+        }
+    }
+
+    /// Accumulate results of frames and evaluate them with respect to the task.
+    ///
+    /// * `evaluation_task` - Task to evaluate.
+    /// * `frame_results`   - Frame results to be accumulated.
+    pub(crate) fn evaluate_frames<'a, I>(
         &mut self,
-        results_map: &HashMap<Label, Vec<PerceptionResult>>,
-        num_gt_map: &HashMap<Label, usize>,
-    ) {
-        let center_distance_scores_map = DetectionMetricsScore::new(
-            results_map,
-            num_gt_map,
+        evaluation_task: &EvaluationTask,
+        frame_results: I,
+    ) -> MetricsResult<()>
+    where
+        I: IntoIterator<Item = &'a PerceptionFrameResult>,
+    {
+        let target_labels = &self.params.target_labels;
+        let mut scene_results: HashMap<Label, Vec<&PerceptionResult>> = HashMap::new();
+        let mut num_scene_gt = HashMap::new();
+
+        target_labels.iter().for_each(|label| {
+            scene_results.insert(label.to_owned(), Vec::new());
+            num_scene_gt.insert(label.to_owned(), 0);
+        });
+
+        frame_results.into_iter().for_each(|frame| {
+            let mut result_map = hash_results_ref(frame.results(), target_labels);
+            let num_gt_map = hash_num_objects(&frame.frame_ground_truth().objects, target_labels);
+            target_labels.iter().for_each(|label| {
+                if let Some(results) = scene_results.get_mut(label) {
+                    if let Some(result) = result_map.get_mut(label) {
+                        results.append(result)
+                    }
+                };
+                if let Some(num_gts) = num_scene_gt.get_mut(label) {
+                    if let Some(num_gt) = num_gt_map.get(label) {
+                        *num_gts += num_gt
+                    }
+                };
+            });
+        });
+
+        let soft_classification = SoftClassificationScore::new(
+            scene_results.values().flatten().copied(),
             &self.params.target_labels,
-            &MatchingMode::CenterDistance,
-            &self.params.center_distance_thresholds,
         );
+        self.soft_classification =
+            (0 < soft_classification.num_pairs()).then_some(soft_classification);
 
-        self.scores.push(center_distance_scores_map);
+        match evaluation_task {
+            EvaluationTask::Detection => self.evaluate_detection(&scene_results, &num_scene_gt),
+            _ => Err(MetricsError::NotImplementedError(evaluation_task.clone())),
+        }
+    }
 
-        let plane_distance_scores_map = DetectionMetricsScore::new(
-            results_map,
-            num_gt_map,
+    pub(crate) fn evaluate_error_rates(&mut self, frame_results: &[PerceptionFrameResult]) {
+        self.summary = Some(ErrorRateSummary::new(
+            frame_results,
             &self.params.target_labels,
-            &MatchingMode::PlaneDistance,
-            &self.params.plane_distance_thresholds,
+        ));
+    }
+
+    pub(crate) fn evaluate_data_quality(
+        &mut self,
+        frame_results: &[PerceptionFrameResult],
+        params: &QualityParams,
+        timeline: &SceneTimeline,
+    ) {
+        let frame_ground_truths = frame_results
+            .iter()
+            .map(|frame| frame.frame_ground_truth())
+            .collect();
+        self.data_quality = Some(
+            DataQualityReport::from_frames(frame_ground_truths, params).with_timeline(timeline),
         );
+    }
+
+    pub(crate) fn evaluate_maneuvers<'a>(
+        &mut self,
+        frame_results: impl IntoIterator<Item = &'a PerceptionFrameResult>,
+        maneuvers: &ManeuverMap,
+    ) {
+        self.maneuver = Some(ManeuverBreakdown::new(
+            frame_results,
+            maneuvers,
+            &self.params.target_labels,
+        ));
+    }
+
+    pub(crate) fn set_orientation_summary(&mut self, orientation: &OrientationSummary) {
+        self.orientation = Some(orientation.to_owned());
+    }
+
+    pub(crate) fn evaluate_alignment<'a>(
+        &mut self,
+        frame_results: impl IntoIterator<Item = &'a PerceptionFrameResult>,
+    ) {
+        self.alignment = Some(AlignmentReport::new(
+            frame_results,
+            &self.params.target_labels,
+            &self.params.plane_distance_config,
+        ));
+    }
+
+    pub(crate) fn evaluate_detection(
+        &mut self,
+        results_map: &HashMap<Label, Vec<&PerceptionResult>>,
+        num_gt_map: &HashMap<Label, usize>,
+    ) -> MetricsResult<()> {
+        for (matching_mode, thresholds) in self.params.evaluated_matchings() {
+            let scores_map = DetectionMetricsScore::new(
+                results_map,
+                num_gt_map,
+                &self.params.target_labels,
+                &matching_mode,
+                thresholds,
+                &self.params.plane_distance_config,
+                &self.params.ap_interpolation,
+                self.params.deterministic_threads,
+            )?;
 
-        self.scores.push(plane_distance_scores_map);
+            self.scores.push(scores_map);
+        }
 
         // let iou2d_scores_map = DetectionMetricsScore::new(
         //     results_map,
@@ -65,6 +492,8 @@ impl MetricsScore {
         //     &self.params.target_labels,
         //     &MatchingMode::Iou2d,
         //     &self.params.iou2d_thresholds,
+        //     &self.params.plane_distance_config,
+        //     &self.params.ap_interpolation,
         // );
 
         // self.scores.push(iou2d_scores_map);
@@ -75,8 +504,351 @@ impl MetricsScore {
         //     &self.params.target_labels,
         //     &MatchingMode::Iou3d,
         //     &self.params.iou3d_thresholds,
+        //     &self.params.plane_distance_config,
+        //     &self.params.ap_interpolation,
         // );
 
         // self.scores.push(iou3d_scores_map);
+        Ok(())
+    }
+}
+
+/// Returns the field quoted as RFC 4180 if it contains commas, quotes or line breaks.
+///
+/// * `field`   - Value of the CSV field.
+fn quote_csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatOptions, MetricsScore, Verbosity};
+    use crate::{
+        config::MetricsParams,
+        dataset::FrameGroundTruth,
+        dataset::{quality::QualityParams, timeline::SceneTimeline},
+        evaluation_task::EvaluationTask,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_format_with() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        let frame_results = vec![PerceptionFrameResult::new(
+            get_perception_results(&[&object], &[&object]),
+            FrameGroundTruth {
+                timestamp: object.timestamp,
+                objects: vec![object],
+                ego_translation: None,
+                ego_rotation: None,
+                sample_token: None,
+                version: None,
+                raw_pointcloud: None,
+                location: None,
+            },
+            &[Label::Car],
+            MatchingMode::PlaneDistance,
+            &[1.0],
+            &PlaneDistanceConfig::default(),
+        )
+        .unwrap()];
+
+        let params = MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5).unwrap();
+        let mut score = MetricsScore::new(&params);
+        score
+            .evaluate_frames(&EvaluationTask::Detection, &frame_results)
+            .unwrap();
+        score.evaluate_error_rates(&frame_results);
+        score.evaluate_data_quality(
+            &frame_results,
+            &QualityParams::default(),
+            &SceneTimeline::default(),
+        );
+
+        let compact = score.format_with(&FormatOptions::new(Verbosity::Compact));
+        assert!(!compact.contains('\n'));
+        assert!(compact.starts_with("[CenterDistance] mAP:"));
+
+        let standard = score.format_with(&FormatOptions::default());
+        assert_eq!(standard, score.to_string());
+        assert!(standard.contains("[Summary]"));
+        assert!(!standard.contains("[Breakdown]"));
+        assert!(standard.contains("[Data Quality]"));
+        assert_eq!(score.data_quality().unwrap().num_frames, 1);
+
+        let verbose = score.format_with(&FormatOptions::new(Verbosity::Verbose));
+        assert!(verbose.starts_with(&standard));
+        assert!(verbose.contains("[Breakdown]"));
+    }
+
+    #[test]
+    fn test_save_pr_curves() {
+        let ground_truth = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        // Results are given in ascending order of confidence.
+        let fp_estimation = DynamicObject {
+            position: [30.0, 1.0, 0.0],
+            confidence: 0.2,
+            ..ground_truth.clone()
+        };
+        let tp_estimation = DynamicObject {
+            confidence: 0.8,
+            ..ground_truth.clone()
+        };
+        let frame_results = vec![PerceptionFrameResult::new(
+            get_perception_results(&[&fp_estimation, &tp_estimation], &[&ground_truth]),
+            FrameGroundTruth {
+                timestamp: ground_truth.timestamp,
+                objects: vec![ground_truth.clone()],
+                ego_translation: None,
+                ego_rotation: None,
+                sample_token: None,
+                version: None,
+                raw_pointcloud: None,
+                location: None,
+            },
+            &[Label::Car],
+            MatchingMode::PlaneDistance,
+            &[1.0],
+            &PlaneDistanceConfig::default(),
+        )
+        .unwrap()];
+
+        let params = MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5).unwrap();
+        let mut score = MetricsScore::new(&params);
+        score
+            .evaluate_frames(&EvaluationTask::Detection, &frame_results)
+            .unwrap();
+
+        let dir = std::env::temp_dir().join("perception_eval_test_save_pr_curves");
+        let paths = score.save_pr_curves(&dir).unwrap();
+        assert_eq!(paths, vec![dir.join("Car.csv")]);
+        let csv = std::fs::read_to_string(&paths[0]).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "matching_mode,confidence,precision,recall,threshold"
+        );
+        // Points are in descending order of confidence.
+        assert_eq!(lines[1], "CenterDistance,0.8,1,1,1");
+        assert_eq!(lines[2], "CenterDistance,0.2,0.5,1,1");
+        assert_eq!(lines[3], "PlaneDistance,0.8,1,1,1");
+        assert_eq!(lines[4], "PlaneDistance,0.2,0.5,1,1");
+        assert_eq!(lines.len(), 5);
+        // The FP with the lower confidence does not decrease AP.
+        assert_eq!(score.mean_ap(&MatchingMode::CenterDistance), Some(1.0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_decisions() {
+        let ground_truth = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("a,\"b\"".to_string()),
+            class_probabilities: None,
+        };
+        let tp_estimation = DynamicObject {
+            position: [1.5, 1.0, 0.0],
+            confidence: 0.9,
+            uuid: None,
+            ..ground_truth.clone()
+        };
+        let fp_estimation = DynamicObject {
+            position: [30.0, 1.0, 0.0],
+            confidence: 0.5,
+            uuid: None,
+            ..ground_truth.clone()
+        };
+        // The FP estimation is in the frame without GT.
+        let frame_results = [
+            (vec![&tp_estimation], vec![ground_truth.clone()]),
+            (vec![&fp_estimation], vec![]),
+        ]
+        .into_iter()
+        .map(|(estimations, ground_truths)| {
+            let results =
+                get_perception_results(&estimations, &ground_truths.iter().collect::<Vec<_>>());
+            PerceptionFrameResult::new(
+                results,
+                FrameGroundTruth {
+                    timestamp: ground_truth.timestamp,
+                    objects: ground_truths,
+                    ego_translation: None,
+                    ego_rotation: None,
+                    sample_token: None,
+                    version: None,
+                    raw_pointcloud: None,
+                    location: None,
+                },
+                &[Label::Car],
+                MatchingMode::PlaneDistance,
+                &[1.0],
+                &PlaneDistanceConfig::default(),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+        let params = MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5).unwrap();
+        let mut score = MetricsScore::new(&params);
+        score
+            .evaluate_frames(&EvaluationTask::Detection, &frame_results)
+            .unwrap();
+
+        let decisions = score
+            .decisions(&Label::Car, &MatchingMode::CenterDistance)
+            .unwrap();
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions.confidence, vec![0.9, 0.5]);
+        assert_eq!(decisions.is_tp, vec![true, false]);
+        assert_eq!(decisions.matching_score, vec![Some(0.5), None]);
+        assert_eq!(decisions.gt_uuid, vec![ground_truth.uuid.clone(), None]);
+        assert!(score
+            .decisions(&Label::Pedestrian, &MatchingMode::CenterDistance)
+            .is_none());
+        assert!(score.decisions(&Label::Car, &MatchingMode::Iou3d).is_none());
+
+        let dir = std::env::temp_dir().join("perception_eval_test_save_decisions");
+        let paths = score.save_decisions(&dir).unwrap();
+        assert_eq!(paths, vec![dir.join("Car.csv")]);
+        let csv = std::fs::read_to_string(&paths[0]).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "matching_mode,confidence,is_tp,matching_score,gt_uuid"
+        );
+        assert_eq!(lines[1], "CenterDistance,0.9,true,0.5,\"a,\"\"b\"\"\"");
+        assert_eq!(lines[2], "CenterDistance,0.5,false,,");
+        // Rows of CenterDistance and PlaneDistance.
+        assert_eq!(lines.len(), 5);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_deterministic_parallel() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let labels = [Label::Car, Label::Pedestrian, Label::Bicycle];
+        let frame_results = (0..7)
+            .map(|i| {
+                let ground_truth = DynamicObject {
+                    timestamp,
+                    frame_id: FrameID::BaseLink,
+                    position: [1.0 + i as f64, 1.0, 0.0],
+                    orientation: [1.0, 0.0, 0.0, 0.0],
+                    size: [2.0, 1.0, 1.0],
+                    velocity: None,
+                    confidence: 1.0,
+                    label: labels[i % labels.len()].clone(),
+                    pointcloud_num: Some(1000),
+                    uuid: Some(i.to_string()),
+                    class_probabilities: None,
+                };
+                let yaw = 0.1 * i as f64;
+                let estimation = DynamicObject {
+                    position: [1.0 + i as f64, 1.0 + 0.1 * i as f64, 0.0],
+                    orientation: [(0.5 * yaw).cos(), 0.0, 0.0, (0.5 * yaw).sin()],
+                    confidence: 1.0 / (1.0 + i as f64),
+                    ..ground_truth.clone()
+                };
+                let false_positive = DynamicObject {
+                    position: [50.0, 50.0, 0.0],
+                    confidence: 0.3,
+                    ..estimation.clone()
+                };
+                PerceptionFrameResult::new(
+                    get_perception_results(&[&estimation, &false_positive], &[&ground_truth]),
+                    FrameGroundTruth {
+                        timestamp,
+                        objects: vec![ground_truth],
+                        ego_translation: None,
+                        ego_rotation: None,
+                        sample_token: None,
+                        version: None,
+                        raw_pointcloud: None,
+                        location: None,
+                    },
+                    &labels,
+                    MatchingMode::CenterDistance,
+                    &[1.0; 3],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let evaluate = |num_threads| {
+            let params =
+                MetricsParams::new(&vec!["Car", "Pedestrian", "Bicycle"], 1.0, 1.0, 0.5, 0.5)
+                    .unwrap()
+                    .with_deterministic_parallel(num_threads);
+            let mut score = MetricsScore::new(&params);
+            score
+                .evaluate_frames(&EvaluationTask::Detection, &frame_results)
+                .unwrap();
+            score
+                .scores
+                .iter()
+                .flat_map(|score| {
+                    score.scores["AP"]
+                        .iter()
+                        .chain(&score.scores["APH"])
+                        .chain(
+                            score
+                                .pr_curves
+                                .iter()
+                                .flatten()
+                                .map(|point| &point.precision),
+                        )
+                        .map(|value| value.to_bits())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        // Scores of the parallel mode are the same as the default sequential evaluation.
+        let expected = evaluate(None);
+        assert!(!expected.is_empty());
+        assert_eq!(evaluate(Some(1)), expected);
+        assert_eq!(evaluate(Some(2)), expected);
+        assert_eq!(evaluate(Some(16)), expected);
     }
 }
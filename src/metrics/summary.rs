@@ -0,0 +1,428 @@
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use crate::{
+    dataset::FrameGroundTruth, label::Label, object::object3d::OrientationValidity,
+    result::frame::PerceptionFrameResult, utils::point::distance_points,
+};
+
+type RateFn = fn(&ErrorRateSummary, &Label) -> Option<f64>;
+
+/// Summary of FP and FN rates normalized by driven distance and elapsed time.
+///
+/// * `target_labels`   - List of Label instances.
+/// * `num_fp`          - Number of FP results for each label.
+/// * `num_fn`          - Number of FN objects for each label.
+/// * `distance`        - Distance traveled by ego vehicle in [m], summed over scenes.
+/// * `duration`        - Elapsed time from the first frame to the last frame in [s], summed over scenes.
+#[derive(Debug, Clone)]
+pub struct ErrorRateSummary {
+    target_labels: Vec<Label>,
+    num_fp: Vec<usize>,
+    num_fn: Vec<usize>,
+    distance: f64,
+    duration: f64,
+}
+
+impl ErrorRateSummary {
+    /// Maximum interval between consecutive frames in [s] regarded as the same scene.
+    pub const MAX_FRAME_INTERVAL: f64 = 10.0;
+
+    /// Returns whether the next frame belongs to the same scene as the previous frame,
+    /// i.e. the dataset version is the same and the interval does not exceed `MAX_FRAME_INTERVAL`.
+    ///
+    /// * `previous`    - Previous frame.
+    /// * `next`        - Next frame.
+    pub(crate) fn is_same_scene(previous: &FrameGroundTruth, next: &FrameGroundTruth) -> bool {
+        previous.version == next.version
+            && (next.timestamp - previous.timestamp)
+                .num_microseconds()
+                .is_some_and(|interval| interval as f64 * 1e-6 <= Self::MAX_FRAME_INTERVAL)
+    }
+
+    /// Construct `ErrorRateSummary` from accumulated frame results.
+    ///
+    /// Frames ordered by timestamp are split into scenes where the dataset version changes
+    /// or the interval exceeds `MAX_FRAME_INTERVAL`, and distance and duration are accumulated per scene.
+    /// The distance is computed from ego translations of frames, and frames without ego translation are skipped.
+    ///
+    /// * `frame_results`   - List of PerceptionFrameResult.
+    /// * `target_labels`   - List of Label instances.
+    pub(crate) fn new(frame_results: &[PerceptionFrameResult], target_labels: &[Label]) -> Self {
+        let mut num_fp = vec![0; target_labels.len()];
+        let mut num_fn = vec![0; target_labels.len()];
+        frame_results.iter().for_each(|frame| {
+            frame.fp_results().iter().for_each(|result| {
+                if let Some(i) = target_labels
+                    .iter()
+                    .position(|label| *label == result.estimated_object.label)
+                {
+                    num_fp[i] += 1;
+                }
+            });
+            frame.fn_objects().iter().for_each(|object| {
+                if let Some(i) = target_labels
+                    .iter()
+                    .position(|label| *label == object.label)
+                {
+                    num_fn[i] += 1;
+                }
+            });
+        });
+
+        let mut frame_ground_truths = frame_results
+            .iter()
+            .map(|frame| frame.frame_ground_truth())
+            .collect::<Vec<_>>();
+        frame_ground_truths.sort_by(|a, b| {
+            a.version
+                .cmp(&b.version)
+                .then_with(|| a.timestamp.cmp(&b.timestamp))
+        });
+
+        // Frames are split into scenes at gaps, which are excluded from distance and duration.
+        let mut distance = 0.0;
+        let mut duration = 0.0;
+        frame_ground_truths
+            .chunk_by(|a, b| Self::is_same_scene(a, b))
+            .for_each(|scene| {
+                distance += scene
+                    .iter()
+                    .filter_map(|frame| frame.ego_translation.as_ref())
+                    .collect::<Vec<_>>()
+                    .windows(2)
+                    .map(|pair| distance_points(pair[0], pair[1]))
+                    .sum::<f64>();
+                if let (Some(first), Some(last)) = (scene.first(), scene.last()) {
+                    duration += (last.timestamp - first.timestamp)
+                        .num_microseconds()
+                        .unwrap_or(0) as f64
+                        * 1e-6;
+                }
+            });
+
+        Self {
+            target_labels: target_labels.to_owned(),
+            num_fp,
+            num_fn,
+            distance,
+            duration,
+        }
+    }
+
+    /// Returns distance traveled by ego vehicle in [km].
+    pub fn distance_km(&self) -> f64 {
+        self.distance * 1e-3
+    }
+
+    /// Returns elapsed time in [h].
+    pub fn duration_hour(&self) -> f64 {
+        self.duration / 3600.0
+    }
+
+    /// Returns the number of FP results per driven [km] for the label.
+    /// If the label is not a target or ego vehicle did not move, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn fp_per_km(&self, label: &Label) -> Option<f64> {
+        self.normalize(&self.num_fp, label, self.distance_km())
+    }
+
+    /// Returns the number of FP results per [h] for the label.
+    /// If the label is not a target or no time elapsed, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn fp_per_hour(&self, label: &Label) -> Option<f64> {
+        self.normalize(&self.num_fp, label, self.duration_hour())
+    }
+
+    /// Returns the number of FN objects per driven [km] for the label.
+    /// If the label is not a target or ego vehicle did not move, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn fn_per_km(&self, label: &Label) -> Option<f64> {
+        self.normalize(&self.num_fn, label, self.distance_km())
+    }
+
+    /// Returns the number of FN objects per [h] for the label.
+    /// If the label is not a target or no time elapsed, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn fn_per_hour(&self, label: &Label) -> Option<f64> {
+        self.normalize(&self.num_fn, label, self.duration_hour())
+    }
+
+    /// Returns the number of FP results for the label.
+    /// If the label is not a target, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn num_fp(&self, label: &Label) -> Option<usize> {
+        let index = self.target_labels.iter().position(|l| l == label)?;
+        Some(self.num_fp[index])
+    }
+
+    /// Returns the number of FN objects for the label.
+    /// If the label is not a target, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn num_fn(&self, label: &Label) -> Option<usize> {
+        let index = self.target_labels.iter().position(|l| l == label)?;
+        Some(self.num_fn[index])
+    }
+
+    /// Returns the table of the number of FP and FN for each label.
+    pub(crate) fn format_counts(&self) -> String {
+        let mut msg = "\n[Breakdown]".to_string();
+        msg += &format!("\n|{0:>10}|", "Label");
+        self.target_labels
+            .iter()
+            .for_each(|label| msg += &format!("{0:^12}|", label.to_string()));
+
+        [("FP", &self.num_fp), ("FN", &self.num_fn)]
+            .iter()
+            .for_each(|(name, counts)| {
+                msg += &format!("\n|{0:>10}|", name);
+                counts
+                    .iter()
+                    .for_each(|count| msg += &format!(" {0:>10} |", count));
+            });
+        msg + "\n"
+    }
+
+    fn normalize(&self, counts: &[usize], label: &Label, denominator: f64) -> Option<f64> {
+        let index = self.target_labels.iter().position(|l| l == label)?;
+        if denominator > 0.0 {
+            Some(counts[index] as f64 / denominator)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for ErrorRateSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "\n".to_string();
+        msg += &format!(
+            "[Summary]\ndistance: {:.3} [km], duration: {:.3} [h]",
+            self.distance_km(),
+            self.duration_hour()
+        );
+
+        msg += &format!("\n|{0:>10}|", "Label");
+        self.target_labels
+            .iter()
+            .for_each(|label| msg += &format!("{0:^12}|", label.to_string()));
+
+        let rows: [(&str, RateFn); 4] = [
+            ("FP/km", Self::fp_per_km),
+            ("FP/h", Self::fp_per_hour),
+            ("FN/km", Self::fn_per_km),
+            ("FN/h", Self::fn_per_hour),
+        ];
+        rows.iter().for_each(|(name, func)| {
+            msg += &format!("\n|{0:>10}|", name);
+            self.target_labels
+                .iter()
+                .for_each(|label| match func(self, label) {
+                    Some(value) => msg += &format!(" {0:>10.3} |", value),
+                    None => msg += &format!(" {0:>10} |", "-"),
+                });
+        });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+/// Counts of estimations whose orientation quaternion is not unit, validated at ingestion.
+///
+/// * `num_objects`         - Number of validated estimations.
+/// * `num_normalized`      - Number of near-unit estimations which were normalized.
+/// * `num_rejected`        - Number of invalid estimations which were rejected.
+/// * `num_rejected_frames` - Number of frames containing at least one rejected estimation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrientationSummary {
+    pub num_objects: usize,
+    pub num_normalized: usize,
+    pub num_rejected: usize,
+    pub num_rejected_frames: usize,
+}
+
+impl OrientationSummary {
+    /// Accumulate validities of estimations at one frame.
+    ///
+    /// * `validities`  - Validity of the orientation of each estimation.
+    pub(crate) fn add_frame(&mut self, validities: &[OrientationValidity]) {
+        let num_rejected = validities
+            .iter()
+            .filter(|validity| **validity == OrientationValidity::Invalid)
+            .count();
+        self.num_objects += validities.len();
+        self.num_normalized += validities
+            .iter()
+            .filter(|validity| matches!(validity, OrientationValidity::NearUnit(_)))
+            .count();
+        self.num_rejected += num_rejected;
+        if 0 < num_rejected {
+            self.num_rejected_frames += 1;
+        }
+    }
+}
+
+impl Display for OrientationSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut msg = "
+[Orientation]"
+            .to_string();
+        msg += &format!(
+            "
+|{0:^10}|{1:^12}|{2:^10}|{3:^17}|",
+            "Objects", "Normalized", "Rejected", "Rejected Frames"
+        );
+        msg += &format!(
+            "
+| {0:>8} | {1:>10} | {2:>8} | {3:>15} |",
+            self.num_objects, self.num_normalized, self.num_rejected, self.num_rejected_frames
+        );
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorRateSummary;
+    use crate::{
+        dataset::FrameGroundTruth,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::PerceptionResult},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_error_rate_summary() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let estimation = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+
+        let frame_result = |seconds: f64, translation: [f64; 3]| {
+            let frame_ground_truth = FrameGroundTruth {
+                timestamp: timestamp + chrono::Duration::microseconds((seconds * 1e6) as i64),
+                objects: Vec::new(),
+                ego_translation: Some(translation),
+                ego_rotation: None,
+                sample_token: None,
+                version: None,
+                raw_pointcloud: None,
+                location: None,
+            };
+            PerceptionFrameResult::new(
+                vec![PerceptionResult::new(estimation.clone(), None)],
+                frame_ground_truth,
+                &[Label::Car],
+                MatchingMode::CenterDistance,
+                &[1.0],
+                &PlaneDistanceConfig::default(),
+            )
+            .unwrap()
+        };
+
+        let frame_results = vec![
+            frame_result(0.0, [0.0; 3]),
+            frame_result(9.0, [250.0, 0.0, 0.0]),
+        ];
+        let summary = ErrorRateSummary::new(&frame_results, &[Label::Car, Label::Pedestrian]);
+        assert_eq!(summary.distance_km(), 0.25);
+        assert_eq!(summary.duration_hour(), 9.0 / 3600.0);
+        assert_eq!(summary.fp_per_km(&Label::Car), Some(8.0));
+        assert_eq!(summary.fp_per_hour(&Label::Car), Some(800.0));
+        assert_eq!(summary.fn_per_km(&Label::Pedestrian), Some(0.0));
+        assert_eq!(summary.fn_per_km(&Label::Bus), None);
+        assert_eq!(summary.num_fp(&Label::Car), Some(2));
+        assert_eq!(summary.num_fn(&Label::Pedestrian), Some(0));
+    }
+
+    #[test]
+    fn test_error_rate_summary_scenes() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let frame_ground_truth =
+            |seconds: i64, translation: [f64; 3], version: &str| FrameGroundTruth {
+                timestamp: timestamp + chrono::Duration::seconds(seconds),
+                objects: Vec::new(),
+                ego_translation: Some(translation),
+                ego_rotation: None,
+                sample_token: None,
+                version: Some(version.to_string()),
+                raw_pointcloud: None,
+                location: None,
+            };
+        // Two scenes separated by an hour, and one scene of another version overlapping in time.
+        let frame_results = [
+            frame_ground_truth(0, [0.0, 0.0, 0.0], "v1"),
+            frame_ground_truth(5, [50.0, 0.0, 0.0], "v1"),
+            frame_ground_truth(3600, [5000.0, 0.0, 0.0], "v1"),
+            frame_ground_truth(3604, [5040.0, 0.0, 0.0], "v1"),
+            frame_ground_truth(1, [-1000.0, 0.0, 0.0], "v2"),
+            frame_ground_truth(2, [-990.0, 0.0, 0.0], "v2"),
+        ]
+        .into_iter()
+        .map(|frame| {
+            PerceptionFrameResult::new(
+                Vec::new(),
+                frame,
+                &[Label::Car],
+                MatchingMode::CenterDistance,
+                &[1.0],
+                &PlaneDistanceConfig::default(),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+        // Gaps between scenes are neither driven nor elapsed.
+        let summary = ErrorRateSummary::new(&frame_results, &[Label::Car]);
+        assert!((summary.distance_km() - 0.1).abs() < 1e-12);
+        assert!((summary.duration_hour() - 10.0 / 3600.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_orientation_summary() {
+        let quickstart = crate::quickstart();
+        let mut manager = quickstart.manager();
+        for (i, (frame, estimations)) in quickstart
+            .frame_ground_truths
+            .iter()
+            .zip(&quickstart.estimations)
+            .enumerate()
+        {
+            let mut estimations = estimations.to_owned();
+            match i {
+                0 => estimations[0].orientation = [0.0; 4],
+                1 => estimations[0].orientation = [1.05, 0.0, 0.0, 0.0],
+                2 => estimations[0].orientation = [f64::NAN, 0.0, 0.0, 1.0],
+                _ => {}
+            }
+            manager.add_frame_result(&estimations, frame).unwrap();
+        }
+        let num_objects = quickstart.estimations.iter().map(Vec::len).sum::<usize>();
+        let score = manager.get_metrics_score().unwrap();
+        let summary = score.orientation().unwrap();
+        assert_eq!(summary.num_objects, num_objects);
+        assert_eq!(summary.num_normalized, 1);
+        assert_eq!(summary.num_rejected, 2);
+        assert_eq!(summary.num_rejected_frames, 2);
+        assert!(score.to_string().contains("[Orientation]"));
+    }
+}
@@ -36,7 +36,7 @@ impl TPMetrics for TPMetricsAPH {
                 if PI < diff_heading {
                     diff_heading = 2.0 * PI - diff_heading;
                 }
-                (1.0 - diff_heading / PI).max(0.0).min(1.0)
+                (1.0 - diff_heading / PI).clamp(0.0, 1.0)
             }
             None => 0.0,
         }
@@ -53,12 +53,12 @@ mod tests {
         object::object3d::DynamicObject,
         result::object::PerceptionResult,
     };
-    use chrono::NaiveDateTime;
+    use chrono::DateTime;
 
     #[test]
     fn test_tp_metrics_ap() {
         let estimation = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -68,10 +68,11 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
         let ground_truth = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [10.0, 10.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -81,6 +82,7 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
         let result = PerceptionResult::new(estimation, Some(ground_truth));
         let value = TPMetricsAP.get_value(&result);
@@ -90,7 +92,7 @@ mod tests {
     #[test]
     fn test_tp_metrics_aph() {
         let estimation = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [1.0, 1.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -100,10 +102,11 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
 
         let ground_truth = DynamicObject {
-            timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
             frame_id: FrameID::BaseLink,
             position: [10.0, 10.0, 0.0],
             orientation: [1.0, 0.0, 0.0, 0.0],
@@ -113,6 +116,7 @@ mod tests {
             label: Label::Car,
             pointcloud_num: Some(1000),
             uuid: Some("111".to_string()),
+            class_probabilities: None,
         };
         let result = PerceptionResult::new(estimation, Some(ground_truth));
         let value = TPMetricsAPH.get_value(&result);
@@ -0,0 +1,508 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FormatResult},
+};
+
+use crate::{label::Label, object::object3d::DynamicObject, result::frame::PerceptionFrameResult};
+
+/// Counter of ID switches over a scene with association memory.
+///
+/// For each GT instance, the estimated track id last associated as TP is remembered.
+/// When the GT is associated with a different track id while it is remembered, an ID switch is counted.
+/// A GT which is not associated for more than `max_missed_frames` consecutive frames is regarded as lost,
+/// and its next association is not penalized. If `max_missed_frames` is None, the association
+/// is remembered through the scene, which follows MOTChallenge conventions.
+///
+/// Frame results must be added in order of timestamp. Objects without uuid are ignored.
+///
+/// * `max_missed_frames`   - Maximum number of consecutive missed frames before a track is regarded as lost.
+/// * `last_associations`   - Last associated track id and the number of consecutive missed frames for each GT id.
+/// * `num_id_switches`     - Number of ID switches for each label.
+#[derive(Debug, Clone, Default)]
+pub struct IdSwitchCounter {
+    max_missed_frames: Option<usize>,
+    last_associations: HashMap<String, (String, usize)>,
+    num_id_switches: HashMap<Label, usize>,
+}
+
+impl IdSwitchCounter {
+    /// Construct `IdSwitchCounter`.
+    ///
+    /// * `max_missed_frames`   - Maximum number of consecutive missed frames before a track is regarded as lost.
+    pub fn new(max_missed_frames: Option<usize>) -> Self {
+        Self {
+            max_missed_frames,
+            ..Default::default()
+        }
+    }
+
+    /// Construct `IdSwitchCounter` from frame results over a scene.
+    ///
+    /// * `frame_results`       - List of PerceptionFrameResult ordered by timestamp.
+    /// * `max_missed_frames`   - Maximum number of consecutive missed frames before a track is regarded as lost.
+    pub fn from_frame_results(
+        frame_results: &[PerceptionFrameResult],
+        max_missed_frames: Option<usize>,
+    ) -> Self {
+        let mut counter = Self::new(max_missed_frames);
+        frame_results.iter().for_each(|frame_result| {
+            counter.add_frame_result(frame_result);
+        });
+        counter
+    }
+
+    /// Accumulate associations at one frame and count ID switches.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult at one frame.
+    pub fn add_frame_result(&mut self, frame_result: &PerceptionFrameResult) -> &mut Self {
+        let max_missed_frames = self.max_missed_frames;
+        let mut associated = Vec::new();
+        frame_result.tp_results().iter().for_each(|result| {
+            let ground_truth = match &result.ground_truth_object {
+                Some(ground_truth) => ground_truth,
+                None => return,
+            };
+            let (est_uuid, gt_uuid) = match (&result.estimated_object.uuid, &ground_truth.uuid) {
+                (Some(est_uuid), Some(gt_uuid)) => (est_uuid, gt_uuid),
+                _ => return,
+            };
+            if let Some((last_uuid, missed)) = self.last_associations.get(gt_uuid) {
+                let is_remembered =
+                    max_missed_frames.is_none_or(|max_missed| *missed <= max_missed);
+                if last_uuid != est_uuid && is_remembered {
+                    *self
+                        .num_id_switches
+                        .entry(ground_truth.label.to_owned())
+                        .or_default() += 1;
+                }
+            }
+            associated.push((gt_uuid.to_owned(), est_uuid.to_owned()));
+        });
+
+        self.last_associations
+            .values_mut()
+            .for_each(|(_, missed)| *missed += 1);
+        associated.into_iter().for_each(|(gt_uuid, est_uuid)| {
+            self.last_associations.insert(gt_uuid, (est_uuid, 0));
+        });
+        self
+    }
+
+    /// Returns the number of ID switches of the label.
+    ///
+    /// * `label`   - Label instance.
+    pub fn num_id_switches(&self, label: &Label) -> usize {
+        self.num_id_switches.get(label).copied().unwrap_or(0)
+    }
+
+    /// Returns the total number of ID switches over all labels.
+    pub fn total_id_switches(&self) -> usize {
+        self.num_id_switches.values().sum()
+    }
+}
+
+/// Smoothness of matched tracks measured by frame-to-frame jitter relative to GT.
+///
+/// For each pair of an estimated track and a GT instance associated as TP at consecutive frames,
+/// the BEV displacement of the estimation is compared with the one of the GT. The position jitter
+/// is RMS of their differences, so that a tracker following GT motion exactly has zero jitter.
+/// The velocity jitter is computed in the same way with BEV velocities, if both objects have velocities.
+///
+/// Frame results must be added in order of timestamp. Objects without uuid are ignored.
+///
+/// * `num_frames`      - Number of added frames.
+/// * `last_states`     - Frame index and objects last associated for each pair of track id and GT id.
+/// * `position_errors` - Sum of squared position jitter and the number of samples for each label.
+/// * `velocity_errors` - Sum of squared velocity jitter and the number of samples for each label.
+#[derive(Debug, Clone, Default)]
+pub struct TrackSmoothness {
+    num_frames: usize,
+    last_states: HashMap<(String, String), (usize, DynamicObject, DynamicObject)>,
+    position_errors: HashMap<Label, (f64, usize)>,
+    velocity_errors: HashMap<Label, (f64, usize)>,
+}
+
+impl TrackSmoothness {
+    /// Construct `TrackSmoothness`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct `TrackSmoothness` from frame results over a scene.
+    ///
+    /// * `frame_results`   - List of PerceptionFrameResult ordered by timestamp.
+    pub fn from_frame_results(frame_results: &[PerceptionFrameResult]) -> Self {
+        let mut smoothness = Self::new();
+        frame_results.iter().for_each(|frame_result| {
+            smoothness.add_frame_result(frame_result);
+        });
+        smoothness
+    }
+
+    /// Accumulate associations at one frame and measure jitter from the previous frame.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult at one frame.
+    pub fn add_frame_result(&mut self, frame_result: &PerceptionFrameResult) -> &mut Self {
+        let frame_index = self.num_frames;
+        frame_result.tp_results().iter().for_each(|result| {
+            let ground_truth = match &result.ground_truth_object {
+                Some(ground_truth) => ground_truth,
+                None => return,
+            };
+            let key = match (&result.estimated_object.uuid, &ground_truth.uuid) {
+                (Some(est_uuid), Some(gt_uuid)) => (est_uuid.to_owned(), gt_uuid.to_owned()),
+                _ => return,
+            };
+            let estimation = &result.estimated_object;
+            if let Some((last_index, last_estimation, last_ground_truth)) =
+                self.last_states.get(&key)
+            {
+                if *last_index + 1 == frame_index {
+                    let label = ground_truth.label.to_owned();
+                    let error = bev_difference(
+                        &displacement(&last_estimation.position, &estimation.position),
+                        &displacement(&last_ground_truth.position, &ground_truth.position),
+                    );
+                    accumulate(&mut self.position_errors, &label, error);
+                    if let (Some(v0), Some(v1), Some(g0), Some(g1)) = (
+                        &last_estimation.velocity,
+                        &estimation.velocity,
+                        &last_ground_truth.velocity,
+                        &ground_truth.velocity,
+                    ) {
+                        let error = bev_difference(&displacement(v0, v1), &displacement(g0, g1));
+                        accumulate(&mut self.velocity_errors, &label, error);
+                    }
+                }
+            }
+            self.last_states.insert(
+                key,
+                (frame_index, estimation.to_owned(), ground_truth.to_owned()),
+            );
+        });
+        self.last_states
+            .retain(|_, (last_index, _, _)| *last_index == frame_index);
+        self.num_frames += 1;
+        self
+    }
+
+    /// Returns RMS of position jitter of the label in [m].
+    /// If there is no consecutive association, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn position_jitter(&self, label: &Label) -> Option<f64> {
+        rms(self.position_errors.get(label))
+    }
+
+    /// Returns RMS of velocity jitter of the label in [m/s].
+    /// If there is no consecutive association with velocities, returns None.
+    ///
+    /// * `label`   - Label instance.
+    pub fn velocity_jitter(&self, label: &Label) -> Option<f64> {
+        rms(self.velocity_errors.get(label))
+    }
+
+    /// Returns the number of consecutive associations of the label used for position jitter.
+    ///
+    /// * `label`   - Label instance.
+    pub fn num_samples(&self, label: &Label) -> usize {
+        self.position_errors
+            .get(label)
+            .map_or(0, |(_, num_samples)| *num_samples)
+    }
+}
+
+impl Display for TrackSmoothness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let mut labels = self.position_errors.keys().collect::<Vec<_>>();
+        labels.sort_by_key(|label| label.to_string());
+
+        let mut msg = "\n[Smoothness]".to_string();
+        msg += &format!(
+            "\n|{0:>12}|{1:^14}|{2:^14}|{3:^10}|",
+            "Label", "Position [m]", "Velocity [m/s]", "Samples"
+        );
+        let format_value = |value: Option<f64>| match value {
+            Some(value) => format!(" {0:>12.3} ", value),
+            None => format!(" {0:>12} ", "-"),
+        };
+        labels.iter().for_each(|label| {
+            msg += &format!(
+                "\n|{0:>12}|{1}|{2}|{3:>9} |",
+                label.to_string(),
+                format_value(self.position_jitter(label)),
+                format_value(self.velocity_jitter(label)),
+                self.num_samples(label)
+            );
+        });
+
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+/// Returns the difference from `from` to `to`.
+fn displacement(from: &[f64; 3], to: &[f64; 3]) -> [f64; 3] {
+    [to[0] - from[0], to[1] - from[1], to[2] - from[2]]
+}
+
+/// Returns the BEV norm of the difference between two vectors.
+fn bev_difference(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+/// Accumulate the squared error of the label.
+fn accumulate(errors: &mut HashMap<Label, (f64, usize)>, label: &Label, error: f64) {
+    let (sum, num_samples) = errors.entry(label.to_owned()).or_default();
+    *sum += error * error;
+    *num_samples += 1;
+}
+
+/// Returns RMS from the sum of squared errors and the number of samples.
+fn rms(errors: Option<&(f64, usize)>) -> Option<f64> {
+    match errors {
+        Some((sum, num_samples)) if *num_samples > 0 => Some((sum / *num_samples as f64).sqrt()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdSwitchCounter, TrackSmoothness};
+    use crate::{
+        config::TrackingWarmup,
+        dataset::FrameGroundTruth,
+        evaluation_task::EvaluationTask,
+        frame_id::FrameID,
+        label::Label,
+        manager::PerceptionEvaluationManager,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        quickstart,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_id_switch_counter() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let object = DynamicObject::new(
+            timestamp,
+            FrameID::BaseLink,
+            [1.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [2.0, 1.0, 1.0],
+            1.0,
+            Label::Car,
+        )
+        .with_pointcloud_num(1000);
+        let with_uuid = |uuid: &str| object.clone().with_uuid(uuid);
+
+        // Instance "a" is tracked by "7", missed for 2 frames, then re-associated with "8".
+        let est_uuids = [Some("7"), None, None, Some("8"), Some("8")];
+        let frame_results = est_uuids
+            .iter()
+            .map(|est_uuid| {
+                let estimations = est_uuid
+                    .iter()
+                    .map(|uuid| with_uuid(uuid))
+                    .collect::<Vec<_>>();
+                let ground_truths = vec![with_uuid("a")];
+                let results = get_perception_results(&estimations, &ground_truths);
+                PerceptionFrameResult::new(
+                    results,
+                    FrameGroundTruth::new(timestamp, ground_truths),
+                    &[Label::Car],
+                    MatchingMode::CenterDistance,
+                    &[1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let count = |max_missed_frames| {
+            IdSwitchCounter::from_frame_results(&frame_results, max_missed_frames)
+                .num_id_switches(&Label::Car)
+        };
+        assert_eq!(count(None), 1);
+        assert_eq!(count(Some(2)), 1);
+        // The track is lost after 1 missed frame, so the re-association is not penalized.
+        assert_eq!(count(Some(1)), 0);
+    }
+
+    #[test]
+    fn test_track_smoothness() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let object = DynamicObject::new(
+            timestamp,
+            FrameID::BaseLink,
+            [10.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [4.0, 2.0, 1.5],
+            1.0,
+            Label::Car,
+        )
+        .with_velocity([10.0, 0.0, 0.0])
+        .with_pointcloud_num(1000)
+        .with_uuid("a");
+
+        // GT moves 1 m per frame, while the estimation zigzags laterally by 0.2 m.
+        // Track "7" is lost at the 3rd frame, so the association over the gap is not measured.
+        let est_uuids = [Some("7"), Some("7"), None, Some("7"), Some("7")];
+        let frame_results = est_uuids
+            .iter()
+            .enumerate()
+            .map(|(i, est_uuid)| {
+                let ground_truth = DynamicObject {
+                    position: [10.0 + i as f64, 0.0, 0.0],
+                    ..object.clone()
+                };
+                let estimations = est_uuid
+                    .iter()
+                    .map(|uuid| DynamicObject {
+                        position: [10.0 + i as f64, 0.1 * (-1.0_f64).powi(i as i32), 0.0],
+                        velocity: Some([10.0, 0.0, 0.0]),
+                        uuid: Some(uuid.to_string()),
+                        ..object.clone()
+                    })
+                    .collect::<Vec<_>>();
+                let ground_truths = vec![ground_truth];
+                PerceptionFrameResult::new(
+                    get_perception_results(&estimations, &ground_truths),
+                    FrameGroundTruth::new(timestamp, ground_truths),
+                    &[Label::Car],
+                    MatchingMode::CenterDistance,
+                    &[1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let smoothness = TrackSmoothness::from_frame_results(&frame_results);
+        assert_eq!(smoothness.num_samples(&Label::Car), 2);
+        assert!((smoothness.position_jitter(&Label::Car).unwrap() - 0.2).abs() < 1e-9);
+        assert_eq!(smoothness.velocity_jitter(&Label::Car), Some(0.0));
+        assert_eq!(smoothness.position_jitter(&Label::Pedestrian), None);
+    }
+
+    #[test]
+    fn test_tracking_warmup() {
+        const WARMUP_FRAMES: usize = 2;
+        let quickstart = quickstart();
+        let evaluate = |tracking_warmup, spill: bool| {
+            let mut config = quickstart.config.clone();
+            config.evaluation_task = EvaluationTask::Tracking;
+            config.metrics_params = config.metrics_params.with_tracking_warmup(tracking_warmup);
+            let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+                &config,
+                quickstart.frame_ground_truths.clone(),
+            );
+            if spill {
+                manager = manager
+                    .with_spill(std::env::temp_dir().join("perception_eval_tracking"), 0)
+                    .unwrap();
+            }
+            for (i, (frame, estimations)) in quickstart
+                .frame_ground_truths
+                .iter()
+                .zip(&quickstart.estimations)
+                .enumerate()
+            {
+                // Tracks are re-initialized with new IDs after warm-up frames.
+                let estimations = estimations
+                    .iter()
+                    .map(|object| DynamicObject {
+                        uuid: object.uuid.as_ref().map(|uuid| match i < WARMUP_FRAMES {
+                            true => format!("warmup-{}", uuid),
+                            false => uuid.to_owned(),
+                        }),
+                        ..object.clone()
+                    })
+                    .collect::<Vec<_>>();
+                manager.add_frame_result(&estimations, frame).unwrap();
+            }
+            (
+                manager.get_id_switches().unwrap(),
+                manager.get_track_smoothness().unwrap(),
+            )
+        };
+
+        let (id_switches, smoothness) = evaluate(None, false);
+        assert!(0 < id_switches.total_id_switches());
+        let (warmup_id_switches, warmup_smoothness) =
+            evaluate(Some(TrackingWarmup::Frames(WARMUP_FRAMES)), false);
+        assert_eq!(warmup_id_switches.total_id_switches(), 0);
+        assert!(warmup_smoothness.num_samples(&Label::Car) < smoothness.num_samples(&Label::Car));
+
+        // Spilled frame results are streamed back with the same warm-up frames.
+        let (spilled_id_switches, spilled_smoothness) =
+            evaluate(Some(TrackingWarmup::Frames(WARMUP_FRAMES)), true);
+        assert_eq!(spilled_id_switches.total_id_switches(), 0);
+        assert_eq!(
+            spilled_smoothness.num_samples(&Label::Car),
+            warmup_smoothness.num_samples(&Label::Car)
+        );
+    }
+
+    #[test]
+    fn test_tracking_warmup_per_scene() {
+        const WARMUP_FRAMES: usize = 2;
+        let quickstart = quickstart();
+        // The second scene replays the first one an hour later with its own GT IDs,
+        // and tracks are re-initialized with new IDs after warm-up frames of each scene.
+        let mut frame_ground_truths = Vec::new();
+        let mut estimations = Vec::new();
+        for scene in 0..2 {
+            let offset = chrono::Duration::hours(scene);
+            let rename = |object: &DynamicObject, prefix: &str| {
+                let mut object = object.clone();
+                object.timestamp += offset;
+                object.uuid = object.uuid.map(|uuid| format!("{prefix}{scene}-{uuid}"));
+                object
+            };
+            for (i, (frame, frame_estimations)) in quickstart
+                .frame_ground_truths
+                .iter()
+                .zip(&quickstart.estimations)
+                .enumerate()
+            {
+                let objects = frame
+                    .objects
+                    .iter()
+                    .map(|object| rename(object, "gt"))
+                    .collect();
+                let mut frame = frame.with_objects(objects);
+                frame.timestamp += offset;
+                frame_ground_truths.push(frame);
+                let prefix = if i < WARMUP_FRAMES { "warmup" } else { "est" };
+                estimations.push(
+                    frame_estimations
+                        .iter()
+                        .map(|object| rename(object, prefix))
+                        .collect::<Vec<_>>(),
+                );
+            }
+        }
+
+        let count_id_switches = |tracking_warmup| {
+            let mut config = quickstart.config.clone();
+            config.evaluation_task = EvaluationTask::Tracking;
+            config.metrics_params = config.metrics_params.with_tracking_warmup(tracking_warmup);
+            let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+                &config,
+                frame_ground_truths.clone(),
+            );
+            for (frame, estimations) in frame_ground_truths.iter().zip(&estimations) {
+                manager.add_frame_result(estimations, frame).unwrap();
+            }
+            manager.get_id_switches().unwrap().total_id_switches()
+        };
+
+        assert!(0 < count_id_switches(None));
+        assert_eq!(
+            count_id_switches(Some(TrackingWarmup::Frames(WARMUP_FRAMES))),
+            0
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::NaiveDateTime;
-use nalgebra::SMatrix;
+use nalgebra::{Quaternion, SMatrix, UnitQuaternion, Vector3};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     frame_id::FrameID,
@@ -10,6 +11,8 @@ use crate::{
     },
 };
 use std::{
+    cmp::Ordering,
+    collections::HashMap,
     f64::consts::PI,
     fmt::{Display, Formatter, Result as FormatResult},
 };
@@ -34,7 +37,53 @@ impl Display for ObjectState {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Maximum deviation of the quaternion norm from 1 regarded as a unit quaternion.
+pub const QUATERNION_UNIT_TOLERANCE: f64 = 1e-6;
+
+/// Maximum deviation of the quaternion norm from 1 which can be fixed by normalization.
+pub const QUATERNION_NORMALIZE_TOLERANCE: f64 = 0.1;
+
+/// Represents the validity of the orientation quaternion of an object.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrientationValidity {
+    /// Unit quaternion.
+    Valid,
+    /// Near-unit quaternion, which contains the normalized quaternion.
+    NearUnit([f64; 4]),
+    /// Zero, non-finite or far from unit quaternion, which produces garbage footprints.
+    Invalid,
+}
+
+/// Per-field tolerances used to compare objects approximately.
+///
+/// * `position`    - Tolerance of each element of position in [m].
+/// * `orientation` - Tolerance of each element of quaternion.
+/// * `size`        - Tolerance of each element of size in [m].
+/// * `velocity`    - Tolerance of each element of velocity in [m/s].
+/// * `confidence`  - Tolerance of confidence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectTolerance {
+    pub position: f64,
+    pub orientation: f64,
+    pub size: f64,
+    pub velocity: f64,
+    pub confidence: f64,
+}
+
+impl Default for ObjectTolerance {
+    fn default() -> Self {
+        Self {
+            position: 1e-6,
+            orientation: 1e-6,
+            size: 1e-6,
+            velocity: 1e-6,
+            confidence: 1e-6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct DynamicObject {
     pub timestamp: NaiveDateTime,
     pub frame_id: FrameID,
@@ -46,6 +95,7 @@ pub struct DynamicObject {
     pub label: Label,
     pub pointcloud_num: Option<usize>,
     pub uuid: Option<String>,
+    pub class_probabilities: Option<HashMap<Label, f64>>,
 }
 
 impl Display for DynamicObject {
@@ -76,6 +126,270 @@ impl PartialEq for DynamicObject {
 }
 
 impl DynamicObject {
+    /// Construct `DynamicObject` with required attributes, where optional attributes are not set.
+    ///
+    /// * `timestamp`   - Timestamp of the object.
+    /// * `frame_id`    - FrameID that the object is with respect to.
+    /// * `position`    - Center position of the object in the order of (x, y, z).
+    /// * `orientation` - Orientation quaternion in the order of (w, x, y, z).
+    /// * `size`        - Size of the object in the order of (width, length, height).
+    /// * `confidence`  - Confidence score of the object.
+    /// * `label`       - Label of the object.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_velocity([1.0, 0.0, 0.0])
+    /// .with_uuid("111");
+    ///
+    /// assert_eq!(object.uuid, Some("111".to_string()));
+    /// assert_eq!(object.pointcloud_num, None);
+    /// ```
+    pub fn new(
+        timestamp: NaiveDateTime,
+        frame_id: FrameID,
+        position: [f64; 3],
+        orientation: [f64; 4],
+        size: [f64; 3],
+        confidence: f64,
+        label: Label,
+    ) -> Self {
+        Self {
+            timestamp,
+            frame_id,
+            position,
+            orientation,
+            size,
+            velocity: None,
+            confidence,
+            label,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        }
+    }
+
+    /// Set the velocity of the object.
+    ///
+    /// * `velocity`    - Velocity in the order of (vx, vy, vz).
+    pub fn with_velocity(mut self, velocity: [f64; 3]) -> Self {
+        self.velocity = Some(velocity);
+        self
+    }
+
+    /// Set the number of points in the object.
+    ///
+    /// * `pointcloud_num`  - Number of points.
+    pub fn with_pointcloud_num(mut self, pointcloud_num: usize) -> Self {
+        self.pointcloud_num = Some(pointcloud_num);
+        self
+    }
+
+    /// Set the UUID of the object.
+    ///
+    /// * `uuid`    - UUID of the object.
+    pub fn with_uuid(mut self, uuid: &str) -> Self {
+        self.uuid = Some(uuid.to_owned());
+        self
+    }
+
+    /// Set the class probabilities of the object.
+    ///
+    /// * `class_probabilities` - Map of labels and their probabilities.
+    pub fn with_class_probabilities(mut self, class_probabilities: HashMap<Label, f64>) -> Self {
+        self.class_probabilities = Some(class_probabilities);
+        self
+    }
+
+    /// Returns the label which has the highest class probability.
+    /// If the object does not have class probabilities, returns None.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+    /// use std::collections::HashMap;
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111")
+    /// .with_class_probabilities(HashMap::from([(Label::Car, 0.3), (Label::Truck, 0.7)]));
+    ///
+    /// assert_eq!(object.argmax_label(), Some(Label::Truck));
+    /// ```
+    pub fn argmax_label(&self) -> Option<Label> {
+        self.class_probabilities
+            .as_ref()?
+            .iter()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .map(|(label, _)| label.to_owned())
+    }
+
+    /// Returns the object whose label is replaced with the argmax of class probabilities.
+    /// If the object does not have class probabilities, the label is kept.
+    pub fn with_argmax_label(self) -> Self {
+        match self.argmax_label() {
+            Some(label) => Self { label, ..self },
+            None => self,
+        }
+    }
+
+    /// Returns the validity of the orientation quaternion.
+    ///
+    /// The quaternion is valid if its norm is within `QUATERNION_UNIT_TOLERANCE` from 1,
+    /// and can be normalized if within `QUATERNION_NORMALIZE_TOLERANCE`.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     object::object3d::{DynamicObject, OrientationValidity},
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.02, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
+    /// assert_eq!(object.orientation_validity(), OrientationValidity::NearUnit([1.0, 0.0, 0.0, 0.0]));
+    ///
+    /// let mut zero = object;
+    /// zero.orientation = [0.0; 4];
+    /// assert_eq!(zero.orientation_validity(), OrientationValidity::Invalid);
+    /// ```
+    pub fn orientation_validity(&self) -> OrientationValidity {
+        let norm = self
+            .orientation
+            .iter()
+            .map(|value| value * value)
+            .sum::<f64>()
+            .sqrt();
+        let deviation = (norm - 1.0).abs();
+        if !deviation.is_finite() || QUATERNION_NORMALIZE_TOLERANCE < deviation {
+            OrientationValidity::Invalid
+        } else if deviation <= QUATERNION_UNIT_TOLERANCE {
+            OrientationValidity::Valid
+        } else {
+            OrientationValidity::NearUnit(self.orientation.map(|value| value / norm))
+        }
+    }
+
+    /// Returns whether the position, size, velocity and confidence are finite.
+    /// Orientation is checked by `orientation_validity()` instead.
+    pub fn is_finite(&self) -> bool {
+        self.position
+            .iter()
+            .chain(&self.size)
+            .chain(self.velocity.iter().flatten())
+            .all(|value| value.is_finite())
+            && self.confidence.is_finite()
+    }
+
+    /// Returns the object transformed into another coord system with the ego pose,
+    /// or None if the transform is not supported.
+    /// Only transforms between `base_link` and `map` are supported.
+    ///
+    /// * `frame_id`        - FrameID the object is transformed into.
+    /// * `ego_translation` - Translation of ego vehicle in the `map` coord system.
+    /// * `ego_rotation`    - Rotation of ego vehicle in the `map` coord system as quaternion.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [10.0, 0.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 4.0, 1.5],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_velocity([5.0, 0.0, 0.0])
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
+    ///
+    /// // Ego vehicle at (100, 200, 0) heading to the y axis of the map.
+    /// let (translation, rotation) = ([100.0, 200.0, 0.0], [0.5f64.sqrt(), 0.0, 0.0, 0.5f64.sqrt()]);
+    /// let transformed = object.transform(&FrameID::Map, &translation, &rotation).unwrap();
+    /// assert_eq!(transformed.frame_id, FrameID::Map);
+    /// assert!((transformed.position[0] - 100.0).abs() < 1e-9);
+    /// assert!((transformed.position[1] - 210.0).abs() < 1e-9);
+    /// assert!(transformed
+    ///     .orientation
+    ///     .iter()
+    ///     .zip(&rotation)
+    ///     .all(|(a, b)| (a - b).abs() < 1e-9));
+    /// assert!((transformed.velocity.unwrap()[1] - 5.0).abs() < 1e-9);
+    ///
+    /// let restored = transformed.transform(&FrameID::BaseLink, &translation, &rotation).unwrap();
+    /// assert!((restored.position[0] - 10.0).abs() < 1e-9);
+    ///
+    /// assert!(object.transform(&FrameID::CamFront, &translation, &rotation).is_none());
+    /// ```
+    pub fn transform(
+        &self,
+        frame_id: &FrameID,
+        ego_translation: &[f64; 3],
+        ego_rotation: &[f64; 4],
+    ) -> Option<Self> {
+        let [w, x, y, z] = *ego_rotation;
+        let ego_rotation = UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z));
+        let ego_translation = Vector3::from(*ego_translation);
+        let (rotation, translation) = match (&self.frame_id, frame_id) {
+            (from, to) if from == to => return Some(self.to_owned()),
+            (FrameID::BaseLink, FrameID::Map) => (ego_rotation, ego_translation),
+            (FrameID::Map, FrameID::BaseLink) => {
+                let inverse = ego_rotation.inverse();
+                (inverse, -(inverse * ego_translation))
+            }
+            _ => return None,
+        };
+
+        let position = rotation * Vector3::from(self.position) + translation;
+        let [w, x, y, z] = self.orientation;
+        let orientation = rotation.into_inner() * Quaternion::new(w, x, y, z);
+        let velocity = self
+            .velocity
+            .map(|velocity| (rotation * Vector3::from(velocity)).into());
+        Some(Self {
+            frame_id: frame_id.to_owned(),
+            position: position.into(),
+            orientation: [orientation.w, orientation.i, orientation.j, orientation.k],
+            velocity,
+            ..self.to_owned()
+        })
+    }
+
     /// Returns `ObjectState` instance.
     pub fn state(&self) -> ObjectState {
         ObjectState {
@@ -86,6 +400,70 @@ impl DynamicObject {
         }
     }
 
+    /// Returns whether two objects are the same within tolerances.
+    ///
+    /// Timestamp, frame id, label and uuid are compared exactly,
+    /// and the other values are compared element-wise within each tolerance.
+    /// Since `q` and `-q` represent the same rotation, both signs of quaternion are accepted.
+    ///
+    /// * `other`       - Object to be compared.
+    /// * `tolerances`  - Per-field tolerances.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::DateTime;
+    /// use perception_eval::{
+    ///     frame_id::FrameID,
+    ///     label::Label,
+    ///     object::object3d::{DynamicObject, ObjectTolerance},
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
+    ///
+    /// let mut other = object.clone();
+    /// other.position = [1.0 + 1e-9, 1.0, 0.0];
+    /// other.orientation = [-1.0, 0.0, 0.0, 0.0];
+    ///
+    /// assert_ne!(object, other);
+    /// assert!(object.approx_eq(&other, &ObjectTolerance::default()));
+    /// ```
+    pub fn approx_eq(&self, other: &DynamicObject, tolerances: &ObjectTolerance) -> bool {
+        let is_close = |a: &[f64], b: &[f64], tol: f64| {
+            a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() <= tol)
+        };
+        let negated = other.orientation.map(|v| -v);
+
+        let is_velocity_close = match (&self.velocity, &other.velocity) {
+            (Some(v1), Some(v2)) => is_close(v1, v2, tolerances.velocity),
+            (None, None) => true,
+            _ => false,
+        };
+
+        self.timestamp == other.timestamp
+            && self.frame_id == other.frame_id
+            && self.label == other.label
+            && self.uuid == other.uuid
+            && is_close(&self.position, &other.position, tolerances.position)
+            && (is_close(
+                &self.orientation,
+                &other.orientation,
+                tolerances.orientation,
+            ) || is_close(&self.orientation, &negated, tolerances.orientation))
+            && is_close(&self.size, &other.size, tolerances.size)
+            && is_velocity_close
+            && (self.confidence - other.confidence).abs() <= tolerances.confidence
+    }
+
     /// Returns name of label in string.
     ///
     /// # Examples
@@ -93,18 +471,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let name = object.label_name();
     ///
@@ -121,18 +498,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let area = object.area();
     ///
@@ -149,18 +525,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let volume = object.volume();
     ///
@@ -177,18 +552,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let distance = object.distance();
     ///
@@ -205,18 +579,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let distance_bev = object.distance_bev();
     ///
@@ -235,18 +608,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let distance = object.distance_from(&[1.0, 1.0, 1.0]);
     ///
@@ -265,18 +637,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let distance_bev = object.distance_bev_from(&[1.0, 1.0, 1.0]);
     ///
@@ -293,18 +664,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let heading = object.heading();
     ///
@@ -331,18 +701,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, utils::math::RotationMatrix, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let rot = object.rotation_matrix();
     ///
@@ -365,18 +734,17 @@ impl DynamicObject {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let euler = object.euler();
     ///
@@ -388,23 +756,25 @@ impl DynamicObject {
 
     /// Returns footprint of object's box.
     ///
+    /// Corners are ordered counter-clockwise seen from above, starting from the front-left corner
+    /// in the object frame, i.e. front-left, rear-left, rear-right and front-right.
+    ///
     /// # Examples
     /// ```
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
     ///
-    /// let object = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 2.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 2.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
     /// let footprint = object.footprint();
     ///
@@ -429,14 +799,15 @@ impl DynamicObject {
             0.0,
         );
 
-        let rot = self.rotation_matrix();
+        // Corners are row vectors, so they are rotated by the transposed rotation matrix.
+        let rot_t = self.rotation_matrix().transpose();
         let position: SMatrix<f64, 1, 3> =
             PositionMatrix::new(self.position[0], self.position[1], self.position[2]);
 
         center2corners
             .row_iter()
             .map(|corner| {
-                let mat = corner * rot + position;
+                let mat = corner * rot_t + position;
                 [mat[(0, 0)], mat[(0, 1)], mat[(0, 2)]]
             })
             .collect()
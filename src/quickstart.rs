@@ -0,0 +1,221 @@
+use std::error::Error;
+
+use chrono::{DateTime, NaiveDateTime};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    config::{FilterParams, MetricsParams, PerceptionEvaluationConfig},
+    dataset::FrameGroundTruth,
+    evaluation_task::EvaluationTask,
+    frame_id::FrameID,
+    label::Label,
+    manager::PerceptionEvaluationManager,
+    metrics::score::MetricsScore,
+    object::object3d::DynamicObject,
+};
+
+const NUM_FRAMES: usize = 10;
+const FRAME_INTERVAL_MICROS: i64 = 100_000;
+const START_TIMESTAMP_MICROS: i64 = 1_600_000_000_000_000;
+const SEED: u64 = 0;
+
+/// Tiny synthetic evaluation set, which runs the full pipeline without any dataset on disk.
+///
+/// * `config`              - Evaluation configuration for detection of Car, Pedestrian and Bicycle.
+/// * `frame_ground_truths` - Synthetic GTs for each frame.
+/// * `estimations`         - Noisy estimations for each frame, including FPs and FNs.
+#[derive(Debug, Clone)]
+pub struct Quickstart {
+    pub config: PerceptionEvaluationConfig,
+    pub frame_ground_truths: Vec<FrameGroundTruth>,
+    pub estimations: Vec<Vec<DynamicObject>>,
+}
+
+impl Quickstart {
+    /// Returns `PerceptionEvaluationManager` which holds the synthetic GTs.
+    pub fn manager(&self) -> PerceptionEvaluationManager<'_> {
+        PerceptionEvaluationManager::from_frame_ground_truths(
+            &self.config,
+            self.frame_ground_truths.to_owned(),
+        )
+    }
+
+    /// Evaluate all synthetic estimations and returns the metrics score.
+    pub fn run(&self) -> Result<MetricsScore, Box<dyn Error>> {
+        let mut manager = self.manager();
+        for (frame, estimations) in self.frame_ground_truths.iter().zip(&self.estimations) {
+            manager.add_frame_result(estimations, frame)?;
+        }
+        Ok(manager.get_metrics_score()?)
+    }
+}
+
+/// Returns a tiny built-in synthetic dataset and its configuration.
+///
+/// The data is generated with a fixed seed, so the result is always the same.
+///
+/// # Examples
+/// ```
+/// let quickstart = perception_eval::quickstart();
+///
+/// let mut manager = quickstart.manager();
+/// for estimations in &quickstart.estimations {
+///     let frame = manager.get_frame_ground_truth(&estimations[0].timestamp).unwrap();
+///     manager.add_frame_result(estimations, &frame).unwrap();
+/// }
+/// let score = manager.get_metrics_score().unwrap();
+/// println!("{}", score);
+/// ```
+pub fn quickstart() -> Quickstart {
+    let target_labels = vec!["Car", "Pedestrian", "Bicycle"];
+    let config = PerceptionEvaluationConfig::new(
+        vec!["quickstart".to_string()],
+        "",
+        EvaluationTask::Detection,
+        FrameID::BaseLink,
+        "work_dir/quickstart",
+        FilterParams::new(&target_labels, 100.0, 100.0, None, None).unwrap(),
+        MetricsParams::new(&target_labels, 1.0, 1.0, 0.5, 0.5).unwrap(),
+    );
+
+    let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+    let mut frame_ground_truths = Vec::with_capacity(NUM_FRAMES);
+    let mut estimations = Vec::with_capacity(NUM_FRAMES);
+    for i in 0..NUM_FRAMES {
+        let timestamp = DateTime::from_timestamp_micros(
+            START_TIMESTAMP_MICROS + FRAME_INTERVAL_MICROS * i as i64,
+        )
+        .unwrap()
+        .naive_utc();
+        let elapsed = (FRAME_INTERVAL_MICROS * i as i64) as f64 * 1e-6;
+
+        let ground_truths = vec![
+            synthetic_object(
+                &timestamp,
+                Label::Car,
+                [10.0 + 5.0 * elapsed, 3.0, 0.0],
+                [1.8, 4.5, 1.5],
+                [5.0, 0.0, 0.0],
+                "car-0",
+            ),
+            synthetic_object(
+                &timestamp,
+                Label::Car,
+                [25.0, -3.5, 0.0],
+                [1.9, 4.8, 1.6],
+                [0.0, 0.0, 0.0],
+                "car-1",
+            ),
+            synthetic_object(
+                &timestamp,
+                Label::Pedestrian,
+                [8.0, -6.0 + 1.2 * elapsed, 0.0],
+                [0.6, 0.6, 1.7],
+                [0.0, 1.2, 0.0],
+                "pedestrian-0",
+            ),
+            synthetic_object(
+                &timestamp,
+                Label::Bicycle,
+                [15.0 - 3.0 * elapsed, 7.0, 0.0],
+                [0.6, 1.8, 1.6],
+                [-3.0, 0.0, 0.0],
+                "bicycle-0",
+            ),
+        ];
+
+        let mut frame_estimations = ground_truths
+            .iter()
+            .enumerate()
+            // The pedestrian is missed every third frame.
+            .filter(|(j, object)| !(object.label == Label::Pedestrian && (i + j) % 3 == 0))
+            .map(|(j, object)| {
+                let mut position = object.position;
+                position[0] += rng.gen_range(-0.3..0.3);
+                position[1] += rng.gen_range(-0.3..0.3);
+                DynamicObject {
+                    position,
+                    confidence: rng.gen_range(0.5..1.0),
+                    uuid: Some(format!("track-{}", j)),
+                    ..object.to_owned()
+                }
+            })
+            .collect::<Vec<_>>();
+        // Ghost detection with low confidence.
+        frame_estimations.push(DynamicObject {
+            confidence: rng.gen_range(0.1..0.4),
+            uuid: Some(format!("ghost-{}", i)),
+            ..synthetic_object(
+                &timestamp,
+                Label::Car,
+                [rng.gen_range(30.0..50.0), rng.gen_range(-10.0..10.0), 0.0],
+                [1.8, 4.5, 1.5],
+                [0.0, 0.0, 0.0],
+                "",
+            )
+        });
+
+        frame_ground_truths.push(FrameGroundTruth {
+            timestamp,
+            objects: ground_truths,
+            ego_translation: Some([0.0, 0.0, 0.0]),
+            ego_rotation: Some([1.0, 0.0, 0.0, 0.0]),
+            sample_token: Some(format!("quickstart-{}", i)),
+            version: Some("quickstart".to_string()),
+            raw_pointcloud: None,
+            location: None,
+        });
+        estimations.push(frame_estimations);
+    }
+
+    Quickstart {
+        config,
+        frame_ground_truths,
+        estimations,
+    }
+}
+
+/// Returns a synthetic GT object in base_link, whose size is [width, length, height].
+fn synthetic_object(
+    timestamp: &NaiveDateTime,
+    label: Label,
+    position: [f64; 3],
+    size: [f64; 3],
+    velocity: [f64; 3],
+    uuid: &str,
+) -> DynamicObject {
+    DynamicObject {
+        timestamp: timestamp.to_owned(),
+        frame_id: FrameID::BaseLink,
+        position,
+        orientation: [1.0, 0.0, 0.0, 0.0],
+        size,
+        velocity: Some(velocity),
+        confidence: 1.0,
+        label,
+        pointcloud_num: Some(100),
+        uuid: Some(uuid.to_string()),
+        class_probabilities: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quickstart;
+    use crate::matching::MatchingMode;
+
+    #[test]
+    fn test_quickstart() {
+        let quickstart = quickstart();
+        assert_eq!(quickstart.frame_ground_truths.len(), 10);
+        assert_eq!(quickstart.estimations.len(), 10);
+
+        let score = quickstart.run().unwrap();
+        let map = score.mean_ap(&MatchingMode::CenterDistance).unwrap();
+        assert!(0.0 < map && map <= 1.0);
+
+        // Generated data is deterministic.
+        assert_eq!(quickstart.estimations, super::quickstart().estimations);
+    }
+}
@@ -1,2 +1,7 @@
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod correspondence;
 pub mod frame;
+pub mod kitti;
 pub mod object;
+pub mod spill;
@@ -0,0 +1,469 @@
+use std::{fs::File, io::Error as IoError, path::Path, sync::Arc};
+
+use arrow_array::{
+    builder::{
+        ArrayBuilder, Float64Builder, StringBuilder, TimestampMicrosecondBuilder, UInt64Builder,
+    },
+    ArrayRef, RecordBatch,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    errors::ParquetError,
+    file::properties::WriterProperties,
+};
+use thiserror::Error as ThisError;
+
+use crate::{
+    dataset::timeline::FrameLabel,
+    fault::create_file,
+    matching::{get_matching_method, MatchingMethod, MatchingMode, PlaneDistanceConfig},
+    object::object3d::DynamicObject,
+};
+
+use super::{frame::PerceptionFrameResult, object::PerceptionResult, spill::SpillError};
+
+pub type ColumnarResult<T> = Result<T, ColumnarError>;
+
+/// Represents errors that occur while exporting records to columnar files.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum ColumnarError {
+    #[error("I/O error: {0}")]
+    IoError(#[from] IoError),
+    #[error("arrow error: {0}")]
+    ArrowError(#[from] ArrowError),
+    #[error("parquet error: {0}")]
+    ParquetError(#[from] ParquetError),
+    #[error("spill error: {0}")]
+    SpillError(#[from] SpillError),
+}
+
+/// Matching modes whose scores are exported for each matched pair.
+const SCORE_MODES: [(&str, MatchingMode); 4] = [
+    ("center_distance", MatchingMode::CenterDistance),
+    ("plane_distance", MatchingMode::PlaneDistance),
+    ("iou_2d", MatchingMode::Iou2d),
+    ("iou_3d", MatchingMode::Iou3d),
+];
+
+/// Number of rows buffered in memory before they are written as a record batch.
+const ROWS_PER_BATCH: usize = 8192;
+
+/// Writer of per-result records to a Parquet file, for querying with tools such as Spark and duckdb.
+///
+/// One row is written for each TP and FP result and each FN GT, with the status in the `status` column.
+/// Columns of the estimation are prefixed with `est_`, and ones of the GT with `gt_`.
+/// Columns of the missing side, and matching scores of records without a paired GT, are null.
+///
+/// # Examples
+/// ```
+/// use perception_eval::result::columnar::{ColumnarResult, ResultRecordWriter};
+/// use perception_eval::matching::PlaneDistanceConfig;
+///
+/// fn main() -> ColumnarResult<()> {
+///     let quickstart = perception_eval::quickstart();
+///     let mut manager = quickstart.manager();
+///     for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+///         manager.add_frame_result(estimations, frame).unwrap();
+///     }
+///
+///     let mut writer = ResultRecordWriter::create(
+///         std::env::temp_dir().join("perception_eval_doc_results.parquet"),
+///         &PlaneDistanceConfig::default(),
+///     )?;
+///     for frame_result in &manager.frame_results {
+///         writer.write_frame(frame_result, None)?;
+///     }
+///     assert!(writer.finish()? > 0);
+///     Ok(())
+/// }
+/// ```
+pub struct ResultRecordWriter {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    methods: Vec<Box<dyn MatchingMethod>>,
+    columns: RecordColumns,
+    num_rows: usize,
+}
+
+impl ResultRecordWriter {
+    /// Create the Parquet file and construct `ResultRecordWriter`.
+    /// If parent directories do not exist, they are created.
+    ///
+    /// * `path`                    - Path of the Parquet file.
+    /// * `plane_distance_config`   - Configuration used for `MatchingMode::PlaneDistance`.
+    pub fn create<P>(path: P, plane_distance_config: &PlaneDistanceConfig) -> ColumnarResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let schema = Arc::new(record_schema());
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let writer = ArrowWriter::try_new(
+            create_file(path.as_ref())?,
+            schema.clone(),
+            Some(properties),
+        )?;
+        let methods = SCORE_MODES
+            .iter()
+            .map(|(_, mode)| get_matching_method(mode, plane_distance_config))
+            .collect();
+
+        Ok(Self {
+            writer,
+            schema,
+            methods,
+            columns: RecordColumns::default(),
+            num_rows: 0,
+        })
+    }
+
+    /// Write records of TP, FP and FN at one frame.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult at the frame.
+    /// * `frame_label`     - Label of the frame on the scene timeline, if available.
+    pub fn write_frame(
+        &mut self,
+        frame_result: &PerceptionFrameResult,
+        frame_label: Option<&FrameLabel>,
+    ) -> ColumnarResult<()> {
+        let frame = FrameColumns {
+            timestamp: frame_result
+                .frame_ground_truth()
+                .timestamp
+                .and_utc()
+                .timestamp_micros(),
+            label: frame_label,
+            sample_token: frame_result.frame_ground_truth().sample_token.as_deref(),
+        };
+        frame_result
+            .tp_results()
+            .iter()
+            .for_each(|result| self.push_result(&frame, "TP", result));
+        frame_result
+            .fp_results()
+            .iter()
+            .for_each(|result| self.push_result(&frame, "FP", result));
+        frame_result.fn_objects().iter().for_each(|object| {
+            self.columns
+                .push(&frame, "FN", None, Some(object), &[None; SCORE_MODES.len()])
+        });
+
+        if self.columns.len() >= ROWS_PER_BATCH {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write buffered records and the footer of the file, and returns the number of written rows.
+    pub fn finish(mut self) -> ColumnarResult<usize> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(self.num_rows)
+    }
+
+    fn push_result(&mut self, frame: &FrameColumns, status: &str, result: &PerceptionResult) {
+        let mut scores = [None; SCORE_MODES.len()];
+        if let Some(ground_truth) = &result.ground_truth_object {
+            scores
+                .iter_mut()
+                .zip(&self.methods)
+                .for_each(|(score, method)| {
+                    *score = Some(
+                        method.calculate_matching_score(&result.estimated_object, ground_truth),
+                    )
+                });
+        }
+        self.columns.push(
+            frame,
+            status,
+            Some(&result.estimated_object),
+            result.ground_truth_object.as_ref(),
+            &scores,
+        );
+    }
+
+    fn flush(&mut self) -> ColumnarResult<()> {
+        let num_rows = self.columns.len();
+        if num_rows == 0 {
+            return Ok(());
+        }
+        let batch = RecordBatch::try_new(self.schema.clone(), self.columns.finish())?;
+        self.writer.write(&batch)?;
+        self.num_rows += num_rows;
+        Ok(())
+    }
+}
+
+/// Returns the schema of records.
+fn record_schema() -> Schema {
+    let mut fields = vec![
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Microsecond, None),
+            false,
+        ),
+        Field::new("frame_sequence", DataType::UInt64, true),
+        Field::new("scene_time", DataType::Float64, true),
+        Field::new("sample_token", DataType::Utf8, true),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("label", DataType::Utf8, false),
+        Field::new("confidence", DataType::Float64, true),
+    ];
+    SCORE_MODES
+        .iter()
+        .for_each(|(name, _)| fields.push(Field::new(*name, DataType::Float64, true)));
+    ["est", "gt"].iter().for_each(|prefix| {
+        fields.push(Field::new(format!("{}_uuid", prefix), DataType::Utf8, true));
+        OBJECT_VALUES.iter().for_each(|name| {
+            fields.push(Field::new(
+                format!("{}_{}", prefix, name),
+                DataType::Float64,
+                true,
+            ))
+        });
+    });
+    Schema::new(fields)
+}
+
+/// Names of float values of an object, in the order of `object_values()`.
+const OBJECT_VALUES: [&str; 13] = [
+    "x", "y", "z", "qw", "qx", "qy", "qz", "width", "length", "height", "vx", "vy", "vz",
+];
+
+/// Returns float values of the object, in the order of `OBJECT_VALUES`.
+fn object_values(object: &DynamicObject) -> [Option<f64>; 13] {
+    let [x, y, z] = object.position.map(Some);
+    let [qw, qx, qy, qz] = object.orientation.map(Some);
+    let [width, length, height] = object.size.map(Some);
+    let [vx, vy, vz] = match object.velocity {
+        Some(velocity) => velocity.map(Some),
+        None => [None; 3],
+    };
+    [x, y, z, qw, qx, qy, qz, width, length, height, vx, vy, vz]
+}
+
+/// Frame information shared by records at one frame.
+struct FrameColumns<'a> {
+    timestamp: i64,
+    label: Option<&'a FrameLabel>,
+    sample_token: Option<&'a str>,
+}
+
+/// Column builders of either the estimation or the GT.
+#[derive(Default)]
+struct ObjectColumns {
+    uuid: StringBuilder,
+    values: [Float64Builder; 13],
+}
+
+impl ObjectColumns {
+    fn push(&mut self, object: Option<&DynamicObject>) {
+        match object {
+            Some(object) => {
+                self.uuid.append_option(object.uuid.as_deref());
+                self.values
+                    .iter_mut()
+                    .zip(object_values(object))
+                    .for_each(|(builder, value)| builder.append_option(value));
+            }
+            None => {
+                self.uuid.append_null();
+                self.values
+                    .iter_mut()
+                    .for_each(|builder| builder.append_null());
+            }
+        }
+    }
+
+    fn finish(&mut self, arrays: &mut Vec<ArrayRef>) {
+        arrays.push(Arc::new(self.uuid.finish()));
+        self.values
+            .iter_mut()
+            .for_each(|builder| arrays.push(Arc::new(builder.finish())));
+    }
+}
+
+/// Column builders of buffered records, in the order of `record_schema()`.
+#[derive(Default)]
+struct RecordColumns {
+    timestamp: TimestampMicrosecondBuilder,
+    frame_sequence: UInt64Builder,
+    scene_time: Float64Builder,
+    sample_token: StringBuilder,
+    status: StringBuilder,
+    label: StringBuilder,
+    confidence: Float64Builder,
+    scores: [Float64Builder; SCORE_MODES.len()],
+    estimation: ObjectColumns,
+    ground_truth: ObjectColumns,
+}
+
+impl RecordColumns {
+    fn len(&self) -> usize {
+        self.status.len()
+    }
+
+    fn push(
+        &mut self,
+        frame: &FrameColumns,
+        status: &str,
+        estimation: Option<&DynamicObject>,
+        ground_truth: Option<&DynamicObject>,
+        scores: &[Option<f64>; SCORE_MODES.len()],
+    ) {
+        self.timestamp.append_value(frame.timestamp);
+        self.frame_sequence
+            .append_option(frame.label.map(|label| label.sequence as u64));
+        self.scene_time
+            .append_option(frame.label.map(|label| label.scene_time));
+        self.sample_token.append_option(frame.sample_token);
+        self.status.append_value(status);
+        // The label of FN records is the one of the GT.
+        let label = estimation
+            .or(ground_truth)
+            .map_or(String::new(), |object| object.label.to_string());
+        self.label.append_value(label);
+        self.confidence
+            .append_option(estimation.map(|object| object.confidence));
+        self.scores
+            .iter_mut()
+            .zip(scores)
+            .for_each(|(builder, score)| builder.append_option(*score));
+        self.estimation.push(estimation);
+        self.ground_truth.push(ground_truth);
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        let mut arrays: Vec<ArrayRef> = vec![
+            Arc::new(self.timestamp.finish()),
+            Arc::new(self.frame_sequence.finish()),
+            Arc::new(self.scene_time.finish()),
+            Arc::new(self.sample_token.finish()),
+            Arc::new(self.status.finish()),
+            Arc::new(self.label.finish()),
+            Arc::new(self.confidence.finish()),
+        ];
+        self.scores
+            .iter_mut()
+            .for_each(|builder| arrays.push(Arc::new(builder.finish())));
+        self.estimation.finish(&mut arrays);
+        self.ground_truth.finish(&mut arrays);
+        arrays
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResultRecordWriter;
+    use crate::{
+        dataset::{timeline::SceneTimeline, FrameGroundTruth},
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use arrow_array::{cast::AsArray, types::Float64Type, Array};
+    use chrono::DateTime;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn test_result_record_writer() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let ground_truth = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 4.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("100".to_string()),
+            class_probabilities: None,
+        };
+        let missed = DynamicObject {
+            position: [-10.0, 0.0, 0.0],
+            label: Label::Pedestrian,
+            uuid: Some("200".to_string()),
+            ..ground_truth.clone()
+        };
+        let tp = DynamicObject {
+            position: [10.5, 0.0, 0.0],
+            confidence: 0.9,
+            uuid: None,
+            ..ground_truth.clone()
+        };
+        let fp = DynamicObject {
+            position: [50.0, 0.0, 0.0],
+            confidence: 0.3,
+            uuid: None,
+            ..ground_truth.clone()
+        };
+        let frame_ground_truth = FrameGroundTruth {
+            timestamp,
+            objects: vec![ground_truth.clone(), missed],
+            ego_translation: None,
+            ego_rotation: None,
+            sample_token: Some("token".to_string()),
+            version: None,
+            raw_pointcloud: None,
+            location: None,
+        };
+        let timeline = SceneTimeline::new(std::slice::from_ref(&frame_ground_truth));
+        let frame_result = PerceptionFrameResult::new(
+            get_perception_results(&[tp, fp], &frame_ground_truth.objects),
+            frame_ground_truth,
+            &[Label::Car, Label::Pedestrian],
+            MatchingMode::CenterDistance,
+            &[1.0, 1.0],
+            &PlaneDistanceConfig::default(),
+        )
+        .unwrap();
+
+        let path = std::env::temp_dir().join("perception_eval_test_columnar/results.parquet");
+        let mut writer =
+            ResultRecordWriter::create(&path, &PlaneDistanceConfig::default()).unwrap();
+        writer
+            .write_frame(&frame_result, timeline.get(&timestamp))
+            .unwrap();
+        assert_eq!(writer.finish().unwrap(), 3);
+
+        let batches = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        let status = batch.column_by_name("status").unwrap().as_string::<i32>();
+        assert_eq!(
+            status.iter().collect::<Vec<_>>(),
+            vec![Some("TP"), Some("FP"), Some("FN")]
+        );
+        let center_distance = batch
+            .column_by_name("center_distance")
+            .unwrap()
+            .as_primitive::<Float64Type>();
+        assert!((center_distance.value(0) - 0.5).abs() < 1e-9);
+        assert!(center_distance.is_null(2));
+        let gt_uuid = batch.column_by_name("gt_uuid").unwrap().as_string::<i32>();
+        assert_eq!(gt_uuid.value(2), "200");
+        let label = batch.column_by_name("label").unwrap().as_string::<i32>();
+        assert_eq!(label.value(2), Label::Pedestrian.to_string());
+        let scene_time = batch
+            .column_by_name("scene_time")
+            .unwrap()
+            .as_primitive::<Float64Type>();
+        assert_eq!(scene_time.value(0), 0.0);
+    }
+}
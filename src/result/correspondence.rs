@@ -0,0 +1,291 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Formatter, Result as FormatResult},
+};
+
+use crate::object::object3d::DynamicObject;
+
+use super::frame::PerceptionFrameResult;
+
+/// Builder of `IdCorrespondence` accumulating TP associations over a scene.
+///
+/// Estimated track ids rarely match GT instance tokens, so each estimated id is mapped to
+/// the GT instance with which it is associated as TP in the majority of frames.
+///
+/// * `associations`    - Number of TP associations for each pair of estimated and GT ids.
+/// * `num_estimations` - Number of frames each estimated id appears in.
+/// * `num_ground_truths` - Number of frames each GT id appears in.
+#[derive(Debug, Clone, Default)]
+pub struct IdCorrespondenceBuilder {
+    associations: HashMap<String, HashMap<String, usize>>,
+    num_estimations: HashMap<String, usize>,
+    num_ground_truths: HashMap<String, usize>,
+}
+
+impl IdCorrespondenceBuilder {
+    /// Construct empty `IdCorrespondenceBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accumulate associations at one frame.
+    /// Objects without uuid are ignored.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult at one frame.
+    pub fn add_frame_result(&mut self, frame_result: &PerceptionFrameResult) -> &mut Self {
+        frame_result
+            .results()
+            .iter()
+            .filter_map(|result| result.estimated_object.uuid.as_ref())
+            .for_each(|uuid| *self.num_estimations.entry(uuid.to_owned()).or_default() += 1);
+
+        frame_result
+            .frame_ground_truth()
+            .objects
+            .iter()
+            .filter_map(|object| object.uuid.as_ref())
+            .for_each(|uuid| *self.num_ground_truths.entry(uuid.to_owned()).or_default() += 1);
+
+        frame_result.tp_results().iter().for_each(|result| {
+            let gt_uuid = result
+                .ground_truth_object
+                .as_ref()
+                .and_then(|gt| gt.uuid.as_ref());
+            if let (Some(est_uuid), Some(gt_uuid)) = (&result.estimated_object.uuid, gt_uuid) {
+                *self
+                    .associations
+                    .entry(est_uuid.to_owned())
+                    .or_default()
+                    .entry(gt_uuid.to_owned())
+                    .or_default() += 1;
+            }
+        });
+        self
+    }
+
+    /// Build `IdCorrespondence` by majority association.
+    /// If the number of associations ties, the smallest GT id is selected.
+    pub fn build(&self) -> IdCorrespondence {
+        let mut mapping = BTreeMap::new();
+        let mut purity = BTreeMap::new();
+        let mut best_counts: HashMap<&String, usize> = HashMap::new();
+
+        for (est_uuid, num_frames) in &self.num_estimations {
+            let majority = self.associations.get(est_uuid).and_then(|counts| {
+                counts
+                    .iter()
+                    .max_by(|(a_uuid, a), (b_uuid, b)| a.cmp(b).then(b_uuid.cmp(a_uuid)))
+            });
+            match majority {
+                Some((gt_uuid, count)) => {
+                    mapping.insert(est_uuid.to_owned(), gt_uuid.to_owned());
+                    purity.insert(est_uuid.to_owned(), *count as f64 / *num_frames as f64);
+                    let best = best_counts.entry(gt_uuid).or_default();
+                    *best = (*best).max(*count);
+                }
+                None => {
+                    purity.insert(est_uuid.to_owned(), 0.0);
+                }
+            }
+        }
+
+        let completeness = self
+            .num_ground_truths
+            .iter()
+            .map(|(gt_uuid, num_frames)| {
+                let count = best_counts.get(gt_uuid).copied().unwrap_or(0);
+                (gt_uuid.to_owned(), count as f64 / *num_frames as f64)
+            })
+            .collect();
+
+        IdCorrespondence {
+            mapping,
+            purity,
+            completeness,
+        }
+    }
+}
+
+/// Correspondence from estimated track ids to GT instance ids.
+///
+/// * `mapping`         - GT id corresponding to each estimated id.
+/// * `purity`          - Ratio of frames of each estimated track associated with its corresponding GT.
+/// * `completeness`    - Ratio of frames of each GT instance covered by the single best estimated track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdCorrespondence {
+    mapping: BTreeMap<String, String>,
+    purity: BTreeMap<String, f64>,
+    completeness: BTreeMap<String, f64>,
+}
+
+impl IdCorrespondence {
+    /// Construct `IdCorrespondence` from frame results over a scene.
+    ///
+    /// * `frame_results`   - List of PerceptionFrameResult.
+    pub fn from_frame_results(frame_results: &[PerceptionFrameResult]) -> Self {
+        let mut builder = IdCorrespondenceBuilder::new();
+        frame_results.iter().for_each(|frame_result| {
+            builder.add_frame_result(frame_result);
+        });
+        builder.build()
+    }
+
+    /// Returns GT id corresponding to the estimated id.
+    ///
+    /// * `estimated_uuid`  - Estimated track id.
+    pub fn get(&self, estimated_uuid: &str) -> Option<&str> {
+        self.mapping.get(estimated_uuid).map(|uuid| uuid.as_str())
+    }
+
+    /// Returns purity of the estimated track.
+    ///
+    /// * `estimated_uuid`  - Estimated track id.
+    pub fn purity(&self, estimated_uuid: &str) -> Option<f64> {
+        self.purity.get(estimated_uuid).copied()
+    }
+
+    /// Returns completeness of the GT instance.
+    ///
+    /// * `ground_truth_uuid`   - GT instance id.
+    pub fn completeness(&self, ground_truth_uuid: &str) -> Option<f64> {
+        self.completeness.get(ground_truth_uuid).copied()
+    }
+
+    /// Returns mean purity over estimated tracks. If there is no track, returns None.
+    pub fn mean_purity(&self) -> Option<f64> {
+        mean(self.purity.values())
+    }
+
+    /// Returns mean completeness over GT instances. If there is no instance, returns None.
+    pub fn mean_completeness(&self) -> Option<f64> {
+        mean(self.completeness.values())
+    }
+
+    /// Returns objects whose uuids are remapped into corresponding GT ids.
+    /// The uuid of objects without correspondence is kept.
+    ///
+    /// * `objects` - List of estimated objects.
+    pub fn remap(&self, objects: &[DynamicObject]) -> Vec<DynamicObject> {
+        objects
+            .iter()
+            .map(|object| {
+                let uuid = object
+                    .uuid
+                    .as_deref()
+                    .and_then(|uuid| self.get(uuid))
+                    .map(|uuid| uuid.to_owned())
+                    .or_else(|| object.uuid.to_owned());
+                DynamicObject {
+                    uuid,
+                    ..object.to_owned()
+                }
+            })
+            .collect()
+    }
+}
+
+impl Display for IdCorrespondence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let format_mean = |value: Option<f64>| match value {
+            Some(value) => format!("{:.3}", value),
+            None => "-".to_string(),
+        };
+        let mut msg = "\n[ID Correspondence]\n".to_string();
+        msg += &format!(
+            "num tracks: {}, num instances: {}, purity: {}, completeness: {}",
+            self.purity.len(),
+            self.completeness.len(),
+            format_mean(self.mean_purity()),
+            format_mean(self.mean_completeness())
+        );
+        writeln!(f, "{}\n", msg)
+    }
+}
+
+fn mean<'a, I>(values: I) -> Option<f64>
+where
+    I: ExactSizeIterator<Item = &'a f64>,
+{
+    let num = values.len();
+    if num == 0 {
+        None
+    } else {
+        Some(values.sum::<f64>() / num as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdCorrespondence;
+    use crate::{
+        dataset::FrameGroundTruth,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_id_correspondence() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let object = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: None,
+            class_probabilities: None,
+        };
+        let with_uuid = |uuid: &str| DynamicObject {
+            uuid: Some(uuid.to_string()),
+            ..object.clone()
+        };
+
+        // Track "7" follows instance "a" for 3 frames, then is swapped with "8" at the last frame.
+        let est_uuids = ["7", "7", "7", "8"];
+        let frame_results = est_uuids
+            .iter()
+            .map(|est_uuid| {
+                let estimations = vec![with_uuid(est_uuid)];
+                let ground_truths = vec![with_uuid("a")];
+                let results = get_perception_results(&estimations, &ground_truths);
+                PerceptionFrameResult::new(
+                    results,
+                    FrameGroundTruth {
+                        timestamp,
+                        objects: ground_truths,
+                        ego_translation: None,
+                        ego_rotation: None,
+                        sample_token: None,
+                        version: None,
+                        raw_pointcloud: None,
+                        location: None,
+                    },
+                    &[Label::Car],
+                    MatchingMode::CenterDistance,
+                    &[1.0],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let correspondence = IdCorrespondence::from_frame_results(&frame_results);
+        assert_eq!(correspondence.get("7"), Some("a"));
+        assert_eq!(correspondence.get("8"), Some("a"));
+        assert_eq!(correspondence.purity("7"), Some(1.0));
+        assert_eq!(correspondence.completeness("a"), Some(0.75));
+        assert_eq!(correspondence.mean_completeness(), Some(0.75));
+
+        let remapped = correspondence.remap(&[with_uuid("7"), with_uuid("9")]);
+        assert_eq!(remapped[0].uuid.as_deref(), Some("a"));
+        assert_eq!(remapped[1].uuid.as_deref(), Some("9"));
+    }
+}
@@ -1,12 +1,14 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     dataset::FrameGroundTruth,
     label::Label,
-    matching::{MatchingMode, MatchingResult},
+    matching::{CustomMatching, MatchingMode, MatchingResult, PlaneDistanceConfig},
     object::object3d::DynamicObject,
     threshold::get_label_threshold,
 };
 
-use super::object::PerceptionResult;
+use super::object::{get_perception_results_with, PerceptionResult};
 
 /// A set of `PerceptionResult` at one frame.
 ///
@@ -17,7 +19,7 @@ use super::object::PerceptionResult;
 /// * `tp_results`          - List of PerceptionResult determined as TP.
 /// * `fp_results`          - List of PerceptionResult determined as FP.
 /// * `fn_results`          - List of DynamicObject of GT determined as FN.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerceptionFrameResult {
     results: Vec<PerceptionResult>,
     frame_ground_truth: FrameGroundTruth,
@@ -47,6 +49,58 @@ impl PerceptionFrameResult {
         &self.fn_objects
     }
 
+    /// Returns the rough number of bytes occupied by the frame result in memory.
+    /// Heap memory owned by objects, such as UUIDs and class probabilities, is not counted.
+    pub(crate) fn estimated_bytes(&self) -> usize {
+        let num_results = self.results.len() + self.tp_results.len() + self.fp_results.len();
+        let num_objects = self.frame_ground_truth.objects.len() + self.fn_objects.len();
+        std::mem::size_of::<Self>()
+            + num_results * std::mem::size_of::<PerceptionResult>()
+            + num_objects * std::mem::size_of::<DynamicObject>()
+    }
+
+    /// Returns the copy of the frame result which only keeps results and GTs satisfying predicates.
+    /// TP, FP and FN determined at construction are kept as they are.
+    ///
+    /// * `keep_result`         - Function returning whether to keep the result.
+    /// * `keep_ground_truth`   - Function returning whether to keep the GT.
+    pub(crate) fn retain<R, G>(&self, keep_result: R, keep_ground_truth: G) -> Self
+    where
+        R: Fn(&PerceptionResult) -> bool,
+        G: Fn(&DynamicObject) -> bool,
+    {
+        let filter_results = |results: &[PerceptionResult]| {
+            results
+                .iter()
+                .filter(|result| keep_result(result))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let filter_objects = |objects: &[DynamicObject]| {
+            objects
+                .iter()
+                .filter(|object| keep_ground_truth(object))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        Self {
+            results: filter_results(&self.results),
+            frame_ground_truth: FrameGroundTruth {
+                timestamp: self.frame_ground_truth.timestamp,
+                objects: filter_objects(&self.frame_ground_truth.objects),
+                ego_translation: self.frame_ground_truth.ego_translation,
+                ego_rotation: None,
+                sample_token: self.frame_ground_truth.sample_token.to_owned(),
+                version: self.frame_ground_truth.version.to_owned(),
+                raw_pointcloud: self.frame_ground_truth.raw_pointcloud.to_owned(),
+                location: self.frame_ground_truth.location.to_owned(),
+            },
+            tp_results: filter_results(&self.tp_results),
+            fp_results: filter_results(&self.fp_results),
+            fn_objects: filter_objects(&self.fn_objects),
+        }
+    }
+
     /// Construct `PerceptionFrameResult`.
     ///
     /// * `results`             - List of PerceptionResult.
@@ -54,15 +108,22 @@ impl PerceptionFrameResult {
     /// * `target_labels`       - List of Label instances.
     /// * `matching_mode`       - MatchingMode to determine whether results are TP or FP.
     /// * `matching_thresholds` - List of matching thresholds.
+    /// * `plane_distance_config` - Configuration used for `MatchingMode::PlaneDistance`.
     pub fn new(
         results: Vec<PerceptionResult>,
         frame_ground_truth: FrameGroundTruth,
         target_labels: &[Label],
         matching_mode: MatchingMode,
         matching_thresholds: &[f64],
+        plane_distance_config: &PlaneDistanceConfig,
     ) -> MatchingResult<Self> {
-        let (tp_results, fp_results) =
-            separate_tp_fp_results(&results, target_labels, &matching_mode, matching_thresholds)?;
+        let (tp_results, fp_results) = separate_tp_fp_results(
+            &results,
+            target_labels,
+            &matching_mode,
+            matching_thresholds,
+            plane_distance_config,
+        )?;
         let fn_objects = extract_fn_objects(&frame_ground_truth.objects, &tp_results);
 
         let ret = Self {
@@ -75,6 +136,43 @@ impl PerceptionFrameResult {
 
         Ok(ret)
     }
+
+    /// Construct `PerceptionFrameResult` with the user-defined matching function,
+    /// which is used both to pair objects and to determine whether results are TP or FP.
+    ///
+    /// * `estimated_objects`   - List of estimated objects.
+    /// * `frame_ground_truth`  - Set of GT objects at current frame.
+    /// * `target_labels`       - List of Label instances.
+    /// * `matching`            - CustomMatching instance.
+    /// * `matching_thresholds` - List of thresholds of the score for each target label.
+    pub fn with_custom_matching<F>(
+        estimated_objects: &[DynamicObject],
+        frame_ground_truth: FrameGroundTruth,
+        target_labels: &[Label],
+        matching: &CustomMatching<F>,
+        matching_thresholds: &[f64],
+    ) -> Self
+    where
+        F: Fn(&DynamicObject, &DynamicObject) -> f64,
+    {
+        let results =
+            get_perception_results_with(estimated_objects, &frame_ground_truth.objects, matching);
+        let (tp_results, fp_results) = separate_results_by(
+            &results,
+            target_labels,
+            matching_thresholds,
+            |result, threshold| result.is_result_correct_by(matching, threshold),
+        );
+        let fn_objects = extract_fn_objects(&frame_ground_truth.objects, &tp_results);
+
+        Self {
+            results,
+            frame_ground_truth,
+            tp_results,
+            fp_results,
+            fn_objects,
+        }
+    }
 }
 
 /// Separate results into TP and FP results.
@@ -85,12 +183,43 @@ impl PerceptionFrameResult {
 /// * `target_labels`       - List of Label instances.
 /// * `matching_mode`       - MatchingMode instance to determine TP or FP.
 /// * `matching_thresholds` - List of matching thresholds.
+/// * `plane_distance_config` - Configuration used for `MatchingMode::PlaneDistance`.
 fn separate_tp_fp_results(
     results: &[PerceptionResult],
     target_labels: &[Label],
     matching_mode: &MatchingMode,
     matching_thresholds: &[f64],
+    plane_distance_config: &PlaneDistanceConfig,
 ) -> MatchingResult<(Vec<PerceptionResult>, Vec<PerceptionResult>)> {
+    plane_distance_config.validate()?;
+    Ok(separate_results_by(
+        results,
+        target_labels,
+        matching_thresholds,
+        |result, threshold| {
+            result
+                .is_result_correct_with(matching_mode, threshold, plane_distance_config)
+                .unwrap()
+        },
+    ))
+}
+
+/// Separate results into TP and FP results with the function to determine whether the result is correct.
+/// Results whose label is not a target are dropped.
+///
+/// * `results`             - List of PerceptionResult at current frame.
+/// * `target_labels`       - List of Label instances.
+/// * `matching_thresholds` - List of matching thresholds.
+/// * `is_correct`          - Function returning whether the result is TP with the threshold of its label.
+fn separate_results_by<C>(
+    results: &[PerceptionResult],
+    target_labels: &[Label],
+    matching_thresholds: &[f64],
+    is_correct: C,
+) -> (Vec<PerceptionResult>, Vec<PerceptionResult>)
+where
+    C: Fn(&PerceptionResult, &f64) -> bool,
+{
     let mut tp_results = Vec::new();
     let mut fp_results = Vec::new();
     results.iter().for_each(|result| {
@@ -99,16 +228,14 @@ fn separate_tp_fp_results(
             target_labels,
             matching_thresholds,
         ) {
-            let is_correct = result.is_result_correct(matching_mode, &threshold).unwrap(); // TODO
-            if is_correct {
+            if is_correct(result, &threshold) {
                 tp_results.push(result.clone());
             } else {
                 fp_results.push(result.clone());
             }
         }
     });
-
-    Ok((tp_results, fp_results))
+    (tp_results, fp_results)
 }
 
 /// Extract FN objects comparing whether input GTs are made up of TP results.
@@ -138,13 +265,10 @@ fn extract_fn_objects(
 /// * `tp_results`  : List of TP results.
 fn is_fn_object(ground_truth: &DynamicObject, tp_results: &[PerceptionResult]) -> bool {
     for tp in tp_results {
-        match &tp.ground_truth_object {
-            Some(gt) => {
-                if gt == ground_truth {
-                    return false;
-                }
+        if let Some(gt) = &tp.ground_truth_object {
+            if gt == ground_truth {
+                return false;
             }
-            None => (),
         }
     }
     true
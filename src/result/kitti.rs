@@ -0,0 +1,451 @@
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter, Result as FormatResult},
+    fs::File,
+    io::{BufWriter, Error as IoError, ErrorKind, Result as IoResult, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    dataset::{timeline::FrameLabel, FrameGroundTruth},
+    fault::create_file,
+    frame_id::FrameID,
+    label::Label,
+    object::object3d::DynamicObject,
+};
+
+use super::frame::PerceptionFrameResult;
+
+/// Placeholder of 2D boxes in [px], which is tall enough to pass the minimum height of every
+/// KITTI difficulty, since images are not available to compute actual boxes.
+pub const KITTI_PLACEHOLDER_BBOX: [f64; 4] = [0.0, 0.0, 100.0, 100.0];
+
+/// Object in the KITTI label format, whose 3D properties are in the KITTI camera coord system,
+/// where x points right, y points down and z points forward.
+///
+/// * `object_type` - KITTI type name, such as `Car` and `Cyclist`.
+/// * `truncated`   - Truncation ratio, which is always 0 since images are not available.
+/// * `occluded`    - Occlusion state, which is always 0 since images are not available.
+/// * `alpha`       - Observation angle in [-pi, pi].
+/// * `bbox`        - 2D box in [px], (left, top, right, bottom) order.
+/// * `dimensions`  - 3D box size in [m], (height, width, length) order.
+/// * `location`    - Bottom center of the 3D box in [m].
+/// * `rotation_y`  - Rotation around the y axis in [-pi, pi].
+/// * `score`       - Confidence of the detection. None for GTs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KittiObject {
+    pub object_type: &'static str,
+    pub truncated: f64,
+    pub occluded: u8,
+    pub alpha: f64,
+    pub bbox: [f64; 4],
+    pub dimensions: [f64; 3],
+    pub location: [f64; 3],
+    pub rotation_y: f64,
+    pub score: Option<f64>,
+}
+
+impl KittiObject {
+    /// Construct `KittiObject` from the object in `base_link`, where x points forward,
+    /// y points left and z points up. The camera is regarded as at the origin of `base_link`.
+    /// If the object is not in `base_link`, returns None.
+    ///
+    /// * `object`  - DynamicObject instance.
+    /// * `score`   - Confidence written for detections. None for GTs.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDateTime;
+    /// use perception_eval::{
+    ///     frame_id::FrameID, label::Label, object::object3d::DynamicObject, result::kitti::KittiObject,
+    /// };
+    ///
+    /// let object = DynamicObject::new(
+    ///     NaiveDateTime::default(),
+    ///     FrameID::BaseLink,
+    ///     [10.0, 0.0, 1.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 4.0, 1.5],
+    ///     0.9,
+    ///     Label::Car,
+    /// );
+    ///
+    /// let kitti = KittiObject::from_object(&object, Some(object.confidence)).unwrap();
+    ///
+    /// assert_eq!(kitti.location, [0.0, -0.25, 10.0]);
+    /// assert_eq!(kitti.dimensions, [1.5, 2.0, 4.0]);
+    /// assert!((kitti.rotation_y + 0.5 * std::f64::consts::PI).abs() < 1e-9);
+    /// assert!((kitti.alpha + 0.5 * std::f64::consts::PI).abs() < 1e-9);
+    ///
+    /// let mut object = object;
+    /// object.frame_id = FrameID::Map;
+    /// assert!(KittiObject::from_object(&object, None).is_none());
+    /// ```
+    pub fn from_object(object: &DynamicObject, score: Option<f64>) -> Option<Self> {
+        if object.frame_id != FrameID::BaseLink {
+            return None;
+        }
+        let [x, y, z] = object.position;
+        let [width, length, height] = object.size;
+        let location = [-y, -(z - 0.5 * height), x];
+        let rotation_y = normalize_angle(-yaw(object) - 0.5 * PI);
+        let alpha = normalize_angle(rotation_y - location[0].atan2(location[2]));
+        Some(Self {
+            object_type: kitti_type(&object.label),
+            truncated: 0.0,
+            occluded: 0,
+            alpha,
+            bbox: KITTI_PLACEHOLDER_BBOX,
+            dimensions: [height, width, length],
+            location,
+            rotation_y,
+            score,
+        })
+    }
+
+    /// Construct `KittiObject` from the object in the frame, which is transformed into `base_link`
+    /// with the ego pose of the frame if needed.
+    ///
+    /// * `object`              - DynamicObject instance.
+    /// * `score`               - Confidence written for detections. None for GTs.
+    /// * `frame_ground_truth`  - FrameGroundTruth of the frame, which has the ego pose.
+    fn from_frame_object(
+        object: &DynamicObject,
+        score: Option<f64>,
+        frame_ground_truth: &FrameGroundTruth,
+    ) -> IoResult<Self> {
+        let transformed = match (
+            &object.frame_id,
+            &frame_ground_truth.ego_translation,
+            &frame_ground_truth.ego_rotation,
+        ) {
+            (FrameID::BaseLink, _, _) => None,
+            (_, Some(translation), Some(rotation)) => {
+                object.transform(&FrameID::BaseLink, translation, rotation)
+            }
+            _ => None,
+        };
+        Self::from_object(transformed.as_ref().unwrap_or(object), score).ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "object in {:?} cannot be transformed into base_link at {}",
+                    object.frame_id, frame_ground_truth.timestamp
+                ),
+            )
+        })
+    }
+}
+
+impl Display for KittiObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let [left, top, right, bottom] = self.bbox;
+        let [height, width, length] = self.dimensions;
+        let [x, y, z] = self.location;
+        write!(
+            f,
+            "{} {:.2} {} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2}",
+            self.object_type,
+            self.truncated,
+            self.occluded,
+            self.alpha,
+            left,
+            top,
+            right,
+            bottom,
+            height,
+            width,
+            length,
+            x,
+            y,
+            z,
+            self.rotation_y
+        )?;
+        if let Some(score) = self.score {
+            write!(f, " {:.4}", score)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the KITTI type name of the label.
+/// Labels without the corresponding KITTI type are named `Misc`.
+///
+/// * `label`   - Label instance.
+pub fn kitti_type(label: &Label) -> &'static str {
+    match label {
+        Label::Car => "Car",
+        Label::Truck => "Truck",
+        Label::Bus => "Tram",
+        Label::Bicycle | Label::Motorbike => "Cyclist",
+        Label::Pedestrian => "Pedestrian",
+        Label::Unknown | Label::Animal => "Misc",
+    }
+}
+
+/// Writer of frame results in the KITTI format, which can be evaluated by KITTI evaluation tools.
+///
+/// For each frame, the following files are written under the root directory.
+///
+/// * `label_2/<index>.txt` - GTs in the KITTI label format.
+/// * `data/<index>.txt`    - TP and FP detections in the KITTI result format with scores.
+/// * `pairs/<index>.txt`   - Matched pairs as `<detection line> <GT line> <status>`,
+///   where line numbers start from 0 and -1 means no counterpart. Status is `TP`, `FP` or `FN`.
+///
+/// `frames.txt` maps frame indices to timestamps in [us] and sample tokens.
+///
+/// Objects not in `base_link` are transformed with the ego pose of the frame,
+/// and writing the frame fails if they cannot be transformed.
+///
+/// # Examples
+/// ```
+/// use perception_eval::result::kitti::KittiWriter;
+///
+/// let quickstart = perception_eval::quickstart();
+///
+/// let mut manager = quickstart.manager();
+/// for (frame, estimations) in quickstart.frame_ground_truths.iter().zip(&quickstart.estimations) {
+///     manager.add_frame_result(estimations, frame).unwrap();
+/// }
+/// let num_frames = manager.export_kitti("work_dir/kitti").unwrap();
+///
+/// assert_eq!(num_frames, quickstart.frame_ground_truths.len());
+/// assert!(std::path::Path::new("work_dir/kitti/label_2/000000.txt").exists());
+/// ```
+#[derive(Debug)]
+pub struct KittiWriter {
+    root: PathBuf,
+    frames: BufWriter<File>,
+    num_frames: usize,
+}
+
+impl KittiWriter {
+    /// Create directories of the KITTI format under the root directory.
+    ///
+    /// * `root`    - Root directory path.
+    pub fn create<P>(root: P) -> IoResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref().to_owned();
+        for dir in ["label_2", "data", "pairs"] {
+            std::fs::create_dir_all(root.join(dir))?;
+        }
+        let frames = BufWriter::new(create_file(root.join("frames.txt"))?);
+        Ok(Self {
+            root,
+            frames,
+            num_frames: 0,
+        })
+    }
+
+    /// Write the frame result as the next frame.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult instance.
+    /// * `frame_label`     - Label of the frame in the scene, whose sample token is preferred if any.
+    pub fn write_frame(
+        &mut self,
+        frame_result: &PerceptionFrameResult,
+        frame_label: Option<&FrameLabel>,
+    ) -> IoResult<()> {
+        let name = format!("{:06}.txt", self.num_frames);
+        let ground_truths = &frame_result.frame_ground_truth().objects;
+
+        let mut label_writer = BufWriter::new(create_file(self.root.join("label_2").join(&name))?);
+        for object in ground_truths {
+            let kitti =
+                KittiObject::from_frame_object(object, None, frame_result.frame_ground_truth())?;
+            writeln!(label_writer, "{}", kitti)?;
+        }
+        label_writer.flush()?;
+
+        let mut data_writer = BufWriter::new(create_file(self.root.join("data").join(&name))?);
+        let mut pairs_writer = BufWriter::new(create_file(self.root.join("pairs").join(&name))?);
+        let detections = frame_result
+            .tp_results()
+            .iter()
+            .map(|result| (result, "TP"))
+            .chain(
+                frame_result
+                    .fp_results()
+                    .iter()
+                    .map(|result| (result, "FP")),
+            );
+        for (i, (result, status)) in detections.enumerate() {
+            let object = &result.estimated_object;
+            let kitti = KittiObject::from_frame_object(
+                object,
+                Some(object.confidence),
+                frame_result.frame_ground_truth(),
+            )?;
+            writeln!(data_writer, "{}", kitti)?;
+            let gt_index = result
+                .ground_truth_object
+                .as_ref()
+                .and_then(|gt| ground_truths.iter().position(|object| object == gt));
+            writeln!(pairs_writer, "{} {} {}", i, line_index(gt_index), status)?;
+        }
+        for object in frame_result.fn_objects() {
+            let gt_index = ground_truths.iter().position(|gt| gt == object);
+            writeln!(pairs_writer, "-1 {} FN", line_index(gt_index))?;
+        }
+        data_writer.flush()?;
+        pairs_writer.flush()?;
+
+        let frame_ground_truth = frame_result.frame_ground_truth();
+        let sample_token = frame_label
+            .and_then(|label| label.sample_token.as_ref())
+            .or(frame_ground_truth.sample_token.as_ref())
+            .map_or("-", |token| token.as_str());
+        writeln!(
+            self.frames,
+            "{:06} {} {}",
+            self.num_frames,
+            frame_ground_truth.timestamp.and_utc().timestamp_micros(),
+            sample_token
+        )?;
+        self.num_frames += 1;
+        Ok(())
+    }
+
+    /// Flush the index of frames, and returns the number of written frames.
+    pub fn finish(mut self) -> IoResult<usize> {
+        self.frames.flush()?;
+        Ok(self.num_frames)
+    }
+}
+
+fn line_index(index: Option<usize>) -> String {
+    index.map_or("-1".to_string(), |i| i.to_string())
+}
+
+/// Returns yaw angle of the object in [-pi, pi].
+///
+/// * `object`  - DynamicObject instance.
+fn yaw(object: &DynamicObject) -> f64 {
+    let [q0, q1, q2, q3] = object.orientation;
+    (2.0 * (q0 * q3 + q1 * q2)).atan2(1.0 - 2.0 * (q2.powi(2) + q3.powi(2)))
+}
+
+/// Returns the angle normalized into [-pi, pi].
+fn normalize_angle(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(2.0 * PI);
+    if PI < angle {
+        angle - 2.0 * PI
+    } else {
+        angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KittiObject;
+    use crate::{
+        dataset::FrameGroundTruth, frame_id::FrameID, label::Label, object::object3d::DynamicObject,
+    };
+    use chrono::NaiveDateTime;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_kitti_object() {
+        let object = DynamicObject {
+            timestamp: NaiveDateTime::default(),
+            frame_id: FrameID::BaseLink,
+            position: [0.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 4.0, 1.5],
+            velocity: None,
+            confidence: 0.5,
+            label: Label::Bicycle,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        };
+        // (yaw, position, rotation_y, alpha)
+        let cases = [
+            (0.25 * PI, [0.0, 10.0, 0.0], -0.75 * PI, -0.25 * PI),
+            (0.75 * PI, [-10.0, 10.0, 0.0], 0.75 * PI, -0.5 * PI),
+            (-0.5 * PI, [0.0, -10.0, 0.0], 0.0, -0.5 * PI),
+            (0.0, [10.0, 0.0, 0.0], -0.5 * PI, -0.5 * PI),
+        ];
+        for (yaw, position, rotation_y, alpha) in cases {
+            let object = DynamicObject {
+                position,
+                orientation: [(0.5 * yaw).cos(), 0.0, 0.0, (0.5 * yaw).sin()],
+                ..object.clone()
+            };
+            let kitti = KittiObject::from_object(&object, Some(object.confidence)).unwrap();
+            assert!(
+                (kitti.rotation_y - rotation_y).abs() < 1e-9,
+                "yaw {}: {}",
+                yaw,
+                kitti.rotation_y
+            );
+            assert!(
+                (kitti.alpha - alpha).abs() < 1e-9,
+                "yaw {}: {}",
+                yaw,
+                kitti.alpha
+            );
+        }
+
+        let line = KittiObject::from_object(&object, Some(0.5))
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            line,
+            "Cyclist 0.00 0 -1.57 0.00 0.00 100.00 100.00 1.50 2.00 4.00 -0.00 0.75 0.00 -1.57 0.5000"
+        );
+        assert_eq!(line.split(' ').count(), 16);
+        assert_eq!(
+            KittiObject::from_object(&object, None)
+                .unwrap()
+                .to_string()
+                .split(' ')
+                .count(),
+            15
+        );
+    }
+
+    #[test]
+    fn test_kitti_object_in_map() {
+        let object = DynamicObject {
+            timestamp: NaiveDateTime::default(),
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 2.0, 1.0],
+            orientation: [(0.1f64).cos(), 0.0, 0.0, (0.1f64).sin()],
+            size: [2.0, 4.0, 1.5],
+            velocity: None,
+            confidence: 0.5,
+            label: Label::Car,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        };
+        let translation = [100.0, 200.0, 0.0];
+        let rotation = [(0.4f64).cos(), 0.0, 0.0, (0.4f64).sin()];
+        let mut frame = FrameGroundTruth {
+            timestamp: object.timestamp,
+            objects: Vec::new(),
+            ego_translation: Some(translation),
+            ego_rotation: Some(rotation),
+            sample_token: None,
+            version: None,
+            raw_pointcloud: None,
+            location: None,
+        };
+        let in_map = object
+            .transform(&FrameID::Map, &translation, &rotation)
+            .unwrap();
+
+        // Objects in map are exported as same as in base_link.
+        let expected = KittiObject::from_object(&object, None).unwrap();
+        let kitti = KittiObject::from_frame_object(&in_map, None, &frame).unwrap();
+        assert_eq!(kitti.to_string(), expected.to_string());
+
+        // Objects cannot be transformed without the ego pose.
+        frame.ego_rotation = None;
+        assert!(KittiObject::from_frame_object(&in_map, None, &frame).is_err());
+        assert!(KittiObject::from_frame_object(&object, None, &frame).is_ok());
+    }
+}
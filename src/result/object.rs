@@ -1,11 +1,14 @@
-use std::vec;
+use std::{borrow::Borrow, vec};
+
+use serde::{Deserialize, Serialize};
 
 use crate::{
     matching::{
-        CenterDistanceMatching, Iou2dMatching, Iou3dMatching, MatchingMethod, MatchingMode,
-        MatchingResult, PlaneDistanceMatching,
+        get_matching_method, CenterDistanceMatching, CustomMatching, MatchingMethod, MatchingMode,
+        MatchingResult, PlaneDistanceConfig,
     },
     object::object3d::DynamicObject,
+    utils::grid::BevGrid,
 };
 
 /// Struct for matching pair of estimated and ground truth objects.
@@ -13,7 +16,7 @@ use crate::{
 ///
 /// * `estimated_object`    - Estimated object.
 /// * `ground_truth_object` - Ground truth object.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerceptionResult {
     pub estimated_object: DynamicObject,
     pub ground_truth_object: Option<DynamicObject>,
@@ -30,31 +33,29 @@ impl PerceptionResult {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject, result::object::PerceptionResult};
     ///
-    /// let estimation = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let estimation = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
-    /// let ground_truth = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("100".to_string()),
-    /// };
+    /// let ground_truth = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("100");
     ///
     /// // Get TP or FP result
     /// let result = PerceptionResult::new(estimation, Some(ground_truth));
@@ -79,31 +80,29 @@ impl PerceptionResult {
     /// use chrono::NaiveDateTime;
     /// use perception_eval::{frame_id::FrameID, label::Label, object::object3d::DynamicObject, result::object::PerceptionResult};
     ///
-    /// let estimation = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let estimation = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
-    /// let ground_truth = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("100".to_string()),
-    /// };
+    /// let ground_truth = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("100");
     ///
     /// let result = PerceptionResult::new(estimation, Some(ground_truth));
     ///
@@ -135,31 +134,29 @@ impl PerceptionResult {
     ///     result::object::PerceptionResult
     /// };
     ///
-    /// let estimation = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("111".to_string()),
-    /// };
+    /// let estimation = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("111");
     ///
-    /// let ground_truth = DynamicObject {
-    ///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-    ///     frame_id: FrameID::BaseLink,
-    ///     position: [1.0, 1.0, 0.0],
-    ///     orientation: [1.0, 0.0, 0.0, 0.0],
-    ///     size: [2.0, 1.0, 1.0],
-    ///     velocity: None,
-    ///     confidence: 1.0,
-    ///     label: Label::Car,
-    ///     pointcloud_num: Some(1000),
-    ///     uuid: Some("100".to_string()),
-    /// };
+    /// let ground_truth = DynamicObject::new(
+    ///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+    ///     FrameID::BaseLink,
+    ///     [1.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [2.0, 1.0, 1.0],
+    ///     1.0,
+    ///     Label::Car,
+    /// )
+    /// .with_pointcloud_num(1000)
+    /// .with_uuid("100");
     ///
     /// let result = PerceptionResult::new(estimation, Some(ground_truth));
     ///
@@ -171,14 +168,23 @@ impl PerceptionResult {
         matching_mode: &MatchingMode,
         threshold: &f64,
     ) -> MatchingResult<bool> {
-        let matching_method: Box<dyn MatchingMethod> = {
-            match matching_mode {
-                MatchingMode::CenterDistance => Box::new(CenterDistanceMatching),
-                MatchingMode::PlaneDistance => Box::new(PlaneDistanceMatching),
-                MatchingMode::Iou2d => Box::new(Iou2dMatching),
-                MatchingMode::Iou3d => Box::new(Iou3dMatching),
-            }
-        };
+        self.is_result_correct_with(matching_mode, threshold, &PlaneDistanceConfig::default())
+    }
+
+    /// Returns whether result is correct, it means TP (=True Positive),
+    /// with specifying the configuration of plane distance matching.
+    ///
+    /// * `matching_mode`           - MatchingMode instance.
+    /// * `threshold`               - Threshold value.
+    /// * `plane_distance_config`   - Configuration used for `MatchingMode::PlaneDistance`.
+    pub fn is_result_correct_with(
+        &self,
+        matching_mode: &MatchingMode,
+        threshold: &f64,
+        plane_distance_config: &PlaneDistanceConfig,
+    ) -> MatchingResult<bool> {
+        plane_distance_config.validate()?;
+        let matching_method = get_matching_method(matching_mode, plane_distance_config);
         let is_correct = {
             match &self.ground_truth_object {
                 Some(gt) => matching_method.is_better_than(&self.estimated_object, gt, threshold),
@@ -187,9 +193,26 @@ impl PerceptionResult {
         };
         Ok(is_correct)
     }
+
+    /// Returns whether result is correct, it means TP (=True Positive),
+    /// with the user-defined matching function.
+    /// If ground truth is None, returns false.
+    ///
+    /// * `matching`    - CustomMatching instance.
+    /// * `threshold`   - Threshold of the score.
+    pub fn is_result_correct_by<F>(&self, matching: &CustomMatching<F>, threshold: &f64) -> bool
+    where
+        F: Fn(&DynamicObject, &DynamicObject) -> f64,
+    {
+        match &self.ground_truth_object {
+            Some(gt) => matching.is_matched(&self.estimated_object, gt, threshold),
+            None => false,
+        }
+    }
 }
 
 /// Returns list of `PerceptionResult`.
+/// Both owned objects and references to objects can be input.
 ///
 /// * `estimated_objects`       - List of estimated objects.
 /// * `ground_truth_objects`    - List of ground truth objects.
@@ -205,139 +228,504 @@ impl PerceptionResult {
 ///     result::object::{PerceptionResult, get_perception_results},
 /// };
 ///
-/// let estimation = DynamicObject {
-///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-///     frame_id: FrameID::BaseLink,
-///     position: [1.0, 1.0, 0.0],
-///     orientation: [1.0, 0.0, 0.0, 0.0],
-///     size: [2.0, 1.0, 1.0],
-///     velocity: None,
-///     confidence: 1.0,
-///     label: Label::Car,
-///     pointcloud_num: Some(1000),
-///     uuid: Some("111".to_string()),
-/// };
+/// let estimation = DynamicObject::new(
+///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("111");
 ///
-/// let ground_truth = DynamicObject {
-///     timestamp: NaiveDateTime::from_timestamp_micros(10000).unwrap(),
-///     frame_id: FrameID::BaseLink,
-///     position: [1.0, 1.0, 0.0],
-///     orientation: [1.0, 0.0, 0.0, 0.0],
-///     size: [2.0, 1.0, 1.0],
-///     velocity: None,
-///     confidence: 1.0,
-///     label: Label::Car,
-///     pointcloud_num: Some(1000),
-///     uuid: Some("100".to_string()),
-/// };
+/// let ground_truth = DynamicObject::new(
+///     NaiveDateTime::from_timestamp_micros(10000).unwrap(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("100");
 ///
 /// let results = get_perception_results(&vec![estimation.clone()], &vec![ground_truth.clone()]);
 /// ```
-pub fn get_perception_results(
-    estimated_objects: &Vec<DynamicObject>,
-    ground_truth_objects: &Vec<DynamicObject>,
-) -> Vec<PerceptionResult> {
-    let mut results: Vec<PerceptionResult> = Vec::new();
+pub fn get_perception_results<E, G>(
+    estimated_objects: &[E],
+    ground_truth_objects: &[G],
+) -> Vec<PerceptionResult>
+where
+    E: Borrow<DynamicObject>,
+    G: Borrow<DynamicObject>,
+{
+    get_perception_results_within(estimated_objects, ground_truth_objects, None)
+}
 
+/// Returns list of `PerceptionResult` first scoring pairs which can be matched within `max_distance`.
+///
+/// Ground truth centers are indexed with a BEV grid, and pairs are first scored within the radius of
+/// `max_distance` plus half diagonals of the largest estimated and ground truth boxes in BEV.
+/// Since pairs beyond the radius have larger pairing costs, they are scored only for objects
+/// left unpaired, so results are the same as `get_perception_results()`.
+/// If `max_distance` is None, all pairs are scored.
+///
+/// * `estimated_objects`       - List of estimated objects.
+/// * `ground_truth_objects`    - List of ground truth objects.
+/// * `max_distance`            - Maximum distance threshold of evaluated matching modes in [m].
+pub fn get_perception_results_within<E, G>(
+    estimated_objects: &[E],
+    ground_truth_objects: &[G],
+    max_distance: Option<f64>,
+) -> Vec<PerceptionResult>
+where
+    E: Borrow<DynamicObject>,
+    G: Borrow<DynamicObject>,
+{
+    let candidate_radius = max_distance.map(|max_distance| {
+        let max_half_diagonal = |objects: &mut dyn Iterator<Item = &DynamicObject>| {
+            objects
+                .map(|object| 0.5 * object.size[0].hypot(object.size[1]))
+                .fold(0.0, f64::max)
+        };
+        max_distance
+            + max_half_diagonal(&mut estimated_objects.iter().map(Borrow::borrow))
+            + max_half_diagonal(&mut ground_truth_objects.iter().map(Borrow::borrow))
+    });
     // Use CenterDistance by default
-    let matching_method = CenterDistanceMatching;
-
-    if estimated_objects.is_empty() {
-        results
-    } else if ground_truth_objects.is_empty() {
-        get_fp_perception_results(estimated_objects)
-    } else {
-        let mut score_table: Vec<Vec<Option<f64>>> =
-            get_score_table(estimated_objects, ground_truth_objects, matching_method);
-        let mut took_indices = Vec::new();
-        let num_estimated_objects = estimated_objects.len();
-        for _ in 0..num_estimated_objects {
-            if score_table
-                .iter()
-                .all(|row| row.iter().all(|col| col.is_none()))
-            {
-                break;
-            }
-            for (est_idx, row_table) in score_table.iter_mut().enumerate() {
-                let (gt_idx, _) = row_table.iter().enumerate().fold(
-                    (usize::MAX, f64::MAX),
-                    |(a_idx, a), (b_idx, b)| match b {
-                        Some(b) => {
-                            if a < *b {
-                                (a_idx, a)
-                            } else {
-                                (b_idx, *b)
-                            }
-                        }
-                        None => (a_idx, a),
-                    },
-                );
+    pair_objects(
+        estimated_objects,
+        ground_truth_objects,
+        &CenterDistanceMatching,
+        candidate_radius,
+    )
+}
 
-                if gt_idx == usize::MAX {
-                    continue;
-                }
+/// Returns list of `PerceptionResult` paired by the user-defined matching function.
+///
+/// Pairs with better scores are assigned first, and estimated objects without any pair are regarded as FP.
+///
+/// * `estimated_objects`       - List of estimated objects.
+/// * `ground_truth_objects`    - List of ground truth objects.
+/// * `matching`                - CustomMatching instance.
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::{
+///     frame_id::FrameID,
+///     label::Label,
+///     matching::{CustomMatching, ScoreDirection},
+///     object::object3d::DynamicObject,
+///     result::object::get_perception_results_with,
+/// };
+///
+/// let object = DynamicObject::new(
+///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_pointcloud_num(1000)
+/// .with_uuid("111");
+///
+/// let matching = CustomMatching::new(
+///     |est: &DynamicObject, gt: &DynamicObject| (est.position[1] - gt.position[1]).abs(),
+///     ScoreDirection::LowerIsBetter,
+/// );
+/// let results = get_perception_results_with(&[&object], &[&object], &matching);
+///
+/// assert!(results[0].is_result_correct_by(&matching, &1.0));
+/// ```
+pub fn get_perception_results_with<E, G, F>(
+    estimated_objects: &[E],
+    ground_truth_objects: &[G],
+    matching: &CustomMatching<F>,
+) -> Vec<PerceptionResult>
+where
+    E: Borrow<DynamicObject>,
+    G: Borrow<DynamicObject>,
+    F: Fn(&DynamicObject, &DynamicObject) -> f64,
+{
+    pair_objects(estimated_objects, ground_truth_objects, matching, None)
+}
 
-                results.push(PerceptionResult {
-                    estimated_object: estimated_objects[est_idx].to_owned(),
-                    ground_truth_object: Some(ground_truth_objects[gt_idx].to_owned()),
-                });
+/// Returns list of `PerceptionResult` paired in ascending order of the pairing cost of the matching method.
+///
+/// Each estimated and ground truth object is paired at most once, and estimated objects
+/// without any pair are regarded as FP.
+///
+/// * `estimated_objects`       - List of estimated objects.
+/// * `ground_truth_objects`    - List of ground truth objects.
+/// * `matching_method`         - Matching method to score pairs.
+/// * `candidate_radius`        - Maximum BEV center distance of candidate pairs scored first in [m].
+fn pair_objects<E, G, T>(
+    estimated_objects: &[E],
+    ground_truth_objects: &[G],
+    matching_method: &T,
+    candidate_radius: Option<f64>,
+) -> Vec<PerceptionResult>
+where
+    E: Borrow<DynamicObject>,
+    G: Borrow<DynamicObject>,
+    T: MatchingMethod,
+{
+    let mut is_paired_est = vec![false; estimated_objects.len()];
+    let mut is_paired_gt = vec![false; ground_truth_objects.len()];
+    let mut results = Vec::new();
+    // Pairs within the radius have less costs than others, so they are assigned first.
+    for radius in candidate_radius.map(Some).into_iter().chain([None]) {
+        let mut candidates = get_candidates(
+            estimated_objects,
+            ground_truth_objects,
+            matching_method,
+            radius,
+            &is_paired_est,
+            &is_paired_gt,
+        );
+        // Ties are broken by indices, so that results do not depend on the order of candidates.
+        candidates.sort_by(|(est1, gt1, cost1), (est2, gt2, cost2)| {
+            cost1
+                .total_cmp(cost2)
+                .then(est1.cmp(est2))
+                .then(gt1.cmp(gt2))
+        });
 
-                row_table[gt_idx] = None;
-                took_indices.push(est_idx);
+        for (est_idx, gt_idx, _) in candidates {
+            if is_paired_est[est_idx] || is_paired_gt[gt_idx] {
+                continue;
             }
-        }
-
-        if took_indices.len() < num_estimated_objects {
-            let index_list = (0..num_estimated_objects).collect::<Vec<usize>>();
-            let mut fp_estimated_objects = Vec::new();
-            index_list.iter().for_each(|idx| {
-                if took_indices.contains(idx) {
-                    fp_estimated_objects.push(estimated_objects[*idx].to_owned());
-                }
+            is_paired_est[est_idx] = true;
+            is_paired_gt[gt_idx] = true;
+            results.push(PerceptionResult {
+                estimated_object: estimated_objects[est_idx].borrow().to_owned(),
+                ground_truth_object: Some(ground_truth_objects[gt_idx].borrow().to_owned()),
             });
-            let mut fp_results = get_fp_perception_results(&fp_estimated_objects);
-            results.append(&mut fp_results);
         }
-        results
     }
+
+    let fp_estimated_objects = estimated_objects
+        .iter()
+        .zip(&is_paired_est)
+        .filter(|(_, is_paired)| !**is_paired)
+        .map(|(object, _)| object.borrow())
+        .collect::<Vec<_>>();
+    results.append(&mut get_fp_perception_results(&fp_estimated_objects));
+    results
 }
 
 /// Returns list of `PerceptionResult` that ground_truth_object of each result is None, it means FP.
 ///
 /// * `estimated_objects`   - List of estimated objects.
-fn get_fp_perception_results(estimated_objects: &[DynamicObject]) -> Vec<PerceptionResult> {
+fn get_fp_perception_results<E>(estimated_objects: &[E]) -> Vec<PerceptionResult>
+where
+    E: Borrow<DynamicObject>,
+{
     estimated_objects
         .iter()
-        .map(|obj| PerceptionResult::new(obj.to_owned(), None))
+        .map(|obj| PerceptionResult::new(obj.borrow().to_owned(), None))
         .collect::<Vec<PerceptionResult>>()
 }
 
-/// Returns NxM score table.
-/// N: Number of estimated objects.
-/// M: Number of ground truth objects.
+/// Returns candidate pairs of unpaired objects with the same label, as tuples of
+/// the index of estimated object, the index of ground truth object and the pairing cost.
+///
+/// With `candidate_radius`, only pairs whose BEV center distance and pairing cost are within the radius
+/// are returned. This requires pairing costs not to be less than BEV center distances.
 ///
 /// * `estimated_objects`       - List of estimated objects.
 /// * `ground_truth_objects`    - List of ground truth objects.
 /// * `matching_method`         - MatchingMethod instance.
-fn get_score_table<T>(
-    estimated_objects: &Vec<DynamicObject>,
-    ground_truth_objects: &Vec<DynamicObject>,
-    matching_method: T,
-) -> Vec<Vec<Option<f64>>>
+/// * `candidate_radius`        - Maximum BEV center distance of candidate pairs in [m]. If None, all pairs are candidates.
+/// * `is_paired_est`           - Whether each estimated object is already paired.
+/// * `is_paired_gt`            - Whether each ground truth object is already paired.
+fn get_candidates<E, G, T>(
+    estimated_objects: &[E],
+    ground_truth_objects: &[G],
+    matching_method: &T,
+    candidate_radius: Option<f64>,
+    is_paired_est: &[bool],
+    is_paired_gt: &[bool],
+) -> Vec<(usize, usize, f64)>
 where
+    E: Borrow<DynamicObject>,
+    G: Borrow<DynamicObject>,
     T: MatchingMethod,
 {
-    let num_est = estimated_objects.len();
-    let num_gt = ground_truth_objects.len();
+    let grid = candidate_radius.map(|radius| {
+        let centers = ground_truth_objects
+            .iter()
+            .map(|gt| gt.borrow().position)
+            .collect::<Vec<_>>();
+        (BevGrid::new(&centers, radius.max(f64::EPSILON)), radius)
+    });
+
+    let mut candidates = Vec::new();
+    for (i, est) in estimated_objects.iter().map(Borrow::borrow).enumerate() {
+        if is_paired_est[i] {
+            continue;
+        }
+        let gt_indices = match &grid {
+            Some((grid, radius)) => grid.query(&est.position, *radius),
+            None => (0..ground_truth_objects.len()).collect(),
+        };
+        for j in gt_indices {
+            let gt = ground_truth_objects[j].borrow();
+            if is_paired_gt[j] || est.label != gt.label {
+                continue;
+            }
+            let cost = matching_method.pairing_cost(est, gt);
+            if candidate_radius.is_none_or(|radius| cost <= radius) {
+                candidates.push((i, j, cost));
+            }
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_perception_results, get_perception_results_with, get_perception_results_within,
+        PerceptionResult,
+    };
+    use crate::{
+        frame_id::FrameID,
+        label::Label,
+        matching::{CustomMatching, ScoreDirection},
+        object::object3d::DynamicObject,
+    };
+    use chrono::DateTime;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn uuid_pair(result: &PerceptionResult) -> (Option<String>, Option<String>) {
+        (
+            result.estimated_object.uuid.clone(),
+            result
+                .ground_truth_object
+                .as_ref()
+                .and_then(|gt| gt.uuid.clone()),
+        )
+    }
 
-    // TODO: refactoring
-    let mut score_table: Vec<Vec<Option<f64>>> = vec![vec![None; num_gt]; num_est];
-    for (i, est) in estimated_objects.iter().enumerate() {
-        for (j, gt) in ground_truth_objects.iter().enumerate() {
-            if est.label == gt.label {
-                score_table[i][j] = Some(matching_method.calculate_matching_score(est, gt));
+    #[test]
+    fn test_get_perception_results_within() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [1.0, 1.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        let estimations = vec![
+            object.clone(),
+            DynamicObject {
+                position: [20.0, 0.0, 0.0],
+                uuid: Some("112".to_string()),
+                ..object.clone()
+            },
+        ];
+        let ground_truths = vec![
+            DynamicObject {
+                position: [1.5, 1.0, 5.0],
+                uuid: Some("100".to_string()),
+                ..object.clone()
+            },
+            DynamicObject {
+                position: [30.0, 0.0, 0.0],
+                uuid: Some("101".to_string()),
+                ..object.clone()
+            },
+        ];
+
+        let pairs = |radius| {
+            get_perception_results_within(&estimations, &ground_truths, radius)
+                .iter()
+                .map(uuid_pair)
+                .collect::<Vec<_>>()
+        };
+        let expected = get_perception_results(&estimations, &ground_truths)
+            .iter()
+            .map(uuid_pair)
+            .collect::<Vec<_>>();
+
+        for radius in [None, Some(2.0), Some(100.0)] {
+            assert_eq!(pairs(radius), expected);
+        }
+    }
+
+    #[test]
+    fn test_get_perception_results_one_to_one() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [0.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        let estimations = vec![
+            object.clone(),
+            DynamicObject {
+                position: [3.0, 0.0, 0.0],
+                uuid: Some("112".to_string()),
+                ..object.clone()
+            },
+            DynamicObject {
+                position: [-3.0, 0.0, 0.0],
+                uuid: Some("113".to_string()),
+                ..object.clone()
+            },
+        ];
+        let ground_truths = vec![
+            DynamicObject {
+                position: [1.0, 0.0, 0.0],
+                uuid: Some("100".to_string()),
+                ..object.clone()
+            },
+            DynamicObject {
+                position: [0.5, 0.0, 0.0],
+                uuid: Some("101".to_string()),
+                ..object.clone()
+            },
+        ];
+
+        let pairs = get_perception_results(&estimations, &ground_truths)
+            .iter()
+            .map(uuid_pair)
+            .collect::<Vec<_>>();
+        let uuid = |value: &str| Some(value.to_string());
+        // Previously, every estimation was paired with both GTs in rounds, which gave
+        // 6 results without FP: ("111", "101"), ("112", "100"), ("113", "101"),
+        // ("111", "100"), ("112", "101") and ("113", "100").
+        // Now "111" takes the nearest GT, "112" takes the remaining GT and "113" is FP.
+        assert_eq!(
+            pairs,
+            vec![
+                (uuid("111"), uuid("101")),
+                (uuid("112"), uuid("100")),
+                (uuid("113"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_perception_results_within_dense() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let labels = [Label::Car, Label::Pedestrian];
+        let mut random_object = |index: usize| {
+            let yaw: f64 = rng.gen_range(-std::f64::consts::PI..std::f64::consts::PI);
+            DynamicObject {
+                timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+                frame_id: FrameID::BaseLink,
+                position: [
+                    rng.gen_range(-20.0..20.0),
+                    rng.gen_range(-20.0..20.0),
+                    rng.gen_range(-1.0..1.0),
+                ],
+                orientation: [(0.5 * yaw).cos(), 0.0, 0.0, (0.5 * yaw).sin()],
+                size: [
+                    rng.gen_range(0.5..3.0),
+                    rng.gen_range(0.5..12.0),
+                    rng.gen_range(1.0..3.0),
+                ],
+                velocity: None,
+                confidence: 1.0,
+                label: labels[rng.gen_range(0..labels.len())].clone(),
+                pointcloud_num: Some(1000),
+                uuid: Some(index.to_string()),
+                class_probabilities: None,
             }
+        };
+        let ground_truths = (0..1000).map(&mut random_object).collect::<Vec<_>>();
+        // Estimations near ground truths with noise, and random ones as clutter.
+        // Large offsets in z are not reflected in BEV center distances of candidates.
+        let mut estimations = (1000..1030).map(&mut random_object).collect::<Vec<_>>();
+        estimations.extend(ground_truths.iter().take(30).map(|gt| DynamicObject {
+            position: [
+                gt.position[0] + rng.gen_range(-1.0..1.0),
+                gt.position[1] + rng.gen_range(-1.0..1.0),
+                gt.position[2] + rng.gen_range(-5.0..5.0),
+            ],
+            size: gt.size.map(|x| x * rng.gen_range(0.8..1.2)),
+            uuid: gt.uuid.as_ref().map(|uuid| format!("est-{uuid}")),
+            ..gt.clone()
+        }));
+
+        let pairs =
+            |results: Vec<PerceptionResult>| results.iter().map(uuid_pair).collect::<Vec<_>>();
+        let expected = pairs(get_perception_results(&estimations, &ground_truths));
+        for max_distance in [0.5, 2.0] {
+            let pruned_results =
+                get_perception_results_within(&estimations, &ground_truths, Some(max_distance));
+            assert_eq!(pairs(pruned_results), expected);
         }
     }
-    score_table
+
+    #[test]
+    fn test_get_perception_results_with() {
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 1.0, 1.0],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: Some(1000),
+            uuid: Some("111".to_string()),
+            class_probabilities: None,
+        };
+        // GT "100" is nearer in center distance, while GT "101" has the same size.
+        let ground_truths = vec![
+            DynamicObject {
+                position: [10.5, 0.0, 0.0],
+                size: [4.0, 2.0, 1.0],
+                uuid: Some("100".to_string()),
+                ..object.clone()
+            },
+            DynamicObject {
+                position: [12.0, 0.0, 0.0],
+                uuid: Some("101".to_string()),
+                ..object.clone()
+            },
+        ];
+        let size_similarity = CustomMatching::new(
+            |est: &DynamicObject, gt: &DynamicObject| {
+                est.volume().min(gt.volume()) / est.volume().max(gt.volume())
+            },
+            ScoreDirection::HigherIsBetter,
+        );
+
+        let results = get_perception_results_with(&[&object], &ground_truths, &size_similarity);
+        assert_eq!(
+            uuid_pair(&results[0]),
+            (Some("111".to_string()), Some("101".to_string()))
+        );
+        assert!(results[0].is_result_correct_by(&size_similarity, &0.9));
+        assert!(!results[0].is_result_correct_by(&size_similarity, &1.0));
+        assert_eq!(
+            uuid_pair(&get_perception_results(&[&object], &ground_truths)[0]),
+            (Some("111".to_string()), Some("100".to_string()))
+        );
+    }
 }
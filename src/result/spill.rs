@@ -0,0 +1,241 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Error as IoError, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use thiserror::Error as ThisError;
+
+use super::frame::PerceptionFrameResult;
+
+pub type SpillResult<T> = Result<T, SpillError>;
+
+/// Represents errors that occur while spilling frame results to disk or reading them back.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum SpillError {
+    #[error("I/O error: {0}")]
+    IoError(#[from] IoError),
+    #[error("corrupted frame result: {0}")]
+    CorruptedFrame(String),
+}
+
+/// Counter to give a unique name to each store in the process.
+static STORE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary file removed when the last store referring to it is dropped.
+#[derive(Debug)]
+struct SpillFile {
+    path: PathBuf,
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if self.path.exists() {
+            if let Err(err) = std::fs::remove_file(&self.path) {
+                log::warn!("failed to remove {}: {}", self.path.display(), err);
+            }
+        }
+    }
+}
+
+/// Temporary on-disk store of `PerceptionFrameResult`s, which keeps them out of memory.
+///
+/// Frame results are appended as zstd compressed JSON lines, one zstd frame per call,
+/// and streamed back in the appended order.
+/// The file is removed when the store and all of its clones are dropped.
+///
+/// # Examples
+/// ```
+/// use perception_eval::result::spill::{FrameResultStore, SpillResult};
+///
+/// fn main() -> SpillResult<()> {
+///     let store = FrameResultStore::new(std::env::temp_dir())?;
+///     assert!(store.is_empty());
+///     assert_eq!(store.iter()?.count(), 0);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameResultStore {
+    file: Arc<SpillFile>,
+    num_frames: usize,
+}
+
+impl FrameResultStore {
+    /// Construct `FrameResultStore` with a new temporary file under the directory.
+    ///
+    /// * `dir` - Directory to place the temporary file.
+    pub fn new<P>(dir: P) -> SpillResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let path = dir.as_ref().join(format!(
+            "perception_eval_spill_{}_{}.jsonl.zst",
+            std::process::id(),
+            STORE_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        File::create(&path)?;
+        Ok(Self {
+            file: Arc::new(SpillFile { path }),
+            num_frames: 0,
+        })
+    }
+
+    /// Returns the path of the temporary file.
+    pub fn path(&self) -> &Path {
+        &self.file.path
+    }
+
+    /// Returns the number of stored frame results.
+    pub fn len(&self) -> usize {
+        self.num_frames
+    }
+
+    /// Returns whether there is no stored frame result.
+    pub fn is_empty(&self) -> bool {
+        self.num_frames == 0
+    }
+
+    /// Append frame results to the end of the store.
+    ///
+    /// * `frame_results`   - List of PerceptionFrameResult.
+    pub fn append(&mut self, frame_results: &[PerceptionFrameResult]) -> SpillResult<()> {
+        let file = OpenOptions::new().append(true).open(self.path())?;
+        let mut encoder = zstd::Encoder::new(file, 0)?;
+        for frame_result in frame_results {
+            let line = serde_json::to_string(frame_result)
+                .map_err(|err| SpillError::CorruptedFrame(format!("{:?}", err)))?;
+            writeln!(encoder, "{}", line)?;
+        }
+        encoder.finish()?;
+        self.num_frames += frame_results.len();
+        Ok(())
+    }
+
+    /// Returns the iterator streaming stored frame results back in the appended order.
+    pub fn iter(&self) -> SpillResult<FrameResultReader> {
+        let decoder = zstd::Decoder::new(File::open(self.path())?)?;
+        Ok(FrameResultReader {
+            lines: BufReader::new(decoder).lines(),
+            num_remaining: self.num_frames,
+        })
+    }
+}
+
+/// Reader streaming frame results back from `FrameResultStore`.
+pub struct FrameResultReader {
+    lines: std::io::Lines<BufReader<zstd::Decoder<'static, BufReader<File>>>>,
+    num_remaining: usize,
+}
+
+impl Iterator for FrameResultReader {
+    type Item = SpillResult<PerceptionFrameResult>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.num_remaining == 0 {
+            return None;
+        }
+        self.num_remaining -= 1;
+        let frame_result = match self.lines.next() {
+            Some(line) => line.map_err(SpillError::from).and_then(|line| {
+                serde_json::from_str(&line)
+                    .map_err(|err| SpillError::CorruptedFrame(format!("{:?}", err)))
+            }),
+            None => Err(SpillError::CorruptedFrame(
+                "store is shorter than the number of appended frames".to_string(),
+            )),
+        };
+        Some(frame_result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameResultStore;
+    use crate::{
+        dataset::FrameGroundTruth,
+        frame_id::FrameID,
+        label::Label,
+        matching::{MatchingMode, PlaneDistanceConfig},
+        object::object3d::DynamicObject,
+        result::{frame::PerceptionFrameResult, object::get_perception_results},
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_frame_result_store_round_trip() {
+        let timestamp = DateTime::from_timestamp_micros(10000).unwrap().naive_utc();
+        let ground_truth = DynamicObject::new(
+            timestamp,
+            FrameID::BaseLink,
+            [1.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [2.0, 1.0, 1.0],
+            1.0,
+            Label::Car,
+        )
+        .with_velocity([1.0, 0.0, 0.0])
+        .with_pointcloud_num(1000)
+        .with_uuid("100")
+        .with_class_probabilities([(Label::Car, 0.9), (Label::Bus, 0.1)].into());
+        let frame_results = (0..3)
+            .map(|i| {
+                let estimation = DynamicObject {
+                    position: [1.0 + i as f64, 1.0, 0.0],
+                    confidence: 0.5,
+                    ..ground_truth.clone()
+                };
+                PerceptionFrameResult::new(
+                    get_perception_results(&[&estimation], &[&ground_truth]),
+                    FrameGroundTruth::new(timestamp, vec![ground_truth.clone()])
+                        .with_ego_pose([i as f64, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0])
+                        .with_sample_token(&i.to_string())
+                        .with_location("singapore-onenorth"),
+                    &[Label::Car],
+                    MatchingMode::CenterDistance,
+                    &[1.5],
+                    &PlaneDistanceConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let dir = std::env::temp_dir().join("perception_eval_test_spill");
+        let mut store = FrameResultStore::new(&dir).unwrap();
+        store.append(&frame_results[..2]).unwrap();
+        store.append(&frame_results[2..]).unwrap();
+        assert_eq!(store.len(), 3);
+
+        let loaded = store
+            .iter()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(loaded.len(), 3);
+        loaded
+            .iter()
+            .zip(&frame_results)
+            .for_each(|(loaded, expected)| {
+                assert_eq!(loaded.frame_ground_truth(), expected.frame_ground_truth());
+                assert_eq!(loaded.tp_results().len(), expected.tp_results().len());
+                assert_eq!(loaded.fn_objects(), expected.fn_objects());
+                assert_eq!(
+                    loaded.results()[0].estimated_object,
+                    expected.results()[0].estimated_object
+                );
+            });
+
+        let path = store.path().to_owned();
+        let cloned = store.clone();
+        drop(store);
+        assert!(path.exists());
+        drop(cloned);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,641 @@
+mod cdr;
+
+use std::{
+    collections::HashMap,
+    io::Error as IoError,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDateTime};
+use nalgebra::Vector3;
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use thiserror::Error as ThisError;
+
+use crate::{
+    frame_id::{FrameID, FrameIdError},
+    label::Label,
+    object::object3d::DynamicObject,
+    utils::{footprint::canonicalize_footprint, math::quaternion2rotation},
+};
+
+use self::cdr::CdrReader;
+
+/// Message types of detected objects which can be read, whose layouts are the same.
+pub const DETECTED_OBJECTS_TYPES: [&str; 2] = [
+    "autoware_perception_msgs/msg/DetectedObjects",
+    "autoware_auto_perception_msgs/msg/DetectedObjects",
+];
+
+/// Type of `Shape` whose footprint is a polygon in the object coord system.
+const SHAPE_TYPE_POLYGON: u8 = 2;
+
+pub type RosbagResult<T> = Result<T, RosbagError>;
+
+/// Represents errors that occur while reading rosbags.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum RosbagError {
+    #[error("I/O error: {0}")]
+    IoError(#[from] IoError),
+    #[error("sqlite error: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+    #[error("no database file found: {0}")]
+    DatabaseNotFound(PathBuf),
+    #[error("topic not found: {0}")]
+    TopicNotFound(String),
+    #[error("unsupported message type of {topic}: {message_type} ({serialization_format})")]
+    UnsupportedMessageType {
+        topic: String,
+        message_type: String,
+        serialization_format: String,
+    },
+    #[error("invalid message: {0}")]
+    InvalidMessage(String),
+    #[error("frame id error: {0}")]
+    FrameIdError(#[from] FrameIdError),
+}
+
+/// Estimated objects decoded from one message.
+///
+/// * `timestamp`   - Timestamp of the message header, which is the sensor time in Autoware.
+/// * `frame_id`    - FrameID of the message header.
+/// * `objects`     - List of estimated objects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstimationFrame {
+    pub timestamp: NaiveDateTime,
+    pub frame_id: FrameID,
+    pub objects: Vec<DynamicObject>,
+}
+
+/// Reader of rosbag2 recorded in the SQLite format.
+///
+/// * `databases`   - Connections to database files of the bag in order.
+#[derive(Debug)]
+pub struct RosbagReader {
+    databases: Vec<Connection>,
+}
+
+impl RosbagReader {
+    /// Open the rosbag2 in read-only mode.
+    ///
+    /// * `path`    - Directory of the bag containing `.db3` files, or a `.db3` file.
+    pub fn open<P>(path: P) -> RosbagResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut paths = if path.is_dir() {
+            std::fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .filter(|entry| {
+                    entry.as_ref().map_or(true, |path| {
+                        path.extension().is_some_and(|ext| ext == "db3")
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else if path.is_file() {
+            vec![path.to_owned()]
+        } else {
+            Vec::new()
+        };
+        if paths.is_empty() {
+            return Err(RosbagError::DatabaseNotFound(path.to_owned()));
+        }
+        // Split files are named with the sequential index, e.g. `bag_0.db3`, `bag_1.db3`.
+        paths.sort_by_key(|path| {
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("");
+            let index = stem
+                .rsplit_once('_')
+                .and_then(|(_, index)| index.parse::<usize>().ok());
+            (index, path.to_owned())
+        });
+        let databases = paths
+            .iter()
+            .map(|path| Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { databases })
+    }
+
+    /// Returns names and message types of topics recorded in the bag, ordered by name.
+    pub fn topics(&self) -> RosbagResult<Vec<(String, String)>> {
+        let mut topics = Vec::new();
+        for database in &self.databases {
+            let mut statement = database.prepare("SELECT name, type FROM topics")?;
+            let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            for row in rows {
+                topics.push(row?);
+            }
+        }
+        topics.sort();
+        topics.dedup();
+        Ok(topics)
+    }
+
+    /// Returns estimations of every message of the detected objects topic, ordered by the recorded time.
+    ///
+    /// Labels are converted from Autoware classifications, where trailers are regarded as trucks.
+    /// Confidences are existence probabilities, and velocities are transformed from the object coord system.
+    ///
+    /// * `topic`   - Name of the topic, whose type is one of `DETECTED_OBJECTS_TYPES`.
+    pub fn read_detected_objects(&self, topic: &str) -> RosbagResult<Vec<EstimationFrame>> {
+        let mut frames = Vec::new();
+        let mut found = false;
+        for database in &self.databases {
+            let Some((topic_id, message_type, serialization_format)) = database
+                .query_row(
+                    "SELECT id, type, serialization_format FROM topics WHERE name = ?1",
+                    [topic],
+                    |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, String>(2)?,
+                        ))
+                    },
+                )
+                .optional()?
+            else {
+                continue;
+            };
+            if !DETECTED_OBJECTS_TYPES.contains(&message_type.as_str())
+                || serialization_format != "cdr"
+            {
+                return Err(RosbagError::UnsupportedMessageType {
+                    topic: topic.to_owned(),
+                    message_type,
+                    serialization_format,
+                });
+            }
+            found = true;
+
+            let mut statement = database
+                .prepare("SELECT data FROM messages WHERE topic_id = ?1 ORDER BY timestamp")?;
+            let rows = statement.query_map([topic_id], |row| row.get::<_, Vec<u8>>(0))?;
+            for data in rows {
+                frames.push(decode_detected_objects(&data?)?);
+            }
+        }
+        match found {
+            true => Ok(frames),
+            false => Err(RosbagError::TopicNotFound(topic.to_owned())),
+        }
+    }
+}
+
+/// Decode `DetectedObjects` message serialized in the CDR format.
+///
+/// * `data`    - Serialized message with the encapsulation header.
+fn decode_detected_objects(data: &[u8]) -> RosbagResult<EstimationFrame> {
+    let mut reader = CdrReader::new(data)?;
+    let seconds = reader.read_i32()?;
+    let nanoseconds = reader.read_u32()?;
+    let timestamp = DateTime::from_timestamp(seconds as i64, nanoseconds)
+        .ok_or_else(|| {
+            RosbagError::InvalidMessage(format!("invalid stamp: {}.{}", seconds, nanoseconds))
+        })?
+        .naive_utc();
+    let frame_id = FrameID::from_str(&reader.read_string()?)?;
+
+    let num_objects = reader.read_sequence_length()?;
+    let objects = (0..num_objects)
+        .map(|_| decode_detected_object(&mut reader, &timestamp, &frame_id))
+        .collect::<RosbagResult<Vec<_>>>()?;
+    Ok(EstimationFrame {
+        timestamp,
+        frame_id,
+        objects,
+    })
+}
+
+/// Decode `DetectedObject` in the message.
+///
+/// * `reader`      - Reader at the beginning of the object.
+/// * `timestamp`   - Timestamp of the message header.
+/// * `frame_id`    - FrameID of the message header.
+fn decode_detected_object(
+    reader: &mut CdrReader,
+    timestamp: &NaiveDateTime,
+    frame_id: &FrameID,
+) -> RosbagResult<DynamicObject> {
+    let existence_probability = reader.read_f32()? as f64;
+    let mut class_probabilities = HashMap::new();
+    for _ in 0..reader.read_sequence_length()? {
+        let label = convert_label(reader.read_u8()?);
+        let probability = reader.read_f32()? as f64;
+        *class_probabilities.entry(label).or_insert(0.0) += probability;
+    }
+
+    // Kinematics, where pose and twist are with covariances.
+    let position = reader.read_f64_array::<3>()?;
+    let [x, y, z, w] = reader.read_f64_array::<4>()?;
+    let orientation = [w, x, y, z];
+    reader.skip_f64(36)?;
+    let _has_position_covariance = reader.read_bool()?;
+    let _orientation_availability = reader.read_u8()?;
+    let linear = reader.read_f64_array::<3>()?;
+    let _angular = reader.read_f64_array::<3>()?;
+    reader.skip_f64(36)?;
+    let has_twist = reader.read_bool()?;
+    let _has_twist_covariance = reader.read_bool()?;
+
+    // Shape, where the footprint is only used for polygons.
+    let shape_type = reader.read_u8()?;
+    let footprint = (0..reader.read_sequence_length()?)
+        .map(|_| {
+            Ok([
+                reader.read_f32()? as f64,
+                reader.read_f32()? as f64,
+                reader.read_f32()? as f64,
+            ])
+        })
+        .collect::<RosbagResult<Vec<_>>>()?;
+    let [length, width, height] = reader.read_f64_array::<3>()?;
+    let [width, length] = match shape_type {
+        SHAPE_TYPE_POLYGON if footprint.len() == 4 => footprint_size(&footprint),
+        _ => [width, length],
+    };
+
+    // Twist is expressed in the object coord system.
+    let velocity =
+        has_twist.then(|| (quaternion2rotation(&orientation) * Vector3::from(linear)).into());
+    let label = class_probabilities
+        .iter()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(Label::Unknown, |(label, _)| label.to_owned());
+    Ok(DynamicObject {
+        timestamp: timestamp.to_owned(),
+        frame_id: frame_id.to_owned(),
+        position,
+        orientation,
+        size: [width, length, height],
+        velocity,
+        confidence: existence_probability,
+        label,
+        pointcloud_num: None,
+        uuid: None,
+        class_probabilities: (!class_probabilities.is_empty()).then_some(class_probabilities),
+    })
+}
+
+/// Returns [width, length] of the rectangular footprint in the object coord system.
+///
+/// Corners of the footprint can be in any order, so that they are canonicalized
+/// to start from the front-left corner counter-clockwise.
+///
+/// * `footprint`   - Four corners of the footprint in the object coord system.
+fn footprint_size(footprint: &[[f64; 3]]) -> [f64; 2] {
+    let origin = DynamicObject {
+        timestamp: NaiveDateTime::default(),
+        frame_id: FrameID::BaseLink,
+        position: [0.0, 0.0, 0.0],
+        orientation: [1.0, 0.0, 0.0, 0.0],
+        size: [0.0, 0.0, 0.0],
+        velocity: None,
+        confidence: 1.0,
+        label: Label::Unknown,
+        pointcloud_num: None,
+        uuid: None,
+        class_probabilities: None,
+    };
+    let corners = canonicalize_footprint(footprint, &origin);
+    let edge = |p: &[f64; 3], q: &[f64; 3]| (p[0] - q[0]).hypot(p[1] - q[1]);
+    // Front-left -> rear-left is the length, and rear-left -> rear-right is the width.
+    [
+        edge(&corners[1], &corners[2]),
+        edge(&corners[0], &corners[1]),
+    ]
+}
+
+/// Convert the label of Autoware `ObjectClassification` into `Label`.
+///
+/// * `label`   - Label value of `ObjectClassification`.
+fn convert_label(label: u8) -> Label {
+    match label {
+        1 => Label::Car,
+        2 | 4 => Label::Truck,
+        3 => Label::Bus,
+        5 => Label::Motorbike,
+        6 => Label::Bicycle,
+        7 => Label::Pedestrian,
+        _ => Label::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use chrono::NaiveDateTime;
+    use nalgebra::Vector3;
+    use rusqlite::Connection;
+
+    use super::{decode_detected_objects, RosbagError, RosbagReader, SHAPE_TYPE_POLYGON};
+    use crate::{
+        config::PerceptionEvaluationConfig, frame_id::FrameID, label::Label,
+        manager::PerceptionEvaluationManager, object::object3d::DynamicObject,
+        utils::math::quaternion2rotation,
+    };
+
+    const TOPIC: &str = "/perception/object_recognition/detection/objects";
+
+    /// Writer of messages in the little-endian CDR format.
+    struct CdrWriter {
+        data: Vec<u8>,
+    }
+
+    impl CdrWriter {
+        fn new() -> Self {
+            Self {
+                data: vec![0x00, 0x01, 0x00, 0x00],
+            }
+        }
+
+        fn align(&mut self, size: usize) {
+            while !(self.data.len() - 4).is_multiple_of(size) {
+                self.data.push(0);
+            }
+        }
+
+        fn u8(&mut self, value: u8) {
+            self.data.push(value);
+        }
+
+        fn u32(&mut self, value: u32) {
+            self.align(4);
+            self.data.extend(value.to_le_bytes());
+        }
+
+        fn f32(&mut self, value: f32) {
+            self.align(4);
+            self.data.extend(value.to_le_bytes());
+        }
+
+        fn f64s(&mut self, values: &[f64]) {
+            self.align(8);
+            values
+                .iter()
+                .for_each(|value| self.data.extend(value.to_le_bytes()));
+        }
+
+        fn string(&mut self, value: &str) {
+            self.u32(value.len() as u32 + 1);
+            self.data.extend(value.as_bytes());
+            self.data.push(0);
+        }
+    }
+
+    /// Encode objects as `DetectedObjects` message, whose labels are encoded with classifications.
+    /// If `footprint` is given, shapes are encoded as polygons without width and length.
+    fn encode_detected_objects(
+        timestamp: &NaiveDateTime,
+        frame_id: &str,
+        objects: &[(DynamicObject, Vec<(u8, f32)>)],
+        footprint: Option<&[[f32; 3]]>,
+    ) -> Vec<u8> {
+        let mut writer = CdrWriter::new();
+        let stamp = timestamp.and_utc();
+        writer.u32(stamp.timestamp() as u32);
+        writer.u32(stamp.timestamp_subsec_nanos());
+        writer.string(frame_id);
+        writer.u32(objects.len() as u32);
+        objects.iter().for_each(|(object, classification)| {
+            writer.f32(object.confidence as f32);
+            writer.u32(classification.len() as u32);
+            classification.iter().for_each(|(label, probability)| {
+                writer.u8(*label);
+                writer.f32(*probability);
+            });
+            let [w, x, y, z] = object.orientation;
+            writer.f64s(&object.position);
+            writer.f64s(&[x, y, z, w]);
+            writer.f64s(&[0.0; 36]);
+            writer.u8(0);
+            writer.u8(1);
+            let linear = object.velocity.map_or([0.0; 3], |velocity| {
+                (quaternion2rotation(&object.orientation).transpose() * Vector3::from(velocity))
+                    .into()
+            });
+            writer.f64s(&linear);
+            writer.f64s(&[0.0; 3]);
+            writer.f64s(&[0.0; 36]);
+            writer.u8(object.velocity.is_some() as u8);
+            writer.u8(0);
+            let [width, length, height] = object.size;
+            match footprint {
+                Some(footprint) => {
+                    writer.u8(SHAPE_TYPE_POLYGON);
+                    writer.u32(footprint.len() as u32);
+                    footprint
+                        .iter()
+                        .flatten()
+                        .for_each(|value| writer.f32(*value));
+                    writer.f64s(&[0.0, 0.0, height]);
+                }
+                None => {
+                    writer.u8(0);
+                    writer.u32(1);
+                    writer.f32(0.0);
+                    writer.f32(0.0);
+                    writer.f32(0.0);
+                    writer.f64s(&[length, width, height]);
+                }
+            }
+        });
+        writer.data
+    }
+
+    /// Write messages into a database file of rosbag2.
+    fn write_database(path: &Path, message_type: &str, messages: &[(i64, Vec<u8>)]) {
+        let database = Connection::open(path).unwrap();
+        database
+            .execute_batch(
+                "CREATE TABLE topics(id INTEGER PRIMARY KEY, name TEXT NOT NULL, type TEXT NOT NULL, \
+                 serialization_format TEXT NOT NULL, offered_qos_profiles TEXT NOT NULL);
+                 CREATE TABLE messages(id INTEGER PRIMARY KEY, topic_id INTEGER NOT NULL, \
+                 timestamp INTEGER NOT NULL, data BLOB NOT NULL);",
+            )
+            .unwrap();
+        database
+            .execute(
+                "INSERT INTO topics VALUES (1, ?1, ?2, 'cdr', '')",
+                [TOPIC, message_type],
+            )
+            .unwrap();
+        messages.iter().for_each(|(timestamp, data)| {
+            database
+                .execute(
+                    "INSERT INTO messages (topic_id, timestamp, data) VALUES (1, ?1, ?2)",
+                    (timestamp, data),
+                )
+                .unwrap();
+        });
+    }
+
+    fn label_value(label: &Label) -> u8 {
+        match label {
+            Label::Car => 1,
+            Label::Truck => 2,
+            Label::Bus => 3,
+            Label::Motorbike => 5,
+            Label::Bicycle => 6,
+            Label::Pedestrian => 7,
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn test_decode_detected_objects() {
+        let timestamp = chrono::DateTime::from_timestamp(1_700_000_000, 500_000_000)
+            .unwrap()
+            .naive_utc();
+        let half = 0.5f64.sqrt();
+        let object = DynamicObject {
+            timestamp,
+            frame_id: FrameID::BaseLink,
+            position: [10.0, -2.0, 0.5],
+            orientation: [half, 0.0, 0.0, half],
+            size: [1.8, 4.5, 1.5],
+            velocity: Some([0.0, 2.0, 0.0]),
+            confidence: 0.75,
+            label: Label::Truck,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        };
+        // Trailer is merged into truck.
+        let classification = vec![(1, 0.25), (2, 0.375), (4, 0.375)];
+        let data = encode_detected_objects(
+            &timestamp,
+            "base_link",
+            &[(object.clone(), classification)],
+            None,
+        );
+
+        let frame = decode_detected_objects(&data).unwrap();
+        assert_eq!(frame.timestamp, timestamp);
+        assert_eq!(frame.frame_id, FrameID::BaseLink);
+        assert_eq!(frame.objects.len(), 1);
+        let decoded = &frame.objects[0];
+        assert_eq!(decoded.position, object.position);
+        assert_eq!(decoded.orientation, object.orientation);
+        assert_eq!(decoded.size, object.size);
+        assert_eq!(decoded.label, Label::Truck);
+        assert_eq!(decoded.confidence, 0.75);
+        assert_eq!(
+            decoded.class_probabilities.as_ref().unwrap()[&Label::Truck],
+            0.75
+        );
+        let velocity = decoded.velocity.unwrap();
+        assert!((velocity[0] - 0.0).abs() < 1e-9 && (velocity[1] - 2.0).abs() < 1e-9);
+
+        // Polygon footprint clockwise from the rear-right corner.
+        let footprint = [
+            [-2.25, -0.9, 0.0],
+            [-2.25, 0.9, 0.0],
+            [2.25, 0.9, 0.0],
+            [2.25, -0.9, 0.0],
+        ];
+        let polygon = encode_detected_objects(
+            &timestamp,
+            "base_link",
+            &[(object.clone(), vec![(2, 1.0)])],
+            Some(&footprint),
+        );
+        let decoded = &decode_detected_objects(&polygon).unwrap().objects[0];
+        assert!((decoded.size[0] - 1.8).abs() < 1e-6);
+        assert!((decoded.size[1] - 4.5).abs() < 1e-6);
+        assert_eq!(decoded.size[2], 1.5);
+
+        assert!(decode_detected_objects(&data[..data.len() - 1]).is_err());
+        let data = encode_detected_objects(&timestamp, "unknown_frame", &[], None);
+        assert!(matches!(
+            decode_detected_objects(&data),
+            Err(RosbagError::FrameIdError(_))
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_rosbag() {
+        let config = PerceptionEvaluationConfig::from(
+            "tests/config/perception.yaml",
+            "work_dir/rosbag",
+            false,
+        )
+        .unwrap();
+        let mut manager = PerceptionEvaluationManager::from(&config).unwrap();
+
+        // Record GTs as estimations into a bag split into 2 files.
+        let messages = manager
+            .frame_ground_truths()
+            .iter()
+            .map(|frame| {
+                let objects = frame
+                    .objects
+                    .iter()
+                    .map(|object| (object.clone(), vec![(label_value(&object.label), 1.0)]))
+                    .collect::<Vec<_>>();
+                let timestamp = frame.timestamp.and_utc().timestamp_nanos_opt().unwrap();
+                (
+                    timestamp,
+                    encode_detected_objects(&frame.timestamp, "base_link", &objects, None),
+                )
+            })
+            .collect::<Vec<_>>();
+        let bag_dir = Path::new("work_dir/rosbag/bag");
+        let _ = std::fs::remove_dir_all(bag_dir);
+        std::fs::create_dir_all(bag_dir).unwrap();
+        let (first, second) = messages.split_at(messages.len() / 2);
+        let message_type = "autoware_perception_msgs/msg/DetectedObjects";
+        write_database(&bag_dir.join("bag_0.db3"), message_type, first);
+        write_database(&bag_dir.join("bag_1.db3"), message_type, second);
+        std::fs::write(bag_dir.join("metadata.yaml"), "").unwrap();
+
+        let reader = RosbagReader::open(bag_dir).unwrap();
+        assert_eq!(
+            reader.topics().unwrap(),
+            vec![(TOPIC.to_string(), message_type.to_string())]
+        );
+        let frames = reader.read_detected_objects(TOPIC).unwrap();
+        assert!(!frames.is_empty());
+        assert_eq!(frames.len(), manager.frame_ground_truths().len());
+        for frame in &frames {
+            let frame_ground_truth = manager.get_frame_ground_truth(&frame.timestamp).unwrap();
+            assert_eq!(frame.objects.len(), frame_ground_truth.objects.len());
+            manager
+                .add_frame_result(&frame.objects, &frame_ground_truth)
+                .unwrap();
+        }
+        // Every GT is detected by the recorded GT.
+        assert!(manager.frame_results.iter().all(|frame_result| {
+            frame_result.tp_results().len() == frame_result.frame_ground_truth().objects.len()
+        }));
+        assert!(manager.get_metrics_score().is_ok());
+
+        assert!(matches!(
+            reader.read_detected_objects("/not/recorded"),
+            Err(RosbagError::TopicNotFound(_))
+        ));
+        let other_dir = Path::new("work_dir/rosbag/other");
+        let _ = std::fs::remove_dir_all(other_dir);
+        std::fs::create_dir_all(other_dir).unwrap();
+        write_database(
+            &other_dir.join("other_0.db3"),
+            "autoware_perception_msgs/msg/TrackedObjects",
+            &[],
+        );
+        assert!(matches!(
+            RosbagReader::open(other_dir)
+                .unwrap()
+                .read_detected_objects(TOPIC),
+            Err(RosbagError::UnsupportedMessageType { .. })
+        ));
+        assert!(matches!(
+            RosbagReader::open("work_dir/rosbag/not_exist"),
+            Err(RosbagError::DatabaseNotFound(_))
+        ));
+    }
+}
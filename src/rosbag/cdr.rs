@@ -0,0 +1,119 @@
+use super::{RosbagError, RosbagResult};
+
+/// Size of the encapsulation header preceding the serialized message.
+const ENCAPSULATION_HEADER_SIZE: usize = 4;
+
+/// Reader of messages serialized in the CDR format, which is the default of ROS 2.
+///
+/// Primitives are aligned to their sizes relative to the end of the encapsulation header.
+///
+/// * `data`            - Serialized message without the encapsulation header.
+/// * `position`        - Current position in `data`.
+/// * `little_endian`   - Whether the message is encoded in little endian.
+pub(crate) struct CdrReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    little_endian: bool,
+}
+
+macro_rules! read_primitive {
+    ($name:ident, $ty:ty) => {
+        pub(crate) fn $name(&mut self) -> RosbagResult<$ty> {
+            let bytes = self.read_aligned::<{ std::mem::size_of::<$ty>() }>()?;
+            Ok(if self.little_endian {
+                <$ty>::from_le_bytes(bytes)
+            } else {
+                <$ty>::from_be_bytes(bytes)
+            })
+        }
+    };
+}
+
+impl<'a> CdrReader<'a> {
+    /// Construct `CdrReader` from the serialized message with the encapsulation header.
+    ///
+    /// * `data`    - Serialized message.
+    pub(crate) fn new(data: &'a [u8]) -> RosbagResult<Self> {
+        let little_endian = match data.get(..ENCAPSULATION_HEADER_SIZE) {
+            Some([0x00, 0x00, _, _]) => false,
+            Some([0x00, 0x01, _, _]) => true,
+            Some(header) => Err(RosbagError::InvalidMessage(format!(
+                "unsupported encapsulation: {:?}",
+                &header[..2]
+            )))?,
+            None => Err(RosbagError::InvalidMessage(
+                "missing encapsulation header".to_string(),
+            ))?,
+        };
+        Ok(Self {
+            data: &data[ENCAPSULATION_HEADER_SIZE..],
+            position: 0,
+            little_endian,
+        })
+    }
+
+    read_primitive!(read_u8, u8);
+    read_primitive!(read_i32, i32);
+    read_primitive!(read_u32, u32);
+    read_primitive!(read_f32, f32);
+    read_primitive!(read_f64, f64);
+
+    /// Read a boolean, which is serialized as one byte.
+    pub(crate) fn read_bool(&mut self) -> RosbagResult<bool> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    /// Read a string, which is serialized as its length including the null terminator and bytes.
+    pub(crate) fn read_string(&mut self) -> RosbagResult<String> {
+        let length = self.read_u32()? as usize;
+        let bytes = self.read_bytes(length)?;
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        String::from_utf8(bytes.to_vec())
+            .map_err(|err| RosbagError::InvalidMessage(format!("invalid string: {}", err)))
+    }
+
+    /// Read the length of a sequence, which is followed by its elements.
+    pub(crate) fn read_sequence_length(&mut self) -> RosbagResult<usize> {
+        Ok(self.read_u32()? as usize)
+    }
+
+    /// Read `N` 64-bit floats of a fixed-size array.
+    pub(crate) fn read_f64_array<const N: usize>(&mut self) -> RosbagResult<[f64; N]> {
+        let mut values = [0.0; N];
+        for value in values.iter_mut() {
+            *value = self.read_f64()?;
+        }
+        Ok(values)
+    }
+
+    /// Skip `num_values` 64-bit floats of a fixed-size array, such as covariance.
+    ///
+    /// * `num_values`  - Number of elements.
+    pub(crate) fn skip_f64(&mut self, num_values: usize) -> RosbagResult<()> {
+        self.align(8);
+        self.read_bytes(8 * num_values).map(|_| ())
+    }
+
+    /// Read bytes of the primitive of `N` bytes after aligning the position to `N`.
+    fn read_aligned<const N: usize>(&mut self) -> RosbagResult<[u8; N]> {
+        self.align(N);
+        let bytes = self.read_bytes(N)?;
+        Ok(bytes.try_into().expect("length is checked"))
+    }
+
+    fn read_bytes(&mut self, length: usize) -> RosbagResult<&'a [u8]> {
+        let end = self.position.saturating_add(length);
+        let bytes = self.data.get(self.position..end).ok_or_else(|| {
+            RosbagError::InvalidMessage(format!(
+                "unexpected end of message: {} bytes required at {}",
+                length, self.position
+            ))
+        })?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn align(&mut self, size: usize) {
+        self.position = self.position.next_multiple_of(size);
+    }
+}
@@ -1,3 +1,8 @@
+pub mod color;
+pub mod footprint;
+pub mod grid;
 pub mod logger;
 pub mod math;
 pub mod point;
+pub mod pointcloud;
+pub mod projection;
@@ -0,0 +1,250 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufWriter, Result as IoResult},
+    path::Path,
+};
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    fault::create_file, label::Label, object::object3d::DynamicObject,
+    result::frame::PerceptionFrameResult,
+};
+
+/// Kind of objects to be colored, which separates the namespace of GT instances and estimated tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    GroundTruth,
+    Estimation,
+}
+
+impl ObjectKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            ObjectKind::GroundTruth => "gt",
+            ObjectKind::Estimation => "est",
+        }
+    }
+}
+
+/// Returns the color of the object, which only depends on its kind and uuid.
+/// Since the color is derived from the hash of the uuid, the same instance or track is painted
+/// with the same color across frames and runs.
+///
+/// * `kind`    - Kind of the object.
+/// * `uuid`    - Instance token of GT or track id of estimation.
+///
+/// # Examples
+/// ```
+/// use perception_eval::utils::color::{object_color, ObjectKind};
+///
+/// let color = object_color(ObjectKind::GroundTruth, "instance");
+///
+/// assert_eq!(color, object_color(ObjectKind::GroundTruth, "instance"));
+/// assert_ne!(color, object_color(ObjectKind::GroundTruth, "other"));
+/// ```
+pub fn object_color(kind: ObjectKind, uuid: &str) -> [u8; 3] {
+    // FNV-1a is used instead of `DefaultHasher`, whose output is not stable across releases.
+    let hash = [kind.prefix(), ":", uuid]
+        .iter()
+        .flat_map(|s| s.bytes())
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    let hue = (hash % 360) as f64;
+    let saturation = 0.55 + ((hash >> 16) % 40) as f64 / 100.0;
+    let value = 0.75 + ((hash >> 32) % 25) as f64 / 100.0;
+    hsv_to_rgb(hue, saturation, value)
+}
+
+/// Returns the RGB color converted from HSV.
+///
+/// * `hue`         - Hue in [0, 360).
+/// * `saturation`  - Saturation in [0, 1].
+/// * `value`       - Value in [0, 1].
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> [u8; 3] {
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    [r, g, b].map(|c| ((c + m) * 255.0).round() as u8)
+}
+
+/// Legend entry of the colored object.
+///
+/// * `kind`        - Kind of the object.
+/// * `uuid`        - Instance token of GT or track id of estimation.
+/// * `label`       - Label of the object when it was first seen.
+/// * `color`       - RGB color of the object.
+/// * `num_frames`  - Number of frames in which the object appears.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LegendEntry {
+    pub kind: ObjectKind,
+    pub uuid: String,
+    pub label: Label,
+    pub color: [u8; 3],
+    pub num_frames: usize,
+}
+
+/// Legend mapping GT instances and estimated tracks to their colors in visualization outputs.
+///
+/// Objects without uuid are not contained, since they can not be tracked across frames.
+///
+/// # Examples
+/// ```
+/// use perception_eval::{
+///     frame_id::FrameID,
+///     label::Label,
+///     object::object3d::DynamicObject,
+///     utils::color::{object_color, ColorLegend, ObjectKind},
+/// };
+/// use chrono::NaiveDateTime;
+///
+/// let object = DynamicObject::new(
+///     NaiveDateTime::default(),
+///     FrameID::BaseLink,
+///     [1.0, 1.0, 1.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 1.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// )
+/// .with_uuid("track");
+///
+/// let mut legend = ColorLegend::default();
+/// legend.insert(ObjectKind::Estimation, &object);
+/// legend.insert(ObjectKind::Estimation, &object);
+///
+/// let entry = legend.get(ObjectKind::Estimation, "track").unwrap();
+/// assert_eq!(entry.color, object_color(ObjectKind::Estimation, "track"));
+/// assert_eq!(entry.num_frames, 2);
+/// assert!(legend.get(ObjectKind::GroundTruth, "track").is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ColorLegend {
+    entries: BTreeMap<(ObjectKind, String), LegendEntry>,
+}
+
+impl ColorLegend {
+    /// Construct `ColorLegend` from frame results,
+    /// which contains all GTs and estimations in the frames.
+    ///
+    /// * `frame_results`   - List of PerceptionFrameResult.
+    pub fn new(frame_results: &[PerceptionFrameResult]) -> Self {
+        let mut legend = Self::default();
+        frame_results
+            .iter()
+            .for_each(|frame_result| legend.add_frame_result(frame_result));
+        legend
+    }
+
+    /// Add GTs and estimations in the frame result.
+    ///
+    /// * `frame_result`    - PerceptionFrameResult instance.
+    pub fn add_frame_result(&mut self, frame_result: &PerceptionFrameResult) {
+        frame_result
+            .frame_ground_truth()
+            .objects
+            .iter()
+            .for_each(|object| {
+                self.insert(ObjectKind::GroundTruth, object);
+            });
+        frame_result.results().iter().for_each(|result| {
+            self.insert(ObjectKind::Estimation, &result.estimated_object);
+        });
+    }
+
+    /// Add the object seen in a frame, and returns its color.
+    /// If the object has no uuid, returns None.
+    ///
+    /// * `kind`    - Kind of the object.
+    /// * `object`  - DynamicObject instance.
+    pub fn insert(&mut self, kind: ObjectKind, object: &DynamicObject) -> Option<[u8; 3]> {
+        let uuid = object.uuid.as_ref()?;
+        let entry = self
+            .entries
+            .entry((kind, uuid.to_owned()))
+            .or_insert_with(|| LegendEntry {
+                kind,
+                uuid: uuid.to_owned(),
+                label: object.label.to_owned(),
+                color: object_color(kind, uuid),
+                num_frames: 0,
+            });
+        entry.num_frames += 1;
+        Some(entry.color)
+    }
+
+    /// Returns the entry of the object.
+    /// If the object has never been added, returns None.
+    ///
+    /// * `kind`    - Kind of the object.
+    /// * `uuid`    - Instance token of GT or track id of estimation.
+    pub fn get(&self, kind: ObjectKind, uuid: &str) -> Option<&LegendEntry> {
+        self.entries.get(&(kind, uuid.to_owned()))
+    }
+
+    /// Returns entries ordered by the kind and uuid.
+    pub fn entries(&self) -> impl Iterator<Item = &LegendEntry> {
+        self.entries.values()
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there is no entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Save the legend as JSON list of entries.
+    ///
+    /// * `path`    - Path of the output file.
+    pub fn save_json<P>(&self, path: P) -> IoResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let writer = BufWriter::new(create_file(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+impl Serialize for ColorLegend {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.entries.values())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hsv_to_rgb, object_color, ObjectKind};
+
+    #[test]
+    fn test_object_color() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), [255, 0, 0]);
+        assert_eq!(hsv_to_rgb(120.0, 1.0, 1.0), [0, 255, 0]);
+        assert_eq!(hsv_to_rgb(240.0, 0.5, 1.0), [128, 128, 255]);
+
+        // Colors are pinned so that they stay consistent across releases.
+        let color = object_color(ObjectKind::GroundTruth, "instance");
+        assert_eq!(color, [227, 43, 169]);
+        assert_ne!(color, object_color(ObjectKind::Estimation, "instance"));
+    }
+}
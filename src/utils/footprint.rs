@@ -0,0 +1,201 @@
+use nalgebra::Vector3;
+
+use crate::{
+    object::object3d::DynamicObject,
+    utils::math::{quaternion2rotation, translate_inv},
+};
+
+/// Winding order of polygon corners seen from above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Winding {
+    CounterClockwise,
+    Clockwise,
+    /// Polygon has no area, such as collinear corners.
+    Degenerate,
+}
+
+/// Returns the signed BEV area of the polygon with the shoelace formula,
+/// which is positive if corners are ordered counter-clockwise.
+///
+/// * `corners` - Corners of the polygon, where z is ignored.
+///
+/// # Examples
+/// ```
+/// use perception_eval::utils::footprint::signed_area;
+///
+/// let corners = [[1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [-1.0, -1.0, 0.0], [1.0, -1.0, 0.0]];
+///
+/// assert_eq!(signed_area(&corners), 4.0);
+/// ```
+pub fn signed_area(corners: &[[f64; 3]]) -> f64 {
+    let num_corners = corners.len();
+    0.5 * (0..num_corners)
+        .map(|i| {
+            let p = &corners[i];
+            let q = &corners[(i + 1) % num_corners];
+            p[0] * q[1] - q[0] * p[1]
+        })
+        .sum::<f64>()
+}
+
+/// Returns the winding order of the polygon seen from above.
+///
+/// * `corners` - Corners of the polygon, where z is ignored.
+pub fn winding(corners: &[[f64; 3]]) -> Winding {
+    let area = signed_area(corners);
+    if area > f64::EPSILON {
+        Winding::CounterClockwise
+    } else if area < -f64::EPSILON {
+        Winding::Clockwise
+    } else {
+        Winding::Degenerate
+    }
+}
+
+/// Returns the footprint corners of the object in the canonical order, which is the order of
+/// `DynamicObject::footprint()`: counter-clockwise seen from above, starting from the front-left
+/// corner in the object frame.
+///
+/// Corners loaded from datasets can be ordered in any winding and from any corner,
+/// so that they should be canonicalized before their order is relied on.
+/// If corners are degenerate, they are returned as they are.
+///
+/// * `corners` - Four corners of the footprint in the same coord system as the object.
+/// * `object`  - DynamicObject instance whose box has the footprint.
+///
+/// # Examples
+/// ```
+/// use perception_eval::{
+///     frame_id::FrameID,
+///     label::Label,
+///     object::object3d::DynamicObject,
+///     utils::footprint::{canonicalize_footprint, is_canonical_footprint},
+/// };
+/// use chrono::NaiveDateTime;
+///
+/// let object = DynamicObject::new(
+///     NaiveDateTime::default(),
+///     FrameID::BaseLink,
+///     [0.0, 0.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [2.0, 4.0, 1.0],
+///     1.0,
+///     Label::Car,
+/// );
+///
+/// // Clockwise from the rear-right corner.
+/// let corners = [[-2.0, -1.0, 0.0], [-2.0, 1.0, 0.0], [2.0, 1.0, 0.0], [2.0, -1.0, 0.0]];
+/// assert!(!is_canonical_footprint(&corners, &object));
+///
+/// let canonical = canonicalize_footprint(&corners, &object);
+/// assert_eq!(canonical, object.footprint());
+/// assert!(is_canonical_footprint(&canonical, &object));
+/// ```
+pub fn canonicalize_footprint(corners: &[[f64; 3]], object: &DynamicObject) -> Vec<[f64; 3]> {
+    let mut corners = corners.to_vec();
+    match winding(&corners) {
+        Winding::CounterClockwise => {}
+        Winding::Clockwise => corners.reverse(),
+        Winding::Degenerate => return corners,
+    }
+    if let Some(front_left) = front_left_index(&corners, object) {
+        corners.rotate_left(front_left);
+    }
+    corners
+}
+
+/// Returns whether the footprint corners are in the canonical order of `canonicalize_footprint()`.
+///
+/// * `corners` - Four corners of the footprint in the same coord system as the object.
+/// * `object`  - DynamicObject instance whose box has the footprint.
+pub fn is_canonical_footprint(corners: &[[f64; 3]], object: &DynamicObject) -> bool {
+    winding(corners) == Winding::CounterClockwise && front_left_index(corners, object) == Some(0)
+}
+
+/// Returns the index of the front-left corner, which maximizes x + y in the object frame.
+///
+/// * `corners` - Corners of the footprint.
+/// * `object`  - DynamicObject instance whose box has the footprint.
+fn front_left_index(corners: &[[f64; 3]], object: &DynamicObject) -> Option<usize> {
+    let rot_t = quaternion2rotation(&object.orientation).transpose();
+    corners
+        .iter()
+        .map(|corner| {
+            let local = rot_t * Vector3::from(translate_inv(corner, &object.position));
+            local[0] + local[1]
+        })
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonicalize_footprint, is_canonical_footprint, signed_area, winding, Winding};
+    use crate::{frame_id::FrameID, label::Label, object::object3d::DynamicObject};
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_canonicalize_footprint() {
+        let base = DynamicObject {
+            timestamp: NaiveDateTime::default(),
+            frame_id: FrameID::BaseLink,
+            position: [0.0, 0.0, 0.0],
+            orientation: [1.0, 0.0, 0.0, 0.0],
+            size: [2.0, 4.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        };
+        // Boxes heading to each quadrant, placed in each quadrant.
+        for (i, yaw_deg) in [30.0_f64, 120.0, 210.0, 300.0].into_iter().enumerate() {
+            let yaw = yaw_deg.to_radians();
+            let object = DynamicObject {
+                position: [10.0 * (yaw + 0.1).cos(), 10.0 * (yaw + 0.1).sin(), 0.5],
+                orientation: [(0.5 * yaw).cos(), 0.0, 0.0, (0.5 * yaw).sin()],
+                ..base.clone()
+            };
+            let footprint = object.footprint();
+            assert!(
+                is_canonical_footprint(&footprint, &object),
+                "yaw {}",
+                yaw_deg
+            );
+            assert!((signed_area(&footprint) - 8.0).abs() < 1e-9);
+
+            // Front-left corner is ahead of and on the left of the center.
+            let heading = [yaw.cos(), yaw.sin()];
+            let left = [-yaw.sin(), yaw.cos()];
+            let offset = [
+                footprint[0][0] - object.position[0],
+                footprint[0][1] - object.position[1],
+            ];
+            assert!((offset[0] * heading[0] + offset[1] * heading[1] - 2.0).abs() < 1e-9);
+            assert!((offset[0] * left[0] + offset[1] * left[1] - 1.0).abs() < 1e-9);
+
+            // Clockwise corners starting from another corner.
+            let mut shuffled = footprint.clone();
+            shuffled.reverse();
+            shuffled.rotate_left(i);
+            assert_eq!(winding(&shuffled), Winding::Clockwise);
+            assert!(!is_canonical_footprint(&shuffled, &object));
+            let canonical = canonicalize_footprint(&shuffled, &object);
+            assert_eq!(canonical, footprint);
+        }
+
+        let collinear = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [3.0, 0.0, 0.0],
+        ];
+        assert_eq!(winding(&collinear), Winding::Degenerate);
+        assert_eq!(
+            canonicalize_footprint(&collinear, &base),
+            collinear.to_vec()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use super::point::distance_points_bev;
+
+/// Uniform grid index over BEV points to look up neighbors within a radius.
+///
+/// * `cell_size`   - Size of each grid cell in [m].
+/// * `points`      - List of indexed points.
+/// * `cells`       - Indices of points contained in each cell.
+#[derive(Debug, Clone)]
+pub struct BevGrid {
+    cell_size: f64,
+    points: Vec<[f64; 3]>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl BevGrid {
+    /// Construct `BevGrid` from points.
+    ///
+    /// * `points`      - List of 3D points, only x and y are used.
+    /// * `cell_size`   - Size of each grid cell in [m], which must be positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::utils::grid::BevGrid;
+    ///
+    /// let grid = BevGrid::new(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0]], 2.0);
+    ///
+    /// assert_eq!(grid.query(&[1.0, 0.0, 0.0], 2.0), vec![0]);
+    /// ```
+    pub fn new(points: &[[f64; 3]], cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "cell size must be positive");
+        let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        points.iter().enumerate().for_each(|(i, point)| {
+            cells
+                .entry(Self::cell_of(point, cell_size))
+                .or_default()
+                .push(i);
+        });
+        Self {
+            cell_size,
+            points: points.to_owned(),
+            cells,
+        }
+    }
+
+    /// Returns indices of points whose BEV distance from `point` is not greater than `radius`.
+    /// Indices are returned in ascending order.
+    ///
+    /// * `point`   - 3D point to query, only x and y are used.
+    /// * `radius`  - Search radius in [m].
+    pub fn query(&self, point: &[f64; 3], radius: f64) -> Vec<usize> {
+        let span = (radius / self.cell_size).ceil() as i64;
+        let (cx, cy) = Self::cell_of(point, self.cell_size);
+        let mut indices = Vec::new();
+        for x in (cx - span)..=(cx + span) {
+            for y in (cy - span)..=(cy + span) {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    indices.extend(
+                        cell.iter()
+                            .filter(|i| distance_points_bev(point, &self.points[**i]) <= radius),
+                    );
+                }
+            }
+        }
+        indices.sort_unstable();
+        indices
+    }
+
+    fn cell_of(point: &[f64; 3], cell_size: f64) -> (i64, i64) {
+        (
+            (point[0] / cell_size).floor() as i64,
+            (point[1] / cell_size).floor() as i64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BevGrid;
+
+    #[test]
+    fn test_bev_grid_query() {
+        let points = [
+            [0.0, 0.0, 0.0],
+            [1.5, 0.0, 5.0],
+            [-1.0, -1.0, 0.0],
+            [3.0, 3.0, 0.0],
+        ];
+        let grid = BevGrid::new(&points, 1.0);
+
+        assert_eq!(grid.query(&[0.0, 0.0, 0.0], 1.5), vec![0, 1, 2]);
+        assert_eq!(grid.query(&[3.0, 2.5, 0.0], 0.5), vec![3]);
+        assert!(grid.query(&[-10.0, 10.0, 0.0], 1.0).is_empty());
+    }
+}
@@ -7,7 +7,7 @@ use log4rs::{
 use std::error::Error;
 use std::path::Path;
 
-pub type LoggerResult<T> = std::result::Result<T, Box<dyn Error>>;
+pub type LoggerResult<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
 
 /// Configure logger instance.
 /// The log output will be saved in `log_dir/output.log`.
@@ -0,0 +1,195 @@
+use std::{
+    io::{BufWriter, Result as IoResult, Write},
+    path::Path,
+};
+
+use crate::{
+    dataset::nuscenes::PointCloudMatrix,
+    fault::create_file,
+    object::object3d::DynamicObject,
+    utils::math::{rotate_inv, translate_inv},
+};
+
+/// Returns whether the point is inside the box of the object expanded by the margin.
+///
+/// * `point`   - 3D point in the same coord system as the object.
+/// * `object`  - DynamicObject instance.
+/// * `margin`  - Margin added to each side of the box in [m].
+///
+/// # Examples
+/// ```
+/// use chrono::DateTime;
+/// use perception_eval::{
+///     frame_id::FrameID, label::Label, object::object3d::DynamicObject,
+///     utils::pointcloud::is_point_in_box,
+/// };
+///
+/// let object = DynamicObject::new(
+///     DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+///     FrameID::BaseLink,
+///     [10.0, 0.0, 0.0],
+///     [1.0, 0.0, 0.0, 0.0],
+///     [4.0, 2.0, 1.5],
+///     1.0,
+///     Label::Car,
+/// );
+///
+/// assert!(is_point_in_box(&[11.5, 0.5, 0.0], &object, 0.0));
+/// assert!(!is_point_in_box(&[12.5, 0.0, 0.0], &object, 0.0));
+/// assert!(is_point_in_box(&[12.5, 0.0, 0.0], &object, 0.5));
+/// ```
+pub fn is_point_in_box(point: &[f64; 3], object: &DynamicObject, margin: f64) -> bool {
+    let local = rotate_inv(&translate_inv(point, &object.position), &object.orientation);
+    local
+        .iter()
+        .zip(&object.size)
+        .all(|(value, size)| value.abs() <= 0.5 * size + margin)
+}
+
+/// Returns points inside the box of the object expanded by the margin.
+///
+/// * `points`  - Pointcloud in the same coord system as the object.
+/// * `object`  - DynamicObject instance.
+/// * `margin`  - Margin added to each side of the box in [m].
+pub fn crop_points_in_box(
+    points: &PointCloudMatrix,
+    object: &DynamicObject,
+    margin: f64,
+) -> PointCloudMatrix {
+    let indices = points
+        .row_iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            let point = [row[0] as f64, row[1] as f64, row[2] as f64];
+            is_point_in_box(&point, object, margin)
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    points.select_rows(indices.iter())
+}
+
+/// Write pointcloud to ASCII PCD file with fields (x, y, z, intensity).
+///
+/// * `path`    - Path of the output file.
+/// * `points`  - Pointcloud.
+pub fn write_pcd<P>(path: P, points: &PointCloudMatrix) -> IoResult<()>
+where
+    P: AsRef<Path>,
+{
+    write_pcd_impl(path, points, None)
+}
+
+/// Write pointcloud to ASCII PCD file with fields (x, y, z, intensity, rgb),
+/// where all points are painted with the same color.
+///
+/// The color is packed into the `rgb` field as float, following the PCL convention.
+///
+/// * `path`    - Path of the output file.
+/// * `points`  - Pointcloud.
+/// * `color`   - RGB color of points.
+pub fn write_pcd_with_color<P>(path: P, points: &PointCloudMatrix, color: [u8; 3]) -> IoResult<()>
+where
+    P: AsRef<Path>,
+{
+    write_pcd_impl(path, points, Some(color))
+}
+
+fn write_pcd_impl<P>(path: P, points: &PointCloudMatrix, color: Option<[u8; 3]>) -> IoResult<()>
+where
+    P: AsRef<Path>,
+{
+    if let Some(parent) = path.as_ref().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(create_file(path)?);
+    let num_points = points.nrows();
+    writeln!(writer, "# .PCD v0.7 - Point Cloud Data file format")?;
+    writeln!(writer, "VERSION 0.7")?;
+    if color.is_some() {
+        writeln!(writer, "FIELDS x y z intensity rgb")?;
+        writeln!(writer, "SIZE 4 4 4 4 4")?;
+        writeln!(writer, "TYPE F F F F F")?;
+        writeln!(writer, "COUNT 1 1 1 1 1")?;
+    } else {
+        writeln!(writer, "FIELDS x y z intensity")?;
+        writeln!(writer, "SIZE 4 4 4 4")?;
+        writeln!(writer, "TYPE F F F F")?;
+        writeln!(writer, "COUNT 1 1 1 1")?;
+    }
+    writeln!(writer, "WIDTH {}", num_points)?;
+    writeln!(writer, "HEIGHT 1")?;
+    writeln!(writer, "VIEWPOINT 0 0 0 1 0 0 0")?;
+    writeln!(writer, "POINTS {}", num_points)?;
+    writeln!(writer, "DATA ascii")?;
+    let rgb =
+        color.map(|[r, g, b]| f32::from_bits(((r as u32) << 16) | ((g as u32) << 8) | b as u32));
+    for row in points.row_iter() {
+        match rgb {
+            Some(rgb) => writeln!(
+                writer,
+                "{} {} {} {} {:e}",
+                row[0], row[1], row[2], row[3], rgb
+            )?,
+            None => writeln!(writer, "{} {} {} {}", row[0], row[1], row[2], row[3])?,
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crop_points_in_box, write_pcd, write_pcd_with_color};
+    use crate::{
+        dataset::nuscenes::PointCloudMatrix, frame_id::FrameID, label::Label,
+        object::object3d::DynamicObject,
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn test_crop_points_in_box() {
+        // Box rotated by 90 degrees around z axis.
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        let object = DynamicObject {
+            timestamp: DateTime::from_timestamp_micros(10000).unwrap().naive_utc(),
+            frame_id: FrameID::BaseLink,
+            position: [10.0, 0.0, 0.0],
+            orientation: [half, 0.0, 0.0, half],
+            size: [4.0, 2.0, 1.5],
+            velocity: None,
+            confidence: 1.0,
+            label: Label::Car,
+            pointcloud_num: None,
+            uuid: None,
+            class_probabilities: None,
+        };
+        #[rustfmt::skip]
+        let points = PointCloudMatrix::from_row_slice(&[
+            10.0, 1.5, 0.0, 1.0, 0.0,
+            11.5, 0.0, 0.0, 2.0, 0.0,
+            10.5, -1.9, 0.5, 3.0, 0.0,
+        ]);
+
+        let cropped = crop_points_in_box(&points, &object, 0.0);
+        assert_eq!(cropped.nrows(), 2);
+        assert_eq!(cropped[(0, 3)], 1.0);
+        assert_eq!(cropped[(1, 3)], 3.0);
+
+        let path = std::env::temp_dir().join("perception_eval_test_crop.pcd");
+        write_pcd(&path, &cropped).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("POINTS 2"));
+        assert_eq!(contents.lines().count(), 13);
+
+        write_pcd_with_color(&path, &cropped, [255, 0, 128]).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("FIELDS x y z intensity rgb"));
+        let rgb = contents
+            .lines()
+            .last()
+            .and_then(|line| line.split(' ').nth(4))
+            .and_then(|value| value.parse::<f32>().ok())
+            .unwrap();
+        assert_eq!(rgb.to_bits(), 0xff0080);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    frame_id::FrameID,
+    utils::math::{rotate, rotate_inv, translate, translate_inv},
+};
+
+/// Project the point in the camera coord system onto the image plane.
+/// If the point is not in front of the camera, returns None.
+///
+/// * `intrinsic`   - Camera intrinsic matrix.
+/// * `point`       - 3D point in the camera coord system, where z is the optical axis.
+///
+/// # Examples
+/// ```
+/// use perception_eval::utils::projection::project_point;
+///
+/// let intrinsic = [[1000.0, 0.0, 800.0], [0.0, 1000.0, 450.0], [0.0, 0.0, 1.0]];
+///
+/// assert_eq!(project_point(&intrinsic, &[1.0, 0.5, 10.0]), Some([900.0, 500.0]));
+/// assert_eq!(project_point(&intrinsic, &[1.0, 0.5, -10.0]), None);
+/// ```
+pub fn project_point(intrinsic: &[[f64; 3]; 3], point: &[f64; 3]) -> Option<[f64; 2]> {
+    if point[2] <= 0.0 {
+        return None;
+    }
+    let [u, v, w] = intrinsic.map(|row| row[0] * point[0] + row[1] * point[1] + row[2] * point[2]);
+    Some([u / w, v / w])
+}
+
+/// Camera calibration used to check visibility of objects.
+///
+/// * `frame_id`    - FrameID of the camera.
+/// * `intrinsic`   - Camera intrinsic matrix.
+/// * `image_size`  - Image size in [px], (width, height) order.
+/// * `translation` - Translation from the camera to `base_link`.
+/// * `rotation`    - Rotation from the camera to `base_link`, [w, x, y, z] order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraModel {
+    pub frame_id: FrameID,
+    pub intrinsic: [[f64; 3]; 3],
+    pub image_size: [u32; 2],
+    pub translation: [f64; 3],
+    pub rotation: [f64; 4],
+}
+
+impl CameraModel {
+    /// Transform the point in the camera coord system into `base_link`.
+    ///
+    /// * `point`   - 3D point in the camera coord system.
+    pub fn to_base_link(&self, point: &[f64; 3]) -> [f64; 3] {
+        translate(&rotate(point, &self.rotation), &self.translation)
+    }
+
+    /// Transform the point in `base_link` into the camera coord system.
+    ///
+    /// * `point`   - 3D point in `base_link`.
+    pub fn from_base_link(&self, point: &[f64; 3]) -> [f64; 3] {
+        rotate_inv(&translate_inv(point, &self.translation), &self.rotation)
+    }
+
+    /// Returns whether the point in the camera coord system is projected inside the image.
+    ///
+    /// * `point`   - 3D point in the camera coord system.
+    ///
+    /// # Examples
+    /// ```
+    /// use perception_eval::{frame_id::FrameID, utils::projection::CameraModel};
+    ///
+    /// let camera = CameraModel {
+    ///     frame_id: FrameID::CamFront,
+    ///     intrinsic: [[1000.0, 0.0, 800.0], [0.0, 1000.0, 450.0], [0.0, 0.0, 1.0]],
+    ///     image_size: [1600, 900],
+    ///     translation: [0.0, 0.0, 0.0],
+    ///     rotation: [1.0, 0.0, 0.0, 0.0],
+    /// };
+    ///
+    /// assert!(camera.is_visible(&[0.0, 0.0, 10.0]));
+    /// assert!(!camera.is_visible(&[10.0, 0.0, 1.0]));
+    /// ```
+    pub fn is_visible(&self, point: &[f64; 3]) -> bool {
+        let [width, height] = self.image_size;
+        match project_point(&self.intrinsic, point) {
+            Some([u, v]) => (0.0..width as f64).contains(&u) && (0.0..height as f64).contains(&v),
+            None => false,
+        }
+    }
+
+    /// Returns whether the point in `base_link` is projected inside the image.
+    ///
+    /// * `point`   - 3D point in `base_link`.
+    pub fn is_visible_from_base_link(&self, point: &[f64; 3]) -> bool {
+        self.is_visible(&self.from_base_link(point))
+    }
+}
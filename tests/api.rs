@@ -0,0 +1,135 @@
+//! Semver checks of the stable facade. Breaking any of these tests requires a major release.
+
+use std::{error::Error as StdError, fmt::Debug, path::PathBuf};
+
+use chrono::NaiveDateTime;
+use perception_eval::api::{
+    load_dataset, quickstart, ConfigError, ConfigParams, ConfigResult, DatasetResult,
+    DynamicObject, Error, EvaluationTask, FilterParams, FrameGroundTruth, FrameID, Label,
+    LabelConverter, MatchingError, MatchingMode, MatchingResult, MetricsError, MetricsParams,
+    MetricsResult, MetricsScore, PerceptionEvaluationConfig, PerceptionEvaluationManager,
+    PerceptionFrameResult, PerceptionResult, Result, Scenario, ScenarioBuilder,
+};
+
+fn assert_clone_debug<T: Clone + Debug>() {}
+
+fn assert_error<T: StdError + Send + Sync + 'static>() {}
+
+/// Errors of modules are converted into the top-level error by `?`.
+fn assert_into_error<T: Into<Error>>() {}
+
+#[test]
+fn test_api_traits() {
+    assert_clone_debug::<PerceptionEvaluationConfig>();
+    assert_clone_debug::<PerceptionEvaluationManager<'static>>();
+    assert_clone_debug::<FilterParams>();
+    assert_clone_debug::<MetricsParams>();
+    assert_clone_debug::<Scenario>();
+    assert_clone_debug::<FrameGroundTruth>();
+    assert_clone_debug::<DynamicObject>();
+    assert_clone_debug::<PerceptionFrameResult>();
+    assert_clone_debug::<PerceptionResult>();
+    assert_clone_debug::<MetricsScore>();
+    assert_clone_debug::<Label>();
+    assert_clone_debug::<FrameID>();
+    assert_clone_debug::<EvaluationTask>();
+    assert_clone_debug::<MatchingMode>();
+    assert_error::<ConfigError>();
+    assert_error::<MatchingError>();
+    assert_error::<MetricsError>();
+    assert_into_error::<ConfigError>();
+    assert_into_error::<MatchingError>();
+    assert_into_error::<MetricsError>();
+    assert_into_error::<Box<dyn StdError + Send + Sync>>();
+    assert_error::<Error>();
+    let _: fn() -> Result<()> = || Ok(());
+}
+
+/// Signatures of the manager, whose lifetime is bound to the configuration.
+fn assert_manager_signatures<'a>(_config: &'a PerceptionEvaluationConfig) {
+    let _: fn(&'a PerceptionEvaluationConfig) -> DatasetResult<PerceptionEvaluationManager<'a>> =
+        PerceptionEvaluationManager::<'a>::from;
+    let _: fn(
+        &mut PerceptionEvaluationManager<'a>,
+        &[DynamicObject],
+        &FrameGroundTruth,
+    ) -> MatchingResult<()> = PerceptionEvaluationManager::<'a>::add_frame_result;
+    let _: fn(&PerceptionEvaluationManager<'a>, &NaiveDateTime) -> Option<FrameGroundTruth> =
+        PerceptionEvaluationManager::<'a>::get_frame_ground_truth;
+    let _: fn(&PerceptionEvaluationManager<'a>) -> MetricsResult<MetricsScore> =
+        PerceptionEvaluationManager::<'a>::get_metrics_score;
+}
+
+#[test]
+fn test_api_signatures() {
+    let _: fn(&str, &str, bool) -> ConfigResult<PerceptionEvaluationConfig> =
+        PerceptionEvaluationConfig::from;
+    let _: fn(&str, &PathBuf, &EvaluationTask, &FrameID) -> DatasetResult<Vec<FrameGroundTruth>> =
+        load_dataset;
+    let _: fn(&MetricsScore, &MatchingMode) -> Option<f64> = MetricsScore::mean_ap;
+    assert_manager_signatures(&quickstart().config);
+}
+
+#[test]
+fn test_api_constructors() {
+    let _: fn(DynamicObject, [f64; 3]) -> DynamicObject = DynamicObject::with_velocity;
+    let _: fn(DynamicObject, usize) -> DynamicObject = DynamicObject::with_pointcloud_num;
+    let _: fn(DynamicObject, &str) -> DynamicObject = DynamicObject::with_uuid;
+    let _: fn(NaiveDateTime, Vec<DynamicObject>) -> FrameGroundTruth = FrameGroundTruth::new;
+    let _: fn(FrameGroundTruth, [f64; 3], [f64; 4]) -> FrameGroundTruth =
+        FrameGroundTruth::with_ego_pose;
+    let _: fn(FrameGroundTruth, &str) -> FrameGroundTruth = FrameGroundTruth::with_sample_token;
+    let _: fn(
+        Vec<String>,
+        &'static str,
+        EvaluationTask,
+        FrameID,
+        &'static str,
+        FilterParams,
+        MetricsParams,
+    ) -> PerceptionEvaluationConfig = PerceptionEvaluationConfig::new;
+    let _: fn(Scenario, &str, bool) -> ConfigResult<PerceptionEvaluationConfig> =
+        PerceptionEvaluationConfig::from_scenario;
+    let _: fn(&str) -> ScenarioBuilder = ScenarioBuilder::new;
+    let _: fn(ScenarioBuilder) -> ConfigResult<Scenario> = ScenarioBuilder::build;
+    let _: fn(EvaluationTask, FrameID, Vec<String>, f64, f64, [f64; 4]) -> ConfigParams =
+        ConfigParams::new;
+
+    // Fields of non-exhaustive structs are still readable and writable.
+    let timestamp: NaiveDateTime = quickstart().estimations[0][0].timestamp;
+    let mut object = DynamicObject::new(
+        timestamp,
+        FrameID::BaseLink,
+        [1.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0],
+        [1.8, 4.5, 1.5],
+        1.0,
+        Label::Car,
+    );
+    object.position = [0.0; 3];
+    let frame = FrameGroundTruth::new(object.timestamp, vec![object]);
+    assert_eq!(frame.objects[0].position, [0.0; 3]);
+    assert_eq!(quickstart().config.frame_id, FrameID::BaseLink);
+}
+
+#[test]
+fn test_api_pipeline() {
+    let quickstart = quickstart();
+    let mut manager = quickstart.manager();
+    for estimations in &quickstart.estimations {
+        let frame = manager
+            .get_frame_ground_truth(&estimations[0].timestamp)
+            .unwrap();
+        manager.add_frame_result(estimations, &frame).unwrap();
+    }
+    let score = manager.get_metrics_score().unwrap();
+    for mode in [MatchingMode::CenterDistance, MatchingMode::PlaneDistance] {
+        let mean_ap = score.mean_ap(&mode).unwrap();
+        assert!((0.0..=1.0).contains(&mean_ap));
+    }
+
+    let converter = LabelConverter::new("autoware").unwrap();
+    assert_eq!(converter.try_convert("car"), Some(Label::Car));
+    let _ = FilterParams::new(&vec!["Car"], 100.0, 100.0, None, None).unwrap();
+    let _ = MetricsParams::new(&vec!["Car"], 1.0, 1.0, 0.5, 0.5).unwrap();
+}
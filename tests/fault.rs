@@ -0,0 +1,161 @@
+//! Robustness checks of the evaluator with injected faults, which require the `fault-injection` feature.
+
+use perception_eval::{
+    api::{quickstart, MatchingMode, PerceptionEvaluationManager},
+    diagnostics::Diagnostic,
+    fault::{inject, Fault},
+};
+
+#[test]
+fn test_corrupted_frame() {
+    let quickstart = quickstart();
+    let mut manager = quickstart.manager();
+    for (i, (frame, estimations)) in quickstart
+        .frame_ground_truths
+        .iter()
+        .zip(&quickstart.estimations)
+        .enumerate()
+    {
+        let _guard = (i == 0).then(|| inject(&[Fault::CorruptedFrame]));
+        manager.add_frame_result(estimations, frame).unwrap();
+    }
+    assert_eq!(
+        manager.num_frame_results(),
+        quickstart.frame_ground_truths.len()
+    );
+
+    // Every object in the corrupted frame is rejected, and the others are evaluated.
+    let diagnostics = manager.diagnostics();
+    let num_rejected_estimations = diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            matches!(
+                diagnostic,
+                Diagnostic::RejectedEstimation {
+                    reason: "non-finite values",
+                    ..
+                }
+            )
+        })
+        .count();
+    let num_rejected_ground_truths = diagnostics
+        .iter()
+        .filter(|diagnostic| matches!(diagnostic, Diagnostic::RejectedGroundTruth { .. }))
+        .count();
+    assert_eq!(num_rejected_estimations, quickstart.estimations[0].len());
+    assert_eq!(
+        num_rejected_ground_truths,
+        quickstart.frame_ground_truths[0].objects.len()
+    );
+    assert!(manager.frame_results[0].tp_results().is_empty());
+
+    let score = manager.get_metrics_score().unwrap();
+    let mean_ap = score.mean_ap(&MatchingMode::CenterDistance).unwrap();
+    assert!((0.0..=1.0).contains(&mean_ap));
+}
+
+#[test]
+fn test_nan_score() {
+    let quickstart = quickstart();
+    let mut manager = quickstart.manager();
+    {
+        let _guard = inject(&[Fault::NanScore]);
+        for (frame, estimations) in quickstart
+            .frame_ground_truths
+            .iter()
+            .zip(&quickstart.estimations)
+        {
+            manager.add_frame_result(estimations, frame).unwrap();
+        }
+    }
+
+    // Estimations without valid scores are rejected, so that GTs are all missed.
+    let num_estimations = quickstart.estimations.iter().map(Vec::len).sum::<usize>();
+    assert_eq!(manager.diagnostics().len(), num_estimations);
+    let score = manager.get_metrics_score().unwrap();
+    for mode in [MatchingMode::CenterDistance, MatchingMode::PlaneDistance] {
+        assert!(score.mean_ap(&mode).is_none_or(|mean_ap| mean_ap == 0.0));
+    }
+}
+
+#[test]
+fn test_missing_ground_truth() {
+    let quickstart = quickstart();
+    let mut manager = quickstart.manager();
+    for (i, estimations) in quickstart.estimations.iter().enumerate() {
+        let _guard = (i % 2 == 0).then(|| inject(&[Fault::MissingGroundTruth]));
+        let Some(frame) = manager.get_frame_ground_truth(&estimations[0].timestamp) else {
+            continue;
+        };
+        manager.add_frame_result(estimations, &frame).unwrap();
+    }
+
+    let num_missing_frames = quickstart.estimations.len().div_ceil(2);
+    assert_eq!(
+        manager.num_frame_results(),
+        quickstart.estimations.len() - num_missing_frames
+    );
+    let diagnostics = manager.diagnostics();
+    assert_eq!(diagnostics.len(), num_missing_frames);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| matches!(diagnostic, Diagnostic::MissingGroundTruth { .. })));
+    assert!(manager.get_metrics_score().is_ok());
+}
+
+#[test]
+fn test_disk_error() {
+    let quickstart = quickstart();
+    let mut config = quickstart.config.clone();
+    config.result_dir = "work_dir/fault".into();
+    config.viz_dir = "work_dir/fault/visualize".into();
+    config.export_pr_curves = true;
+    let mut manager = PerceptionEvaluationManager::from_frame_ground_truths(
+        &config,
+        quickstart.frame_ground_truths.clone(),
+    );
+    for (frame, estimations) in quickstart
+        .frame_ground_truths
+        .iter()
+        .zip(&quickstart.estimations)
+    {
+        manager.add_frame_result(estimations, frame).unwrap();
+    }
+
+    let score = manager.get_metrics_score().unwrap();
+    let _guard = inject(&[Fault::DiskError]);
+    // PR curves are explicitly requested by the config, so failures to save them are errors.
+    assert!(manager.get_metrics_score().is_err());
+    assert!(manager.diagnostics().is_empty());
+
+    // Explicitly requested exports return errors.
+    assert!(manager.export_kitti("work_dir/fault/kitti").is_err());
+    assert!(manager.export_color_legend().is_err());
+    assert!(score.save_decisions("work_dir/fault/decisions").is_err());
+}
+
+#[test]
+fn test_fault_guard() {
+    let quickstart = quickstart();
+    let manager = quickstart.manager();
+    let timestamp = quickstart.frame_ground_truths[0].timestamp;
+    {
+        let _outer = inject(&[Fault::DiskError]);
+        {
+            let _inner = inject(&[Fault::MissingGroundTruth]);
+            assert!(manager.get_frame_ground_truth(&timestamp).is_none());
+        }
+        assert!(manager.get_frame_ground_truth(&timestamp).is_some());
+        assert!(manager.export_color_legend().is_err());
+    }
+    assert_eq!(manager.diagnostics().len(), 1);
+
+    // Faults are not injected on other threads.
+    let _guard = inject(&[Fault::MissingGroundTruth]);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let manager = quickstart.manager();
+            assert!(manager.get_frame_ground_truth(&timestamp).is_some());
+        });
+    });
+}